@@ -0,0 +1,273 @@
+use super::{
+    BTreeMap, BTreeSet, CodeBlock, LibraryPath, ProcedureId, ProcedureName, RpoDigest, String, Vec,
+};
+
+// CALL GRAPH
+// ================================================================================================
+
+/// Invocations between procedures compiled by an assembler.
+///
+/// Procedures are identified by the IDs derived from their fully-qualified names, as distinct
+/// procedures may have the same MAST root (e.g., a procedure which consists of a single `exec` of
+/// another procedure). For each procedure, the graph records the procedures inlined into it via
+/// `exec` together with the number of inlined copies, and the procedures it invokes via `call` or
+/// `syscall`. The body of a program is recorded in the graph as a procedure as well (see
+/// [program_body_id()]).
+#[derive(Debug, Default, Clone)]
+pub struct CallGraph {
+    procedures: BTreeMap<ProcedureId, ProcedureNode>,
+}
+
+/// A procedure in the [CallGraph].
+#[derive(Debug, Clone)]
+pub struct ProcedureNode {
+    /// Fully-qualified name of the procedure.
+    pub name: String,
+    /// MAST root of the procedure.
+    pub mast_root: RpoDigest,
+    /// Size of the MAST of the procedure (see [mast_size()]).
+    pub size: usize,
+    /// Procedures directly invoked by this procedure.
+    pub invocations: Invocations,
+}
+
+/// Procedures directly invoked by a procedure.
+#[derive(Debug, Default, Clone)]
+pub struct Invocations {
+    /// IDs of procedures inlined via `exec`, and the number of copies inlined; invocations inside
+    /// `repeat` blocks are counted once per repetition.
+    pub execs: BTreeMap<ProcedureId, usize>,
+    /// IDs of procedures invoked via `call` or `syscall`.
+    pub calls: BTreeSet<ProcedureId>,
+    /// MAST roots of procedures invoked via `call` by their MAST roots; such invocations are
+    /// attributed to the procedures with these MAST roots.
+    pub root_calls: BTreeSet<RpoDigest>,
+}
+
+impl CallGraph {
+    /// Adds a procedure with the specified ID to this graph, replacing the procedure with the
+    /// same ID (if any).
+    pub fn insert(&mut self, proc_id: ProcedureId, node: ProcedureNode) {
+        self.procedures.insert(proc_id, node);
+    }
+
+    /// Adds all procedures from the provided graph to this graph.
+    pub fn merge(&mut self, other: &Self) {
+        self.procedures
+            .extend(other.procedures.iter().map(|(id, node)| (*id, node.clone())));
+    }
+
+    /// Returns IDs of all procedures reachable from the procedure with the specified ID,
+    /// including the procedure itself.
+    pub fn reachable(&self, proc_id: ProcedureId) -> BTreeSet<ProcedureId> {
+        let mut reachable = BTreeSet::new();
+        let mut pending = vec![proc_id];
+        while let Some(proc_id) = pending.pop() {
+            if reachable.insert(proc_id) {
+                if let Some(node) = self.procedures.get(&proc_id) {
+                    pending.extend(node.invocations.execs.keys());
+                    pending.extend(node.invocations.calls.iter());
+                    for mast_root in node.invocations.root_calls.iter() {
+                        pending.extend(self.find_by_root(*mast_root));
+                    }
+                }
+            }
+        }
+        reachable
+    }
+
+    /// Returns the procedures inlined into the MAST of the program with the specified body ID,
+    /// sorted by the total size of their inlined copies in descending order.
+    ///
+    /// The MAST of a program consists of the program body and the bodies of all procedures
+    /// invoked via `call` or `syscall`; procedures inlined into each of these are counted
+    /// separately, transitively through other inlined procedures.
+    pub fn inlined_procedures(&self, program_id: ProcedureId) -> Vec<InlinedProcedure> {
+        // the roots of the MAST are the program body and all procedures invoked via call; each
+        // of them is a separate tree in the program MAST, thus procedures with the same MAST root
+        // are counted once
+        let reachable = self.reachable(program_id);
+        let mut trees = BTreeMap::new();
+        for node in reachable.iter().filter_map(|proc_id| self.procedures.get(proc_id)) {
+            let called_roots = node.invocations.root_calls.iter();
+            let called_ids =
+                node.invocations.calls.iter().copied().chain(
+                    called_roots.flat_map(|mast_root| self.find_by_root(*mast_root).take(1)),
+                );
+            for proc_id in called_ids {
+                if let Some(callee) = self.procedures.get(&proc_id) {
+                    trees.entry(callee.mast_root).or_insert(proc_id);
+                }
+            }
+        }
+        if let Some(program) = self.procedures.get(&program_id) {
+            trees.insert(program.mast_root, program_id);
+        }
+
+        let mut memo = BTreeMap::new();
+        let mut in_progress = BTreeSet::new();
+        let mut copies = BTreeMap::<ProcedureId, usize>::new();
+        for tree in trees.into_values() {
+            for (proc_id, count) in self.inlined_copies(tree, &mut memo, &mut in_progress) {
+                *copies.entry(proc_id).or_default() += count;
+            }
+        }
+
+        let mut procedures = copies
+            .into_iter()
+            .filter_map(|(proc_id, copies)| {
+                let node = self.procedures.get(&proc_id)?;
+                Some(InlinedProcedure {
+                    name: node.name.clone(),
+                    size: node.size,
+                    copies,
+                })
+            })
+            .collect::<Vec<_>>();
+        procedures.sort_by(|a, b| b.total_size().cmp(&a.total_size()).then(a.name.cmp(&b.name)));
+        procedures
+    }
+
+    /// Returns the number of copies of each procedure inlined into the MAST of the procedure with
+    /// the specified ID, including copies inlined into other inlined procedures.
+    ///
+    /// Results are memoized per procedure. Procedures can inline only procedures compiled before
+    /// them, thus the graph of inlined procedures is expected to be acyclic; still, procedures
+    /// which are already being visited are skipped to make sure the recursion terminates.
+    fn inlined_copies(
+        &self,
+        proc_id: ProcedureId,
+        memo: &mut BTreeMap<ProcedureId, BTreeMap<ProcedureId, usize>>,
+        in_progress: &mut BTreeSet<ProcedureId>,
+    ) -> BTreeMap<ProcedureId, usize> {
+        if let Some(copies) = memo.get(&proc_id) {
+            return copies.clone();
+        }
+        if !in_progress.insert(proc_id) {
+            return BTreeMap::new();
+        }
+
+        let mut copies = BTreeMap::<ProcedureId, usize>::new();
+        if let Some(node) = self.procedures.get(&proc_id) {
+            for (&callee, &count) in node.invocations.execs.iter() {
+                *copies.entry(callee).or_default() += count;
+                for (nested, nested_count) in self.inlined_copies(callee, memo, in_progress) {
+                    *copies.entry(nested).or_default() += count * nested_count;
+                }
+            }
+        }
+        in_progress.remove(&proc_id);
+        memo.insert(proc_id, copies.clone());
+        copies
+    }
+
+    /// Returns IDs of the procedures with the specified MAST root.
+    fn find_by_root(&self, mast_root: RpoDigest) -> impl Iterator<Item = ProcedureId> + '_ {
+        self.procedures
+            .iter()
+            .filter(move |(_, node)| node.mast_root == mast_root)
+            .map(|(proc_id, _)| *proc_id)
+    }
+}
+
+// CALL GRAPH REPORT
+// ================================================================================================
+
+/// Analysis of the procedures invoked by a compiled program.
+///
+/// The report lists the procedures exported from the modules imported by the program which the
+/// program never invokes, and the procedures inlined into the program MAST via `exec`. The latter
+/// helps to decide which procedures to invoke via `call` or `dynexec` instead: an inlined
+/// procedure adds its whole body to the MAST for each inlined copy, while a called procedure is
+/// included in the MAST only once, but each invocation takes a few extra cycles to enter and
+/// leave the procedure.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct CallGraphReport {
+    unused_exports: Vec<String>,
+    inlined_procedures: Vec<InlinedProcedure>,
+}
+
+impl CallGraphReport {
+    /// Returns a new report from the specified unused exports and inlined procedures.
+    pub(super) fn new(
+        unused_exports: Vec<String>,
+        inlined_procedures: Vec<InlinedProcedure>,
+    ) -> Self {
+        Self {
+            unused_exports,
+            inlined_procedures,
+        }
+    }
+
+    /// Returns fully-qualified names of procedures exported from the modules imported by the
+    /// program which are not invoked by the program, either directly or transitively.
+    ///
+    /// Only modules from which the program invokes at least one procedure are considered; modules
+    /// which are imported but not used at all are reported by the linter.
+    pub fn unused_exports(&self) -> &[String] {
+        &self.unused_exports
+    }
+
+    /// Returns the procedures inlined into the program MAST, sorted by the total size of their
+    /// inlined copies in descending order.
+    pub fn inlined_procedures(&self) -> &[InlinedProcedure] {
+        &self.inlined_procedures
+    }
+}
+
+/// A procedure inlined into the MAST of a program via `exec`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InlinedProcedure {
+    name: String,
+    size: usize,
+    copies: usize,
+}
+
+impl InlinedProcedure {
+    /// Returns the fully-qualified name of this procedure.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the size of the MAST of this procedure, i.e., the number of operations in its SPAN
+    /// blocks plus the number of its control blocks.
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Returns the number of copies of this procedure inlined into the program MAST.
+    pub fn copies(&self) -> usize {
+        self.copies
+    }
+
+    /// Returns the combined size of all inlined copies of this procedure.
+    pub fn total_size(&self) -> usize {
+        self.size * self.copies
+    }
+
+    /// Returns the reduction of the program MAST size if all copies of this procedure were
+    /// replaced with calls to a single copy of it.
+    pub fn call_size_savings(&self) -> usize {
+        self.size * (self.copies - 1)
+    }
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Returns the ID under which the body of a program is recorded in the [CallGraph].
+pub fn program_body_id() -> ProcedureId {
+    ProcedureId::from_name(ProcedureName::MAIN_PROC_NAME, &LibraryPath::exec_path())
+}
+
+/// Returns the size of the specified MAST, i.e., the number of operations in its SPAN blocks
+/// plus the number of its control blocks.
+pub fn mast_size(block: &CodeBlock) -> usize {
+    match block {
+        CodeBlock::Span(span) => span.op_batches().iter().map(|batch| batch.ops().len()).sum(),
+        CodeBlock::Join(join) => 1 + mast_size(join.first()) + mast_size(join.second()),
+        CodeBlock::Split(split) => 1 + mast_size(split.on_true()) + mast_size(split.on_false()),
+        CodeBlock::Loop(r#loop) => 1 + mast_size(r#loop.body()),
+        CodeBlock::Call(_) | CodeBlock::Dyn(_) | CodeBlock::Proxy(_) => 1,
+    }
+}
@@ -1,9 +1,11 @@
 use super::{
+    call_graph::{mast_size, program_body_id, CallGraph, Invocations, ProcedureNode},
     AssemblyError, BTreeMap, CallSet, CodeBlock, CodeBlockTable, Kernel, LibraryPath,
-    NamedProcedure, Procedure, ProcedureCache, ProcedureId, ProcedureName, RpoDigest, ToString,
-    Vec,
+    NamedProcedure, OptimizationLevel, OptimizationReport, Procedure, ProcedureCache, ProcedureId,
+    ProcedureName, RpoDigest, String, ToString, Vec,
 };
-use crate::ast::{ModuleAst, ProgramAst};
+use crate::ast::{ModuleAst, ProgramAst, SourceLocation};
+use vm_core::AsmOpLocation;
 
 // ASSEMBLY CONTEXT
 // ================================================================================================
@@ -19,6 +21,10 @@ pub struct AssemblyContext {
     is_kernel: bool,
     kernel: Option<Kernel>,
     allow_phantom_calls: bool,
+    source_path: Option<String>,
+    branch_locations: Vec<(RpoDigest, AsmOpLocation)>,
+    optimization_report: OptimizationReport,
+    call_graph: CallGraph,
 }
 
 impl AssemblyContext {
@@ -34,6 +40,10 @@ impl AssemblyContext {
             is_kernel: is_kernel_module,
             kernel: None,
             allow_phantom_calls: false,
+            source_path: None,
+            branch_locations: Vec::new(),
+            optimization_report: OptimizationReport::default(),
+            call_graph: CallGraph::default(),
         }
     }
 
@@ -49,6 +59,10 @@ impl AssemblyContext {
             is_kernel: false,
             kernel: None,
             allow_phantom_calls: false,
+            source_path: None,
+            branch_locations: Vec::new(),
+            optimization_report: OptimizationReport::default(),
+            call_graph: CallGraph::default(),
         }
     }
 
@@ -65,6 +79,18 @@ impl AssemblyContext {
         self
     }
 
+    /// Sets the path of the source file of the program compiled in this context, and enables
+    /// tracking of source locations of the compiled instructions.
+    ///
+    /// When source locations are tracked, AsmOp decorators carrying the location of each
+    /// instruction are added to the compiled code even if the assembler is not in debug mode.
+    /// Library modules compiled in this context are located at paths relative to the root of
+    /// their library (e.g., `std::math::u64` is located at `std/math/u64.masm`).
+    pub fn with_source_path(mut self, source_path: &str) -> Self {
+        self.source_path = Some(source_path.to_string());
+        self
+    }
+
     // PUBLIC ACCESSORS
     // --------------------------------------------------------------------------------------------
 
@@ -73,11 +99,27 @@ impl AssemblyContext {
         self.is_kernel
     }
 
+    /// Returns true if source locations of the compiled instructions are tracked in this context.
+    pub fn tracks_source_locations(&self) -> bool {
+        self.source_path.is_some()
+    }
+
     /// Returns the number of memory locals allocated for the procedure currently being compiled.
     pub fn num_proc_locals(&self) -> u16 {
         self.current_proc_context().expect("no procedures").num_locals
     }
 
+    /// Returns the number of cycles saved by optimizations in each of the procedures compiled in
+    /// this context.
+    pub fn optimization_report(&self) -> &OptimizationReport {
+        &self.optimization_report
+    }
+
+    /// Returns the invocations between the procedures compiled in this context.
+    pub(crate) fn call_graph(&self) -> &CallGraph {
+        &self.call_graph
+    }
+
     /// Returns the name of the procedure by its ID from the procedure map.
     pub fn get_imported_procedure_name(&self, id: &ProcedureId) -> Option<ProcedureName> {
         if let Some(module) = self.module_stack.first() {
@@ -128,9 +170,15 @@ impl AssemblyContext {
     /// Completes compilation of the current module.
     ///
     /// This pops the module off the module stack and return all local procedures of the module
-    /// (both exported and internal) together with the combined callset of module's procedures.
+    /// (both exported and internal) together with the combined callset of module's exported
+    /// procedures. Internal procedures which are not reachable from the exported procedures do
+    /// not contribute to the callset.
     pub fn complete_module(&mut self) -> (Vec<NamedProcedure>, CallSet) {
-        let module_ctx = self.module_stack.pop().expect("no modules");
+        let mut module_ctx = self.module_stack.pop().expect("no modules");
+        for proc in module_ctx.compiled_procs.iter().filter(|proc| proc.is_export()) {
+            module_ctx.callset.append(proc.callset());
+        }
+
         if self.is_kernel && self.module_stack.is_empty() {
             // if we are compiling a kernel and this is the last module on the module stack, then
             // it must be the Kernel module; thus, we build a Kernel struct from the procedures
@@ -173,8 +221,57 @@ impl AssemblyContext {
 
     /// Completes compilation of the current procedure and adds the compiled procedure to the list
     /// of the current module's compiled procedures.
+    ///
+    /// This also records the procedures invoked by the compiled procedure in the call graph of
+    /// this context.
     pub fn complete_proc(&mut self, code: CodeBlock) {
-        self.module_stack.last_mut().expect("no modules").complete_proc(code);
+        let module = self.module_stack.last_mut().expect("no modules");
+        let invocations = module.complete_proc(code);
+        let proc = module.compiled_procs.last().expect("no compiled procedures");
+        let node = ProcedureNode {
+            name: format!("{}::{}", module.path, proc.name()),
+            mast_root: proc.mast_root(),
+            size: mast_size(proc.code()),
+            invocations,
+        };
+        self.call_graph.insert(ProcedureId::from_name(proc.name(), &module.path), node);
+    }
+
+    /// Records the procedures invoked by the body of the program compiled in this context in the
+    /// call graph of this context.
+    ///
+    /// # Panics
+    /// Panics if the current procedure is not the main procedure of a program.
+    pub(crate) fn complete_program_body(&mut self, code: &CodeBlock) {
+        let proc = self.current_proc_context().expect("no procedures");
+        assert!(proc.is_main(), "not main procedure");
+        let node = ProcedureNode {
+            name: self.current_proc_path(),
+            mast_root: code.hash(),
+            size: mast_size(code),
+            invocations: proc.invocations.clone(),
+        };
+        self.call_graph.insert(program_body_id(), node);
+    }
+
+    /// Marks the beginning of the body of a `repeat` block with the specified number of
+    /// repetitions in the current procedure, and returns the repetition factor of the enclosing
+    /// code which must be passed to `complete_repeat()` once the body is compiled.
+    ///
+    /// The body of a `repeat` block is compiled once and then unrolled; thus, until the block is
+    /// completed, all inlined invocations and optimization savings recorded for the procedure are
+    /// counted once per repetition.
+    pub(crate) fn begin_repeat(&mut self, times: u32) -> usize {
+        let proc = self.current_proc_context_mut().expect("no procedures");
+        let repeat_factor = proc.repeat_factor;
+        proc.repeat_factor *= times as usize;
+        repeat_factor
+    }
+
+    /// Marks the end of the body of a `repeat` block in the current procedure, restoring the
+    /// repetition factor returned by the matching `begin_repeat()`.
+    pub(crate) fn complete_repeat(&mut self, repeat_factor: usize) {
+        self.current_proc_context_mut().expect("no procedures").repeat_factor = repeat_factor;
     }
 
     // CALL PROCESSORS
@@ -215,6 +312,8 @@ impl AssemblyContext {
     /// - inlined, when the procedure body is inlined into the MAST.
     /// - not inlined: when a new CALL or SYSCALL block is created for the procedure call.
     ///
+    /// The ID of the procedure is None if the procedure is called by its MAST root.
+    ///
     /// # Errors
     /// Returns an error if:
     /// - A procedure at the specified index could not be found.
@@ -222,6 +321,7 @@ impl AssemblyContext {
     pub fn register_external_call(
         &mut self,
         proc: &Procedure,
+        proc_id: Option<ProcedureId>,
         inlined: bool,
     ) -> Result<(), AssemblyError> {
         // non-inlined calls (i.e., `call` instructions) cannot be executed in a kernel
@@ -233,7 +333,7 @@ impl AssemblyContext {
         self.module_stack
             .last_mut()
             .expect("no modules")
-            .register_external_call(proc, inlined);
+            .register_external_call(proc, proc_id, inlined);
 
         Ok(())
     }
@@ -316,12 +416,82 @@ impl AssemblyContext {
         self.module_stack.last().and_then(|m| m.proc_stack.last())
     }
 
+    /// Returns a mutable reference to the context of the procedure currently being compiled, or
+    /// None if module or procedure stacks are empty.
+    fn current_proc_context_mut(&mut self) -> Option<&mut ProcedureContext> {
+        self.module_stack.last_mut().and_then(|m| m.proc_stack.last_mut())
+    }
+
     /// Returns the name of the current procedure, or the reserved name for the main block.
     pub(crate) fn current_context_name(&self) -> &str {
         self.current_proc_context()
             .map(|p| p.name().as_ref())
             .expect("library compilation mode is currently not supported!")
     }
+
+    /// Returns the fully-qualified name of the current procedure (e.g., `std::math::u64::add` or
+    /// `#exec::#main`).
+    fn current_proc_path(&self) -> String {
+        let module = self.module_stack.last().expect("no modules");
+        format!("{}::{}", module.path, self.current_context_name())
+    }
+
+    /// Returns the location of an instruction of the current procedure starting at the specified
+    /// location in the source code of the current module, or None if source locations are not
+    /// tracked in this context.
+    pub(crate) fn asmop_location(&self, location: &SourceLocation) -> Option<AsmOpLocation> {
+        let source_path = self.source_path.as_ref()?;
+        let module = self.module_stack.last()?;
+        let path = if module.is_executable() {
+            source_path.clone()
+        } else {
+            module.path.to_source_path()
+        };
+        let procedure = self.current_proc_path();
+        Some(AsmOpLocation::new(path, location.line(), location.column(), procedure))
+    }
+
+    /// Records the location of the conditional or loop statement of the current procedure which
+    /// was compiled into the specified SPLIT or LOOP block. This is a no-op if source locations
+    /// are not tracked in this context.
+    pub(crate) fn record_branch(&mut self, block: &CodeBlock, location: &SourceLocation) {
+        if let Some(location) = self.asmop_location(location) {
+            self.branch_locations.push((block.hash(), location));
+        }
+    }
+
+    /// Returns the locations of the conditional and loop statements compiled in this context,
+    /// together with the hashes of the SPLIT and LOOP blocks they were compiled into.
+    pub(crate) fn branch_locations(&self) -> &[(RpoDigest, AsmOpLocation)] {
+        &self.branch_locations
+    }
+
+    // OPTIMIZATIONS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the optimization level with which the procedures of the current module are
+    /// compiled.
+    pub(crate) fn optimization_level(&self) -> OptimizationLevel {
+        self.module_stack
+            .last()
+            .map(|module| module.optimization_level)
+            .unwrap_or_default()
+    }
+
+    /// Sets the optimization level with which the procedures of the current module are compiled.
+    pub(crate) fn set_optimization_level(&mut self, optimization_level: OptimizationLevel) {
+        self.module_stack.last_mut().expect("no modules").optimization_level = optimization_level;
+    }
+
+    /// Adds the specified number of cycles to the savings of the current procedure. This is a
+    /// no-op if the current module is compiled without optimizations.
+    pub(crate) fn add_cycles_saved(&mut self, cycles: usize) {
+        if self.optimization_level() != OptimizationLevel::None {
+            let procedure = self.current_proc_path();
+            let repeat_factor = self.current_proc_context().expect("no procedures").repeat_factor;
+            self.optimization_report.add_cycles_saved(procedure, cycles * repeat_factor);
+        }
+    }
 }
 
 // MODULE CONTEXT
@@ -338,10 +508,14 @@ struct ModuleContext {
     compiled_procs: Vec<NamedProcedure>,
     /// Fully qualified path of this module.
     path: LibraryPath,
-    /// A combined callset of all procedure callsets in this module.
+    /// A combined callset of the procedures through which this module is entered (i.e., of the
+    /// exported procedures, or of the main procedure of an executable module). This is populated
+    /// only when the module is completed.
     callset: CallSet,
     /// A map containing id and names of all imported procedures in the module.
     proc_map: BTreeMap<ProcedureId, ProcedureName>,
+    /// Optimization level with which the procedures of this module are compiled.
+    optimization_level: OptimizationLevel,
 }
 
 impl ModuleContext {
@@ -361,6 +535,7 @@ impl ModuleContext {
             path: LibraryPath::exec_path(),
             callset: CallSet::default(),
             proc_map,
+            optimization_level: OptimizationLevel::None,
         }
     }
 
@@ -377,6 +552,7 @@ impl ModuleContext {
             path: module_path.clone(),
             callset: CallSet::default(),
             proc_map,
+            optimization_level: OptimizationLevel::None,
         }
     }
 
@@ -427,14 +603,14 @@ impl ModuleContext {
     /// Completes compilation of a procedure currently on the top of procedure stack.
     ///
     /// This pops a procedure context off the top of the procedure stack, converts it into a
-    /// compiled procedure, and adds it to the list of compiled procedures.
-    ///
-    /// This also updates module callset to include the callset of the newly compiled procedure.
-    pub fn complete_proc(&mut self, code: CodeBlock) {
+    /// compiled procedure, and adds it to the list of compiled procedures. The procedures invoked
+    /// by the compiled procedure are returned.
+    pub fn complete_proc(&mut self, code: CodeBlock) -> Invocations {
         let proc_context = self.proc_stack.pop().expect("no procedures");
+        let invocations = proc_context.invocations.clone();
         let proc = proc_context.into_procedure(code);
-        self.callset.append(proc.callset());
         self.compiled_procs.push(proc);
+        invocations
     }
 
     // CALL PROCESSORS
@@ -471,6 +647,8 @@ impl ModuleContext {
         if !inlined {
             context.callset.insert(called_proc.mast_root());
         }
+        let proc_id = ProcedureId::from_name(called_proc.name(), &self.path);
+        context.record_invocation(Some(proc_id), called_proc.mast_root(), inlined);
         Ok(called_proc.inner())
     }
 
@@ -480,7 +658,12 @@ impl ModuleContext {
     /// This also appends the callset of the called procedure to the callset of the current
     /// procedure at the top of procedure stack. If inlined == false, the called procedure itself
     /// is added to the callset of the current procedure as well.
-    pub fn register_external_call(&mut self, called_proc: &Procedure, inlined: bool) {
+    pub fn register_external_call(
+        &mut self,
+        called_proc: &Procedure,
+        proc_id: Option<ProcedureId>,
+        inlined: bool,
+    ) {
         // get the context of the procedure currently being compiled
         let context = self.proc_stack.last_mut().expect("no proc context");
 
@@ -492,6 +675,7 @@ impl ModuleContext {
         if !inlined {
             context.callset.insert(called_proc.mast_root());
         }
+        context.record_invocation(proc_id, called_proc.mast_root(), inlined);
     }
 
     // EXECUTABLE FINALIZER
@@ -526,6 +710,11 @@ struct ProcedureContext {
     is_export: bool,
     num_locals: u16,
     callset: CallSet,
+    /// Procedures directly invoked by this procedure.
+    invocations: Invocations,
+    /// Number of times the code currently being compiled is repeated in the procedure body
+    /// (i.e., the product of repetition counts of the enclosing `repeat` blocks).
+    repeat_factor: usize,
 }
 
 impl ProcedureContext {
@@ -535,6 +724,32 @@ impl ProcedureContext {
             is_export,
             num_locals,
             callset: CallSet::default(),
+            invocations: Invocations::default(),
+            repeat_factor: 1,
+        }
+    }
+
+    /// Records a direct invocation of the procedure with the specified ID and MAST root; the ID
+    /// is None if the procedure is called by its MAST root.
+    pub fn record_invocation(
+        &mut self,
+        proc_id: Option<ProcedureId>,
+        mast_root: RpoDigest,
+        inlined: bool,
+    ) {
+        match (proc_id, inlined) {
+            (Some(proc_id), true) => {
+                // code repeated zero times is not included in the MAST
+                if self.repeat_factor > 0 {
+                    *self.invocations.execs.entry(proc_id).or_default() += self.repeat_factor;
+                }
+            }
+            (Some(proc_id), false) => {
+                self.invocations.calls.insert(proc_id);
+            }
+            (None, _) => {
+                self.invocations.root_calls.insert(mast_root);
+            }
         }
     }
 
@@ -555,6 +770,7 @@ impl ProcedureContext {
             is_export,
             num_locals,
             callset,
+            ..
         } = self;
 
         NamedProcedure::new(name, is_export, num_locals as u32, code_root, callset)
@@ -1,6 +1,6 @@
 use super::{
     Assembler, AssemblyContext, AssemblyError, CodeBlock, Felt, Instruction, Operation,
-    ProcedureId, RpoDigest, SpanBuilder, ONE, ZERO,
+    ProcedureId, RpoDigest, SourceLocation, SpanBuilder, ONE, ZERO,
 };
 use crate::utils::bound_into_included_u64;
 use core::ops::RangeBounds;
@@ -24,16 +24,19 @@ impl Assembler {
     pub(super) fn compile_instruction(
         &self,
         instruction: &Instruction,
+        location: Option<&SourceLocation>,
         span: &mut SpanBuilder,
         ctx: &mut AssemblyContext,
     ) -> Result<Option<CodeBlock>, AssemblyError> {
         use Operation::*;
 
-        // if the assembler is in debug mode, start tracking the instruction about to be executed;
-        // this will allow us to map the instruction to the sequence of operations which were
-        // executed as a part of this instruction.
-        if self.in_debug_mode() {
-            span.track_instruction(instruction, ctx);
+        // if the assembler is in debug mode or the context tracks source locations, start
+        // tracking the instruction about to be executed; this will allow us to map the
+        // instruction to the sequence of operations which were executed as a part of this
+        // instruction.
+        let should_track = self.in_debug_mode() || ctx.tracks_source_locations();
+        if should_track {
+            span.track_instruction(instruction, location, ctx);
         }
 
         let result = match instruction {
@@ -328,11 +331,17 @@ impl Assembler {
             Instruction::DynExec => self.dynexec(),
             Instruction::DynCall => self.dyncall(),
 
+            // ----- events -----------------------------------------------------------------------
+            Instruction::Emit(event_id) => {
+                span.push_decorator(Decorator::Event(*event_id));
+                Ok(None)
+            }
+
             // ----- debug decorators -------------------------------------------------------------
             Instruction::Breakpoint => {
                 if self.in_debug_mode() {
                     span.add_op(Noop)?;
-                    span.track_instruction(instruction, ctx);
+                    span.track_instruction(instruction, location, ctx);
                 }
                 Ok(None)
             }
@@ -346,7 +355,7 @@ impl Assembler {
         };
 
         // compute and update the cycle count of the instruction which just finished executing
-        if self.in_debug_mode() {
+        if should_track {
             span.set_instruction_cycle_count();
         }
 
@@ -1,4 +1,7 @@
-use super::{Assembler, AssemblyContext, AssemblyError, CodeBlock, ProcedureId, RpoDigest};
+use super::{
+    Assembler, AssemblyContext, AssemblyError, CodeBlock, Decorator, ProcedureId, RpoDigest,
+};
+use crate::{String, ToString};
 
 // PROCEDURE INVOCATIONS
 // ================================================================================================
@@ -12,13 +15,15 @@ impl Assembler {
         // register an "inlined" call to the procedure at the specified index in the module
         // currently being complied; this updates the callset of the procedure currently being
         // compiled
+        let caller = context.current_context_name().to_string();
         let proc = context.register_local_call(proc_idx, true)?;
 
         // TODO: if the procedure consists of a single SPAN block, we could just append all
         // operations from that SPAN block to the span builder instead of returning a code block
 
         // return the code block of the procedure
-        Ok(Some(proc.code().clone()))
+        let code = proc.code().clone();
+        Ok(Some(self.inline_code(code, caller, context)))
     }
 
     pub(super) fn exec_imported(
@@ -35,13 +40,35 @@ impl Assembler {
 
         // register an "inlined" call to the procedure; this updates the callset of the
         // procedure currently being compiled
-        context.register_external_call(proc, true)?;
+        let caller = context.current_context_name().to_string();
+        context.register_external_call(proc, Some(proc_cache.resolve_id(*proc_id)), true)?;
 
         // TODO: if the procedure consists of a single SPAN block, we could just append all
         // operations from that SPAN block to the span builder instead of returning a code block
 
         // return the code block of the procedure
-        Ok(Some(proc.code().clone()))
+        Ok(Some(self.inline_code(proc.code().clone(), caller, context)))
+    }
+
+    /// Returns the code of a procedure inlined into the procedure with the specified context
+    /// name.
+    ///
+    /// If instructions are tracked, the caller is added to the exec paths of all AsmOp decorators
+    /// of the code, so that the stack of inlined procedures can be reconstructed during execution.
+    fn inline_code(
+        &self,
+        mut code: CodeBlock,
+        caller: String,
+        context: &AssemblyContext,
+    ) -> CodeBlock {
+        if self.in_debug_mode() || context.tracks_source_locations() {
+            code.update_decorators(&mut |decorator| {
+                if let Decorator::AsmOp(assembly_op) = decorator {
+                    assembly_op.inline_into(caller.clone());
+                }
+            });
+        }
+        code
     }
 
     pub(super) fn call_local(
@@ -70,7 +97,7 @@ impl Assembler {
         // "non-inlined" call to the procedure (to update the callset of the procedure currently
         // being compiled); otherwise, register a "phantom" call.
         match proc_cache.get_by_hash(mast_root) {
-            Some(proc) => context.register_external_call(proc, false)?,
+            Some(proc) => context.register_external_call(proc, None, false)?,
             None => context.register_phantom_call(*mast_root)?,
         }
 
@@ -92,7 +119,7 @@ impl Assembler {
 
         // register a "non-inlined" call to the procedure; this updates the callset of the
         // procedure currently being compiled
-        context.register_external_call(proc, false)?;
+        context.register_external_call(proc, Some(proc_cache.resolve_id(*proc_id)), false)?;
 
         // create a new CALL block for the procedure call and return
         Ok(Some(CodeBlock::new_call(proc.mast_root())))
@@ -118,7 +145,7 @@ impl Assembler {
 
         // register a "non-inlined" call to the procedure; this updates the callset of the
         // procedure currently being compiled
-        context.register_external_call(proc, false)?;
+        context.register_external_call(proc, Some(proc_cache.resolve_id(*proc_id)), false)?;
 
         // create a new SYSCALL block for the procedure call and return
         Ok(Some(CodeBlock::new_syscall(proc.mast_root())))
@@ -1,13 +1,17 @@
 use super::{
-    ast::{Instruction, ModuleAst, Node, ProcedureAst, ProgramAst},
+    ast::{
+        CodeBody, ConstantResolver, ExportedConstMap, Instruction, ModuleAst, Node, ProcedureAst,
+        ProgramAst, SourceLocation,
+    },
     btree_map,
     crypto::hash::RpoDigest,
-    AssemblyError, BTreeMap, CallSet, CodeBlock, CodeBlockTable, Felt, Kernel, Library,
-    LibraryError, LibraryPath, Module, NamedProcedure, Operation, Procedure, ProcedureId,
-    ProcedureName, Program, ToString, Vec, ONE, ZERO,
+    AssemblyError, BTreeMap, BTreeSet, ByteReader, ByteWriter, CallSet, CodeBlock, CodeBlockTable,
+    Deserializable, DeserializationError, Felt, Kernel, Library, LibraryError, LibraryPath, Module,
+    NamedProcedure, Operation, Procedure, ProcedureId, ProcedureName, Program, Serializable,
+    String, ToString, Vec, ONE, ZERO,
 };
 use core::{borrow::Borrow, cell::RefCell};
-use vm_core::{utils::group_vector_elements, Decorator, DecoratorList};
+use vm_core::{utils::group_vector_elements, Decorator, DecoratorList, SourceMap};
 
 mod instruction;
 
@@ -17,6 +21,16 @@ use module_provider::ModuleProvider;
 mod span_builder;
 use span_builder::SpanBuilder;
 
+mod optimizer;
+pub use optimizer::{OptimizationLevel, OptimizationReport};
+
+mod call_graph;
+use call_graph::{program_body_id, CallGraph};
+pub use call_graph::{CallGraphReport, InlinedProcedure};
+
+mod stack_checker;
+use stack_checker::StackChecker;
+
 mod context;
 pub use context::AssemblyContext;
 
@@ -34,12 +48,17 @@ mod tests;
 /// - If `with_kernel()` or `with_kernel_module()` methods are not used, the assembler will be
 ///   instantiated with a default empty kernel. Programs compiled using such assembler
 ///   cannot make calls to kernel procedures via `syscall` instruction.
+/// - If `with_optimization_level()` method is not used, the assembler will emit operations exactly
+///   as specified by the source code.
 #[derive(Default)]
 pub struct Assembler {
     kernel: Kernel,
     module_provider: ModuleProvider,
     proc_cache: RefCell<ProcedureCache>,
     in_debug_mode: bool,
+    optimization_level: OptimizationLevel,
+    optimization_report: RefCell<OptimizationReport>,
+    call_graph: RefCell<CallGraph>,
 }
 
 impl Assembler {
@@ -52,6 +71,19 @@ impl Assembler {
         self
     }
 
+    /// Sets the optimization level with which the assembler compiles programs and kernels.
+    ///
+    /// Optimizations change MAST roots of the compiled procedures. Modules provided by libraries
+    /// are always compiled with the optimization level recorded in their library (see
+    /// [Library::optimization_level()]), so that the roots of library procedures do not depend on
+    /// the assembler they are compiled with.
+    ///
+    /// This should be called before `with_kernel()` for the kernel to be optimized as well.
+    pub fn with_optimization_level(mut self, optimization_level: OptimizationLevel) -> Self {
+        self.optimization_level = optimization_level;
+        self
+    }
+
     /// Adds the library to provide modules for the compilation.
     pub fn with_library<L>(mut self, library: &L) -> Result<Self, AssemblyError>
     where
@@ -78,7 +110,7 @@ impl Assembler {
     /// # Panics
     /// Panics if the assembler has already been used to compile programs.
     pub fn with_kernel(self, kernel_source: &str) -> Result<Self, AssemblyError> {
-        let kernel_ast = ModuleAst::parse(kernel_source)?;
+        let kernel_ast = ModuleAst::parse_with_constants(kernel_source, &self)?;
         self.with_kernel_module(kernel_ast)
     }
 
@@ -94,6 +126,8 @@ impl Assembler {
 
         // convert the context into Kernel; this builds the kernel from hashes of procedures
         // exported form the kernel module
+        self.optimization_report.borrow_mut().merge(context.optimization_report());
+        self.call_graph.borrow_mut().merge(context.call_graph());
         self.kernel = context.into_kernel();
 
         Ok(self)
@@ -107,6 +141,17 @@ impl Assembler {
         self.in_debug_mode
     }
 
+    /// Returns the optimization level with which this assembler compiles programs and kernels.
+    pub fn optimization_level(&self) -> OptimizationLevel {
+        self.optimization_level
+    }
+
+    /// Returns the number of cycles saved by optimizations in each of the procedures compiled by
+    /// this assembler so far.
+    pub fn optimization_report(&self) -> OptimizationReport {
+        self.optimization_report.borrow().clone()
+    }
+
     /// Returns a reference to the kernel for this assembler.
     ///
     /// If the assembler was instantiated without a kernel, the internal kernel will be empty.
@@ -128,7 +173,7 @@ impl Assembler {
     {
         // parse the program into an AST
         let source = source.as_ref();
-        let program = ProgramAst::parse(source)?;
+        let program = ProgramAst::parse_with_constants(source, self)?;
 
         // compile the program and return
         self.compile_ast(&program)
@@ -143,6 +188,8 @@ impl Assembler {
         // compile the program
         let mut context = AssemblyContext::for_program(Some(program));
         let program_root = self.compile_in_context(program, &mut context)?;
+        self.optimization_report.borrow_mut().merge(context.optimization_report());
+        self.call_graph.borrow_mut().merge(context.call_graph());
 
         // convert the context into a call block table for the program
         let cb_table = context.into_cb_table(&self.proc_cache.borrow())?;
@@ -151,6 +198,96 @@ impl Assembler {
         Ok(Program::with_kernel(program_root, self.kernel.clone(), cb_table))
     }
 
+    /// Compiles the provided abstract syntax tree of the program located at the specified source
+    /// path into a [Program], and returns the program together with its [SourceMap].
+    ///
+    /// The source map maps operations of the program to the locations of the instructions they
+    /// were compiled from; it requires the provided AST to contain source locations (i.e., to be
+    /// parsed from source rather than deserialized). The returned program contains AsmOp
+    /// decorators even if the assembler is not in debug mode.
+    ///
+    /// Procedures compiled by this assembler before this call are cached without source locations;
+    /// thus, for complete source maps, a fresh assembler should be used.
+    ///
+    /// # Errors
+    /// Returns an error if the compilation of the specified program fails.
+    pub fn compile_with_source_map(
+        &self,
+        program: &ProgramAst,
+        source_path: &str,
+    ) -> Result<(Program, SourceMap), AssemblyError> {
+        // compile the program tracking source locations of all instructions
+        let mut context = AssemblyContext::for_program(Some(program)).with_source_path(source_path);
+        let program_root = self.compile_in_context(program, &mut context)?;
+        self.optimization_report.borrow_mut().merge(context.optimization_report());
+        self.call_graph.borrow_mut().merge(context.call_graph());
+        let branch_locations = context.branch_locations().to_vec();
+        let cb_table = context.into_cb_table(&self.proc_cache.borrow())?;
+        let program = Program::with_kernel(program_root, self.kernel.clone(), cb_table);
+
+        // build the source map from the locations carried by AsmOp decorators, and add the
+        // locations of control blocks recorded during compilation
+        let mut source_map = SourceMap::from_program(&program);
+        for (block_hash, location) in branch_locations {
+            source_map.add_branch(block_hash, location);
+        }
+        Ok((program, source_map))
+    }
+
+    /// Compiles the provided abstract syntax tree into a [Program], and returns the program
+    /// together with a [CallGraphReport] describing the procedures invoked by the program.
+    ///
+    /// The report lists the procedures exported from the imported modules which the program never
+    /// invokes, and the procedures inlined into the program MAST via `exec` together with the
+    /// number of their inlined copies.
+    ///
+    /// # Errors
+    /// Returns an error if the compilation of the specified program fails.
+    pub fn compile_with_call_report(
+        &self,
+        program: &ProgramAst,
+    ) -> Result<(Program, CallGraphReport), AssemblyError> {
+        let compiled_program = self.compile_ast(program)?;
+        let call_graph = self.call_graph.borrow();
+        let reachable = call_graph.reachable(program_body_id());
+
+        // find the procedures exported from the imported modules which are never invoked; modules
+        // which are imported but not used at all are not considered
+        let proc_cache = self.proc_cache.borrow();
+        let mut unused_exports = Vec::new();
+        let imported_modules =
+            program.import_info().into_iter().flat_map(|imports| imports.imported_modules());
+        for (_, path) in imported_modules {
+            let Some(module) = self.module_provider.get_module_by_path(path) else {
+                continue;
+            };
+            let exports = module
+                .ast
+                .procs()
+                .iter()
+                .filter(|proc| proc.is_export)
+                .map(|proc| &proc.name)
+                .chain(module.ast.reexported_procs().iter().map(|proc| proc.name()))
+                .filter_map(|name| {
+                    let proc_id = ProcedureId::from_name(name, path);
+                    proc_cache.contains_id(&proc_id).then(|| (name, proc_cache.resolve_id(proc_id)))
+                })
+                .collect::<Vec<_>>();
+            if exports.iter().any(|(_, proc_id)| reachable.contains(proc_id)) {
+                unused_exports.extend(
+                    exports
+                        .into_iter()
+                        .filter(|(_, proc_id)| !reachable.contains(proc_id))
+                        .map(|(name, _)| format!("{path}::{name}")),
+                );
+            }
+        }
+
+        let inlined_procedures = call_graph.inlined_procedures(program_body_id());
+        let report = CallGraphReport::new(unused_exports, inlined_procedures);
+        Ok((compiled_program, report))
+    }
+
     /// Compiles the provided [ProgramAst] into a program and returns the program root
     /// ([CodeBlock]). Mutates the provided context by adding all of the call targets of
     /// the program to the [CallSet].
@@ -169,6 +306,13 @@ impl Assembler {
         if context.current_context_name() != ProcedureName::main().as_str() {
             return Err(AssemblyError::InvalidProgramAssemblyContext);
         }
+        context.set_optimization_level(self.optimization_level);
+
+        // make sure the local procedures leave the stack at the depth declared by their signatures
+        let mut stack_checker = StackChecker::new(&self.module_provider);
+        for proc_ast in program.procedures() {
+            stack_checker.check_procedure(proc_ast, program.procedures())?;
+        }
 
         // compile all local procedures; this will add the procedures to the specified context
         for proc_ast in program.procedures() {
@@ -179,7 +323,8 @@ impl Assembler {
         }
 
         // compile the program body
-        let program_root = self.compile_body(program.body().nodes().iter(), context, None)?;
+        let program_root = self.compile_body(program.body(), context, None)?;
+        context.complete_program_body(&program_root);
 
         Ok(program_root)
     }
@@ -204,6 +349,12 @@ impl Assembler {
         let mut proc_roots = Vec::new();
         context.begin_module(path.unwrap_or(&LibraryPath::anon_path()), module)?;
 
+        // modules provided by libraries are compiled with the optimization level of their library
+        let optimization_level = path
+            .and_then(|path| self.module_provider.get_optimization_level(path))
+            .unwrap_or(self.optimization_level);
+        context.set_optimization_level(optimization_level);
+
         // process all re-exported procedures
         for reexporteed_proc in module.reexported_procs().iter() {
             // make sure the re-exported procedure is loaded into the procedure cache
@@ -234,6 +385,12 @@ impl Assembler {
             proc_roots.push(proc_mast_root);
         }
 
+        // make sure the local procedures leave the stack at the depth declared by their signatures
+        let mut stack_checker = StackChecker::new(&self.module_provider);
+        for proc_ast in module.procs().iter() {
+            stack_checker.check_procedure(proc_ast, module.procs())?;
+        }
+
         // compile all local (internal end exported) procedures in the module; once the compilation
         // is complete, we get all compiled procedures (and their combined callset) from the
         // context
@@ -246,7 +403,9 @@ impl Assembler {
         // cache only if:
         // - a procedure is exported from the module, or
         // - a procedure is present in the combined callset - i.e., it is an internal procedure
-        //   which has been invoked via a local call instruction.
+        //   which has been invoked via a local call instruction from an exported procedure
+        //   (directly or transitively). Internal procedures which cannot be reached from the
+        //   exported procedures are dropped.
         for (proc_index, proc) in module_procs.into_iter().enumerate() {
             if proc.is_export() {
                 proc_roots.push(proc.mast_root());
@@ -287,9 +446,9 @@ impl Assembler {
                 prologue: vec![Operation::Push(num_locals), Operation::FmpUpdate],
                 epilogue: vec![Operation::Push(-num_locals), Operation::FmpUpdate],
             };
-            self.compile_body(proc.body.nodes().iter(), context, Some(wrapper))?
+            self.compile_body(&proc.body, context, Some(wrapper))?
         } else {
-            self.compile_body(proc.body.nodes().iter(), context, None)?
+            self.compile_body(&proc.body, context, None)?
         };
 
         context.complete_proc(code);
@@ -301,25 +460,24 @@ impl Assembler {
     // --------------------------------------------------------------------------------------------
 
     /// TODO: add comments
-    fn compile_body<A, N>(
+    fn compile_body(
         &self,
-        body: A,
+        body: &CodeBody,
         context: &mut AssemblyContext,
         wrapper: Option<BodyWrapper>,
-    ) -> Result<CodeBlock, AssemblyError>
-    where
-        A: Iterator<Item = N>,
-        N: Borrow<Node>,
-    {
+    ) -> Result<CodeBlock, AssemblyError> {
         let mut blocks: Vec<CodeBlock> = Vec::new();
-        let mut span = SpanBuilder::new(wrapper);
+        let mut span = SpanBuilder::new(wrapper, context.optimization_level());
 
-        for node in body {
-            match node.borrow() {
+        for (index, node) in body.nodes().iter().enumerate() {
+            let location = body.source_locations().get(index);
+            match node {
                 Node::Instruction(inner) => {
-                    if let Some(block) = self.compile_instruction(inner, &mut span, context)? {
+                    if let Some(block) =
+                        self.compile_instruction(inner, location, &mut span, context)?
+                    {
                         span.extract_span_into(&mut blocks);
-                        blocks.push(block);
+                        span.push_block(block, &mut blocks);
                     }
                 }
 
@@ -329,48 +487,61 @@ impl Assembler {
                 } => {
                     span.extract_span_into(&mut blocks);
 
-                    let true_case = self.compile_body(true_case.nodes().iter(), context, None)?;
+                    let true_case = self.compile_body(true_case, context, None)?;
 
                     // else is an exception because it is optional; hence, will have to be replaced
                     // by noop span
                     let false_case = if !false_case.nodes().is_empty() {
-                        self.compile_body(false_case.nodes().iter(), context, None)?
+                        self.compile_body(false_case, context, None)?
                     } else {
                         CodeBlock::new_span(vec![Operation::Noop])
                     };
 
                     let block = CodeBlock::new_split(true_case, false_case);
+                    if let Some(location) = location {
+                        context.record_branch(&block, location);
+                    }
 
-                    blocks.push(block);
+                    span.push_block(block, &mut blocks);
                 }
 
                 Node::Repeat { times, body } => {
                     span.extract_span_into(&mut blocks);
 
-                    let block = self.compile_body(body.nodes().iter(), context, None)?;
+                    // the body is unrolled, thus everything recorded for it applies to each
+                    // repetition
+                    let repeat_factor = context.begin_repeat(*times);
+                    let block = self.compile_body(body, context, None)?;
+                    context.complete_repeat(repeat_factor);
 
                     for _ in 0..*times {
-                        blocks.push(block.clone());
+                        span.push_block(block.clone(), &mut blocks);
                     }
                 }
 
                 Node::While { body } => {
                     span.extract_span_into(&mut blocks);
 
-                    let block = self.compile_body(body.nodes().iter(), context, None)?;
+                    let block = self.compile_body(body, context, None)?;
                     let block = CodeBlock::new_loop(block);
+                    if let Some(location) = location {
+                        context.record_branch(&block, location);
+                    }
 
-                    blocks.push(block);
+                    span.push_block(block, &mut blocks);
                 }
             }
         }
 
-        span.extract_final_span_into(&mut blocks);
-        Ok(if blocks.is_empty() {
-            CodeBlock::new_span(vec![Operation::Noop])
+        let cycles_saved = span.extract_final_span_into(&mut blocks);
+        if blocks.is_empty() {
+            // a body left empty by optimizations still takes a cycle to execute its NOOP
+            context.add_cycles_saved(cycles_saved.saturating_sub(1));
+            Ok(CodeBlock::new_span(vec![Operation::Noop]))
         } else {
-            combine_blocks(blocks)
-        })
+            context.add_cycles_saved(cycles_saved);
+            Ok(combine_blocks(blocks))
+        }
     }
 
     // PROCEDURE CACHE
@@ -422,6 +593,16 @@ impl Assembler {
     }
 }
 
+impl ConstantResolver for Assembler {
+    /// Returns the constants exported from the module with the specified path, if the module is
+    /// provided by one of the libraries added to this assembler.
+    fn get_exported_constants(&self, module_path: &LibraryPath) -> Option<ExportedConstMap> {
+        self.module_provider
+            .get_module_by_path(module_path)
+            .map(|module| module.ast.exported_constants().clone())
+    }
+}
+
 // BODY WRAPPER
 // ================================================================================================
 
@@ -1,4 +1,6 @@
-use super::{BTreeMap, Library, LibraryError, Module, ProcedureId, Vec};
+use super::{
+    BTreeMap, Library, LibraryError, LibraryPath, Module, OptimizationLevel, ProcedureId, Vec,
+};
 
 // MODULE PROVIDER
 // ================================================================================================
@@ -10,6 +12,8 @@ pub struct ModuleProvider {
     modules: Vec<Module>,
     /// Map from procedure id to the index of a module in which the procedure is defined.
     procedures: BTreeMap<ProcedureId, usize>,
+    /// Optimization levels of the libraries the modules come from, indexed by module.
+    optimization_levels: Vec<OptimizationLevel>,
 }
 
 impl ModuleProvider {
@@ -22,6 +26,20 @@ impl ModuleProvider {
         self.procedures.get(id).map(|i| &self.modules[*i])
     }
 
+    /// Fetch a module with the provided path.
+    pub fn get_module_by_path(&self, path: &LibraryPath) -> Option<&Module> {
+        self.modules.iter().find(|module| module.path == *path)
+    }
+
+    /// Returns the optimization level of the library providing the module with the specified
+    /// path, or None if the module is not provided by any library.
+    pub fn get_optimization_level(&self, path: &LibraryPath) -> Option<OptimizationLevel> {
+        self.modules
+            .iter()
+            .position(|module| module.path == *path)
+            .map(|idx| self.optimization_levels[idx])
+    }
+
     // MODULE AND LIBRARY MUTATORS
     // --------------------------------------------------------------------------------------------
 
@@ -30,7 +48,11 @@ impl ModuleProvider {
     /// # Errors
     ///
     /// Will error if there is a duplicated module path.
-    fn add_module(&mut self, module: Module) -> Result<(), LibraryError> {
+    fn add_module(
+        &mut self,
+        module: Module,
+        optimization_level: OptimizationLevel,
+    ) -> Result<(), LibraryError> {
         if self.modules.iter().any(|m| module.path == m.path) {
             return Err(LibraryError::duplicate_module_path(&module.path));
         }
@@ -46,6 +68,7 @@ impl ModuleProvider {
             self.procedures.insert(proc_id, module_idx);
         }
         self.modules.push(module);
+        self.optimization_levels.push(optimization_level);
         Ok(())
     }
 
@@ -59,9 +82,10 @@ impl ModuleProvider {
         L: Library,
     {
         let namespace = library.root_ns();
+        let optimization_level = library.optimization_level();
         library.modules().try_for_each(|module| {
             module.check_namespace(namespace)?;
-            self.add_module(module.clone())
+            self.add_module(module.clone(), optimization_level)
         })
     }
 }
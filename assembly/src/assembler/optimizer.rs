@@ -0,0 +1,346 @@
+use super::{
+    BTreeMap, ByteReader, ByteWriter, Decorator, DecoratorList, Deserializable,
+    DeserializationError, Felt, Operation, Serializable, String, Vec, ONE, ZERO,
+};
+use core::fmt;
+use vm_core::FieldElement;
+
+// OPTIMIZATION LEVEL
+// ================================================================================================
+
+/// Specifies how the assembler optimizes the operations of compiled procedures.
+///
+/// Optimizations never change the observable behavior of a program (i.e., its outputs and the
+/// errors it may fail with), but they change the operations of the compiled code, and thus, the
+/// MAST roots of the compiled procedures. The only exception are values which depend on the cycle
+/// count of the VM (e.g., the result of the `clk` instruction), which reflect the optimized code.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum OptimizationLevel {
+    /// Operations are emitted exactly as specified by the source code.
+    #[default]
+    None,
+    /// Sequences of operations within SPAN blocks are simplified: operations which cancel each
+    /// other out (e.g., `swap swap` or `dup.0 drop`) are removed, arithmetic identities (e.g.,
+    /// `push.0 add`) are eliminated, and arithmetic over constants is folded.
+    Basic,
+}
+
+impl fmt::Display for OptimizationLevel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::None => write!(f, "none"),
+            Self::Basic => write!(f, "basic"),
+        }
+    }
+}
+
+impl Serializable for OptimizationLevel {
+    fn write_into<W: ByteWriter>(&self, target: &mut W) {
+        target.write_u8(*self as u8);
+    }
+}
+
+impl Deserializable for OptimizationLevel {
+    fn read_from<R: ByteReader>(source: &mut R) -> Result<Self, DeserializationError> {
+        match source.read_u8()? {
+            0 => Ok(Self::None),
+            1 => Ok(Self::Basic),
+            value => Err(DeserializationError::InvalidValue(format!(
+                "invalid optimization level: {value}"
+            ))),
+        }
+    }
+}
+
+// OPTIMIZATION REPORT
+// ================================================================================================
+
+/// Number of VM cycles saved by optimizations in each of the compiled procedures.
+///
+/// Procedures are identified by their fully-qualified names (e.g., `std::math::u64::wrapping_add`);
+/// the body of a program is reported as `#exec::#main`. Cycles are counted statically, i.e., each
+/// removed operation is counted once per occurrence in the compiled code (with `repeat` blocks
+/// unrolled), regardless of how many times the operation would be executed.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct OptimizationReport {
+    procedures: BTreeMap<String, usize>,
+}
+
+impl OptimizationReport {
+    /// Returns the number of cycles saved in the procedure with the specified fully-qualified
+    /// name, or None if the procedure was not compiled with optimizations.
+    pub fn cycles_saved(&self, procedure: &str) -> Option<usize> {
+        self.procedures.get(procedure).copied()
+    }
+
+    /// Returns the total number of cycles saved across all optimized procedures.
+    pub fn total_cycles_saved(&self) -> usize {
+        self.procedures.values().sum()
+    }
+
+    /// Returns an iterator over the names of the optimized procedures and the number of cycles
+    /// saved in each of them.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, usize)> {
+        self.procedures.iter().map(|(name, cycles)| (name.as_str(), *cycles))
+    }
+
+    /// Returns true if no procedures were compiled with optimizations.
+    pub fn is_empty(&self) -> bool {
+        self.procedures.is_empty()
+    }
+
+    /// Adds the specified number of cycles to the savings of the specified procedure.
+    pub(super) fn add_cycles_saved(&mut self, procedure: String, cycles: usize) {
+        *self.procedures.entry(procedure).or_default() += cycles;
+    }
+
+    /// Merges the savings from the provided report into this report, replacing the entries of
+    /// procedures present in both reports.
+    pub(super) fn merge(&mut self, other: &Self) {
+        self.procedures
+            .extend(other.procedures.iter().map(|(name, cycles)| (name.clone(), *cycles)));
+    }
+}
+
+impl fmt::Display for OptimizationReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (procedure, cycles) in self.iter() {
+            writeln!(f, "{procedure}: {cycles} cycles saved")?;
+        }
+        write!(f, "total: {} cycles saved", self.total_cycles_saved())
+    }
+}
+
+// SPAN OPTIMIZER
+// ================================================================================================
+
+/// Optimizes the provided operations of a SPAN block in place and returns the number of removed
+/// operations.
+///
+/// Operations are rewritten one at a time in a single pass: after an operation is appended to the
+/// optimized sequence, the tail of the sequence is repeatedly replaced with a shorter equivalent
+/// until no more rewrites apply. Decorators which observe the state of the VM (advice injectors,
+/// debug decorators, events, and breakpoints) act as barriers - operations on different sides of
+/// such decorators are never rewritten together.
+///
+/// Decorators are moved to the positions of the optimized operations, and cycle counts of AsmOp
+/// decorators are updated to the number of operations left for their instructions; AsmOp
+/// decorators of instructions whose operations were removed entirely are dropped.
+pub fn optimize_span(ops: &mut Vec<Operation>, decorators: &mut DecoratorList) -> usize {
+    // decorators are added in the order of their positions, and thus, barriers are sorted
+    let barriers = decorators
+        .iter()
+        .filter(|(_, decorator)| is_barrier(decorator))
+        .map(|(pos, _)| *pos)
+        .collect::<Vec<_>>();
+
+    // for each optimized operation, keep the index of the original operation it was derived from;
+    // a replacement inherits the origin of the first operation of the replaced sequence
+    let mut optimized = Vec::with_capacity(ops.len());
+    let mut origins = Vec::with_capacity(ops.len());
+    for (origin, &op) in ops.iter().enumerate() {
+        optimized.push(op);
+        origins.push(origin);
+
+        loop {
+            let segment_start = segment_start(&origins, &barriers);
+            let Some((len, replacement)) = rewrite_tail(&optimized[segment_start..]) else {
+                break;
+            };
+            let window_start = optimized.len() - len;
+            let origin = origins[window_start];
+            optimized.truncate(window_start);
+            origins.truncate(window_start);
+            origins.resize(window_start + replacement.len(), origin);
+            optimized.extend(replacement);
+        }
+    }
+
+    // move the decorators to the positions of the optimized operations; since origins are sorted,
+    // the new position of a decorator is the number of optimized operations originating before it
+    let new_pos = |pos: usize| origins.partition_point(|&origin| origin < pos);
+    let mut remapped = DecoratorList::with_capacity(decorators.len());
+    for (pos, mut decorator) in decorators.drain(..) {
+        if let Decorator::AsmOp(ref mut assembly_op) = decorator {
+            if assembly_op.num_cycles() > 0 {
+                let end = pos + assembly_op.num_cycles() as usize;
+                match new_pos(end) - new_pos(pos) {
+                    // all operations of the instruction were removed
+                    0 => continue,
+                    num_cycles => assembly_op.set_num_cycles(num_cycles as u8),
+                }
+            }
+        }
+        remapped.push((new_pos(pos), decorator));
+    }
+    *decorators = remapped;
+
+    // decorators must be attached to an operation, thus a SPAN block with decorators cannot be
+    // emptied entirely
+    if optimized.is_empty() && !decorators.is_empty() {
+        optimized.push(Operation::Noop);
+    }
+
+    let cycles_saved = ops.len() - optimized.len();
+    *ops = optimized;
+    cycles_saved
+}
+
+/// Returns true if the specified decorator observes the state of the VM at the point it is
+/// executed.
+fn is_barrier(decorator: &Decorator) -> bool {
+    match decorator {
+        Decorator::AsmOp(assembly_op) => assembly_op.should_break(),
+        Decorator::Advice(_) | Decorator::Debug(_) | Decorator::Event(_) => true,
+    }
+}
+
+/// Returns the index of the first operation in the tail of the optimized sequence which is not
+/// separated from the last operation of the sequence by a barrier.
+fn segment_start(origins: &[usize], barriers: &[usize]) -> usize {
+    let Some(&last) = origins.last() else {
+        return 0;
+    };
+    // a barrier at position p is executed right before the original operation at position p
+    match barriers.partition_point(|&pos| pos <= last) {
+        0 => 0,
+        idx => origins.partition_point(|&origin| origin < barriers[idx - 1]),
+    }
+}
+
+// REWRITE RULES
+// ================================================================================================
+
+/// Returns the number of operations at the end of the provided sequence which can be replaced
+/// with a shorter equivalent sequence, together with the replacement.
+fn rewrite_tail(ops: &[Operation]) -> Option<(usize, Vec<Operation>)> {
+    use Operation::*;
+
+    // constant expressions are replaced with the shortest sequence pushing the same value
+    if let Some((value, len)) = trailing_constant(ops) {
+        let folded = push_constant(value);
+        if folded.len() < len {
+            return Some((len, folded));
+        }
+    }
+
+    let (&last, rest) = ops.split_last()?;
+    match (rest.last(), last) {
+        (_, Noop) => Some((1, Vec::new())),
+        (Some(&prev), op) if cancel_out(prev, op) => Some((2, Vec::new())),
+        // the operands of commutative operations do not need to be swapped
+        (Some(Swap), Add | Mul | Eq) => Some((2, vec![last])),
+        (Some(Dup0 | Dup1 | Dup2 | Dup3 | Dup4 | Dup5 | Dup6 | Dup7), Drop) => {
+            Some((2, Vec::new()))
+        }
+        (Some(Dup9 | Dup11 | Dup13 | Dup15), Drop) => Some((2, Vec::new())),
+        (_, Drop) => trailing_constant(rest).map(|(_, len)| (len + 1, Vec::new())),
+        // x + 0 = x, x + 1 = incr(x), and x * 1 = x
+        (_, Add) => match trailing_constant(rest)? {
+            (value, len) if value == ZERO => Some((len + 1, Vec::new())),
+            (value, len) if value == ONE => Some((len + 1, vec![Incr])),
+            _ => None,
+        },
+        (_, Mul) => match trailing_constant(rest)? {
+            (value, len) if value == ONE => Some((len + 1, Vec::new())),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Returns true if executing the specified operations one after the other leaves the stack
+/// unchanged.
+fn cancel_out(first: Operation, second: Operation) -> bool {
+    use Operation::*;
+
+    matches!(
+        (first, second),
+        (Swap, Swap)
+            | (SwapW, SwapW)
+            | (SwapW2, SwapW2)
+            | (SwapW3, SwapW3)
+            | (SwapDW, SwapDW)
+            | (Neg, Neg)
+            | (MovUp2, MovDn2)
+            | (MovUp3, MovDn3)
+            | (MovUp4, MovDn4)
+            | (MovUp5, MovDn5)
+            | (MovUp6, MovDn6)
+            | (MovUp7, MovDn7)
+            | (MovUp8, MovDn8)
+            | (MovDn2, MovUp2)
+            | (MovDn3, MovUp3)
+            | (MovDn4, MovUp4)
+            | (MovDn5, MovUp5)
+            | (MovDn6, MovUp6)
+            | (MovDn7, MovUp7)
+            | (MovDn8, MovUp8)
+    )
+}
+
+/// Returns the value of the constant expression at the end of the provided sequence together
+/// with the number of operations in the expression, or None if the sequence does not end with a
+/// constant expression.
+///
+/// Operations which may fail (e.g., `inv` of zero or `not` of a non-binary value) are treated
+/// as constant only when they are guaranteed to succeed.
+fn trailing_constant(ops: &[Operation]) -> Option<(Felt, usize)> {
+    use Operation::*;
+
+    let (last, rest) = ops.split_last()?;
+    match last {
+        Pad => Some((ZERO, 1)),
+        Push(value) => Some((*value, 1)),
+        Incr | Neg | Inv | Eqz | Not => {
+            let (value, len) = trailing_constant(rest)?;
+            let value = match last {
+                Incr => value + ONE,
+                Neg => -value,
+                Inv if value != ZERO => value.inv(),
+                Eqz => to_felt(value == ZERO),
+                Not if is_binary(value) => ONE - value,
+                _ => return None,
+            };
+            Some((value, len + 1))
+        }
+        Add | Mul | Eq | And | Or => {
+            let (b, len_b) = trailing_constant(rest)?;
+            let (a, len_a) = trailing_constant(&rest[..rest.len() - len_b])?;
+            let value = match last {
+                Add => a + b,
+                Mul => a * b,
+                Eq => to_felt(a == b),
+                And if is_binary(a) && is_binary(b) => a * b,
+                Or if is_binary(a) && is_binary(b) => a + b - a * b,
+                _ => return None,
+            };
+            Some((value, len_a + len_b + 1))
+        }
+        _ => None,
+    }
+}
+
+/// Returns the shortest sequence of operations pushing the specified value onto the stack; this
+/// matches the encoding of `push` instructions.
+fn push_constant(value: Felt) -> Vec<Operation> {
+    if value == ZERO {
+        vec![Operation::Pad]
+    } else if value == ONE {
+        vec![Operation::Pad, Operation::Incr]
+    } else {
+        vec![Operation::Push(value)]
+    }
+}
+
+fn is_binary(value: Felt) -> bool {
+    value == ZERO || value == ONE
+}
+
+fn to_felt(value: bool) -> Felt {
+    if value {
+        ONE
+    } else {
+        ZERO
+    }
+}
@@ -53,6 +53,12 @@ impl ProcedureCache {
         self.proc_id_map.contains_key(id) || self.proc_aliases.contains_key(id)
     }
 
+    /// Returns the ID of the procedure referenced by the provided [ProcedureId], i.e., the ID of
+    /// the aliased procedure if the provided ID is an alias, or the provided ID otherwise.
+    pub fn resolve_id(&self, id: ProcedureId) -> ProcedureId {
+        self.proc_aliases.get(&id).copied().unwrap_or(id)
+    }
+
     // MUTATORS
     // --------------------------------------------------------------------------------------------
 
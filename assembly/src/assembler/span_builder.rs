@@ -1,6 +1,7 @@
 use super::{
-    AssemblyContext, AssemblyError, BodyWrapper, Borrow, CodeBlock, Decorator, DecoratorList,
-    Instruction, Operation, ToString, Vec,
+    optimizer::optimize_span, AssemblyContext, AssemblyError, BodyWrapper, Borrow, CodeBlock,
+    Decorator, DecoratorList, Instruction, Operation, OptimizationLevel, SourceLocation, ToString,
+    Vec,
 };
 use vm_core::{AdviceInjector, AssemblyOp};
 
@@ -14,31 +15,43 @@ use vm_core::{AdviceInjector, AssemblyOp};
 ///
 /// The same span builder can be used to construct many blocks. It is expected that when the last
 /// SPAN block in a procedure's body is constructed `extract_final_span_into()` will be used.
+///
+/// If the builder is instantiated with an optimization level other than
+/// [OptimizationLevel::None], operations of each SPAN block are optimized when the block is
+/// extracted from the builder.
 #[derive(Default)]
 pub struct SpanBuilder {
     ops: Vec<Operation>,
     decorators: DecoratorList,
     epilogue: Vec<Operation>,
     last_asmop_pos: usize,
+    optimization_level: OptimizationLevel,
+    cycles_saved: usize,
 }
 
 impl SpanBuilder {
     // CONSTRUCTOR
     // --------------------------------------------------------------------------------------------
-    /// Returns a new [SpanBuilder] instantiated with the specified optional wrapper.
+    /// Returns a new [SpanBuilder] instantiated with the specified optional wrapper and
+    /// optimization level.
     ///
     /// If the wrapper is provided, the prologue of the wrapper is immediately appended to the
     /// vector of span operations. The epilogue of the wrapper is appended to the list of
     /// operations upon consumption of the builder via `extract_final_span_into()` method.
-    pub(super) fn new(wrapper: Option<BodyWrapper>) -> Self {
+    pub(super) fn new(wrapper: Option<BodyWrapper>, optimization_level: OptimizationLevel) -> Self {
         match wrapper {
             Some(wrapper) => Self {
                 ops: wrapper.prologue,
                 decorators: Vec::new(),
                 epilogue: wrapper.epilogue,
                 last_asmop_pos: 0,
+                optimization_level,
+                cycles_saved: 0,
+            },
+            None => Self {
+                optimization_level,
+                ..Self::default()
             },
-            None => Self::default(),
         }
     }
 
@@ -98,12 +111,23 @@ impl SpanBuilder {
     ///
     /// This indicates that the provided instruction should be tracked and the cycle count for
     /// this instruction will be computed when the call to set_instruction_cycle_count() is made.
-    pub fn track_instruction(&mut self, instruction: &Instruction, ctx: &AssemblyContext) {
+    ///
+    /// If the context tracks source locations and the location of the instruction is provided,
+    /// the decorator also carries the location of the instruction in the source code.
+    pub fn track_instruction(
+        &mut self,
+        instruction: &Instruction,
+        location: Option<&SourceLocation>,
+        ctx: &AssemblyContext,
+    ) {
         let context_name = ctx.current_context_name().to_string();
         let num_cycles = 0;
         let op = instruction.to_string();
         let should_break = instruction.should_break();
-        let op = AssemblyOp::new(context_name, num_cycles, op, should_break);
+        let mut op = AssemblyOp::new(context_name, num_cycles, op, should_break);
+        if let Some(location) = location.and_then(|location| ctx.asmop_location(location)) {
+            op = op.with_location(location);
+        }
         self.push_decorator(Decorator::AsmOp(op));
         self.last_asmop_pos = self.decorators.len() - 1;
     }
@@ -139,28 +163,69 @@ impl SpanBuilder {
     ///
     /// This consumes all operations and decorators in the builder, but does not touch the
     /// operations in the epilogue of the builder.
+    ///
+    /// If optimizations are enabled, the operations are optimized before the block is created; if
+    /// the optimizations remove all operations, no block is created.
+    ///
+    /// Decorators which are not followed by any operations (e.g., decorators of `emit`
+    /// instructions at the end of a body) are attached to the last block in the target so that
+    /// they are executed after it. If the target is empty, the decorators are retained by the
+    /// builder until the next block is added via `push_block()`. In either case, no operations
+    /// are added, and thus the decorators do not change the hash of the compiled code.
     pub fn extract_span_into(&mut self, target: &mut Vec<CodeBlock>) {
-        if !self.ops.is_empty() {
-            let ops = self.ops.drain(..).collect();
-            let decorators = self.decorators.drain(..).collect();
+        let mut ops: Vec<Operation> = self.ops.drain(..).collect();
+        let mut decorators: DecoratorList = self.decorators.drain(..).collect();
+        if !ops.is_empty() && self.optimization_level != OptimizationLevel::None {
+            self.cycles_saved += optimize_span(&mut ops, &mut decorators);
+        }
+
+        if !ops.is_empty() {
             target.push(CodeBlock::new_span_with_decorators(ops, decorators));
-        } else if !self.decorators.is_empty() {
-            // this is a bug in the assembler. we shouldn't have decorators added without their
-            // associated operations
-            // TODO: change this to an error or allow decorators in empty span blocks
-            unreachable!("decorators in an empty SPAN block")
+        } else if !decorators.is_empty() {
+            match target.last_mut() {
+                Some(block) => block.append_decorators(
+                    decorators.into_iter().map(|(_, decorator)| decorator).collect(),
+                ),
+                None => self.decorators = decorators,
+            }
         }
     }
 
+    /// Appends the specified block to the provided target.
+    ///
+    /// The SPAN block with the operations currently in this builder is expected to have been
+    /// extracted into the target before the block is pushed. Decorators retained by the builder
+    /// because no block preceded them are attached to the block so that they are executed
+    /// before it.
+    pub fn push_block(&mut self, mut block: CodeBlock, target: &mut Vec<CodeBlock>) {
+        debug_assert!(self.ops.is_empty(), "operations were not extracted before the block");
+        if !self.decorators.is_empty() {
+            block.prepend_decorators(
+                self.decorators.drain(..).map(|(_, decorator)| decorator).collect(),
+            );
+        }
+        target.push(block);
+    }
+
     /// Creates a new SPAN block from the operations and decorators currently in this builder and
     /// appends the block to the provided target.
     ///
     /// The main differences from the `extract_span_int()` method above are:
     /// - Operations contained in the epilogue of the span builder are appended to the list of
     ///   ops which go into the new SPAN block.
-    /// - The span builder is consumed in the process.
-    pub fn extract_final_span_into(mut self, target: &mut Vec<CodeBlock>) {
+    /// - The span builder is consumed in the process, and the number of cycles saved by
+    ///   optimizing all SPAN blocks extracted from the builder is returned.
+    /// - If the target is left empty, but decorators without following operations remain in the
+    ///   builder, the decorators are attached to a NOOP in the same way as for a body which was
+    ///   left empty by optimizations.
+    pub fn extract_final_span_into(mut self, target: &mut Vec<CodeBlock>) -> usize {
         self.ops.append(&mut self.epilogue);
         self.extract_span_into(target);
+        if !self.decorators.is_empty() {
+            let decorators = self.decorators.drain(..).collect();
+            target.push(CodeBlock::new_span_with_decorators(vec![Operation::Noop], decorators));
+            self.cycles_saved = self.cycles_saved.saturating_sub(1);
+        }
+        self.cycles_saved
     }
 }
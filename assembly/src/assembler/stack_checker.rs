@@ -0,0 +1,369 @@
+use super::{
+    AssemblyError, BTreeMap, CodeBody, Instruction, Module, ModuleProvider, Node, ProcedureAst,
+    ProcedureId, SourceLocation, ToString, Vec,
+};
+
+// STACK CHECKER
+// ================================================================================================
+
+/// Symbolically tracks the stack depth over procedure bodies to verify that procedures with
+/// declared stack signatures leave the stack at the depth their callers expect.
+///
+/// The net stack effect of each instruction is known statically, except for `dynexec` and `exec`
+/// of procedures whose effect cannot be determined. The effect of a procedure invoked via `exec`
+/// is taken from its signature if one is declared, and is otherwise inferred from its body.
+/// Procedures invoked via `call`, `syscall`, or `dyncall` are executed in a new context, and thus
+/// do not change the depth of the caller's stack.
+///
+/// When the stack effect of a procedure body cannot be determined (e.g., because it executes a
+/// dynamic code block), the signature of this procedure is not verified.
+pub struct StackChecker<'a> {
+    module_provider: &'a ModuleProvider,
+    /// Inferred stack effects of imported procedures; None means that the effect is unknown.
+    imported_effects: BTreeMap<ProcedureId, Option<i32>>,
+    /// IDs of imported procedures whose stack effect is currently being inferred.
+    in_progress: Vec<ProcedureId>,
+}
+
+impl<'a> StackChecker<'a> {
+    // CONSTRUCTOR
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns a new [StackChecker] which resolves imported procedures via the provided module
+    /// provider.
+    pub fn new(module_provider: &'a ModuleProvider) -> Self {
+        Self {
+            module_provider,
+            imported_effects: BTreeMap::new(),
+            in_progress: Vec::new(),
+        }
+    }
+
+    // PUBLIC METHODS
+    // --------------------------------------------------------------------------------------------
+
+    /// Verifies that the body of the provided procedure matches its declared stack signature.
+    /// Procedures without a signature are not checked.
+    ///
+    /// `local_procs` must contain the procedures of the module (or program) in which the
+    /// procedure is defined, in the order of their indexes.
+    ///
+    /// # Errors
+    /// Returns an error if:
+    /// - The net stack effect of the procedure body differs from the one declared by its
+    ///   signature.
+    /// - The branches of an `if.true` statement have different stack effects.
+    /// - The body of a `while.true` loop does not leave the stack at the depth it had on entering
+    ///   the loop, including the loop condition.
+    pub fn check_procedure(
+        &mut self,
+        proc: &ProcedureAst,
+        local_procs: &[ProcedureAst],
+    ) -> Result<(), AssemblyError> {
+        let signature = match proc.signature {
+            Some(signature) => signature,
+            None => return Ok(()),
+        };
+
+        let context = CheckContext {
+            proc_name: proc.name.as_ref(),
+            start: proc.start,
+        };
+        if let Some(delta) = self.body_effect(&proc.body, local_procs, Some(&context))? {
+            if delta != signature.stack_delta() {
+                let location = context.end_location(&proc.body);
+                return Err(AssemblyError::stack_signature_mismatch(
+                    proc.name.as_ref(),
+                    location,
+                    &signature.to_string(),
+                    delta,
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
+    // HELPER METHODS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the net stack effect of the provided code body, or None if it cannot be determined.
+    ///
+    /// When the check context is provided, unbalanced control flow blocks are reported as errors;
+    /// otherwise, they make the stack effect of the body unknown.
+    fn body_effect(
+        &mut self,
+        body: &CodeBody,
+        local_procs: &[ProcedureAst],
+        context: Option<&CheckContext>,
+    ) -> Result<Option<i32>, AssemblyError> {
+        let mut delta: i32 = 0;
+        for (idx, node) in body.nodes().iter().enumerate() {
+            let node_delta = match node {
+                Node::Instruction(instruction) => self.instruction_effect(instruction, local_procs),
+                Node::IfElse {
+                    true_case,
+                    false_case,
+                } => {
+                    let true_delta = self.body_effect(true_case, local_procs, context)?;
+                    let false_delta = self.body_effect(false_case, local_procs, context)?;
+                    match (true_delta, false_delta) {
+                        (Some(t), Some(f)) if t == f => Some(t - 1),
+                        (Some(t), Some(f)) => match context {
+                            Some(context) => {
+                                return Err(AssemblyError::unbalanced_if_else(
+                                    context.proc_name,
+                                    context.node_location(body, idx),
+                                    t,
+                                    f,
+                                ))
+                            }
+                            None => None,
+                        },
+                        _ => None,
+                    }
+                }
+                Node::Repeat {
+                    times,
+                    body: repeat_body,
+                } => self
+                    .body_effect(repeat_body, local_procs, context)?
+                    .and_then(|d| d.checked_mul(i32::try_from(*times).ok()?)),
+                Node::While { body: loop_body } => {
+                    // the condition is consumed on entering the loop and on each iteration, and
+                    // thus the body must push exactly one new condition onto the stack
+                    match self.body_effect(loop_body, local_procs, context)? {
+                        Some(1) => Some(-1),
+                        Some(d) => match context {
+                            Some(context) => {
+                                return Err(AssemblyError::unbalanced_while_body(
+                                    context.proc_name,
+                                    context.node_location(body, idx),
+                                    d - 1,
+                                ))
+                            }
+                            None => None,
+                        },
+                        None => None,
+                    }
+                }
+            };
+
+            delta = match node_delta.and_then(|d| delta.checked_add(d)) {
+                Some(delta) => delta,
+                None => return Ok(None),
+            };
+        }
+
+        Ok(Some(delta))
+    }
+
+    /// Returns the stack effect of the procedure at the specified index in the list of local
+    /// procedures, or None if it cannot be determined.
+    fn local_proc_effect(&mut self, index: u16, local_procs: &[ProcedureAst]) -> Option<i32> {
+        let proc = local_procs.get(index as usize)?;
+        match proc.signature {
+            Some(signature) => Some(signature.stack_delta()),
+            // a procedure can invoke only the local procedures defined before it, and thus it
+            // is safe to pass the full list of local procedures here
+            None => self.body_effect(&proc.body, local_procs, None).ok().flatten(),
+        }
+    }
+
+    /// Returns the stack effect of the imported procedure with the specified ID, or None if it
+    /// cannot be determined.
+    fn imported_proc_effect(&mut self, proc_id: &ProcedureId) -> Option<i32> {
+        if let Some(effect) = self.imported_effects.get(proc_id) {
+            return *effect;
+        }
+        if self.in_progress.contains(proc_id) {
+            return None;
+        }
+
+        self.in_progress.push(*proc_id);
+        let module_provider = self.module_provider;
+        let effect = module_provider
+            .get_module(proc_id)
+            .and_then(|module| self.module_proc_effect(module, proc_id));
+        self.in_progress.pop();
+
+        self.imported_effects.insert(*proc_id, effect);
+        effect
+    }
+
+    /// Returns the stack effect of the procedure with the specified ID defined in or re-exported
+    /// from the provided module.
+    fn module_proc_effect(&mut self, module: &Module, proc_id: &ProcedureId) -> Option<i32> {
+        let is_proc = |name: &str| {
+            module.path.append(name).map(|path| ProcedureId::from(&path) == *proc_id).ok()
+        };
+
+        let procs = module.ast.procs();
+        if let Some(index) = procs.iter().position(|proc| is_proc(proc.name.as_ref()) == Some(true))
+        {
+            return self.local_proc_effect(index as u16, procs);
+        }
+
+        module
+            .ast
+            .reexported_procs()
+            .iter()
+            .find(|proc| is_proc(proc.name().as_ref()) == Some(true))
+            .and_then(|proc| self.imported_proc_effect(&proc.proc_id()))
+    }
+
+    /// Returns the net stack effect of the provided instruction, or None if it cannot be
+    /// determined.
+    fn instruction_effect(
+        &mut self,
+        instruction: &Instruction,
+        local_procs: &[ProcedureAst],
+    ) -> Option<i32> {
+        use Instruction::*;
+
+        let delta = match instruction {
+            // ----- field operations -------------------------------------------------------------
+            Assert | AssertWithError(_) | Assertz | AssertzWithError(_) => -1,
+            AssertEq | AssertEqWithError(_) => -2,
+            AssertEqw | AssertEqwWithError(_) => -8,
+            Add | Sub | Mul | Div | Exp | ExpBitLength(_) | And | Or | Xor | Eq | Neq | Lt
+            | Lte | Gt | Gte => -1,
+            AddImm(_) | SubImm(_) | MulImm(_) | DivImm(_) | Neg | Inv | Incr | Pow2 | ExpImm(_)
+            | Not | EqImm(_) | NeqImm(_) | IsOdd => 0,
+            Eqw => 1,
+
+            // ----- ext2 operations --------------------------------------------------------------
+            Ext2Add | Ext2Sub | Ext2Mul | Ext2Div => -2,
+            Ext2Neg | Ext2Inv => 0,
+
+            // ----- u32 operations ---------------------------------------------------------------
+            U32Test | U32TestW | U32Split => 1,
+            U32Assert
+            | U32AssertWithError(_)
+            | U32Assert2
+            | U32Assert2WithError(_)
+            | U32AssertW
+            | U32AssertWWithError(_)
+            | U32Cast => 0,
+            U32CheckedAdd | U32WrappingAdd | U32CheckedSub | U32WrappingSub | U32CheckedMul
+            | U32WrappingMul | U32CheckedDiv | U32UncheckedDiv | U32CheckedMod
+            | U32UncheckedMod | U32CheckedAnd | U32CheckedOr | U32CheckedXor | U32CheckedShr
+            | U32UncheckedShr | U32CheckedShl | U32UncheckedShl | U32CheckedRotr
+            | U32UncheckedRotr | U32CheckedRotl | U32UncheckedRotl | U32CheckedEq
+            | U32CheckedNeq | U32CheckedLt | U32UncheckedLt | U32CheckedLte | U32UncheckedLte
+            | U32CheckedGt | U32UncheckedGt | U32CheckedGte | U32UncheckedGte | U32CheckedMin
+            | U32UncheckedMin | U32CheckedMax | U32UncheckedMax => -1,
+            U32CheckedAddImm(_)
+            | U32WrappingAddImm(_)
+            | U32CheckedSubImm(_)
+            | U32WrappingSubImm(_)
+            | U32CheckedMulImm(_)
+            | U32WrappingMulImm(_)
+            | U32CheckedDivImm(_)
+            | U32UncheckedDivImm(_)
+            | U32CheckedModImm(_)
+            | U32UncheckedModImm(_)
+            | U32CheckedNot
+            | U32CheckedShrImm(_)
+            | U32UncheckedShrImm(_)
+            | U32CheckedShlImm(_)
+            | U32UncheckedShlImm(_)
+            | U32CheckedRotrImm(_)
+            | U32UncheckedRotrImm(_)
+            | U32CheckedRotlImm(_)
+            | U32UncheckedRotlImm(_)
+            | U32CheckedPopcnt
+            | U32UncheckedPopcnt
+            | U32CheckedEqImm(_)
+            | U32CheckedNeqImm(_) => 0,
+            U32OverflowingAdd | U32OverflowingSub | U32OverflowingMul | U32CheckedDivMod
+            | U32UncheckedDivMod => 0,
+            U32OverflowingAddImm(_)
+            | U32OverflowingSubImm(_)
+            | U32OverflowingMulImm(_)
+            | U32CheckedDivModImm(_)
+            | U32UncheckedDivModImm(_) => 1,
+            U32OverflowingAdd3 | U32OverflowingMadd => -1,
+            U32WrappingAdd3 | U32WrappingMadd => -2,
+
+            // ----- stack manipulation -----------------------------------------------------------
+            Drop => -1,
+            DropW => -4,
+            PadW => 4,
+            Dup0 | Dup1 | Dup2 | Dup3 | Dup4 | Dup5 | Dup6 | Dup7 | Dup8 | Dup9 | Dup10 | Dup11
+            | Dup12 | Dup13 | Dup14 | Dup15 => 1,
+            DupW0 | DupW1 | DupW2 | DupW3 => 4,
+            Swap1 | Swap2 | Swap3 | Swap4 | Swap5 | Swap6 | Swap7 | Swap8 | Swap9 | Swap10
+            | Swap11 | Swap12 | Swap13 | Swap14 | Swap15 | SwapW1 | SwapW2 | SwapW3 | SwapDw
+            | MovUp2 | MovUp3 | MovUp4 | MovUp5 | MovUp6 | MovUp7 | MovUp8 | MovUp9 | MovUp10
+            | MovUp11 | MovUp12 | MovUp13 | MovUp14 | MovUp15 | MovUpW2 | MovUpW3 | MovDn2
+            | MovDn3 | MovDn4 | MovDn5 | MovDn6 | MovDn7 | MovDn8 | MovDn9 | MovDn10 | MovDn11
+            | MovDn12 | MovDn13 | MovDn14 | MovDn15 | MovDnW2 | MovDnW3 => 0,
+            CSwap | CSwapW => -1,
+            CDrop => -2,
+            CDropW => -5,
+
+            // ----- input / output operations ----------------------------------------------------
+            PushU8(_) | PushU16(_) | PushU32(_) | PushFelt(_) => 1,
+            PushWord(_) => 4,
+            PushU8List(values) => values.len() as i32,
+            PushU16List(values) => values.len() as i32,
+            PushU32List(values) => values.len() as i32,
+            PushFeltList(values) => values.len() as i32,
+            Locaddr(_) | Sdepth | Clk => 1,
+            Caller => 0,
+
+            MemLoad | LocLoadW(_) | MemLoadWImm(_) => 0,
+            MemLoadImm(_) | LocLoad(_) => 1,
+            MemLoadW => -1,
+
+            MemStore => -2,
+            MemStoreImm(_) | LocStore(_) | MemStoreW => -1,
+            MemStoreWImm(_) | LocStoreW(_) => 0,
+
+            MemStream | AdvPipe | AdvLoadW | AdvInject(_) => 0,
+            AdvPush(n) => *n as i32,
+
+            // ----- cryptographic operations -----------------------------------------------------
+            Hash | HPerm | MTreeVerify => 0,
+            HMerge | MTreeMerge => -4,
+            MTreeGet => 2,
+            MTreeSet => -2,
+            FriExt2Fold4 => -1,
+
+            // ----- exec / call ------------------------------------------------------------------
+            ExecLocal(index) => return self.local_proc_effect(*index, local_procs),
+            ExecImported(proc_id) => return self.imported_proc_effect(proc_id),
+            CallLocal(_) | CallMastRoot(_) | CallImported(_) | SysCall(_) | DynCall => 0,
+            DynExec => return None,
+
+            // ----- events and debug decorators --------------------------------------------------
+            Emit(_) | Breakpoint | Debug(_) => 0,
+        };
+
+        Some(delta)
+    }
+}
+
+// CHECK CONTEXT
+// ================================================================================================
+
+/// Information about the procedure being checked, used to report errors.
+struct CheckContext<'a> {
+    proc_name: &'a str,
+    start: SourceLocation,
+}
+
+impl CheckContext<'_> {
+    /// Returns the location of the node at the specified index in the provided body, or the start
+    /// of the procedure if the body has no source locations.
+    fn node_location(&self, body: &CodeBody, index: usize) -> SourceLocation {
+        body.source_locations().get(index).copied().unwrap_or(self.start)
+    }
+
+    /// Returns the location of the `end` token of the provided body, or the start of the
+    /// procedure if the body has no source locations.
+    fn end_location(&self, body: &CodeBody) -> SourceLocation {
+        body.source_locations().last().copied().unwrap_or(self.start)
+    }
+}
@@ -1,4 +1,4 @@
-use super::{combine_blocks, Assembler, CodeBlock, Library, Module, Operation};
+use super::{combine_blocks, Assembler, AssemblyContext, CodeBlock, Library, Module, Operation};
 use crate::{ast::ModuleAst, LibraryNamespace, LibraryPath, Version};
 use core::slice::Iter;
 
@@ -139,3 +139,31 @@ fn nested_blocks() {
 
     assert_eq!(combined.hash(), program.hash());
 }
+
+#[test]
+fn unreachable_module_procedures_are_not_cached() {
+    let path = LibraryPath::try_from("foo::bar".to_string()).unwrap();
+    let module = ModuleAst::parse(
+        r#"
+        proc.helper
+            push.1
+        end
+        proc.unused
+            push.2
+        end
+        proc.dead
+            call.unused
+        end
+        export.baz
+            call.helper
+        end"#,
+    )
+    .unwrap();
+
+    let assembler = Assembler::default();
+    let mut context = AssemblyContext::for_module(false);
+    assembler.compile_module(&module, Some(&path), &mut context).unwrap();
+
+    // only the exported procedure and the internal procedure it calls are cached
+    assert_eq!(assembler.proc_cache.borrow().len(), 2);
+}
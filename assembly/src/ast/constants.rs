@@ -0,0 +1,150 @@
+use super::{
+    BTreeMap, ByteReader, ByteWriter, Deserializable, DeserializationError, LibraryPath,
+    Serializable, String, Vec, MAX_LABEL_LEN,
+};
+use crate::MAX_PUSH_INPUTS;
+use core::fmt;
+
+// CONSTANT VALUE
+// ================================================================================================
+
+/// Value of a constant declared via a `const` or an `export.const` statement.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConstantValue {
+    /// A single field element (e.g., `const.A=42`).
+    Scalar(u64),
+    /// A list of field elements (e.g., `const.ROOT=[1,2,3,4]`). Array constants can be used only
+    /// as immediate values of the `push` instruction.
+    Array(Vec<u64>),
+}
+
+impl ConstantValue {
+    /// Returns the value of this constant if it is a single field element, or None otherwise.
+    pub fn as_scalar(&self) -> Option<u64> {
+        match self {
+            Self::Scalar(value) => Some(*value),
+            Self::Array(_) => None,
+        }
+    }
+
+    /// Returns the field elements of this constant.
+    pub fn values(&self) -> &[u64] {
+        match self {
+            Self::Scalar(value) => core::slice::from_ref(value),
+            Self::Array(values) => values,
+        }
+    }
+}
+
+impl fmt::Display for ConstantValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Scalar(value) => write!(f, "{value}"),
+            Self::Array(values) => {
+                write!(f, "[")?;
+                for (i, value) in values.iter().enumerate() {
+                    if i != 0 {
+                        write!(f, ",")?;
+                    }
+                    write!(f, "{value}")?;
+                }
+                write!(f, "]")
+            }
+        }
+    }
+}
+
+impl Serializable for ConstantValue {
+    fn write_into<W: ByteWriter>(&self, target: &mut W) {
+        match self {
+            Self::Scalar(value) => {
+                target.write_u8(0);
+                target.write_u64(*value);
+            }
+            Self::Array(values) => {
+                // the number of array elements is limited by the constant parser
+                debug_assert!(values.len() <= MAX_PUSH_INPUTS, "too many array elements");
+                target.write_u8(1);
+                target.write_u8(values.len() as u8);
+                values.iter().for_each(|value| target.write_u64(*value));
+            }
+        }
+    }
+}
+
+impl Deserializable for ConstantValue {
+    fn read_from<R: ByteReader>(source: &mut R) -> Result<Self, DeserializationError> {
+        match source.read_u8()? {
+            0 => Ok(Self::Scalar(source.read_u64()?)),
+            1 => {
+                let num_values = source.read_u8()? as usize;
+                if num_values == 0 || num_values > MAX_PUSH_INPUTS {
+                    return Err(DeserializationError::InvalidValue(format!(
+                        "array constant must contain between 1 and {MAX_PUSH_INPUTS} elements, but contained {num_values}"
+                    )));
+                }
+                let values =
+                    (0..num_values).map(|_| source.read_u64()).collect::<Result<_, _>>()?;
+                Ok(Self::Array(values))
+            }
+            tag => Err(DeserializationError::InvalidValue(format!(
+                "invalid constant value tag: {tag}"
+            ))),
+        }
+    }
+}
+
+// CONSTANT RESOLVER
+// ================================================================================================
+
+/// Provides the constants exported from library modules, so that they can be referenced from
+/// modules and programs which import these modules via `use` statements.
+///
+/// Constants are resolved during parsing: a constant exported as `export.const.NAME=...` from a
+/// module imported as `use.std::foo::bar` can be referenced as `bar::NAME` wherever a constant can
+/// be used.
+pub trait ConstantResolver {
+    /// Returns the constants exported from the module with the specified path, or None if the
+    /// module is not known to this resolver.
+    fn get_exported_constants(&self, module_path: &LibraryPath) -> Option<ExportedConstMap>;
+}
+
+/// A map from the names of exported constants to their values.
+pub type ExportedConstMap = BTreeMap<String, ConstantValue>;
+
+// SERIALIZATION HELPERS
+// ================================================================================================
+
+/// Writes the provided map of exported constants into the target.
+pub(super) fn write_exported_constants<W: ByteWriter>(
+    constants: &ExportedConstMap,
+    target: &mut W,
+) {
+    // the number of constants is limited by the number of tokens in a module, and the names of
+    // constants cannot exceed MAX_LABEL_LEN characters
+    assert!(constants.len() <= u16::MAX as usize, "too many exported constants");
+    target.write_u16(constants.len() as u16);
+    for (name, value) in constants.iter() {
+        debug_assert!(name.len() <= MAX_LABEL_LEN, "constant name too long");
+        target.write_u8(name.len() as u8);
+        target.write_bytes(name.as_bytes());
+        value.write_into(target);
+    }
+}
+
+/// Reads a map of exported constants from the source.
+pub(super) fn read_exported_constants<R: ByteReader>(
+    source: &mut R,
+) -> Result<ExportedConstMap, DeserializationError> {
+    let num_constants = source.read_u16()?;
+    let mut constants = ExportedConstMap::new();
+    for _ in 0..num_constants {
+        let name_len = source.read_u8()? as usize;
+        let name = source.read_vec(name_len)?;
+        let name = String::from_utf8(name)
+            .map_err(|err| DeserializationError::InvalidValue(format!("{err}")))?;
+        let value = ConstantValue::read_from(source)?;
+        constants.insert(name, value);
+    }
+    Ok(constants)
+}
@@ -1,8 +1,10 @@
 use super::{
-    BTreeMap, ByteReader, ByteWriter, Deserializable, DeserializationError, InvokedProcsMap,
-    LibraryPath, ParsingError, ProcedureId, ProcedureName, Serializable, String, ToString, Token,
-    TokenStream, Vec, MAX_IMPORTS, MAX_INVOKED_IMPORTED_PROCS,
+    BTreeMap, BTreeSet, ByteReader, ByteWriter, Deserializable, DeserializationError,
+    InvokedProcsMap, LibraryPath, ParsingError, ProcedureId, ProcedureName, Serializable,
+    SourceLocation, String, ToString, Token, TokenStream, Vec, MAX_IMPORTS,
+    MAX_INVOKED_IMPORTED_PROCS,
 };
+use crate::diagnostics::find_similar_name;
 
 // TYPE ALIASES
 // ================================================================================================
@@ -13,12 +15,21 @@ type ImportedModulesMap = BTreeMap<String, LibraryPath>;
 // ================================================================================================
 
 /// Information about imports stored in the AST
-#[derive(Debug, Default, Clone, PartialEq, Eq)]
+///
+/// Locations of the `use` statements and the set of imported modules referenced from the source
+/// are available only for imports parsed from source; they are neither serialized nor compared
+/// when checking two instances for equality.
+#[derive(Debug, Default, Clone, Eq)]
 pub struct ModuleImports {
     /// Imported libraries.
     imports: ImportedModulesMap,
     /// Imported procedures that are called from somewhere in the AST.
     invoked_procs: InvokedProcsMap,
+    /// Locations of the `use` statements keyed by the names of the imported modules.
+    locations: BTreeMap<String, SourceLocation>,
+    /// Names of the imported modules which are referenced from the source (e.g., via procedure
+    /// invocations, re-exports, or constants).
+    referenced_modules: BTreeSet<String>,
 }
 
 impl ModuleImports {
@@ -38,6 +49,8 @@ impl ModuleImports {
         Self {
             imports,
             invoked_procs,
+            locations: BTreeMap::new(),
+            referenced_modules: BTreeSet::new(),
         }
     }
 
@@ -45,17 +58,26 @@ impl ModuleImports {
     // --------------------------------------------------------------------------------------------
     /// Parses all `use` statements into a map of imports which maps a module name (e.g., "u64") to
     /// its fully-qualified path (e.g., "std::math::u64").
+    ///
+    /// The remaining tokens of the stream are scanned for qualified names (e.g., `u64::add`) to
+    /// determine which of the imported modules are referenced from the source.
     pub fn parse(tokens: &mut TokenStream) -> Result<Self, ParsingError> {
         let mut imports = BTreeMap::<String, LibraryPath>::new();
+        let mut locations = BTreeMap::<String, SourceLocation>::new();
         // read tokens from the token stream until all `use` tokens are consumed
         while let Some(token) = tokens.read() {
             match token.parts()[0] {
                 Token::USE => {
                     let (module_path, module_name) = token.parse_use()?;
-                    if imports.values().any(|path| *path == module_path) {
-                        return Err(ParsingError::duplicate_module_import(token, &module_path));
+                    if let Some(name) = imports
+                        .iter()
+                        .find_map(|(name, path)| (*path == module_path).then_some(name))
+                    {
+                        return Err(ParsingError::duplicate_module_import(token, &module_path)
+                            .with_label(locations[name], "first imported here"));
                     }
 
+                    locations.insert(module_name.clone(), *token.location());
                     imports.insert(module_name, module_path);
 
                     // consume the `use` token
@@ -68,9 +90,20 @@ impl ModuleImports {
         if imports.len() > MAX_IMPORTS {
             return Err(ParsingError::too_many_imports(imports.len(), MAX_IMPORTS));
         }
+
+        let referenced_modules = tokens
+            .remaining_tokens()
+            .iter()
+            .flat_map(|token| qualifier_names(token))
+            .filter(|name| imports.contains_key(*name))
+            .map(|name| name.to_string())
+            .collect();
+
         Ok(Self {
             imports,
             invoked_procs: BTreeMap::new(),
+            locations,
+            referenced_modules,
         })
     }
 
@@ -87,11 +120,35 @@ impl ModuleImports {
         self.imports.values().collect()
     }
 
+    /// Returns an iterator over the names under which modules are imported and the paths of these
+    /// modules.
+    pub fn imported_modules(&self) -> impl Iterator<Item = (&str, &LibraryPath)> {
+        self.imports.iter().map(|(name, path)| (name.as_str(), path))
+    }
+
     /// Returns a reference to the invoked procedure map which maps procedure IDs to their names.
     pub fn invoked_procs(&self) -> &InvokedProcsMap {
         &self.invoked_procs
     }
 
+    /// Returns the location of the `use` statement which imports the module with the specified
+    /// name, or None if the imports were not parsed from source.
+    pub fn import_location(&self, module_name: &str) -> Option<&SourceLocation> {
+        self.locations.get(module_name)
+    }
+
+    /// Returns true if the module imported under the specified name is referenced from the source
+    /// this instance was parsed from, or if any of the invoked procedures belongs to this module.
+    pub fn is_referenced(&self, module_name: &str) -> bool {
+        if self.referenced_modules.contains(module_name) {
+            return true;
+        }
+        match self.imports.get(module_name) {
+            Some(path) => self.invoked_procs.values().any(|(_, proc_path)| proc_path == path),
+            None => false,
+        }
+    }
+
     // STATE MUTATORS
     // --------------------------------------------------------------------------------------------
 
@@ -111,7 +168,7 @@ impl ModuleImports {
         let module_path = self
             .imports
             .get(module_name)
-            .ok_or_else(|| ParsingError::procedure_module_not_imported(token, module_name))?;
+            .ok_or_else(|| self.module_not_imported(token, module_name))?;
         let proc_id = ProcedureId::from_name(proc_name.as_ref(), module_path);
         self.invoked_procs.insert(proc_id, (proc_name.clone(), module_path.clone()));
         if self.invoked_procs.len() > MAX_INVOKED_IMPORTED_PROCS {
@@ -123,6 +180,34 @@ impl ModuleImports {
         }
         Ok(proc_id)
     }
+
+    // ERROR BUILDERS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns an error for a reference to a module which has not been imported, suggesting a
+    /// similarly named imported module and pointing to its `use` statement if there is one.
+    pub(crate) fn module_not_imported(&self, token: &Token, module_name: &str) -> ParsingError {
+        let err = ParsingError::procedure_module_not_imported(token, module_name);
+        let module_names = self.imports.keys().map(|name| name.as_str());
+        match find_similar_name(module_name, module_names) {
+            Some(similar) => {
+                let err = err.with_help(&format!("did you mean `{similar}`?"));
+                match self.locations.get(similar) {
+                    Some(location) => {
+                        err.with_label(*location, &format!("module `{similar}` imported here"))
+                    }
+                    None => err,
+                }
+            }
+            None => err,
+        }
+    }
+}
+
+impl PartialEq for ModuleImports {
+    fn eq(&self, other: &Self) -> bool {
+        self.imports == other.imports && self.invoked_procs == other.invoked_procs
+    }
 }
 
 impl Serializable for ModuleImports {
@@ -160,3 +245,20 @@ impl Deserializable for ModuleImports {
         Ok(Self::new(imports, used_imported_procs))
     }
 }
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Returns an iterator over the names qualifying the items referenced in the provided token, e.g.,
+/// `u64` for `exec.u64::add` or `bar` for `push.bar::FOO`.
+fn qualifier_names(token: &str) -> impl Iterator<Item = &str> {
+    let delim = LibraryPath::PATH_DELIM;
+    token.match_indices(delim).map(move |(idx, _)| {
+        let prefix = &token[..idx];
+        let start = prefix
+            .rfind(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+            .map(|pos| pos + 1)
+            .unwrap_or(0);
+        &prefix[start..]
+    })
+}
@@ -4,7 +4,7 @@
 //! code into relevant ASTs. This can be done via their `parse()` methods.
 
 use super::{
-    crypto::hash::RpoDigest, BTreeMap, ByteReader, ByteWriter, Deserializable,
+    crypto::hash::RpoDigest, BTreeMap, BTreeSet, ByteReader, ByteWriter, Deserializable,
     DeserializationError, Felt, LabelError, LibraryPath, ParsingError, ProcedureId, ProcedureName,
     Serializable, SliceReader, StarkField, String, ToString, Token, TokenStream, Vec,
     MAX_LABEL_LEN,
@@ -26,6 +26,10 @@ pub use code_body::CodeBody;
 mod format;
 use format::*;
 
+mod constants;
+use constants::{read_exported_constants, write_exported_constants};
+pub use constants::{ConstantResolver, ConstantValue, ExportedConstMap};
+
 mod imports;
 pub use imports::ModuleImports;
 
@@ -33,13 +37,17 @@ mod invocation_target;
 pub use invocation_target::InvocationTarget;
 
 mod parsers;
-use parsers::{parse_constants, ParserContext};
+pub use parsers::INSTRUCTION_NAMES;
+use parsers::{parse_constants, LocalMacroMap, ParserContext};
 
 pub(crate) use parsers::{NAMESPACE_LABEL_PARSER, PROCEDURE_LABEL_PARSER};
 
 mod serde;
 pub use serde::AstSerdeOptions;
 
+mod signature;
+pub use signature::StackSignature;
+
 #[cfg(test)]
 pub mod tests;
 
@@ -70,7 +78,7 @@ const MAX_STACK_WORD_OFFSET: u8 = 12;
 // TYPE ALIASES
 // ================================================================================================
 type LocalProcMap = BTreeMap<ProcedureName, (u16, ProcedureAst)>;
-type LocalConstMap = BTreeMap<String, u64>;
+type LocalConstMap = BTreeMap<String, ConstantValue>;
 type ReExportedProcMap = BTreeMap<ProcedureName, ProcReExport>;
 type InvokedProcsMap = BTreeMap<ProcedureId, (ProcedureName, LibraryPath)>;
 
@@ -139,6 +147,11 @@ impl ProgramAst {
     // PUBLIC ACCESSORS
     // --------------------------------------------------------------------------------------------
 
+    /// Returns the location of the `begin` token of this program.
+    pub const fn start(&self) -> &SourceLocation {
+        &self.start
+    }
+
     /// Returns the [SourceLocation] associated with this program, if present.
     pub fn source_locations(&self) -> impl Iterator<Item = &'_ SourceLocation> {
         iter::once(&self.start).chain(self.body.source_locations().iter())
@@ -154,6 +167,11 @@ impl ProgramAst {
         &self.body
     }
 
+    /// Returns the import information of this program, if present.
+    pub fn import_info(&self) -> Option<&ModuleImports> {
+        self.import_info.as_ref()
+    }
+
     /// Returns a map containing IDs and names of imported procedures.
     pub fn get_imported_procedures_map(&self) -> BTreeMap<ProcedureId, ProcedureName> {
         if let Some(info) = &self.import_info {
@@ -168,16 +186,36 @@ impl ProgramAst {
     /// Parses the provided source into a [ProgramAst].
     ///
     /// A program consist of a body and a set of internal (i.e., not exported) procedures.
+    ///
+    /// Constants exported from imported modules cannot be referenced by the program parsed via
+    /// this method; use [ProgramAst::parse_with_constants()] to make them available.
     pub fn parse(source: &str) -> Result<ProgramAst, ParsingError> {
+        Self::parse_internal(source, None)
+    }
+
+    /// Parses the provided source into a [ProgramAst], resolving the constants exported from
+    /// imported modules via the provided resolver.
+    pub fn parse_with_constants(
+        source: &str,
+        resolver: &dyn ConstantResolver,
+    ) -> Result<ProgramAst, ParsingError> {
+        Self::parse_internal(source, Some(resolver))
+    }
+
+    fn parse_internal(
+        source: &str,
+        resolver: Option<&dyn ConstantResolver>,
+    ) -> Result<ProgramAst, ParsingError> {
         let mut tokens = TokenStream::new(source)?;
         let mut import_info = ModuleImports::parse(&mut tokens)?;
-        let local_constants = parse_constants(&mut tokens)?;
+        let (local_constants, _) = parse_constants(&mut tokens, &import_info, resolver, false)?;
 
         let mut context = ParserContext {
             import_info: &mut import_info,
             local_procs: LocalProcMap::default(),
             reexported_procs: ReExportedProcMap::default(),
             local_constants,
+            local_macros: LocalMacroMap::default(),
         };
 
         context.parse_procedures(&mut tokens, false)?;
@@ -258,7 +296,7 @@ impl ProgramAst {
         // serialize procedures
         assert!(self.local_procs.len() <= MAX_LOCAL_PROCS, "too many local procs");
         target.write_u16(self.local_procs.len() as u16);
-        self.local_procs.write_into(&mut target);
+        self.local_procs.iter().for_each(|proc| proc.write_into(&mut target, options));
 
         // serialize program body
         assert!(self.body.nodes().len() <= MAX_BODY_LEN, "too many body instructions");
@@ -286,7 +324,9 @@ impl ProgramAst {
 
         // deserialize local procs
         let num_local_procs = source.read_u16()?;
-        let local_procs = Deserializable::read_batch_from(&mut source, num_local_procs as usize)?;
+        let local_procs = (0..num_local_procs)
+            .map(|_| ProcedureAst::read_from(&mut source, options))
+            .collect::<Result<_, _>>()?;
 
         // deserialize program body
         let body_len = source.read_u16()? as usize;
@@ -353,9 +393,7 @@ impl ProgramAst {
             fs::create_dir_all(dir)?;
         }
 
-        let bytes = self.to_bytes(AstSerdeOptions {
-            serialize_imports: true,
-        });
+        let bytes = self.to_bytes(AstSerdeOptions::new(true));
         fs::write(path, bytes)
     }
 }
@@ -412,6 +450,7 @@ pub struct ModuleAst {
     local_procs: Vec<ProcedureAst>,
     reexported_procs: Vec<ProcReExport>,
     import_info: Option<ModuleImports>,
+    exported_constants: ExportedConstMap,
     docs: Option<String>,
 }
 
@@ -444,6 +483,7 @@ impl ModuleAst {
             local_procs,
             reexported_procs,
             import_info: None,
+            exported_constants: ExportedConstMap::new(),
             docs,
         })
     }
@@ -458,20 +498,51 @@ impl ModuleAst {
         self
     }
 
+    /// Adds the provided constants to the set of constants exported from this module.
+    ///
+    /// # Panics
+    /// Panics if exported constants have already been added.
+    pub fn with_exported_constants(mut self, exported_constants: ExportedConstMap) -> Self {
+        assert!(self.exported_constants.is_empty(), "exported constants have already been added");
+        self.exported_constants = exported_constants;
+        self
+    }
+
     // PARSER
     // --------------------------------------------------------------------------------------------
     /// Parses the provided source into a [ModuleAst].
     ///
     /// A module consists of internal and exported procedures but does not contain a body.
+    ///
+    /// Constants exported from imported modules cannot be referenced by the module parsed via
+    /// this method; use [ModuleAst::parse_with_constants()] to make them available.
     pub fn parse(source: &str) -> Result<Self, ParsingError> {
+        Self::parse_internal(source, None)
+    }
+
+    /// Parses the provided source into a [ModuleAst], resolving the constants exported from
+    /// imported modules via the provided resolver.
+    pub fn parse_with_constants(
+        source: &str,
+        resolver: &dyn ConstantResolver,
+    ) -> Result<Self, ParsingError> {
+        Self::parse_internal(source, Some(resolver))
+    }
+
+    fn parse_internal(
+        source: &str,
+        resolver: Option<&dyn ConstantResolver>,
+    ) -> Result<Self, ParsingError> {
         let mut tokens = TokenStream::new(source)?;
         let mut import_info = ModuleImports::parse(&mut tokens)?;
-        let local_constants = parse_constants(&mut tokens)?;
+        let (local_constants, exported_constants) =
+            parse_constants(&mut tokens, &import_info, resolver, true)?;
         let mut context = ParserContext {
             import_info: &mut import_info,
             local_procs: LocalProcMap::default(),
             reexported_procs: ReExportedProcMap::default(),
             local_constants,
+            local_macros: LocalMacroMap::default(),
         };
         context.parse_procedures(&mut tokens, true)?;
 
@@ -493,7 +564,9 @@ impl ModuleAst {
         // get module docs and make sure the size is within the limit
         let docs = tokens.take_module_comments();
 
-        Ok(Self::new(local_procs, reexported_procs, docs)?.with_import_info(import_info))
+        Ok(Self::new(local_procs, reexported_procs, docs)?
+            .with_import_info(import_info)
+            .with_exported_constants(exported_constants))
     }
 
     // PUBLIC ACCESSORS
@@ -509,6 +582,11 @@ impl ModuleAst {
         &self.reexported_procs
     }
 
+    /// Returns a map of constants exported from this module.
+    pub fn exported_constants(&self) -> &ExportedConstMap {
+        &self.exported_constants
+    }
+
     /// Returns doc comments for this module.
     pub fn docs(&self) -> Option<&String> {
         self.docs.as_ref()
@@ -522,6 +600,11 @@ impl ModuleAst {
         }
     }
 
+    /// Returns the import information of this module, if present.
+    pub fn import_info(&self) -> Option<&ModuleImports> {
+        self.import_info.as_ref()
+    }
+
     /// Returns a map containing IDs and names of imported procedures.
     pub fn get_imported_procedures_map(&self) -> BTreeMap<ProcedureId, ProcedureName> {
         if let Some(info) = &self.import_info {
@@ -570,6 +653,9 @@ impl ModuleAst {
             }
         }
 
+        // serialize exported constants
+        write_exported_constants(&self.exported_constants, target);
+
         // serialize procedures
         assert!(self.local_procs.len() <= u16::MAX as usize, "too many local procs");
         assert!(
@@ -579,7 +665,7 @@ impl ModuleAst {
         target.write_u16((self.reexported_procs.len()) as u16);
         self.reexported_procs.write_into(target);
         target.write_u16(self.local_procs.len() as u16);
-        self.local_procs.write_into(target);
+        self.local_procs.iter().for_each(|proc| proc.write_into(target, options));
     }
 
     /// Returns a [ModuleAst] struct deserialized from the provided source.
@@ -606,20 +692,28 @@ impl ModuleAst {
             import_info = Some(ModuleImports::read_from(source)?);
         }
 
+        // deserialize exported constants
+        let exported_constants = read_exported_constants(source)?;
+
         // deserialize re-exports
         let num_reexported_procs = source.read_u16()? as usize;
         let reexported_procs = Deserializable::read_batch_from(source, num_reexported_procs)?;
 
         // deserialize local procs
         let num_local_procs = source.read_u16()? as usize;
-        let local_procs = Deserializable::read_batch_from(source, num_local_procs)?;
+        let local_procs = (0..num_local_procs)
+            .map(|_| ProcedureAst::read_from(source, options))
+            .collect::<Result<_, _>>()?;
 
         match Self::new(local_procs, reexported_procs, docs) {
             Err(err) => Err(DeserializationError::UnknownError(err.message().clone())),
-            Ok(res) => match import_info {
-                Some(info) => Ok(res.with_import_info(info)),
-                None => Ok(res),
-            },
+            Ok(res) => {
+                let res = res.with_exported_constants(exported_constants);
+                match import_info {
+                    Some(info) => Ok(res.with_import_info(info)),
+                    None => Ok(res),
+                }
+            }
         }
     }
 
@@ -705,6 +799,14 @@ impl fmt::Display for ModuleAst {
             }
         }
 
+        // Exported constants
+        for (name, value) in self.exported_constants.iter() {
+            writeln!(f, "export.const.{name}={value}")?;
+        }
+        if !self.exported_constants.is_empty() {
+            writeln!(f)?;
+        }
+
         // Re-exports
         for proc in self.reexported_procs.iter() {
             writeln!(f, "export.{}", proc.name)?;
@@ -741,6 +843,7 @@ pub struct ProcedureAst {
     pub body: CodeBody,
     pub start: SourceLocation,
     pub is_export: bool,
+    pub signature: Option<StackSignature>,
 }
 
 impl ProcedureAst {
@@ -766,9 +869,16 @@ impl ProcedureAst {
             body,
             is_export,
             start,
+            signature: None,
         }
     }
 
+    /// Sets the declared stack signature of this procedure.
+    pub fn with_signature(mut self, signature: StackSignature) -> Self {
+        self.signature = Some(signature);
+        self
+    }
+
     /// Binds the provided `locations` into the ast nodes.
     ///
     /// The `start` location points to the first node of this block.
@@ -801,31 +911,11 @@ impl ProcedureAst {
     // SERIALIZATION / DESERIALIZATION
     // --------------------------------------------------------------------------------------------
 
-    /// Loads the [SourceLocation] from the `source`.
+    /// Writes byte representation of this [ProcedureAst] into `target`.
     ///
-    /// It expects the `start` location at the first position, and will subsequently load the
-    /// body via [CodeBody::load_source_locations].
-    pub fn load_source_locations<R: ByteReader>(
-        &mut self,
-        source: &mut R,
-    ) -> Result<(), DeserializationError> {
-        self.start = SourceLocation::read_from(source)?;
-        self.body.load_source_locations(source)?;
-        Ok(())
-    }
-
-    /// Writes the [SourceLocation] into `target`.
-    ///
-    /// It will write the `start` location, and then execute the body serialization via
-    /// [CodeBlock::write_source_locations].
-    pub fn write_source_locations<W: ByteWriter>(&self, target: &mut W) {
-        self.start.write_into(target);
-        self.body.write_source_locations(target);
-    }
-}
-
-impl Serializable for ProcedureAst {
-    fn write_into<W: ByteWriter>(&self, target: &mut W) {
+    /// The procedure signature is written only if the serde options enable serialization of
+    /// signatures.
+    pub fn write_into<W: ByteWriter>(&self, target: &mut W, options: AstSerdeOptions) {
         // asserts below are OK because we enforce limits on the procedure body size and length of
         // procedure docs in the procedure parser
 
@@ -843,14 +933,27 @@ impl Serializable for ProcedureAst {
 
         target.write_bool(self.is_export);
         target.write_u16(self.num_locals);
+        if options.serialize_signatures {
+            match &self.signature {
+                Some(signature) => {
+                    target.write_bool(true);
+                    signature.write_into(target);
+                }
+                None => target.write_bool(false),
+            }
+        }
         assert!(self.body.nodes().len() <= MAX_BODY_LEN, "too many body instructions");
         target.write_u16(self.body.nodes().len() as u16);
         self.body.nodes().write_into(target);
     }
-}
 
-impl Deserializable for ProcedureAst {
-    fn read_from<R: ByteReader>(source: &mut R) -> Result<Self, DeserializationError> {
+    /// Returns a [ProcedureAst] deserialized from the provided source.
+    ///
+    /// The serde options must correspond to the options used for serialization.
+    pub fn read_from<R: ByteReader>(
+        source: &mut R,
+        options: AstSerdeOptions,
+    ) -> Result<Self, DeserializationError> {
         let name = ProcedureName::read_from(source)?;
         let docs_len = source.read_u16()? as usize;
         let docs = if docs_len != 0 {
@@ -864,6 +967,11 @@ impl Deserializable for ProcedureAst {
 
         let is_export = source.read_bool()?;
         let num_locals = source.read_u16()?;
+        let signature = if options.serialize_signatures && source.read_bool()? {
+            Some(StackSignature::read_from(source)?)
+        } else {
+            None
+        };
         let body_len = source.read_u16()? as usize;
         let nodes = Deserializable::read_batch_from(source, body_len)?;
         let body = CodeBody::new(nodes);
@@ -874,9 +982,32 @@ impl Deserializable for ProcedureAst {
             body,
             start,
             is_export,
+            signature,
             docs,
         })
     }
+
+    /// Loads the [SourceLocation] from the `source`.
+    ///
+    /// It expects the `start` location at the first position, and will subsequently load the
+    /// body via [CodeBody::load_source_locations].
+    pub fn load_source_locations<R: ByteReader>(
+        &mut self,
+        source: &mut R,
+    ) -> Result<(), DeserializationError> {
+        self.start = SourceLocation::read_from(source)?;
+        self.body.load_source_locations(source)?;
+        Ok(())
+    }
+
+    /// Writes the [SourceLocation] into `target`.
+    ///
+    /// It will write the `start` location, and then execute the body serialization via
+    /// [CodeBlock::write_source_locations].
+    pub fn write_source_locations<W: ByteWriter>(&self, target: &mut W) {
+        self.start.write_into(target);
+        self.body.write_source_locations(target);
+    }
 }
 
 /// Represents a re-exported procedure.
@@ -310,6 +310,9 @@ pub enum Instruction {
     DynExec,
     DynCall,
 
+    // ----- events -------------------------------------------------------------------------------
+    Emit(u32),
+
     // ----- debug decorators ---------------------------------------------------------------------
     Breakpoint,
     Debug(DebugOptions),
@@ -596,6 +599,9 @@ impl fmt::Display for Instruction {
             Self::DynExec => write!(f, "dynexec"),
             Self::DynCall => write!(f, "dyncall"),
 
+            // ----- events -----------------------------------------------------------------------
+            Self::Emit(event_id) => write!(f, "emit.{event_id}"),
+
             // ----- debug decorators -------------------------------------------------------------
             Self::Breakpoint => write!(f, "breakpoint"),
             Self::Debug(options) => write!(f, "debug.{options}"),
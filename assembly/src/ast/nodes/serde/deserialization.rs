@@ -364,6 +364,9 @@ impl Deserializable for Instruction {
             OpCode::DynExec => Ok(Instruction::DynExec),
             OpCode::DynCall => Ok(Instruction::DynCall),
 
+            // ----- events -----------------------------------------------------------------------
+            OpCode::Emit => Ok(Instruction::Emit(source.read_u32()?)),
+
             // ----- debugging --------------------------------------------------------------------
             OpCode::Debug => {
                 let options = debug::read_options_from(source)?;
@@ -286,6 +286,9 @@ pub enum OpCode {
     // ----- debugging ----------------------------------------------------------------------------
     Debug = 249,
 
+    // ----- events -------------------------------------------------------------------------------
+    Emit = 250,
+
     // ----- control flow -------------------------------------------------------------------------
     IfElse = 253,
     Repeat = 254,
@@ -522,6 +522,12 @@ impl Serializable for Instruction {
             Self::DynExec => OpCode::DynExec.write_into(target),
             Self::DynCall => OpCode::DynCall.write_into(target),
 
+            // ----- events -----------------------------------------------------------------------
+            Self::Emit(event_id) => {
+                OpCode::Emit.write_into(target);
+                target.write_u32(*event_id);
+            }
+
             // ----- debug decorators -------------------------------------------------------------
             Self::Breakpoint => {
                 // this is a transparent instruction and will not be encoded into the library
@@ -217,12 +217,29 @@ impl<'a> OperationIterator<'a> {
 }
 
 /// Returns the number in `value` or the constant value if the value is the name of the constant.
+///
+/// Numbers can be specified either in decimal or in hexadecimal (e.g., `0x1f`) form.
 fn parse_operand(
     op: &Token,
     expression: &str,
     constants: &LocalConstMap,
     value: String,
 ) -> Result<Operation, ParsingError> {
+    // if the value is a hexadecimal number, parse it and make sure it is a valid field element
+    if let Some(hex_value) = value.strip_prefix("0x") {
+        let parsed_number = u64::from_str_radix(hex_value, 16)
+            .ok()
+            .filter(|number| *number < Felt::MODULUS)
+            .ok_or_else(|| {
+                ParsingError::invalid_const_value(
+                    op,
+                    expression,
+                    &format!("{value} is not a valid hexadecimal field element"),
+                )
+            })?;
+        return Ok(Operation::Value(Felt::new(parsed_number)));
+    }
+
     let parsed_number = value.parse::<u64>();
     // if the parsed value is a number push it on the stack
     if let Ok(parsed_number) = parsed_number {
@@ -237,7 +254,14 @@ fn parse_operand(
                 &format!("constant with name {} was not initialized", value),
             )
         })?;
-        Ok(Operation::Value(Felt::new(*parsed_number)))
+        let parsed_number = parsed_number.as_scalar().ok_or_else(|| {
+            ParsingError::invalid_const_value(
+                op,
+                expression,
+                &format!("array constant {} cannot be used in expressions", value),
+            )
+        })?;
+        Ok(Operation::Value(Felt::new(parsed_number)))
     }
 }
 
@@ -292,8 +316,12 @@ fn compute_statement(
 mod tests {
     use super::{Felt, LocalConstMap, Token};
     use crate::{
-        ast::parsers::constants::{
-            build_postfix_expression, evaluate_postfix_expression, Operation,
+        ast::{
+            parsers::constants::{
+                build_postfix_expression, calculate_const_value, evaluate_postfix_expression,
+                Operation,
+            },
+            ConstantValue,
         },
         ONE,
     };
@@ -301,7 +329,10 @@ mod tests {
 
     #[test]
     fn test_build_postfix_expression() {
-        let constants = LocalConstMap::from([("A".to_string(), 3), ("B".to_string(), 10)]);
+        let constants = LocalConstMap::from([
+            ("A".to_string(), ConstantValue::Scalar(3)),
+            ("B".to_string(), ConstantValue::Scalar(10)),
+        ]);
 
         let expression = "51-A+22";
         let result = build_postfix_expression(&Token::new_dummy(), expression, &constants).unwrap();
@@ -370,4 +401,27 @@ mod tests {
         let expected = Felt::new(48);
         assert_eq!(result, expected);
     }
+
+    #[test]
+    fn test_calculate_const_value_with_hex_and_imports() {
+        let constants = LocalConstMap::from([
+            ("A".to_string(), ConstantValue::Scalar(3)),
+            ("bar::B".to_string(), ConstantValue::Scalar(10)),
+            ("ROOT".to_string(), ConstantValue::Array(vec![1, 2, 3, 4])),
+        ]);
+
+        let result = calculate_const_value(&Token::new_dummy(), "0x10+A*bar::B", &constants);
+        assert_eq!(result.unwrap(), Felt::new(46));
+
+        let result = calculate_const_value(&Token::new_dummy(), "0xffffffff//0x100", &constants);
+        assert_eq!(result.unwrap(), Felt::new(0xffffff));
+
+        // hex values must be valid field elements
+        let result = calculate_const_value(&Token::new_dummy(), "0xffffffffffffffff+1", &constants);
+        assert!(result.is_err());
+
+        // array constants cannot be used in expressions
+        let result = calculate_const_value(&Token::new_dummy(), "ROOT+1", &constants);
+        assert!(result.is_err());
+    }
 }
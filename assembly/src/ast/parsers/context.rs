@@ -1,7 +1,10 @@
 use super::{
-    super::ProcReExport, adv_ops, debug, field_ops, io_ops, stack_ops, sys_ops, u32_ops, CodeBody,
-    Instruction, InvocationTarget, LibraryPath, LocalConstMap, LocalProcMap, ModuleImports, Node,
-    ParsingError, ProcedureAst, ProcedureId, ProcedureName, ReExportedProcMap, Token, TokenStream,
+    super::ProcReExport,
+    adv_ops, debug, field_ops, io_ops,
+    macros::{self, MacroDefinition},
+    stack_ops, sys_ops, u32_ops, CodeBody, Instruction, InvocationTarget, LibraryPath,
+    LocalConstMap, LocalMacroMap, LocalProcMap, ModuleImports, Node, ParsingError, ProcedureAst,
+    ProcedureId, ProcedureName, ReExportedProcMap, StackSignature, Token, TokenStream,
     MAX_BODY_LEN, MAX_DOCS_LEN,
 };
 use vm_core::utils::{collections::Vec, string::ToString};
@@ -15,6 +18,7 @@ pub struct ParserContext<'a> {
     pub local_procs: LocalProcMap,
     pub reexported_procs: ReExportedProcMap,
     pub local_constants: LocalConstMap,
+    pub local_macros: LocalMacroMap,
 }
 
 impl ParserContext<'_> {
@@ -216,7 +220,7 @@ impl ParserContext<'_> {
     // PROCEDURE PARSERS
     // --------------------------------------------------------------------------------------------
 
-    /// Parse procedures in the source and store them in the program
+    /// Parse procedures and macros in the source and store them in the program
     pub fn parse_procedures(
         &mut self,
         tokens: &mut TokenStream,
@@ -226,6 +230,9 @@ impl ParserContext<'_> {
         while let Some(token) = tokens.read() {
             let is_reexport = match token.parts()[0] {
                 Token::EXPORT => {
+                    if token.parts().get(1) == Some(&Token::CONST) {
+                        return Err(ParsingError::const_invalid_scope(token));
+                    }
                     if !allow_export {
                         let proc_name = token.parts()[1];
                         return Err(ParsingError::proc_export_not_allowed(token, proc_name));
@@ -236,6 +243,19 @@ impl ParserContext<'_> {
                     // no validation needed, parse the procedure below
                     false
                 }
+                Token::MACRO => {
+                    // parse the macro and add it to the set of macros available to the code below
+                    let macro_start = tokens.pos();
+                    let (name, definition) = MacroDefinition::parse(tokens, &self.local_macros)?;
+                    if let Some(previous) = self.local_macros.get(&name) {
+                        let first_location = *previous.location();
+                        let token = tokens.read_at(macro_start).expect("no macro token");
+                        return Err(ParsingError::duplicate_macro_name(token, &name)
+                            .with_label(first_location, "first defined here"));
+                    }
+                    self.local_macros.insert(name, definition);
+                    continue;
+                }
                 _ => break,
             };
 
@@ -270,7 +290,7 @@ impl ParserContext<'_> {
         let header = tokens.read().expect("missing procedure header");
         let (name, num_locals, is_export) = header.parse_proc()?;
         if self.contains_proc_name(&name) {
-            return Err(ParsingError::duplicate_proc_name(header, name.as_str()));
+            return Err(self.duplicate_proc_name(header, &name));
         }
         let start = *header.location();
         tokens.advance();
@@ -290,6 +310,18 @@ impl ParserContext<'_> {
             None
         };
 
+        // parse the stack signature declared in the procedure header comment (if any)
+        let signature = match tokens.take_signature_at(proc_start) {
+            Some(signature) => {
+                let signature = StackSignature::parse(signature).map_err(|reason| {
+                    let token = tokens.read_at(proc_start).expect("no proc token");
+                    ParsingError::invalid_proc_signature(token, signature, reason)
+                })?;
+                Some(signature)
+            }
+            None => None,
+        };
+
         // parse procedure body
         let body = self.parse_body(tokens, false)?;
 
@@ -311,8 +343,12 @@ impl ParserContext<'_> {
 
         // build and return the procedure
         let (nodes, locations) = body.into_parts();
-        Ok(ProcedureAst::new(name, num_locals, nodes, is_export, docs)
-            .with_source_locations(locations, start))
+        let proc = ProcedureAst::new(name, num_locals, nodes, is_export, docs)
+            .with_source_locations(locations, start);
+        Ok(match signature {
+            Some(signature) => proc.with_signature(signature),
+            None => proc,
+        })
     }
 
     /// Parses procedure re-export from the token stream and adds it to the set of procedures
@@ -333,14 +369,14 @@ impl ParserContext<'_> {
         let header = tokens.read().expect("missing procedure header");
         let (proc_name, ref_name, module) = header.parse_reexported_proc()?;
         if self.contains_proc_name(&proc_name) {
-            return Err(ParsingError::duplicate_proc_name(header, proc_name.as_str()));
+            return Err(self.duplicate_proc_name(header, &proc_name));
         }
 
         // check if the module from which the procedure is re-exported was imported
         let module_path = self
             .import_info
             .get_module_path(module)
-            .ok_or(ParsingError::procedure_module_not_imported(header, module))?;
+            .ok_or_else(|| self.import_info.module_not_imported(header, module))?;
 
         // consume the `export` token
         tokens.advance();
@@ -407,11 +443,21 @@ impl ParserContext<'_> {
                 Token::USE => {
                     return Err(ParsingError::import_inside_body(token));
                 }
+                Token::MACRO => {
+                    return Err(ParsingError::macro_invalid_scope(token));
+                }
                 Token::EXPORT | Token::PROC | Token::BEGIN => {
                     // break out of the loop; whether this results in an error will be determined
                     // by the function which invoked parse_body()
                     break;
                 }
+                _ if macros::is_macro_invocation(token) => {
+                    let (expanded_nodes, expanded_locations) =
+                        self.expand_macro(token)?.into_parts();
+                    nodes.extend(expanded_nodes);
+                    locations.extend(expanded_locations);
+                    tokens.advance();
+                }
                 _ => {
                     locations.push(*token.location());
                     nodes.push(self.parse_op_token(token)?);
@@ -428,6 +474,47 @@ impl ParserContext<'_> {
         Ok(CodeBody::new(nodes).with_source_locations(locations))
     }
 
+    // MACRO EXPANSION
+    // --------------------------------------------------------------------------------------------
+
+    /// Expands a macro invocation into a code body.
+    ///
+    /// The tokens of the macro body, with the macro parameters substituted by the invocation
+    /// arguments, are parsed in the current context. The resulting nodes retain the source
+    /// locations of the macro body, and errors in the expanded code additionally reference the
+    /// invocation site.
+    ///
+    /// # Errors
+    /// Returns an error if the macro has not been declared before the invocation, the invocation
+    /// is malformed, or the expanded code could not be parsed.
+    fn expand_macro(&mut self, token: &Token) -> Result<CodeBody, ParsingError> {
+        let (name, args) = macros::parse_macro_invocation(token)?;
+        let body = self
+            .local_macros
+            .get(name)
+            .ok_or_else(|| {
+                let macro_names = self.local_macros.keys().map(|name| name.as_str());
+                ParsingError::undefined_macro(token, name).with_similar_name(name, macro_names)
+            })?
+            .instantiate(token, name, &args)?;
+
+        if body.is_empty() {
+            return Ok(CodeBody::default());
+        }
+
+        let (body_tokens, body_locations): (Vec<_>, Vec<_>) =
+            body.iter().map(|(token_str, location)| (token_str.as_str(), *location)).unzip();
+        let mut body_stream = TokenStream::from_tokens(body_tokens, body_locations);
+        let body = self
+            .parse_body(&mut body_stream, false)
+            .map_err(|err| err.in_macro_expansion(token, name))?;
+
+        // macro bodies are validated to contain only balanced code blocks when parsed
+        debug_assert!(body_stream.eof(), "macro body was not fully expanded");
+
+        Ok(body)
+    }
+
     // HELPER METHODS
     // --------------------------------------------------------------------------------------------
 
@@ -618,6 +705,9 @@ impl ParserContext<'_> {
             "dynexec" => simple_instruction(op, DynExec),
             "dyncall" => simple_instruction(op, DynCall),
 
+            // ----- events -----------------------------------------------------------------------
+            "emit" => sys_ops::parse_emit(op, &self.local_constants),
+
             // ----- constant statements ----------------------------------------------------------
             "const" => Err(ParsingError::const_invalid_scope(op)),
 
@@ -626,7 +716,7 @@ impl ParserContext<'_> {
             "debug" => debug::parse_debug(op),
 
             // ----- catch all --------------------------------------------------------------------
-            _ => Err(ParsingError::invalid_op(op)),
+            _ => Err(self.invalid_op(op)),
         }
     }
 
@@ -641,7 +731,11 @@ impl ParserContext<'_> {
     ) -> Result<u16, ParsingError> {
         self.local_procs
             .get(&proc_name)
-            .ok_or_else(|| ParsingError::undefined_local_proc(token, proc_name.as_ref()))
+            .ok_or_else(|| {
+                let proc_names = self.local_procs.keys().map(|name| name.as_ref());
+                ParsingError::undefined_local_proc(token, proc_name.as_ref())
+                    .with_similar_name(proc_name.as_ref(), proc_names)
+            })
             .map(|(index, _)| *index)
     }
 
@@ -650,8 +744,63 @@ impl ParserContext<'_> {
     fn contains_proc_name(&self, proc_name: &ProcedureName) -> bool {
         self.local_procs.contains_key(proc_name) || self.reexported_procs.contains_key(proc_name)
     }
+
+    // ERROR BUILDERS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns an error for a procedure declared under the name of a previously declared
+    /// procedure, pointing to the previous declaration if it is a local procedure.
+    fn duplicate_proc_name(&self, token: &Token, proc_name: &ProcedureName) -> ParsingError {
+        let err = ParsingError::duplicate_proc_name(token, proc_name.as_str());
+        match self.local_procs.get(proc_name) {
+            Some((_, proc)) => err.with_label(proc.start, "first defined here"),
+            None => err,
+        }
+    }
+
+    /// Returns an error for an unrecognized instruction, suggesting a similarly named instruction
+    /// or, if the instruction is the name of a local procedure, an invocation of this procedure.
+    fn invalid_op(&self, op: &Token) -> ParsingError {
+        let name = op.parts()[0];
+        let err = ParsingError::invalid_op(op);
+        if self.local_procs.keys().any(|proc_name| proc_name.as_ref() == name) {
+            err.with_help(&format!("to invoke procedure `{name}`, use `exec.{name}`"))
+        } else {
+            err.with_similar_name(name, INSTRUCTION_NAMES.iter().copied())
+        }
+    }
 }
 
+// INSTRUCTION NAMES
+// ================================================================================================
+
+/// Names of all instructions recognized by [ParserContext::parse_op_token()], used to suggest
+/// corrections for misspelled instructions and by tools completing instruction names. The names
+/// are listed in the order in which they are matched by the parser.
+#[rustfmt::skip]
+pub const INSTRUCTION_NAMES: &[&str] = &[
+    "assert", "assertz", "assert_eq", "assert_eqw", "add", "sub", "mul", "div", "neg", "inv",
+    "pow2", "exp", "not", "and", "or", "xor", "eq", "neq", "lt", "lte", "gt", "gte", "is_odd",
+    "eqw", "ext2add", "ext2sub", "ext2mul", "ext2div", "ext2neg", "ext2inv", "u32test", "u32testw",
+    "u32assert", "u32assert2", "u32assertw", "u32cast", "u32split", "u32checked_add",
+    "u32wrapping_add", "u32overflowing_add", "u32overflowing_add3", "u32wrapping_add3",
+    "u32checked_sub", "u32wrapping_sub", "u32overflowing_sub", "u32checked_mul", "u32wrapping_mul",
+    "u32overflowing_mul", "u32overflowing_madd", "u32wrapping_madd", "u32checked_div",
+    "u32unchecked_div", "u32checked_mod", "u32unchecked_mod", "u32checked_divmod",
+    "u32unchecked_divmod", "u32checked_and", "u32checked_or", "u32checked_xor", "u32checked_not",
+    "u32checked_shr", "u32unchecked_shr", "u32checked_shl", "u32unchecked_shl", "u32checked_rotr",
+    "u32unchecked_rotr", "u32checked_rotl", "u32unchecked_rotl", "u32checked_popcnt",
+    "u32unchecked_popcnt", "u32checked_eq", "u32checked_neq", "u32checked_lt", "u32unchecked_lt",
+    "u32checked_lte", "u32unchecked_lte", "u32checked_gt", "u32unchecked_gt", "u32checked_gte",
+    "u32unchecked_gte", "u32checked_min", "u32unchecked_min", "u32checked_max", "u32unchecked_max",
+    "drop", "dropw", "padw", "dup", "dupw", "swap", "swapw", "swapdw", "movup", "movupw", "movdn",
+    "movdnw", "cswap", "cswapw", "cdrop", "cdropw", "push", "sdepth", "locaddr", "caller", "clk",
+    "mem_load", "loc_load", "mem_loadw", "loc_loadw", "mem_store", "loc_store", "mem_storew",
+    "loc_storew", "mem_stream", "adv_pipe", "adv_push", "adv_loadw", "adv", "hash", "hmerge",
+    "hperm", "mtree_get", "mtree_set", "mtree_merge", "mtree_verify", "fri_ext2fold4", "exec",
+    "call", "syscall", "dynexec", "dyncall", "emit", "breakpoint", "debug",
+];
+
 // HELPER FUNCTIONS
 // ================================================================================================
 
@@ -1,12 +1,12 @@
 use super::{
-    parse_checked_param, parse_param_with_constant_lookup, Felt,
+    parse_checked_param, parse_param_with_constant_lookup, try_get_constant, ConstantValue, Felt,
     Instruction::*,
     LocalConstMap,
     Node::{self, Instruction},
-    ParsingError, Token, Vec, CONSTANT_LABEL_PARSER,
+    ParsingError, Token, Vec,
 };
 use crate::{StarkField, ADVICE_READ_LIMIT, HEX_CHUNK_SIZE, MAX_PUSH_INPUTS};
-use core::{convert::TryFrom, ops::RangeBounds};
+use core::convert::TryFrom;
 use vm_core::WORD_SIZE;
 
 /// Helper enum for endianness determination in the parsing functions.
@@ -45,16 +45,17 @@ pub fn parse_push(op: &Token, constants: &LocalConstMap) -> Result<Node, Parsing
                 }
                 // if we have many hex parameters without delimiter
                 Some(param_str) => parse_long_hex_param(op, param_str),
-                // if we have one decimal parameter
-                None => {
-                    let value = parse_non_hex_param_with_constants_lookup(
-                        op,
-                        constants,
-                        1,
-                        0..Felt::MODULUS,
-                    )?;
-                    build_push_one_instruction(value)
-                }
+                // if we have one decimal parameter or a constant
+                None => match try_get_constant(op, param_str, constants)? {
+                    Some(ConstantValue::Scalar(value)) => build_push_one_instruction(*value),
+                    Some(ConstantValue::Array(values)) => {
+                        build_push_many_instruction(values.iter().map(|&value| Ok(value)))
+                    }
+                    None => {
+                        let value = parse_checked_param(op, 1, 0..Felt::MODULUS)?;
+                        build_push_one_instruction(value)
+                    }
+                },
             }
         }
         // if we have many parameters (decimal or hex) separated by delimiters
@@ -250,42 +251,34 @@ pub fn parse_loc_storew(op: &Token, constants: &LocalConstMap) -> Result<Node, P
 // HELPER FUNCTIONS
 // ================================================================================================
 
-/// Parses a list of parameters (each of which could be in decimal or hexadecimal form) and returns
-/// an appropriate push instruction node.
+/// Parses a list of parameters (each of which could be in decimal or hexadecimal form, or be a
+/// reference to a constant) and returns an appropriate push instruction node.
+///
+/// Array constants are expanded into their values; the total number of values must not exceed
+/// MAX_PUSH_INPUTS.
 fn parse_param_list(op: &Token, constants: &LocalConstMap) -> Result<Node, ParsingError> {
-    let values =
-        op.parts().iter().enumerate().skip(1).map(|(param_idx, &param_str)| {
-            match param_str.strip_prefix("0x") {
-                Some(param_str) => parse_hex_value(op, param_str, param_idx, Endianness::Big),
-                None => parse_non_hex_param_with_constants_lookup(
-                    op,
-                    constants,
-                    param_idx,
-                    0..Felt::MODULUS,
-                ),
+    let mut values = Vec::with_capacity(op.num_parts() - 1);
+    for (param_idx, &param_str) in op.parts().iter().enumerate().skip(1) {
+        match param_str.strip_prefix("0x") {
+            Some(param_str) => {
+                values.push(parse_hex_value(op, param_str, param_idx, Endianness::Big)?)
             }
-        });
-
-    build_push_many_instruction(values)
-}
+            None => match try_get_constant(op, param_str, constants)? {
+                Some(value) => values.extend_from_slice(value.values()),
+                None => values.push(parse_checked_param(op, param_idx, 0..Felt::MODULUS)?),
+            },
+        }
+    }
 
-/// Parses a non hexadecimal parameter and returns the value. Takes as argument a constant map
-/// for constant lookup.
-fn parse_non_hex_param_with_constants_lookup<R: RangeBounds<u64>>(
-    op: &Token,
-    constants: &LocalConstMap,
-    param_idx: usize,
-    range: R,
-) -> Result<u64, ParsingError> {
-    let param_str = op.parts()[param_idx];
-    // if we have a valid constant label then try and fetch it
-    match CONSTANT_LABEL_PARSER.parse_label(param_str) {
-        Ok(_) => constants
-            .get(param_str)
-            .cloned()
-            .ok_or_else(|| ParsingError::const_not_found(op)),
-        Err(_) => parse_checked_param(op, param_idx, range),
+    if values.len() > MAX_PUSH_INPUTS {
+        return Err(ParsingError::invalid_param_with_reason(
+            op,
+            1,
+            &format!("push cannot take more than {MAX_PUSH_INPUTS} values"),
+        ));
     }
+
+    build_push_many_instruction(values.into_iter().map(Ok))
 }
 
 /// Parses a 64-character hex string into a word (4 field elements) and returns an appropriate push
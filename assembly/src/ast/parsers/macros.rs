@@ -0,0 +1,302 @@
+use super::{
+    BTreeMap, ParsingError, SourceLocation, String, ToString, Token, TokenStream, Vec,
+    PROCEDURE_LABEL_PARSER,
+};
+
+// CONSTANTS
+// ================================================================================================
+
+/// Prefix of a macro parameter reference inside a macro body (e.g., `dup.$i`).
+const PARAM_PREFIX: char = '$';
+
+/// Delimiter of macro parameters and arguments.
+const PARAM_DELIM: char = ',';
+
+/// Maximum number of parameters a macro can declare.
+const MAX_MACRO_PARAMS: usize = u8::MAX as usize;
+
+// TYPE ALIASES
+// ================================================================================================
+
+/// A map from the names of macros declared in a module or a program to their definitions.
+pub type LocalMacroMap = BTreeMap<String, MacroDefinition>;
+
+// MACRO DEFINITION
+// ================================================================================================
+
+/// Definition of a macro declared via a `macro.<name>(<params>)` statement.
+///
+/// A macro body is kept as a list of raw tokens together with their source locations. When the
+/// macro is invoked (e.g., `name(1,2)`), references to the macro parameters in the body (e.g.,
+/// `push.$k`) are substituted with the invocation arguments, and the resulting tokens are parsed
+/// in place of the invocation. Thus, a macro invocation has no effect on the shape of the MAST
+/// beyond the expanded code.
+///
+/// Macros are hygienic: a macro body can reference only its own parameters, and can invoke only
+/// the macros declared before it. The latter also guarantees that macro expansion terminates.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MacroDefinition {
+    params: Vec<String>,
+    body: Vec<(String, SourceLocation)>,
+    location: SourceLocation,
+}
+
+impl MacroDefinition {
+    // PARSER
+    // --------------------------------------------------------------------------------------------
+
+    /// Parses a macro declaration from the token stream and returns the name of the macro together
+    /// with its definition. The stream is advanced past the `end` token of the macro.
+    ///
+    /// The provided map contains the macros declared before this macro.
+    ///
+    /// # Errors
+    /// Returns an error if:
+    /// - The macro header is malformed or the macro name is invalid.
+    /// - The macro body references an undeclared parameter, invokes a macro which is not declared
+    ///   before this macro (including the macro itself), or contains statements which are not
+    ///   allowed inside code bodies (e.g., procedure declarations).
+    /// - The macro has no matching `end`.
+    pub fn parse(
+        tokens: &mut TokenStream,
+        declared_macros: &LocalMacroMap,
+    ) -> Result<(String, Self), ParsingError> {
+        let macro_start = tokens.pos();
+        let header = tokens.read().expect("missing macro header");
+        let (name, params) = parse_macro_header(header)?;
+        let location = *header.location();
+        tokens.advance();
+
+        // read the body tokens until the `end` token which matches the macro header
+        let mut body = Vec::new();
+        let mut depth = 0_usize;
+        loop {
+            let token = match tokens.read() {
+                Some(token) => token,
+                None => {
+                    let token = tokens.read_at(macro_start).expect("no macro token");
+                    return Err(ParsingError::unmatched_macro(token, &name));
+                }
+            };
+
+            match token.parts()[0] {
+                Token::IF | Token::WHILE | Token::REPEAT => depth += 1,
+                Token::END if depth == 0 => break,
+                Token::END => depth -= 1,
+                Token::ELSE if depth == 0 => return Err(ParsingError::dangling_else(token)),
+                Token::BEGIN
+                | Token::CONST
+                | Token::EXPORT
+                | Token::MACRO
+                | Token::PROC
+                | Token::USE => {
+                    return Err(ParsingError::invalid_macro_body(token, &name));
+                }
+                _ => (),
+            }
+
+            if is_macro_invocation(token) {
+                let invoked_name = token.parts()[0].split_once('(').map_or("", |(name, _)| name);
+                if !declared_macros.contains_key(invoked_name) {
+                    return Err(ParsingError::undeclared_macro_in_body(token, &name, invoked_name));
+                }
+            }
+
+            let token_str = token.to_string();
+            validate_param_references(token, &token_str, &params)?;
+            body.push((token_str, *token.location()));
+            tokens.advance();
+        }
+
+        // validate and consume the `end` token
+        tokens.read().expect("no end token").validate_end()?;
+        tokens.advance();
+
+        Ok((
+            name,
+            Self {
+                params,
+                body,
+                location,
+            },
+        ))
+    }
+
+    // PUBLIC ACCESSORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the location of the header of this macro declaration.
+    pub fn location(&self) -> &SourceLocation {
+        &self.location
+    }
+
+    // EXPANSION
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the tokens of this macro body with all parameter references substituted with the
+    /// provided arguments.
+    ///
+    /// # Errors
+    /// Returns an error if the number of arguments differs from the number of macro parameters.
+    pub fn instantiate(
+        &self,
+        token: &Token,
+        name: &str,
+        args: &[&str],
+    ) -> Result<Vec<(String, SourceLocation)>, ParsingError> {
+        if args.len() != self.params.len() {
+            return Err(ParsingError::invalid_macro_arity(
+                token,
+                name,
+                self.params.len(),
+                args.len(),
+            ));
+        }
+
+        Ok(self
+            .body
+            .iter()
+            .map(|(token_str, location)| (self.substitute(token_str, args), *location))
+            .collect())
+    }
+
+    // HELPER METHODS
+    // --------------------------------------------------------------------------------------------
+
+    /// Substitutes all parameter references in the provided token with the respective arguments.
+    ///
+    /// All references are expected to have been validated when the macro was parsed.
+    fn substitute(&self, token_str: &str, args: &[&str]) -> String {
+        let mut result = String::with_capacity(token_str.len());
+        let mut remainder = token_str;
+        while let Some(idx) = remainder.find(PARAM_PREFIX) {
+            result.push_str(&remainder[..idx]);
+            let (param, rest) = split_param_name(&remainder[idx + 1..]);
+            let param_idx = self
+                .params
+                .iter()
+                .position(|p| p == param)
+                .expect("macro parameter references are validated during parsing");
+            result.push_str(args[param_idx]);
+            remainder = rest;
+        }
+        result.push_str(remainder);
+        result
+    }
+}
+
+// MACRO INVOCATION
+// ================================================================================================
+
+/// Returns true if the provided token is a macro invocation (e.g., `name(1,2)`).
+pub fn is_macro_invocation(token: &Token) -> bool {
+    token.parts()[0].contains('(')
+}
+
+/// Parses a macro invocation token of the form `name(arg1,arg2,...)` into the macro name and the
+/// list of arguments.
+///
+/// Arguments can be numbers or names of constants; they cannot contain dots or parameter
+/// references.
+pub fn parse_macro_invocation<'a>(
+    token: &'a Token,
+) -> Result<(&'a str, Vec<&'a str>), ParsingError> {
+    if token.num_parts() > 1 {
+        return Err(ParsingError::invalid_macro_invocation(
+            token,
+            "macro arguments cannot contain dots",
+        ));
+    }
+
+    let (name, args) = split_signature(token.parts()[0]).ok_or_else(|| {
+        ParsingError::invalid_macro_invocation(token, "expected <name>(<arguments>)")
+    })?;
+
+    for arg in args.iter() {
+        if arg.is_empty() || !arg.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == ':')
+        {
+            let reason = format!("invalid macro argument '{arg}'");
+            return Err(ParsingError::invalid_macro_invocation(token, &reason));
+        }
+    }
+
+    Ok((name, args))
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Parses a `macro.<name>(<params>)` header into the macro name and the list of its parameters.
+fn parse_macro_header(token: &Token) -> Result<(String, Vec<String>), ParsingError> {
+    assert_eq!(Token::MACRO, token.parts()[0], "not a macro");
+    let signature = match token.num_parts() {
+        0 => unreachable!(),
+        1 => return Err(ParsingError::missing_param(token, "macro.<name>(<params>)")),
+        2 => token.parts()[1],
+        _ => return Err(ParsingError::extra_param(token)),
+    };
+
+    let (name, params) = split_signature(signature)
+        .ok_or_else(|| ParsingError::missing_param(token, "macro.<name>(<params>)"))?;
+
+    let name = PROCEDURE_LABEL_PARSER
+        .parse_label(name)
+        .map_err(|err| ParsingError::invalid_macro_name(token, err))?;
+
+    if params.len() > MAX_MACRO_PARAMS {
+        let reason = format!("a macro cannot have more than {MAX_MACRO_PARAMS} parameters");
+        return Err(ParsingError::invalid_macro_param(token, name, &reason));
+    }
+
+    let mut result: Vec<String> = Vec::with_capacity(params.len());
+    for param in params {
+        if let Err(err) = PROCEDURE_LABEL_PARSER.parse_label(param) {
+            return Err(ParsingError::invalid_macro_param(token, name, &err.to_string()));
+        }
+        if result.iter().any(|p| p == param) {
+            let reason = format!("duplicate parameter '{param}'");
+            return Err(ParsingError::invalid_macro_param(token, name, &reason));
+        }
+        result.push(param.to_string());
+    }
+
+    Ok((name.to_string(), result))
+}
+
+/// Splits a string of the form `name(a,b,c)` into the name and the list of comma-separated
+/// values. Returns None if the string is not of this form.
+fn split_signature(signature: &str) -> Option<(&str, Vec<&str>)> {
+    let (name, rest) = signature.split_once('(')?;
+    let values = rest.strip_suffix(')')?;
+    if values.is_empty() {
+        Some((name, Vec::new()))
+    } else {
+        Some((name, values.split(PARAM_DELIM).collect()))
+    }
+}
+
+/// Splits the provided string into a leading parameter name and the remainder of the string.
+fn split_param_name(value: &str) -> (&str, &str) {
+    let end = value
+        .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+        .unwrap_or(value.len());
+    value.split_at(end)
+}
+
+/// Makes sure that all parameter references in a macro body token refer to the declared macro
+/// parameters.
+fn validate_param_references(
+    token: &Token,
+    token_str: &str,
+    params: &[String],
+) -> Result<(), ParsingError> {
+    let mut remainder = token_str;
+    while let Some(idx) = remainder.find(PARAM_PREFIX) {
+        let (param, rest) = split_param_name(&remainder[idx + 1..]);
+        if !params.iter().any(|p| p == param) {
+            return Err(ParsingError::undefined_macro_param(token, param));
+        }
+        remainder = rest;
+    }
+    Ok(())
+}
@@ -1,10 +1,12 @@
 use super::{
-    bound_into_included_u64, AdviceInjectorNode, CodeBody, Deserializable, Felt, Instruction,
-    InvocationTarget, LabelError, LibraryPath, LocalConstMap, LocalProcMap, ModuleImports, Node,
-    ParsingError, ProcedureAst, ProcedureId, ProcedureName, ReExportedProcMap, RpoDigest,
-    SliceReader, StarkField, String, ToString, Token, TokenStream, Vec, MAX_BODY_LEN, MAX_DOCS_LEN,
-    MAX_LABEL_LEN, MAX_STACK_WORD_OFFSET,
+    bound_into_included_u64, AdviceInjectorNode, BTreeMap, CodeBody, ConstantResolver,
+    ConstantValue, Deserializable, ExportedConstMap, Felt, Instruction, InvocationTarget,
+    LabelError, LibraryPath, LocalConstMap, LocalProcMap, ModuleImports, Node, ParsingError,
+    ProcedureAst, ProcedureId, ProcedureName, ReExportedProcMap, RpoDigest, SliceReader,
+    SourceLocation, StackSignature, StarkField, String, ToString, Token, TokenStream, Vec,
+    MAX_BODY_LEN, MAX_DOCS_LEN, MAX_LABEL_LEN, MAX_STACK_WORD_OFFSET,
 };
+use crate::MAX_PUSH_INPUTS;
 use core::{fmt::Display, ops::RangeBounds};
 
 mod adv_ops;
@@ -19,7 +21,10 @@ mod constants;
 use constants::calculate_const_value;
 
 mod context;
-pub use context::ParserContext;
+pub use context::{ParserContext, INSTRUCTION_NAMES};
+
+mod macros;
+pub use macros::LocalMacroMap;
 
 mod labels;
 pub use labels::{
@@ -30,41 +35,93 @@ pub use labels::{
 // PARSERS FUNCTIONS
 // ================================================================================================
 
-/// Parses all `const` statements into a map which maps a const name to a value
-pub fn parse_constants(tokens: &mut TokenStream) -> Result<LocalConstMap, ParsingError> {
-    // instantiate new constant map for this module
+/// Parses all `const` and `export.const` statements into a map which maps a const name to a
+/// value, and a map of constants exported from the module.
+///
+/// Constants exported from the modules imported via `use` statements are fetched from the
+/// provided resolver and added to the map of available constants under their qualified names
+/// (e.g., `bar::FOO`).
+///
+/// # Errors
+/// Returns an error if:
+/// - A constant declaration is malformed or its value could not be computed.
+/// - A constant with the same name has already been declared.
+/// - A constant is exported and `allow_export` is false.
+pub fn parse_constants(
+    tokens: &mut TokenStream,
+    import_info: &ModuleImports,
+    resolver: Option<&dyn ConstantResolver>,
+    allow_export: bool,
+) -> Result<(LocalConstMap, ExportedConstMap), ParsingError> {
+    // instantiate new constant map for this module and populate it with the constants exported
+    // from imported modules
     let mut constants = LocalConstMap::new();
+    if let Some(resolver) = resolver {
+        for (module_name, module_path) in import_info.imported_modules() {
+            let imported_constants = resolver.get_exported_constants(module_path);
+            for (name, value) in imported_constants.into_iter().flatten() {
+                constants.insert(format!("{module_name}{}{name}", LibraryPath::PATH_DELIM), value);
+            }
+        }
+    }
+    let mut exported_constants = ExportedConstMap::new();
+    let mut declarations = BTreeMap::<String, SourceLocation>::new();
 
     // iterate over tokens until we find a const declaration
     while let Some(token) = tokens.read() {
-        match token.parts()[0] {
-            Token::CONST => {
-                let (name, value) = parse_constant(token, &constants)?;
-
-                if constants.contains_key(&name) {
-                    return Err(ParsingError::duplicate_const_name(token, &name));
+        let is_export = match token.parts() {
+            [Token::CONST, ..] => false,
+            [Token::EXPORT, Token::CONST, ..] => {
+                if !allow_export {
+                    return Err(ParsingError::const_export_not_allowed(token));
                 }
-
-                constants.insert(name, value);
-                tokens.advance();
+                true
             }
             _ => break,
+        };
+
+        let (name, value) = parse_constant(token, is_export, &constants)?;
+
+        if constants.contains_key(&name) {
+            let err = ParsingError::duplicate_const_name(token, &name);
+            return Err(match declarations.get(&name) {
+                Some(location) => err.with_label(*location, "first defined here"),
+                None => err,
+            });
+        }
+        declarations.insert(name.clone(), *token.location());
+
+        if is_export {
+            exported_constants.insert(name.clone(), value.clone());
         }
+        constants.insert(name, value);
+        tokens.advance();
     }
 
-    Ok(constants)
+    Ok((constants, exported_constants))
 }
 
 /// Parses a constant token and returns a (constant_name, constant_value) tuple
-fn parse_constant(token: &Token, constants: &LocalConstMap) -> Result<(String, u64), ParsingError> {
-    match token.num_parts() {
-        0 => unreachable!(),
-        1 => Err(ParsingError::missing_param(token, "const.<name>=<value>")),
-        2 => {
-            let const_declaration: Vec<&str> = token.parts()[1].split('=').collect();
+fn parse_constant(
+    token: &Token,
+    is_export: bool,
+    constants: &LocalConstMap,
+) -> Result<(String, ConstantValue), ParsingError> {
+    // skip the `export` part of exported constant declarations
+    let num_prefix_parts = if is_export { 2 } else { 1 };
+    let declaration_format = if is_export {
+        "export.const.<name>=<value>"
+    } else {
+        "const.<name>=<value>"
+    };
+
+    match token.num_parts() - num_prefix_parts {
+        0 => Err(ParsingError::missing_param(token, declaration_format)),
+        1 => {
+            let const_declaration: Vec<&str> = token.parts()[num_prefix_parts].split('=').collect();
             match const_declaration.len() {
                 0 => unreachable!(),
-                1 => Err(ParsingError::missing_param(token, "const.<name>=<value>")),
+                1 => Err(ParsingError::missing_param(token, declaration_format)),
                 2 => {
                     let name = CONSTANT_LABEL_PARSER
                         .parse_label(const_declaration[0])
@@ -82,8 +139,40 @@ fn parse_constant(token: &Token, constants: &LocalConstMap) -> Result<(String, u
 // HELPER FUNCTIONS
 // ================================================================================================
 
+/// Returns true if `name` is a valid reference to a constant, i.e., if it is either a valid name
+/// of a local constant (e.g., `FOO`) or a name of a constant qualified with the name of an
+/// imported module (e.g., `bar::FOO`).
+fn is_constant_reference(name: &str) -> bool {
+    match name.split_once(LibraryPath::PATH_DELIM) {
+        Some((module_name, const_name)) => {
+            NAMESPACE_LABEL_PARSER.parse_label(module_name).is_ok()
+                && CONSTANT_LABEL_PARSER.parse_label(const_name).is_ok()
+        }
+        None => CONSTANT_LABEL_PARSER.parse_label(name).is_ok(),
+    }
+}
+
+/// If `const_name` is a valid reference to a constant, returns the value of this constant or an
+/// error if the constant does not exist in set of available constants.
+///
+/// If `const_name` is not a valid constant reference, returns None.
+fn try_get_constant<'a>(
+    op: &Token,
+    const_name: &str,
+    constants: &'a LocalConstMap,
+) -> Result<Option<&'a ConstantValue>, ParsingError> {
+    if is_constant_reference(const_name) {
+        constants
+            .get(const_name)
+            .ok_or_else(|| ParsingError::const_not_found(op))
+            .map(Some)
+    } else {
+        Ok(None)
+    }
+}
+
 /// If `constant_name` is a valid constant name, returns the value of this constant or an error if
-/// the constant does not exist in set of available constants.
+/// the constant does not exist in set of available constants or is not a single value.
 ///
 /// If `constant_name` is not a valid constant name, returns None.
 fn try_get_constant_value(
@@ -91,20 +180,51 @@ fn try_get_constant_value(
     const_name: &str,
     constants: &LocalConstMap,
 ) -> Result<Option<u64>, ParsingError> {
-    match CONSTANT_LABEL_PARSER.parse_label(const_name) {
-        Ok(_) => constants
-            .get(const_name)
-            .ok_or_else(|| ParsingError::const_not_found(op))
-            .map(|v| Some(*v)),
-        Err(_) => Ok(None),
+    match try_get_constant(op, const_name, constants)? {
+        Some(value) => value
+            .as_scalar()
+            .ok_or_else(|| ParsingError::const_not_scalar(op, const_name))
+            .map(Some),
+        None => Ok(None),
     }
 }
 
 /// Parses a constant value and ensures it falls within bounds specified by the caller.
+///
+/// The value can be either a single value expression (e.g., `2*FOO+1`), a reference to another
+/// constant, or a list of single value expressions enclosed in square brackets (e.g., `[1,2,FOO]`).
 fn parse_const_value(
     op: &Token,
     const_value: &str,
     constants: &LocalConstMap,
+) -> Result<ConstantValue, ParsingError> {
+    if let Some(elements) = const_value.strip_prefix('[').and_then(|v| v.strip_suffix(']')) {
+        let values = elements
+            .split(',')
+            .map(|element| parse_scalar_const_value(op, element, constants))
+            .collect::<Result<Vec<_>, _>>()?;
+        if values.len() > MAX_PUSH_INPUTS {
+            let reason =
+                format!("array constant cannot contain more than {MAX_PUSH_INPUTS} values");
+            return Err(ParsingError::invalid_const_value(op, const_value, &reason));
+        }
+        return Ok(ConstantValue::Array(values));
+    }
+
+    // a reference to an array constant makes this constant an array as well
+    if let Some(value @ ConstantValue::Array(_)) = try_get_constant(op, const_value, constants)? {
+        return Ok(value.clone());
+    }
+
+    parse_scalar_const_value(op, const_value, constants).map(ConstantValue::Scalar)
+}
+
+/// Parses a single value constant expression and ensures that the result is a valid field
+/// element.
+fn parse_scalar_const_value(
+    op: &Token,
+    const_value: &str,
+    constants: &LocalConstMap,
 ) -> Result<u64, ParsingError> {
     let result = match const_value.parse::<u64>() {
         Ok(value) => value,
@@ -1,5 +1,5 @@
 use super::{
-    parse_error_code,
+    parse_error_code, parse_param_with_constant_lookup,
     Instruction::*,
     LocalConstMap,
     Node::{self, Instruction},
@@ -97,3 +97,21 @@ pub fn parse_assert_eqw(op: &Token, constants: &LocalConstMap) -> Result<Node, P
         _ => Err(ParsingError::extra_param(op)),
     }
 }
+
+/// Returns `Emit` instruction node with the specified event ID.
+///
+/// # Errors
+/// Returns an error if the instruction token contains a wrong number of parameters, or if
+/// the provided parameter is not a u32 value.
+pub fn parse_emit(op: &Token, constants: &LocalConstMap) -> Result<Node, ParsingError> {
+    debug_assert_eq!(op.parts()[0], "emit");
+    match op.num_parts() {
+        0 => unreachable!(),
+        1 => Err(ParsingError::missing_param(op, "emit.<event_id>")),
+        2 => {
+            let event_id = parse_param_with_constant_lookup::<u32>(op, 1, constants)?;
+            Ok(Instruction(Emit(event_id)))
+        }
+        _ => Err(ParsingError::extra_param(op)),
+    }
+}
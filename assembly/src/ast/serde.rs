@@ -10,23 +10,34 @@ use super::{ByteReader, ByteWriter, Deserializable, DeserializationError, Serial
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub struct AstSerdeOptions {
     pub serialize_imports: bool,
+    pub serialize_signatures: bool,
 }
 
 impl AstSerdeOptions {
+    /// Returns options with the specified serialization of imports; procedure signatures are
+    /// serialized.
     pub fn new(serialize_imports: bool) -> Self {
-        Self { serialize_imports }
+        Self {
+            serialize_imports,
+            serialize_signatures: true,
+        }
     }
 }
 
 impl Serializable for AstSerdeOptions {
     fn write_into<W: ByteWriter>(&self, target: &mut W) {
         target.write_bool(self.serialize_imports);
+        target.write_bool(self.serialize_signatures);
     }
 }
 
 impl Deserializable for AstSerdeOptions {
     fn read_from<R: ByteReader>(source: &mut R) -> Result<Self, DeserializationError> {
         let serialize_imports = source.read_bool()?;
-        Ok(Self::new(serialize_imports))
+        let serialize_signatures = source.read_bool()?;
+        Ok(Self {
+            serialize_imports,
+            serialize_signatures,
+        })
     }
 }
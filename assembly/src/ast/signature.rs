@@ -0,0 +1,124 @@
+use super::{ByteReader, ByteWriter, Deserializable, DeserializationError, Serializable};
+use core::fmt;
+
+// CONSTANTS
+// ================================================================================================
+
+/// Delimiter between the inputs and the outputs of a stack signature.
+const SIGNATURE_DELIM: &str = "--";
+
+// STACK SIGNATURE
+// ================================================================================================
+
+/// Declared stack effect of a procedure: the number of stack elements the procedure consumes and
+/// the number of elements it leaves on the stack in their place.
+///
+/// A signature is declared via a comment on the procedure header line, listing the names of the
+/// input and output elements separated by `--`, e.g.:
+///
+/// ```masm
+/// export.foo.2 # (a b -- c)
+/// ```
+///
+/// A side of the signature can also be specified by a single number, e.g., `(2 -- 1)`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StackSignature {
+    num_inputs: u16,
+    num_outputs: u16,
+}
+
+impl StackSignature {
+    // CONSTRUCTORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns a new [StackSignature] instantiated with the specified number of inputs and
+    /// outputs.
+    pub const fn new(num_inputs: u16, num_outputs: u16) -> Self {
+        Self {
+            num_inputs,
+            num_outputs,
+        }
+    }
+
+    /// Parses a signature of the form `(<inputs> -- <outputs>)`.
+    ///
+    /// Each side of the signature is either a whitespace-separated list of element names, or a
+    /// single number specifying the number of elements.
+    ///
+    /// # Errors
+    /// Returns an error message if the signature is malformed.
+    pub fn parse(signature: &str) -> Result<Self, &'static str> {
+        let signature = signature
+            .strip_prefix('(')
+            .and_then(|s| s.strip_suffix(')'))
+            .ok_or("signature must be enclosed in parentheses")?;
+        let (inputs, outputs) = signature
+            .split_once(SIGNATURE_DELIM)
+            .ok_or("inputs and outputs must be separated by '--'")?;
+        if outputs.contains(SIGNATURE_DELIM) {
+            return Err("signature can contain only one '--' delimiter");
+        }
+
+        Ok(Self {
+            num_inputs: parse_signature_side(inputs)?,
+            num_outputs: parse_signature_side(outputs)?,
+        })
+    }
+
+    // PUBLIC ACCESSORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the number of stack elements consumed by the procedure.
+    pub const fn num_inputs(&self) -> u16 {
+        self.num_inputs
+    }
+
+    /// Returns the number of stack elements produced by the procedure.
+    pub const fn num_outputs(&self) -> u16 {
+        self.num_outputs
+    }
+
+    /// Returns the net change of the stack depth caused by executing the procedure.
+    pub const fn stack_delta(&self) -> i32 {
+        self.num_outputs as i32 - self.num_inputs as i32
+    }
+}
+
+impl fmt::Display for StackSignature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "({} {SIGNATURE_DELIM} {})", self.num_inputs, self.num_outputs)
+    }
+}
+
+impl Serializable for StackSignature {
+    fn write_into<W: ByteWriter>(&self, target: &mut W) {
+        target.write_u16(self.num_inputs);
+        target.write_u16(self.num_outputs);
+    }
+}
+
+impl Deserializable for StackSignature {
+    fn read_from<R: ByteReader>(source: &mut R) -> Result<Self, DeserializationError> {
+        let num_inputs = source.read_u16()?;
+        let num_outputs = source.read_u16()?;
+        Ok(Self::new(num_inputs, num_outputs))
+    }
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Returns the number of elements specified by one side of a signature.
+fn parse_signature_side(side: &str) -> Result<u16, &'static str> {
+    let mut names = side.split_whitespace().peekable();
+    match (names.next(), names.peek()) {
+        (None, _) => Ok(0),
+        (Some(name), None) if name.starts_with(|c: char| c.is_ascii_digit()) => {
+            name.parse::<u16>().map_err(|_| "invalid number of stack elements")
+        }
+        (Some(_), _) => {
+            let num_names = 1 + names.count();
+            u16::try_from(num_names).map_err(|_| "too many stack elements")
+        }
+    }
+}
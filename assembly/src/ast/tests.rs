@@ -1,7 +1,7 @@
 use super::{
-    AstSerdeOptions, BTreeMap, CodeBody, Felt, Instruction, LocalProcMap, ModuleAst, Node,
-    ParsingError, ProcedureAst, ProcedureId, ProcedureName, ProgramAst, SourceLocation, String,
-    ToString, Token,
+    AstSerdeOptions, BTreeMap, CodeBody, ConstantValue, Felt, Instruction, LocalProcMap, ModuleAst,
+    Node, ParsingError, ProcedureAst, ProcedureId, ProcedureName, ProgramAst, SourceLocation,
+    StackSignature, String, ToString, Token,
 };
 use vm_core::utils::SliceReader;
 
@@ -241,6 +241,22 @@ fn test_ast_parsing_adv_injection() {
     assert_program_output(source, BTreeMap::new(), nodes);
 }
 
+#[test]
+fn test_ast_parsing_emit() {
+    let source = "\
+    const.NOTE_CONSUMED=7
+    begin
+        emit.1 emit.NOTE_CONSUMED
+    end";
+    let nodes: Vec<Node> =
+        vec![Node::Instruction(Instruction::Emit(1)), Node::Instruction(Instruction::Emit(7))];
+    assert_program_output(source, BTreeMap::new(), nodes);
+
+    assert!(ProgramAst::parse("begin emit end").is_err());
+    assert!(ProgramAst::parse("begin emit.1.2 end").is_err());
+    assert!(ProgramAst::parse("begin emit.4294967296 end").is_err());
+}
+
 #[test]
 fn test_ast_parsing_use() {
     let source = "\
@@ -255,6 +271,65 @@ fn test_ast_parsing_use() {
     assert_program_output(source, procedures, nodes);
 }
 
+#[test]
+fn test_ast_parsing_exported_constants() {
+    let source = "\
+    const.SIZE=4
+    export.const.ROOT=[1,2,3,SIZE]
+    export.const.OFFSET=0x10+SIZE
+
+    export.foo
+        push.ROOT
+    end";
+    let module = ModuleAst::parse(source).unwrap();
+    let expected = BTreeMap::from([
+        ("OFFSET".to_string(), ConstantValue::Scalar(20)),
+        ("ROOT".to_string(), ConstantValue::Array(vec![1, 2, 3, 4])),
+    ]);
+    assert_eq!(&expected, module.exported_constants());
+
+    // exported constants must be declared before procedures
+    let source = "\
+    export.foo
+        push.1
+    end
+    export.const.ROOT=1";
+    assert!(ModuleAst::parse(source).is_err());
+
+    // array constants cannot contain more than 16 values
+    let source = "export.const.ROOT=[1,2,3,4,5,6,7,8,9,10,11,12,13,14,15,16,17]";
+    assert!(ModuleAst::parse(source).is_err());
+}
+
+#[test]
+fn test_ast_parsing_proc_signature() {
+    let source = "\
+    proc.foo.1 # (a b -- c)
+        add
+    end
+    # a regular comment
+    export.bar # (2 -- )
+        drop drop
+    end
+    export.baz # not a signature
+        push.1
+    end";
+    let module = ModuleAst::parse(source).unwrap();
+    let signatures: Vec<_> = module.procs().iter().map(|proc| proc.signature).collect();
+    let expected = vec![Some(StackSignature::new(2, 1)), Some(StackSignature::new(2, 0)), None];
+    assert_eq!(expected, signatures);
+
+    let source = "\
+    export.foo # (a b -- c -- d)
+        add
+    end";
+    let err = ModuleAst::parse(source).unwrap_err();
+    assert_eq!(
+        "invalid procedure stack signature '(a b -- c -- d)': signature can contain only one '--' delimiter",
+        err.message()
+    );
+}
+
 #[test]
 fn test_ast_parsing_module_nested_if() {
     let source = "\
@@ -473,6 +548,109 @@ fn parsed_while_if_body() {
     assert_eq!(body, expected);
 }
 
+#[test]
+fn parsed_macro_body() {
+    let source = "\
+    macro.dup_add(i,k)
+        dup.$i
+        if.true
+            push.$k
+        end
+        add
+    end
+
+    begin
+        push.1
+        dup_add(2,3)
+        mul
+    end
+    ";
+
+    let body = ProgramAst::parse(source).unwrap().body;
+
+    // expanded nodes retain the source locations of the macro body
+    let expected = CodeBody::new([
+        Node::Instruction(Instruction::PushU8(1)),
+        Node::Instruction(Instruction::Dup2),
+        Node::IfElse {
+            true_case: CodeBody::new([Node::Instruction(Instruction::PushU8(3))])
+                .with_source_locations([SourceLocation::new(4, 13), SourceLocation::new(5, 9)]),
+            false_case: CodeBody::default(),
+        },
+        Node::Instruction(Instruction::Add),
+        Node::Instruction(Instruction::Mul),
+    ])
+    .with_source_locations([
+        SourceLocation::new(10, 9),
+        SourceLocation::new(2, 9),
+        SourceLocation::new(3, 9),
+        SourceLocation::new(6, 9),
+        SourceLocation::new(12, 9),
+        SourceLocation::new(13, 5),
+    ]);
+
+    assert_eq!(body, expected);
+}
+
+#[test]
+fn test_ast_parsing_macro_errors() {
+    // macros must be declared before they are invoked
+    let source = "begin foo(1) end macro.foo(a) push.$a end";
+    let err = ProgramAst::parse(source).unwrap_err();
+    assert_eq!("undefined macro: foo", err.message());
+
+    // macro bodies can reference only their own parameters
+    let source = "macro.foo(a) push.$b end begin foo(1) end";
+    let err = ProgramAst::parse(source).unwrap_err();
+    assert_eq!("undefined macro parameter '$b' used in `push.$b`", err.message());
+
+    // the number of arguments must match the number of parameters
+    let source = "macro.foo(a,b) push.$a.$b end begin foo(1) end";
+    let err = ProgramAst::parse(source).unwrap_err();
+    assert_eq!(
+        "macro 'foo' expects 2 arguments, but 1 were provided in `foo(1)`",
+        err.message()
+    );
+
+    // macros cannot invoke themselves
+    let source = "macro.a() a() end begin a() end";
+    let err = ProgramAst::parse(source).unwrap_err();
+    assert_eq!("macro 'a' invokes macro 'a' which is not declared before it", err.message());
+
+    // macros can invoke only the macros declared before them, thus cannot be mutually recursive
+    let source = "macro.a() b() end macro.b() a() end begin a() end";
+    let err = ProgramAst::parse(source).unwrap_err();
+    assert_eq!("macro 'a' invokes macro 'b' which is not declared before it", err.message());
+    assert_eq!(&SourceLocation::new(1, 11), err.location());
+
+    // macros cannot be declared twice
+    let source = "macro.foo() add end macro.foo() mul end begin foo() end";
+    let err = ProgramAst::parse(source).unwrap_err();
+    assert_eq!("duplicate macro name: foo", err.message());
+    assert_eq!(&SourceLocation::new(1, 21), err.location());
+
+    // macros cannot contain procedure declarations
+    let source = "macro.foo() proc.bar add end end begin foo() end";
+    let err = ProgramAst::parse(source).unwrap_err();
+    assert_eq!("`proc.bar` is not allowed in the body of macro 'foo'", err.message());
+
+    // macros cannot be declared inside code bodies
+    let source = "begin macro.foo() add end end";
+    assert!(ProgramAst::parse(source).is_err());
+
+    // errors in expanded code point to the macro body and reference the invocation
+    let source = "\
+    macro.foo(a)
+        dup.$a
+    end
+    begin
+        foo(16)
+    end";
+    let err = ProgramAst::parse(source).unwrap_err();
+    assert_eq!(&SourceLocation::new(2, 9), err.location());
+    assert!(err.message().ends_with("(in expansion of macro 'foo' at [5:9])"));
+}
+
 // PROCEDURE IMPORTS
 // ================================================================================================
 
@@ -755,6 +933,90 @@ fn test_ast_parsing_module_docs_fail() {
         .expect_err("Procedure comment is not immediately followed by a procedure declaration.");
 }
 
+// DIAGNOSTICS
+// ================================================================================================
+
+#[test]
+fn test_ast_parsing_error_labels() {
+    fn labels(err: &ParsingError) -> Vec<(SourceLocation, &str)> {
+        err.labels().iter().map(|label| (*label.location(), label.message())).collect()
+    }
+
+    // duplicate procedures point to the first declaration
+    let source = "proc.foo add end\nproc.bar mul end\nproc.foo sub end\nbegin exec.foo end";
+    let err = ProgramAst::parse(source).unwrap_err();
+    assert_eq!(&SourceLocation::new(3, 1), err.location());
+    assert_eq!(vec![(SourceLocation::new(1, 1), "first defined here")], labels(&err));
+
+    // duplicate constants point to the first declaration
+    let source = "const.A=1\nconst.B=2\nconst.A=3\nbegin push.A end";
+    let err = ProgramAst::parse(source).unwrap_err();
+    assert_eq!(vec![(SourceLocation::new(1, 1), "first defined here")], labels(&err));
+
+    // duplicate macros point to the first declaration
+    let source = "macro.foo() add end\nmacro.foo() mul end\nbegin foo() end";
+    let err = ProgramAst::parse(source).unwrap_err();
+    assert_eq!(vec![(SourceLocation::new(1, 1), "first defined here")], labels(&err));
+
+    // duplicate imports point to the first import of the module
+    let source = "use.std::math::u64\nuse.std::crypto::hashes::blake3\nuse.std::math::u64\n\
+        begin exec.u64::checked_add end";
+    let err = ProgramAst::parse(source).unwrap_err();
+    assert_eq!(&SourceLocation::new(3, 1), err.location());
+    assert_eq!(vec![(SourceLocation::new(1, 1), "first imported here")], labels(&err));
+
+    // errors in expanded macros point to the invocation
+    let source = "macro.foo() push.1 bar end\nbegin\n    foo()\nend";
+    let err = ProgramAst::parse(source).unwrap_err();
+    assert_eq!(&SourceLocation::new(1, 20), err.location());
+    assert_eq!(
+        vec![(SourceLocation::new(3, 5), "in this invocation of macro 'foo'")],
+        labels(&err)
+    );
+}
+
+#[test]
+fn test_ast_parsing_error_suggestions() {
+    // misspelled instructions
+    let source = "begin push.1 dupp end";
+    let err = ProgramAst::parse(source).unwrap_err();
+    assert_eq!(Some("did you mean `dup`?"), err.help());
+
+    let source = "begin push.1.2 u32checked_ad end";
+    let err = ProgramAst::parse(source).unwrap_err();
+    assert_eq!(Some("did you mean `u32checked_add`?"), err.help());
+
+    // instructions which are not similar to any other instruction
+    let source = "begin push.1 frobnicate end";
+    let err = ProgramAst::parse(source).unwrap_err();
+    assert_eq!(None, err.help());
+
+    // local procedures used as instructions
+    let source = "proc.foo add end begin push.1.2 foo end";
+    let err = ProgramAst::parse(source).unwrap_err();
+    assert_eq!(Some("to invoke procedure `foo`, use `exec.foo`"), err.help());
+
+    // misspelled local procedures
+    let source = "proc.compute add end begin push.1.2 exec.compte end";
+    let err = ProgramAst::parse(source).unwrap_err();
+    assert_eq!("undefined local procedure: compte", err.message());
+    assert_eq!(Some("did you mean `compute`?"), err.help());
+
+    // misspelled macros
+    let source = "macro.double() dup add end begin push.1 doubel() end";
+    let err = ProgramAst::parse(source).unwrap_err();
+    assert_eq!(Some("did you mean `double`?"), err.help());
+
+    // misspelled modules point to the import of the similar module
+    let source = "use.std::math::u64\nbegin\n    exec.u46::checked_add\nend";
+    let err = ProgramAst::parse(source).unwrap_err();
+    assert_eq!("module 'u46' was not imported", err.message());
+    assert_eq!(Some("did you mean `u64`?"), err.help());
+    assert_eq!(1, err.labels().len());
+    assert_eq!(&SourceLocation::new(1, 1), err.labels()[0].location());
+    assert_eq!("module `u64` imported here", err.labels()[0].message());
+}
+
 // SERIALIZATION AND DESERIALIZATION TESTS
 // ================================================================================================
 
@@ -764,6 +1026,12 @@ fn test_ast_program_serde_simple() {
     assert_correct_program_serialization(source, true);
 }
 
+#[test]
+fn test_ast_program_serde_emit() {
+    let source = "begin push.1 emit.3 emit.4294967295 end";
+    assert_correct_program_serialization(source, true);
+}
+
 #[test]
 fn test_ast_program_serde_local_procs() {
     let source = "\
@@ -876,7 +1144,9 @@ fn assert_parsing_line_invalid_op() {
     end";
     let err = ProgramAst::parse(source).err().unwrap();
     let location = SourceLocation::new(28, 13);
-    assert_eq!(err, ParsingError::invalid_op(&Token::new("u32overflowing_mulx", location)));
+    let expected = ParsingError::invalid_op(&Token::new("u32overflowing_mulx", location))
+        .with_help("did you mean `u32overflowing_mul`?");
+    assert_eq!(err, expected);
 }
 
 #[test]
@@ -951,6 +1221,46 @@ fn test_ast_module_serde_imports_not_serialized() {
     assert_correct_module_serialization(source, false);
 }
 
+#[test]
+fn test_ast_module_serde_exported_constants() {
+    let source = "\
+    use.std::math::u64
+
+    export.const.BASE=1024
+    export.const.ROOT=[1,2,3,4]
+
+    export.foo
+        push.ROOT
+        exec.u64::checked_add
+    end";
+    assert_correct_module_serialization(source, true);
+    assert_correct_module_serialization(source, false);
+}
+
+#[test]
+fn test_ast_module_serde_signatures() {
+    let source = "\
+    export.foo # (a b -- c)
+        add
+    end
+    export.bar.2
+        push.1
+    end";
+    assert_correct_module_serialization(source, true);
+
+    // signatures are dropped if their serialization is disabled
+    let module = ModuleAst::parse(source).unwrap();
+    let options = AstSerdeOptions {
+        serialize_imports: true,
+        serialize_signatures: false,
+    };
+    let deserialized = ModuleAst::from_bytes(&module.to_bytes(options)).unwrap();
+    assert!(deserialized.procs().iter().all(|proc| proc.signature.is_none()));
+    let mut expected = clear_procs_loc_module(module);
+    expected.local_procs[0].signature = None;
+    assert_eq!(expected, deserialized);
+}
+
 fn assert_program_output(source: &str, procedures: LocalProcMap, body: Vec<Node>) {
     let program = ProgramAst::parse(source).unwrap();
     assert_eq!(program.body.nodes(), body);
@@ -0,0 +1,267 @@
+use super::{tokens::SourceLocation, String, ToString, Vec};
+use core::fmt;
+
+#[cfg(test)]
+mod tests;
+
+// DIAGNOSTIC
+// ================================================================================================
+
+/// A description of an error in Miden assembly source code which can be rendered together with
+/// the relevant excerpts of the source code.
+///
+/// A diagnostic consists of a message, a primary location at which the error occurred, a set of
+/// secondary labels pointing to other relevant locations (e.g., to the first definition of a
+/// duplicated name), and an optional help message. When rendered, each location is underlined in
+/// an excerpt of the source line containing it:
+/// ```text
+/// error: duplicate procedure name: foo
+///  --> main.masm:4:1
+///   |
+/// 1 | proc.foo
+///   | -------- first defined here
+/// ...
+/// 4 | proc.foo
+///   | ^^^^^^^^
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    message: String,
+    location: SourceLocation,
+    labels: Vec<Label>,
+    help: Option<String>,
+}
+
+impl Diagnostic {
+    // CONSTRUCTORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns a new [Diagnostic] with the specified message and primary location.
+    pub fn new(message: &str, location: SourceLocation) -> Self {
+        Self {
+            message: message.to_string(),
+            location,
+            labels: Vec::new(),
+            help: None,
+        }
+    }
+
+    /// Attaches the specified secondary labels to this diagnostic.
+    pub fn with_labels(mut self, labels: &[Label]) -> Self {
+        self.labels.extend_from_slice(labels);
+        self
+    }
+
+    /// Attaches a help message to this diagnostic.
+    pub fn with_help(mut self, help: &str) -> Self {
+        self.help = Some(help.to_string());
+        self
+    }
+
+    // PUBLIC ACCESSORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the message of this diagnostic.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// Returns the location at which the described error occurred.
+    pub const fn location(&self) -> &SourceLocation {
+        &self.location
+    }
+
+    /// Returns the secondary labels of this diagnostic.
+    pub fn labels(&self) -> &[Label] {
+        &self.labels
+    }
+
+    /// Returns the help message of this diagnostic, if any.
+    pub fn help(&self) -> Option<&str> {
+        self.help.as_deref()
+    }
+
+    // RENDERING
+    // --------------------------------------------------------------------------------------------
+
+    /// Renders this diagnostic against the provided source code of the file at the specified path.
+    ///
+    /// The item at the primary location is underlined with carets, and the items at the locations
+    /// of secondary labels are underlined with dashes followed by the label message. Locations
+    /// which do not exist in the provided source are omitted from the output.
+    pub fn render(&self, path: &str, source: &str) -> String {
+        let lines = source.lines().collect::<Vec<_>>();
+
+        // collect the annotations on the lines of the source; the primary annotation goes first,
+        // so that it is rendered above secondary annotations pointing to the same line
+        let mut annotations = Vec::with_capacity(self.labels.len() + 1);
+        annotations.push((self.location, '^', ""));
+        for label in self.labels.iter() {
+            annotations.push((label.location, '-', label.message.as_str()));
+        }
+        annotations.retain(|(location, ..)| {
+            location.line() >= 1 && location.line() as usize <= lines.len()
+        });
+        annotations.sort_by_key(|(location, ..)| location.line());
+
+        let gutter_width = annotations
+            .iter()
+            .map(|(location, ..)| location.line().to_string().len())
+            .max()
+            .unwrap_or(1);
+        let padding = " ".repeat(gutter_width);
+
+        let mut output = Vec::new();
+        output.push(format!("error: {}", self.message));
+        output.push(format!(
+            "{padding}--> {path}:{}:{}",
+            self.location.line(),
+            self.location.column()
+        ));
+
+        if !annotations.is_empty() {
+            output.push(format!("{padding} |"));
+        }
+        let mut prev_line = None;
+        for (location, marker, message) in annotations {
+            let line_number = location.line();
+            let line = lines[line_number as usize - 1];
+            if prev_line != Some(line_number) {
+                if prev_line.is_some_and(|prev| prev + 1 < line_number) {
+                    output.push("...".to_string());
+                }
+                output.push(format!("{line_number:>gutter_width$} | {line}"));
+                prev_line = Some(line_number);
+            }
+
+            let (indent, len) = underline_span(line, location.column());
+            let underline = marker.to_string().repeat(len);
+            let annotation = format!("{padding} | {indent}{underline} {message}");
+            output.push(annotation.trim_end().to_string());
+        }
+
+        if let Some(ref help) = self.help {
+            output.push(format!("{padding} |"));
+            output.push(format!("{padding} = help: {help}"));
+        }
+
+        output.join("\n")
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "error at {}: {}", self.location, self.message)?;
+        if let Some(ref help) = self.help {
+            write!(f, " (help: {help})")?;
+        }
+        Ok(())
+    }
+}
+
+// LABEL
+// ================================================================================================
+
+/// A secondary location of a [Diagnostic] together with a message explaining its relevance.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Label {
+    location: SourceLocation,
+    message: String,
+}
+
+impl Label {
+    /// Returns a new [Label] pointing to the specified location.
+    pub fn new(location: SourceLocation, message: &str) -> Self {
+        Self {
+            location,
+            message: message.to_string(),
+        }
+    }
+
+    /// Returns the location this label points to.
+    pub const fn location(&self) -> &SourceLocation {
+        &self.location
+    }
+
+    /// Returns the message of this label.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+// SUGGESTIONS
+// ================================================================================================
+
+/// Returns the candidate which is the most similar to the specified name, or None if none of the
+/// candidates is similar enough to be a plausible correction of a misspelling.
+///
+/// Similarity is measured by the edit distance between the names; the distance to the returned
+/// candidate is at most one third of the length of the name (but at least 1). Ties are resolved
+/// in favor of the candidate which comes first.
+pub fn find_similar_name<'a, I>(name: &str, candidates: I) -> Option<&'a str>
+where
+    I: IntoIterator<Item = &'a str>,
+{
+    let max_distance = (name.chars().count() / 3).max(1);
+    let mut best: Option<(usize, &str)> = None;
+    for candidate in candidates {
+        if candidate == name {
+            continue;
+        }
+        let distance = edit_distance(name, candidate);
+        if distance <= max_distance && best.map_or(true, |(best, _)| distance < best) {
+            best = Some((distance, candidate));
+        }
+    }
+    best.map(|(_, candidate)| candidate)
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Returns the whitespace preceding the specified 1-based column of the line, and the number of
+/// characters in the item starting at this column (i.e., up to the next whitespace character).
+///
+/// The whitespace is re-used from the line, so that tabs are preserved and the underline is
+/// aligned with the item.
+fn underline_span(line: &str, column: u32) -> (String, usize) {
+    let offset = column.saturating_sub(1) as usize;
+    let indent = line
+        .chars()
+        .take(offset)
+        .map(|c| if c == '\t' { '\t' } else { ' ' })
+        .collect::<String>();
+    let len = line.chars().skip(offset).take_while(|c| !c.is_whitespace()).count();
+    (indent, len.max(1))
+}
+
+/// Returns the edit distance between the specified strings, counting insertions, deletions,
+/// substitutions, and transpositions of adjacent characters as single edits.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let a = a.chars().collect::<Vec<_>>();
+    let b = b.chars().collect::<Vec<_>>();
+
+    // distances[i][j] holds the distance between the first i chars of a and the first j chars of b
+    let mut distances = vec![vec![0; b.len() + 1]; a.len() + 1];
+    for (i, row) in distances.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for (j, distance) in distances[0].iter_mut().enumerate() {
+        *distance = j;
+    }
+
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            let mut distance = (distances[i - 1][j - 1] + cost)
+                .min(distances[i - 1][j] + 1)
+                .min(distances[i][j - 1] + 1);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                distance = distance.min(distances[i - 2][j - 2] + 1);
+            }
+            distances[i][j] = distance;
+        }
+    }
+
+    distances[a.len()][b.len()]
+}
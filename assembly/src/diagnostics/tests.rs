@@ -0,0 +1,130 @@
+use super::{find_similar_name, Diagnostic, Label, SourceLocation};
+use crate::{ast::ProgramAst, Assembler, AssemblyError};
+
+// RENDERING
+// ================================================================================================
+
+#[test]
+fn render_primary_location() {
+    let source = "begin\n    push.1\n    dupp\nend";
+    let err = ProgramAst::parse(source).unwrap_err();
+
+    let expected = "\
+error: instruction 'dupp' is invalid
+ --> main.masm:3:5
+  |
+3 |     dupp
+  |     ^^^^
+  |
+  = help: did you mean `dup`?";
+    assert_eq!(expected, err.diagnostic().render("main.masm", source));
+}
+
+#[test]
+fn render_secondary_labels() {
+    let source = "proc.foo\n    add\nend\n\nproc.foo\n    mul\nend\n\nbegin\n    exec.foo\nend";
+    let err = ProgramAst::parse(source).unwrap_err();
+
+    let expected = "\
+error: duplicate procedure name: foo
+ --> main.masm:5:1
+  |
+1 | proc.foo
+  | -------- first defined here
+...
+5 | proc.foo
+  | ^^^^^^^^";
+    assert_eq!(expected, err.diagnostic().render("main.masm", source));
+
+    // labels on adjacent lines and on the same line are rendered without gaps
+    let source = "begin\n\tpush.1 push.2\n\tadd\nend";
+    let diagnostic = Diagnostic::new("oops", SourceLocation::new(3, 2)).with_labels(&[
+        Label::new(SourceLocation::new(2, 9), "second"),
+        Label::new(SourceLocation::new(2, 2), "first"),
+    ]);
+
+    let expected = "\
+error: oops
+ --> main.masm:3:2
+  |
+2 | \tpush.1 push.2
+  | \t       ------ second
+  | \t------ first
+3 | \tadd
+  | \t^^^";
+    assert_eq!(expected, diagnostic.render("main.masm", source));
+}
+
+#[test]
+fn render_missing_lines() {
+    // locations outside of the source are omitted
+    let diagnostic = Diagnostic::new("oops", SourceLocation::new(12, 1))
+        .with_labels(&[Label::new(SourceLocation::new(1, 1), "here")]);
+    let expected = "\
+error: oops
+ --> main.masm:12:1
+  |
+1 | begin
+  | ----- here";
+    assert_eq!(expected, diagnostic.render("main.masm", "begin\nend"));
+
+    let diagnostic = Diagnostic::new("oops", SourceLocation::new(12, 1)).with_help("try again");
+    let expected = "\
+error: oops
+ --> main.masm:12:1
+  |
+  = help: try again";
+    assert_eq!(expected, diagnostic.render("main.masm", "begin\nend"));
+}
+
+#[test]
+fn render_assembly_error() {
+    let source = "\
+proc.foo # (a b -- c)
+    if.true
+        push.1
+    else
+        push.1.2
+    end
+end
+
+begin
+    exec.foo
+end";
+    let assembler = Assembler::default();
+    let err = assembler.compile(source).unwrap_err();
+    let diagnostic = err.diagnostic().expect("no diagnostic");
+    assert_eq!(&SourceLocation::new(2, 5), diagnostic.location());
+    assert!(diagnostic
+        .render("main.masm", source)
+        .contains("2 |     if.true\n  |     ^^^^^^^"));
+
+    // parsing errors are described by the diagnostic of the parsing error
+    let err = assembler.compile("begin push.1 dupp end").unwrap_err();
+    let diagnostic = err.diagnostic().expect("no diagnostic");
+    assert_eq!(Some("did you mean `dup`?"), diagnostic.help());
+    assert_eq!("instruction 'dupp' is invalid", err.to_string());
+
+    // errors without a location have no diagnostic
+    assert_eq!(None, AssemblyError::division_by_zero().diagnostic());
+}
+
+// SUGGESTIONS
+// ================================================================================================
+
+#[test]
+fn similar_names() {
+    let candidates = ["add", "adv_push", "u32checked_add", "u32wrapping_add", "push"];
+
+    assert_eq!(Some("add"), find_similar_name("ad", candidates));
+    assert_eq!(Some("push"), find_similar_name("psuh", candidates));
+    assert_eq!(Some("adv_push"), find_similar_name("adv_psh", candidates));
+    assert_eq!(Some("u32checked_add"), find_similar_name("u32chekced_add", candidates));
+
+    // the closest candidate is preferred
+    assert_eq!(Some("u32wrapping_add"), find_similar_name("u32wraping_add", candidates));
+
+    // exact matches and dissimilar names are not suggested
+    assert_eq!(None, find_similar_name("add", ["add"]));
+    assert_eq!(None, find_similar_name("mul", candidates));
+}
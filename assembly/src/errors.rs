@@ -1,6 +1,9 @@
 use super::{
-    ast::ProcReExport, crypto::hash::RpoDigest, tokens::SourceLocation, LibraryNamespace,
-    ProcedureId, ProcedureName, String, ToString, Token, Vec,
+    ast::ProcReExport,
+    crypto::hash::RpoDigest,
+    diagnostics::{find_similar_name, Diagnostic, Label},
+    tokens::SourceLocation,
+    LibraryNamespace, ProcedureId, ProcedureName, String, ToString, Token, Vec,
 };
 use core::fmt;
 
@@ -26,11 +29,14 @@ pub enum AssemblyError {
     InvalidCacheLock,
     KernelProcNotFound(ProcedureId),
     LocalProcNotFound(u16, String),
-    ParsingError(String),
+    ParsingError(ParsingError),
     ParamOutOfBounds(u64, u64, u64),
     PhantomCallsNotAllowed(RpoDigest),
     ProcedureNameError(String),
+    StackSignatureMismatch(String, SourceLocation, String, i32),
     SysCallInKernel(String),
+    UnbalancedIfElse(String, SourceLocation, i32, i32),
+    UnbalancedWhileBody(String, SourceLocation, i32),
     LibraryError(String),
     Io(String),
 }
@@ -102,18 +108,63 @@ impl AssemblyError {
         Self::PhantomCallsNotAllowed(mast_root)
     }
 
+    pub fn stack_signature_mismatch(
+        proc_name: &str,
+        location: SourceLocation,
+        signature: &str,
+        stack_delta: i32,
+    ) -> Self {
+        Self::StackSignatureMismatch(
+            proc_name.to_string(),
+            location,
+            signature.to_string(),
+            stack_delta,
+        )
+    }
+
     pub fn syscall_in_kernel(kernel_proc_name: &str) -> Self {
         Self::SysCallInKernel(kernel_proc_name.to_string())
     }
 
+    pub fn unbalanced_if_else(
+        proc_name: &str,
+        location: SourceLocation,
+        true_delta: i32,
+        false_delta: i32,
+    ) -> Self {
+        Self::UnbalancedIfElse(proc_name.to_string(), location, true_delta, false_delta)
+    }
+
+    pub fn unbalanced_while_body(proc_name: &str, location: SourceLocation, delta: i32) -> Self {
+        Self::UnbalancedWhileBody(proc_name.to_string(), location, delta)
+    }
+
     pub fn invalid_cache_lock() -> Self {
         Self::InvalidCacheLock
     }
+
+    // DIAGNOSTICS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns a [Diagnostic] describing this error, or None if the error is not associated with
+    /// a location in the source code being compiled.
+    pub fn diagnostic(&self) -> Option<Diagnostic> {
+        use AssemblyError::*;
+        match self {
+            ParsingError(err) => Some(err.diagnostic()),
+            StackSignatureMismatch(_, location, _, _)
+            | UnbalancedIfElse(_, location, _, _)
+            | UnbalancedWhileBody(_, location, _) => {
+                Some(Diagnostic::new(&self.to_string(), *location))
+            }
+            _ => None,
+        }
+    }
 }
 
 impl From<ParsingError> for AssemblyError {
     fn from(err: ParsingError) -> Self {
-        Self::ParsingError(err.message)
+        Self::ParsingError(err)
     }
 }
 
@@ -149,11 +200,15 @@ impl fmt::Display for AssemblyError {
             InvalidCacheLock => write!(f, "an attempt was made to lock a borrowed procedures cache"),
             Io(description) => write!(f, "I/O error: {description}"),
             KernelProcNotFound(proc_id) => write!(f, "procedure {proc_id} not found in kernel"),
-            LibraryError(err) | ParsingError(err) | ProcedureNameError(err) => write!(f, "{err}"),
+            LibraryError(err) | ProcedureNameError(err) => write!(f, "{err}"),
             LocalProcNotFound(proc_idx, module_path) => write!(f, "procedure at index {proc_idx} not found in module {module_path}"),
+            ParsingError(err) => write!(f, "{}", err.message()),
             ParamOutOfBounds(value, min, max) => write!(f, "parameter value must be greater than or equal to {min} and less than or equal to {max}, but was {value}"),
             PhantomCallsNotAllowed(mast_root) => write!(f, "cannot call phantom procedure with MAST root {mast_root}: phantom calls not allowed"),
+            StackSignatureMismatch(proc_name, location, signature, delta) => write!(f, "procedure '{proc_name}' ending at {location} changes the stack depth by {delta}, which does not match its signature {signature}"),
             SysCallInKernel(proc_name) => write!(f, "syscall instruction used in kernel procedure '{proc_name}'"),
+            UnbalancedIfElse(proc_name, location, true_delta, false_delta) => write!(f, "branches of if.true statement at {location} in procedure '{proc_name}' change the stack depth differently: by {true_delta} and by {false_delta}"),
+            UnbalancedWhileBody(proc_name, location, delta) => write!(f, "body of while.true loop at {location} in procedure '{proc_name}' changes the stack depth by {delta} on each iteration"),
         }
     }
 }
@@ -177,6 +232,8 @@ pub struct ParsingError {
     message: String,
     location: SourceLocation,
     op: String,
+    labels: Vec<Label>,
+    help: Option<String>,
 }
 
 impl ParsingError {
@@ -188,6 +245,8 @@ impl ParsingError {
             message: "source code cannot be an empty string".to_string(),
             location: SourceLocation::default(),
             op: "".to_string(),
+            labels: Vec::new(),
+            help: None,
         }
     }
 
@@ -196,6 +255,8 @@ impl ParsingError {
             message: "unexpected EOF".to_string(),
             location,
             op: "".to_string(),
+            labels: Vec::new(),
+            help: None,
         }
     }
 
@@ -204,6 +265,8 @@ impl ParsingError {
             message: format!("unexpected token: expected '{expected}' but was '{token}'"),
             location: *token.location(),
             op: token.to_string(),
+            labels: Vec::new(),
+            help: None,
         }
     }
 
@@ -214,6 +277,8 @@ impl ParsingError {
             message: format!("duplicate constant name: '{label}'"),
             location: *token.location(),
             op: token.to_string(),
+            labels: Vec::new(),
+            help: None,
         }
     }
 
@@ -222,6 +287,8 @@ impl ParsingError {
             message: format!("invalid constant name: {err}"),
             location: *token.location(),
             op: token.to_string(),
+            labels: Vec::new(),
+            help: None,
         }
     }
 
@@ -232,6 +299,8 @@ impl ParsingError {
             ),
             location: *token.location(),
             op: token.to_string(),
+            labels: Vec::new(),
+            help: None,
         }
     }
 
@@ -240,6 +309,8 @@ impl ParsingError {
             message: format!("invalid constant declaration: `{token}` - constants can only be defined below imports and above procedure / program bodies"),
             location: *token.location(),
             op: token.to_string(),
+            labels: Vec::new(),
+            help: None,
         }
     }
 
@@ -248,6 +319,8 @@ impl ParsingError {
             message: format!("constant used in operation `{token}` not found"),
             location: *token.location(),
             op: token.to_string(),
+            labels: Vec::new(),
+            help: None,
         }
     }
 
@@ -258,6 +331,8 @@ impl ParsingError {
             ),
             location: *token.location(),
             op: token.to_string(),
+            labels: Vec::new(),
+            help: None,
         }
     }
 
@@ -266,6 +341,30 @@ impl ParsingError {
             message: format!("constant expression {token} contains division by zero"),
             location: *token.location(),
             op: token.to_string(),
+            labels: Vec::new(),
+            help: None,
+        }
+    }
+
+    pub fn const_not_scalar(token: &Token, name: &str) -> Self {
+        ParsingError {
+            message: format!(
+                "array constant '{name}' used in `{token}` where a single value is expected"
+            ),
+            location: *token.location(),
+            op: token.to_string(),
+            labels: Vec::new(),
+            help: None,
+        }
+    }
+
+    pub fn const_export_not_allowed(token: &Token) -> Self {
+        ParsingError {
+            message: format!("exported constants not allowed in this context: `{token}`"),
+            location: *token.location(),
+            op: token.to_string(),
+            labels: Vec::new(),
+            help: None,
         }
     }
 
@@ -277,6 +376,8 @@ impl ParsingError {
             message: format!("instruction '{token}' is invalid"),
             location: *token.location(),
             op: token.to_string(),
+            labels: Vec::new(),
+            help: None,
         }
     }
 
@@ -288,6 +389,8 @@ impl ParsingError {
             ),
             location: *token.location(),
             op: token.to_string(),
+            labels: Vec::new(),
+            help: None,
         }
     }
 
@@ -296,6 +399,8 @@ impl ParsingError {
             message: format!("malformed instruction '{token}': too many parameters provided"),
             location: *token.location(),
             op: token.to_string(),
+            labels: Vec::new(),
+            help: None,
         }
     }
 
@@ -307,6 +412,8 @@ impl ParsingError {
             ),
             location: *token.location(),
             op: token.to_string(),
+            labels: Vec::new(),
+            help: None,
         }
     }
 
@@ -318,6 +425,8 @@ impl ParsingError {
             ),
             location: *token.location(),
             op: token.to_string(),
+            labels: Vec::new(),
+            help: None,
         }
     }
 
@@ -329,6 +438,18 @@ impl ParsingError {
             message: "else without matching if".to_string(),
             location: *token.location(),
             op: token.to_string(),
+            labels: Vec::new(),
+            help: None,
+        }
+    }
+
+    pub fn dangling_end(token: &Token) -> Self {
+        ParsingError {
+            message: "end without matching block".to_string(),
+            location: *token.location(),
+            op: token.to_string(),
+            labels: Vec::new(),
+            help: None,
         }
     }
 
@@ -337,6 +458,8 @@ impl ParsingError {
             message: "if without matching else/end".to_string(),
             location: *token.location(),
             op: token.to_string(),
+            labels: Vec::new(),
+            help: None,
         }
     }
 
@@ -345,6 +468,8 @@ impl ParsingError {
             message: "while without matching end".to_string(),
             location: *token.location(),
             op: token.to_string(),
+            labels: Vec::new(),
+            help: None,
         }
     }
 
@@ -353,6 +478,8 @@ impl ParsingError {
             message: "repeat without matching end".to_string(),
             location: *token.location(),
             op: token.to_string(),
+            labels: Vec::new(),
+            help: None,
         }
     }
 
@@ -361,6 +488,8 @@ impl ParsingError {
             message: "else without matching end".to_string(),
             location: *token.location(),
             op: token.to_string(),
+            labels: Vec::new(),
+            help: None,
         }
     }
 
@@ -369,6 +498,8 @@ impl ParsingError {
             message: "begin without matching end".to_string(),
             location: *token.location(),
             op: token.to_string(),
+            labels: Vec::new(),
+            help: None,
         }
     }
 
@@ -377,6 +508,8 @@ impl ParsingError {
             message: "dangling instructions after program end".to_string(),
             location: *token.location(),
             op: token.to_string(),
+            labels: Vec::new(),
+            help: None,
         }
     }
 
@@ -385,6 +518,8 @@ impl ParsingError {
             message: "dangling instructions after module end".to_string(),
             location: *token.location(),
             op: token.to_string(),
+            labels: Vec::new(),
+            help: None,
         }
     }
 
@@ -394,6 +529,8 @@ impl ParsingError {
                 .to_string(),
             location,
             op: "".to_string(),
+            labels: Vec::new(),
+            help: None,
         }
     }
 
@@ -402,6 +539,8 @@ impl ParsingError {
             message: "not a module: `begin` instruction found".to_string(),
             location: *token.location(),
             op: token.to_string(),
+            labels: Vec::new(),
+            help: None,
         }
     }
 
@@ -412,6 +551,8 @@ impl ParsingError {
             ),
             location: SourceLocation::default(),
             op: "".to_string(),
+            labels: Vec::new(),
+            help: None,
         }
     }
 
@@ -422,6 +563,8 @@ impl ParsingError {
             ),
             location: SourceLocation::default(),
             op: "".to_string(),
+            labels: Vec::new(),
+            help: None,
         }
     }
 
@@ -430,6 +573,8 @@ impl ParsingError {
             message: format!("body block size cannot contain more than {max_body_size} instructions, but had {body_size}"),
             location: *token.location(),
             op: token.to_string(),
+            labels: Vec::new(),
+            help: None,
         }
     }
 
@@ -441,6 +586,8 @@ impl ParsingError {
             message: format!("duplicate procedure name: {label}"),
             location: *token.location(),
             op: token.to_string(),
+            labels: Vec::new(),
+            help: None,
         }
     }
 
@@ -449,6 +596,8 @@ impl ParsingError {
             message: format!("invalid procedure name: {err}"),
             location: *token.location(),
             op: token.to_string(),
+            labels: Vec::new(),
+            help: None,
         }
     }
 
@@ -457,6 +606,8 @@ impl ParsingError {
             message: format!("invalid re-exported procedure: {label}"),
             location: *token.location(),
             op: token.to_string(),
+            labels: Vec::new(),
+            help: None,
         }
     }
 
@@ -468,6 +619,8 @@ impl ParsingError {
             ),
             location: *token.location(),
             op: token.to_string(),
+            labels: Vec::new(),
+            help: None,
         }
     }
 
@@ -476,6 +629,8 @@ impl ParsingError {
             message: format!("invalid procedure locals: {locals}"),
             location: *token.location(),
             op: token.to_string(),
+            labels: Vec::new(),
+            help: None,
         }
     }
 
@@ -484,6 +639,18 @@ impl ParsingError {
             message: format!("number of procedure locals cannot be greater than {max_locals} characters, but was {num_locals}"),
             location: *token.location(),
             op: token.to_string(),
+            labels: Vec::new(),
+            help: None,
+        }
+    }
+
+    pub fn invalid_proc_signature(token: &Token, signature: &str, reason: &str) -> Self {
+        ParsingError {
+            message: format!("invalid procedure stack signature '{signature}': {reason}"),
+            location: *token.location(),
+            op: token.to_string(),
+            labels: Vec::new(),
+            help: None,
         }
     }
 
@@ -492,6 +659,8 @@ impl ParsingError {
             message: format!("procedure '{proc_name}' has no matching end"),
             location: *token.location(),
             op: token.to_string(),
+            labels: Vec::new(),
+            help: None,
         }
     }
 
@@ -500,6 +669,8 @@ impl ParsingError {
             message: format!("exported procedures not allowed in this context: {label}"),
             location: *token.location(),
             op: token.to_string(),
+            labels: Vec::new(),
+            help: None,
         }
     }
 
@@ -510,9 +681,141 @@ impl ParsingError {
             ),
             location: *token.location(),
             op: token.to_string(),
+            labels: Vec::new(),
+            help: None,
+        }
+    }
+
+    // MACROS
+    // --------------------------------------------------------------------------------------------
+
+    pub fn duplicate_macro_name(token: &Token, label: &str) -> Self {
+        ParsingError {
+            message: format!("duplicate macro name: {label}"),
+            location: *token.location(),
+            op: token.to_string(),
+            labels: Vec::new(),
+            help: None,
         }
     }
 
+    pub fn invalid_macro_name(token: &Token, err: LabelError) -> Self {
+        ParsingError {
+            message: format!("invalid macro name: {err}"),
+            location: *token.location(),
+            op: token.to_string(),
+            labels: Vec::new(),
+            help: None,
+        }
+    }
+
+    pub fn invalid_macro_param(token: &Token, label: &str, reason: &str) -> Self {
+        ParsingError {
+            message: format!("invalid parameters of macro '{label}': {reason}"),
+            location: *token.location(),
+            op: token.to_string(),
+            labels: Vec::new(),
+            help: None,
+        }
+    }
+
+    pub fn undefined_macro_param(token: &Token, param: &str) -> Self {
+        ParsingError {
+            message: format!("undefined macro parameter '${param}' used in `{token}`"),
+            location: *token.location(),
+            op: token.to_string(),
+            labels: Vec::new(),
+            help: None,
+        }
+    }
+
+    pub fn invalid_macro_body(token: &Token, label: &str) -> Self {
+        ParsingError {
+            message: format!("`{token}` is not allowed in the body of macro '{label}'"),
+            location: *token.location(),
+            op: token.to_string(),
+            labels: Vec::new(),
+            help: None,
+        }
+    }
+
+    pub fn unmatched_macro(token: &Token, label: &str) -> Self {
+        ParsingError {
+            message: format!("macro '{label}' has no matching end"),
+            location: *token.location(),
+            op: token.to_string(),
+            labels: Vec::new(),
+            help: None,
+        }
+    }
+
+    pub fn macro_invalid_scope(token: &Token) -> Self {
+        ParsingError {
+            message: format!("invalid macro declaration: `{token}` - macros can only be defined below constants and outside of procedure / program bodies"),
+            location: *token.location(),
+            op: token.to_string(),
+            labels: Vec::new(),
+            help: None,
+        }
+    }
+
+    pub fn invalid_macro_invocation(token: &Token, reason: &str) -> Self {
+        ParsingError {
+            message: format!("invalid macro invocation `{token}`: {reason}"),
+            location: *token.location(),
+            op: token.to_string(),
+            labels: Vec::new(),
+            help: None,
+        }
+    }
+
+    pub fn invalid_macro_arity(token: &Token, label: &str, expected: usize, actual: usize) -> Self {
+        ParsingError {
+            message: format!(
+                "macro '{label}' expects {expected} arguments, but {actual} were provided in `{token}`"
+            ),
+            location: *token.location(),
+            op: token.to_string(),
+            labels: Vec::new(),
+            help: None,
+        }
+    }
+
+    pub fn undefined_macro(token: &Token, label: &str) -> Self {
+        ParsingError {
+            message: format!("undefined macro: {label}"),
+            location: *token.location(),
+            op: token.to_string(),
+            labels: Vec::new(),
+            help: None,
+        }
+    }
+
+    pub fn undeclared_macro_in_body(token: &Token, label: &str, invoked: &str) -> Self {
+        ParsingError {
+            message: format!(
+                "macro '{label}' invokes macro '{invoked}' which is not declared before it"
+            ),
+            location: *token.location(),
+            op: token.to_string(),
+            labels: Vec::new(),
+            help: None,
+        }
+    }
+
+    /// Attaches the macro invocation which caused the specified error to this error.
+    ///
+    /// The location of the error is retained, so that it points into the body of the macro, and
+    /// the invocation is attached to the error as a secondary label.
+    pub fn in_macro_expansion(self, token: &Token, label: &str) -> Self {
+        let location = *token.location();
+        ParsingError {
+            message: format!("{} (in expansion of macro '{label}' at {location})", self.message),
+            ..self
+        }
+        .with_label(location, &format!("in this invocation of macro '{label}'"))
+    }
+
     // PROCEDURE INVOCATION
     // --------------------------------------------------------------------------------------------
 
@@ -521,6 +824,8 @@ impl ParsingError {
             message: format!("invalid procedure root invocation: {label} - {err}"),
             location: *token.location(),
             op: token.to_string(),
+            labels: Vec::new(),
+            help: None,
         }
     }
 
@@ -529,6 +834,8 @@ impl ParsingError {
             message: format!("invalid procedure invocation: {label}"),
             location: *token.location(),
             op: token.to_string(),
+            labels: Vec::new(),
+            help: None,
         }
     }
 
@@ -537,6 +844,8 @@ impl ParsingError {
             message: "invalid exec: cannot invoke a procedure on a mast root".to_string(),
             location: *token.location(),
             op: token.to_string(),
+            labels: Vec::new(),
+            help: None,
         }
     }
 
@@ -545,6 +854,8 @@ impl ParsingError {
             message: "invalid syscall: cannot invoke a syscall on a named module".to_string(),
             location: *token.location(),
             op: token.to_string(),
+            labels: Vec::new(),
+            help: None,
         }
     }
 
@@ -553,6 +864,8 @@ impl ParsingError {
             message: "invalid syscall: cannot invoke a syscall on a mast root".to_string(),
             location: *token.location(),
             op: token.to_string(),
+            labels: Vec::new(),
+            help: None,
         }
     }
 
@@ -561,6 +874,8 @@ impl ParsingError {
             message: format!("undefined local procedure: {label}"),
             location: *token.location(),
             op: token.to_string(),
+            labels: Vec::new(),
+            help: None,
         }
     }
 
@@ -569,6 +884,8 @@ impl ParsingError {
             message: format!("module '{module_name}' was not imported"),
             location: *token.location(),
             op: token.to_string(),
+            labels: Vec::new(),
+            help: None,
         }
     }
 
@@ -583,6 +900,8 @@ impl ParsingError {
             ),
             location: *token.location(),
             op: token.to_string(),
+            labels: Vec::new(),
+            help: None,
         }
     }
 
@@ -594,6 +913,8 @@ impl ParsingError {
             message: format!("duplicate module import found: {module}"),
             location: *token.location(),
             op: token.to_string(),
+            labels: Vec::new(),
+            help: None,
         }
     }
 
@@ -602,6 +923,8 @@ impl ParsingError {
             message: format!("invalid module import path: {module_path}"),
             location: *token.location(),
             op: token.to_string(),
+            labels: Vec::new(),
+            help: None,
         }
     }
 
@@ -610,6 +933,8 @@ impl ParsingError {
             message: format!("invalid module name: {name}"),
             location: *token.location(),
             op: token.to_string(),
+            labels: Vec::new(),
+            help: None,
         }
     }
 
@@ -618,6 +943,8 @@ impl ParsingError {
             message: "import in procedure body".to_string(),
             location: *token.location(),
             op: token.to_string(),
+            labels: Vec::new(),
+            help: None,
         }
     }
 
@@ -626,6 +953,8 @@ impl ParsingError {
             message: format!("invalid path resolution: {error}"),
             location: *token.location(),
             op: token.to_string(),
+            labels: Vec::new(),
+            help: None,
         }
     }
 
@@ -636,6 +965,35 @@ impl ParsingError {
             ),
             location: SourceLocation::default(),
             op: "".to_string(),
+            labels: Vec::new(),
+            help: None,
+        }
+    }
+
+    // DIAGNOSTIC DETAILS
+    // --------------------------------------------------------------------------------------------
+
+    /// Attaches a secondary label pointing to the specified location to this error.
+    pub fn with_label(mut self, location: SourceLocation, message: &str) -> Self {
+        self.labels.push(Label::new(location, message));
+        self
+    }
+
+    /// Attaches a help message to this error.
+    pub fn with_help(mut self, help: &str) -> Self {
+        self.help = Some(help.to_string());
+        self
+    }
+
+    /// Attaches a "did you mean" suggestion to this error if one of the candidates is similar to
+    /// the specified name.
+    pub fn with_similar_name<'a, I>(self, name: &str, candidates: I) -> Self
+    where
+        I: IntoIterator<Item = &'a str>,
+    {
+        match find_similar_name(name, candidates) {
+            Some(similar) => self.with_help(&format!("did you mean `{similar}`?")),
+            None => self,
         }
     }
 
@@ -652,6 +1010,26 @@ impl ParsingError {
     pub const fn location(&self) -> &SourceLocation {
         &self.location
     }
+
+    /// Returns the secondary labels attached to this error.
+    pub fn labels(&self) -> &[Label] {
+        &self.labels
+    }
+
+    /// Returns the help message attached to this error, if any.
+    pub fn help(&self) -> Option<&str> {
+        self.help.as_deref()
+    }
+
+    /// Returns a [Diagnostic] describing this error, which can be rendered against the source
+    /// code the error was produced for.
+    pub fn diagnostic(&self) -> Diagnostic {
+        let diagnostic = Diagnostic::new(&self.message, self.location).with_labels(&self.labels);
+        match self.help {
+            Some(ref help) => diagnostic.with_help(help),
+            None => diagnostic,
+        }
+    }
 }
 
 impl fmt::Debug for ParsingError {
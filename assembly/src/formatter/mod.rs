@@ -0,0 +1,254 @@
+use super::{
+    tokens::SourceLocation, LibraryPath, ParsingError, String, ToString, Token, TokenStream, Vec,
+};
+
+#[cfg(test)]
+mod tests;
+
+// CONSTANTS
+// ================================================================================================
+
+/// The string used to indent the contents of a block by one level.
+const INDENT_STRING: &str = "    ";
+
+// FORMATTER
+// ================================================================================================
+
+/// Formats the provided Miden assembly source code.
+///
+/// The formatter works directly on the lines of the source, and thus preserves comments (including
+/// doc comments and stack signatures) as well as the grouping of instructions on lines, while
+/// normalizing the layout of the code:
+/// - The contents of `begin`, `proc`, `export`, `macro`, `if.true`, `else`, `while.true`, and
+///   `repeat` blocks are indented by four spaces per nesting level, and comments are indented to
+///   the level of the surrounding code.
+/// - Block headers, `else`, and `end` tokens, as well as `use` and `const` statements, are placed
+///   on their own lines. Consecutive instructions on a line are separated by a single space, while
+///   the spacing before trailing comments is kept to retain their alignment.
+/// - Runs of blank lines are collapsed into a single blank line, and blank lines at the start and
+///   at the end of the source and of blocks are removed.
+///
+/// Formatting is idempotent, and the formatted source parses into the same AST as the original
+/// source (up to source locations).
+///
+/// # Errors
+/// Returns an error if the source is empty, contains misplaced doc comments, or its blocks are not
+/// balanced.
+pub fn format_source(source: &str) -> Result<String, ParsingError> {
+    // make sure the source can be tokenized; this also validates the placement of doc comments
+    TokenStream::new(source)?;
+
+    let mut formatter = Formatter::default();
+    for (line_idx, line) in source.lines().enumerate() {
+        formatter.format_line(line_idx as u32 + 1, line)?;
+    }
+    formatter.finish()
+}
+
+/// Internal state of the formatter.
+#[derive(Default)]
+struct Formatter<'a> {
+    /// Formatted lines, without the trailing newline characters.
+    output: Vec<String>,
+    /// Headers of the blocks enclosing the current line.
+    blocks: Vec<Token<'a>>,
+    /// Set when a blank line was encountered since the last formatted line.
+    pending_blank: bool,
+    /// Set when the last formatted line opened a block.
+    after_opener: bool,
+}
+
+impl<'a> Formatter<'a> {
+    /// Formats a single line of the source, which may result in several formatted lines.
+    fn format_line(&mut self, line_num: u32, line: &'a str) -> Result<(), ParsingError> {
+        let words = split_words(line_num, line);
+
+        // comments start with the first word prefixed by '#' and extend to the end of the line
+        let comment_start = words.iter().position(|token| token.parts()[0].starts_with('#'));
+        let (code, comment) = match comment_start {
+            Some(idx) => {
+                let column = words[idx].location().column() as usize;
+                (&words[..idx], Some(line[column - 1..].trim_end()))
+            }
+            None => (&words[..], None),
+        };
+
+        if code.is_empty() {
+            match comment {
+                Some(comment) => self.push_line(comment, false, false),
+                None => self.pending_blank = true,
+            }
+            return Ok(());
+        }
+
+        // group the tokens into segments, each of which is formatted as a separate line: block
+        // delimiters and statements form segments of their own, while consecutive instructions are
+        // kept together
+        let mut segments: Vec<(TokenKind, Vec<&Token<'a>>)> = Vec::new();
+        for token in code {
+            let kind = TokenKind::of(token);
+            match segments.last_mut() {
+                Some((TokenKind::Instruction, tokens)) if kind == TokenKind::Instruction => {
+                    tokens.push(token)
+                }
+                _ => segments.push((kind, vec![token])),
+            }
+        }
+
+        // a trailing comment stays with the last segment of the line, unless the line contains a
+        // procedure header; in this case the comment may be the stack signature of the procedure,
+        // which must remain on the line of the header
+        let comment_idx = segments
+            .iter()
+            .position(|(kind, tokens)| *kind == TokenKind::Open && is_proc_header(tokens[0]))
+            .unwrap_or(segments.len() - 1);
+
+        // if the line is not split, the whitespace preceding the trailing comment is retained, so
+        // that comments aligned across consecutive lines remain aligned
+        let comment_gap = match (segments.len(), comment_start) {
+            (1, Some(idx)) => {
+                let last = &words[idx - 1];
+                let code_end = last.location().column() as usize - 1 + last.to_string().len();
+                &line[code_end..words[idx].location().column() as usize - 1]
+            }
+            _ => " ",
+        };
+
+        for (idx, (kind, tokens)) in segments.into_iter().enumerate() {
+            let mut text =
+                tokens.iter().map(|token| token.to_string()).collect::<Vec<_>>().join(" ");
+            if let Some(comment) = comment.filter(|_| idx == comment_idx) {
+                text.push_str(comment_gap);
+                text.push_str(comment);
+            }
+
+            match kind {
+                TokenKind::Open => {
+                    self.push_line(&text, true, false);
+                    self.blocks.push(tokens[0].clone());
+                }
+                TokenKind::Else => match self.blocks.last() {
+                    Some(block) if block.parts()[0] == Token::IF => {
+                        self.push_line(&text, true, true)
+                    }
+                    _ => return Err(ParsingError::dangling_else(tokens[0])),
+                },
+                TokenKind::End => {
+                    if self.blocks.is_empty() {
+                        return Err(ParsingError::dangling_end(tokens[0]));
+                    }
+                    self.push_line(&text, false, true);
+                    self.blocks.pop();
+                }
+                TokenKind::Statement | TokenKind::Instruction => {
+                    self.push_line(&text, false, false)
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Appends a line indented to the current nesting level to the output.
+    ///
+    /// A blank line encountered before this line is retained unless this line is the first line
+    /// of the output or of a block, or the line closes a block.
+    fn push_line(&mut self, text: &str, opens_block: bool, closes_block: bool) {
+        if self.pending_blank && !self.output.is_empty() && !self.after_opener && !closes_block {
+            self.output.push(String::new());
+        }
+        self.pending_blank = false;
+        self.after_opener = opens_block;
+
+        let depth = self.blocks.len() - usize::from(closes_block);
+        self.output.push(INDENT_STRING.repeat(depth) + text);
+    }
+
+    /// Returns the formatted source.
+    ///
+    /// # Errors
+    /// Returns an error if some of the blocks are not closed.
+    fn finish(self) -> Result<String, ParsingError> {
+        if let Some(block) = self.blocks.last() {
+            let name = block.parts().get(1).map(|name| name.split('(').next().unwrap_or(name));
+            return Err(match block.parts()[0] {
+                Token::BEGIN => ParsingError::unmatched_begin(block),
+                Token::IF => ParsingError::unmatched_if(block),
+                Token::WHILE => ParsingError::unmatched_while(block),
+                Token::REPEAT => ParsingError::unmatched_repeat(block),
+                Token::MACRO => ParsingError::unmatched_macro(block, name.unwrap_or_default()),
+                _ => ParsingError::unmatched_proc(block, name.unwrap_or_default()),
+            });
+        }
+
+        let mut formatted = self.output.join("\n");
+        formatted.push('\n');
+        Ok(formatted)
+    }
+}
+
+// TOKEN KINDS
+// ================================================================================================
+
+/// Kinds of tokens which determine the layout of the formatted source.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TokenKind {
+    /// A token which opens a block terminated by `end` (e.g., `proc.foo` or `if.true`).
+    Open,
+    /// The `else` token.
+    Else,
+    /// The `end` token.
+    End,
+    /// A statement which does not open a block (e.g., `use` or `const`).
+    Statement,
+    /// An instruction or a macro invocation.
+    Instruction,
+}
+
+impl TokenKind {
+    /// Returns the kind of the specified token.
+    fn of(token: &Token) -> Self {
+        let parts = token.parts();
+        match parts[0] {
+            Token::BEGIN
+            | Token::IF
+            | Token::WHILE
+            | Token::REPEAT
+            | Token::PROC
+            | Token::MACRO => Self::Open,
+            Token::EXPORT => match parts.get(1) {
+                // exported constants and re-exported procedures have no body
+                Some(&Token::CONST) => Self::Statement,
+                Some(name) if name.contains(LibraryPath::PATH_DELIM) => Self::Statement,
+                _ => Self::Open,
+            },
+            Token::ELSE => Self::Else,
+            Token::END => Self::End,
+            Token::USE | Token::CONST => Self::Statement,
+            _ => Self::Instruction,
+        }
+    }
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Returns true if the specified token is the header of a procedure declaration.
+fn is_proc_header(token: &Token) -> bool {
+    matches!(token.parts()[0], Token::PROC | Token::EXPORT)
+}
+
+/// Splits the specified line into whitespace-separated tokens located at their positions in the
+/// line.
+fn split_words(line_num: u32, line: &str) -> Vec<Token<'_>> {
+    let mut words = Vec::new();
+    let mut remainder = line;
+    while let Some(start) = remainder.find(|c: char| !c.is_whitespace()) {
+        let word = &remainder[start..];
+        let end = word.find(char::is_whitespace).unwrap_or(word.len());
+        let column = (line.len() - word.len()) as u32 + 1;
+        words.push(Token::new(&word[..end], SourceLocation::new(line_num, column)));
+        remainder = &word[end..];
+    }
+    words
+}
@@ -0,0 +1,180 @@
+use super::format_source;
+use crate::ast::{AstSerdeOptions, ModuleAst, ProgramAst};
+
+// LAYOUT
+// ================================================================================================
+
+#[test]
+fn format_blocks() {
+    let source = "\
+use.std::math::u64
+const.A=1
+proc.foo.2 # (a b -- c)
+  loc_store.0   loc_store.1
+      if.true push.1 else
+  push.2 end
+end
+begin
+push.A   exec.foo
+while.true
+    repeat.2 dup drop end
+  push.0 end
+end
+";
+    let expected = "\
+use.std::math::u64
+const.A=1
+proc.foo.2 # (a b -- c)
+    loc_store.0 loc_store.1
+    if.true
+        push.1
+    else
+        push.2
+    end
+end
+begin
+    push.A exec.foo
+    while.true
+        repeat.2
+            dup drop
+        end
+        push.0
+    end
+end
+";
+    assert_eq!(expected, format_source(source).unwrap());
+}
+
+#[test]
+fn format_comments_and_blank_lines() {
+    let source = "\n\n\
+#! Module docs.
+
+use.std::math::u64 # unused
+
+
+
+#! Procedure docs.
+#!
+#! With a second paragraph.
+export.foo
+
+    # comment in the body
+push.1 push.2 add   # trailing comment
+        # comment before end
+
+end
+
+
+macro.bar(a) push.$a end # end of macro
+export.u64::checked_add->add
+
+";
+    let expected = "\
+#! Module docs.
+
+use.std::math::u64 # unused
+
+#! Procedure docs.
+#!
+#! With a second paragraph.
+export.foo
+    # comment in the body
+    push.1 push.2 add   # trailing comment
+    # comment before end
+end
+
+macro.bar(a)
+    push.$a
+end # end of macro
+export.u64::checked_add->add
+";
+    let formatted = format_source(source).unwrap();
+    assert_eq!(expected, formatted);
+
+    // docs are preserved
+    let module = ModuleAst::parse(source).unwrap();
+    let formatted_module = ModuleAst::parse(&formatted).unwrap();
+    assert_eq!(module.docs(), formatted_module.docs());
+    assert_eq!(module.procs()[0].docs, formatted_module.procs()[0].docs);
+}
+
+#[test]
+fn format_signature_with_split_header() {
+    // the stack signature stays on the line of the procedure header
+    let source = "proc.foo add # (a b -- c)\nend\nbegin exec.foo end";
+    let expected = "proc.foo # (a b -- c)\n    add\nend\nbegin\n    exec.foo\nend\n";
+    let formatted = format_source(source).unwrap();
+    assert_eq!(expected, formatted);
+
+    let program = ProgramAst::parse(source).unwrap();
+    let formatted_program = ProgramAst::parse(&formatted).unwrap();
+    assert_eq!(program.procedures()[0].signature, formatted_program.procedures()[0].signature);
+    assert!(formatted_program.procedures()[0].signature.is_some());
+}
+
+#[test]
+fn format_errors() {
+    let err = format_source("begin add end end").unwrap_err();
+    assert_eq!("end without matching block", err.message());
+
+    let err = format_source("begin add else end").unwrap_err();
+    assert_eq!("else without matching if", err.message());
+
+    let err = format_source("begin\n    while.true\n        add\n    end\n").unwrap_err();
+    assert_eq!("begin without matching end", err.message());
+
+    let err = format_source("proc.foo\n    if.true add end\nbegin end").unwrap_err();
+    assert_eq!("procedure 'foo' has no matching end", err.message());
+
+    let err = format_source("  \n").unwrap_err();
+    assert_eq!("source code cannot be an empty string", err.message());
+}
+
+// STANDARD LIBRARY
+// ================================================================================================
+
+/// Formats every module of the standard library and checks that formatting is idempotent and
+/// preserves the AST of the module.
+#[cfg(feature = "std")]
+#[test]
+fn format_stdlib() {
+    use std::{fs, path::Path};
+
+    fn visit(dir: &Path, files: &mut Vec<std::path::PathBuf>) {
+        for entry in fs::read_dir(dir).unwrap() {
+            let path = entry.unwrap().path();
+            if path.is_dir() {
+                visit(&path, files);
+            } else if path.extension().is_some_and(|ext| ext == "masm") {
+                files.push(path);
+            }
+        }
+    }
+
+    let mut files = Vec::new();
+    visit(&Path::new(env!("CARGO_MANIFEST_DIR")).join("../stdlib/asm"), &mut files);
+    assert!(!files.is_empty(), "no stdlib modules found");
+
+    for path in files {
+        let source = fs::read_to_string(&path).unwrap();
+        let formatted = format_source(&source).unwrap();
+        assert_eq!(formatted, format_source(&formatted).unwrap(), "{}", path.display());
+
+        let options = AstSerdeOptions::new(true);
+        match (ModuleAst::parse(&source), ModuleAst::parse(&formatted)) {
+            (Ok(module), Ok(formatted_module)) => {
+                assert_eq!(
+                    module.to_bytes(options),
+                    formatted_module.to_bytes(options),
+                    "{}",
+                    path.display()
+                );
+            }
+            (Err(err), Err(formatted_err)) => {
+                assert_eq!(err.message(), formatted_err.message(), "{}", path.display())
+            }
+            _ => panic!("formatting changed the validity of {}", path.display()),
+        }
+    }
+}
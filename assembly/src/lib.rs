@@ -16,7 +16,9 @@ use vm_core::{
 };
 
 mod library;
-pub use library::{Library, LibraryNamespace, LibraryPath, MaslLibrary, Module, Version};
+pub use library::{
+    Library, LibraryNamespace, LibraryPath, MaslLibrary, Module, Version, MASL_MAGIC, MASL_VERSION,
+};
 
 mod procedures;
 use procedures::{CallSet, NamedProcedure, Procedure};
@@ -28,11 +30,23 @@ use ast::{NAMESPACE_LABEL_PARSER, PROCEDURE_LABEL_PARSER};
 mod tokens;
 use tokens::{Token, TokenStream};
 
+mod diagnostics;
+pub use diagnostics::{Diagnostic, Label};
+
 mod errors;
 pub use errors::{AssemblyError, LabelError, LibraryError, ParsingError, PathError};
 
 mod assembler;
-pub use assembler::{Assembler, AssemblyContext};
+pub use assembler::{
+    Assembler, AssemblyContext, CallGraphReport, InlinedProcedure, OptimizationLevel,
+    OptimizationReport,
+};
+
+mod linter;
+pub use linter::{LintConfig, LintFinding, LintRule, Linter};
+
+mod formatter;
+pub use formatter::format_source;
 
 #[cfg(test)]
 mod tests;
@@ -1,7 +1,7 @@
 use super::{
     super::BTreeSet, AstSerdeOptions, ByteReader, ByteWriter, Deserializable, DeserializationError,
-    Library, LibraryError, LibraryNamespace, LibraryPath, Module, ModuleAst, Serializable, Vec,
-    Version, MAX_DEPENDENCIES, MAX_MODULES,
+    Library, LibraryError, LibraryNamespace, LibraryPath, Module, ModuleAst, OptimizationLevel,
+    Serializable, Vec, Version, MAX_DEPENDENCIES, MAX_MODULES,
 };
 use core::slice::Iter;
 
@@ -9,10 +9,29 @@ use core::slice::Iter;
 // ================================================================================================
 //
 
+/// Magic bytes with which every serialized [MaslLibrary] starts.
+pub const MASL_MAGIC: [u8; 4] = *b"MASL";
+
+/// Version of the [MaslLibrary] serialization format produced by this crate. Libraries serialized
+/// with an unknown version are rejected during deserialization.
+///
+/// The versions of the format are:
+/// - 1: the initial format.
+/// - 2: procedures are serialized with their stack signatures.
+/// - 3: the optimization level of the library is serialized after its version.
+pub const MASL_VERSION: u8 = 3;
+
+/// First version of the format in which procedures are serialized with their stack signatures.
+const MASL_SIGNATURES_VERSION: u8 = 2;
+
+/// First version of the format in which the optimization level of the library is serialized.
+const MASL_OPTIMIZATION_LEVEL_VERSION: u8 = 3;
+
 /// Serialization options for [ModuleAst]. Imports and information about imported procedures are
 /// part of the ModuleAst serialization by default.
 const AST_DEFAULT_SERDE_OPTIONS: AstSerdeOptions = AstSerdeOptions {
     serialize_imports: true,
+    serialize_signatures: true,
 };
 
 // LIBRARY IMPLEMENTATION FOR MASL FILES
@@ -29,6 +48,8 @@ pub struct MaslLibrary {
     namespace: LibraryNamespace,
     /// Version of the library.
     version: Version,
+    /// Optimization level with which the modules of the library are compiled.
+    optimization_level: OptimizationLevel,
     /// Flag defining if locations are serialized with the library.
     has_source_locations: bool,
     /// Available modules.
@@ -55,6 +76,10 @@ impl Library for MaslLibrary {
     fn dependencies(&self) -> &[LibraryNamespace] {
         &self.dependencies
     }
+
+    fn optimization_level(&self) -> OptimizationLevel {
+        self.optimization_level
+    }
 }
 
 impl MaslLibrary {
@@ -101,12 +126,22 @@ impl MaslLibrary {
         Ok(Self {
             namespace,
             version,
+            optimization_level: OptimizationLevel::None,
             has_source_locations,
             modules,
             dependencies,
         })
     }
 
+    /// Sets the optimization level with which the modules of this library are compiled.
+    ///
+    /// The level is serialized with the library; changing it changes MAST roots of the library
+    /// procedures.
+    pub fn with_optimization_level(mut self, optimization_level: OptimizationLevel) -> Self {
+        self.optimization_level = optimization_level;
+        self
+    }
+
     // STATE MUTATORS
     // --------------------------------------------------------------------------------------------
 
@@ -119,7 +154,11 @@ impl MaslLibrary {
 #[cfg(feature = "std")]
 mod use_std {
     use super::*;
-    use crate::{ast::ModuleAst, BTreeMap};
+    use crate::{
+        ast::{ConstantResolver, ExportedConstMap, ModuleAst},
+        BTreeMap, ParsingError, String,
+    };
+    use core::cell::RefCell;
     use std::{fs, io, path::Path};
 
     impl MaslLibrary {
@@ -166,15 +205,28 @@ mod use_std {
                 ));
             }
 
-            let modules = read_from_dir_helper(
-                Default::default(),
-                path,
-                &module_path,
-                &mut dependencies_set,
-            )?
-            .into_iter()
-            .map(|(path, ast)| Module { path, ast })
-            .collect();
+            let sources = read_from_dir_helper(Default::default(), path, &module_path)?;
+
+            // parse the modules; constants imported from other modules of this library are
+            // resolved by parsing the imported modules first
+            let parser = ModuleParser::new(sources);
+            for path in parser.sources.keys() {
+                parser.parse(path)?;
+            }
+
+            let modules = parser
+                .parsed
+                .into_inner()
+                .into_iter()
+                .map(|(path, ast)| {
+                    // add dependencies of this module to the dependencies of this library
+                    for path in ast.import_paths() {
+                        let ns = LibraryNamespace::new(path.first())?;
+                        dependencies_set.insert(ns);
+                    }
+                    Ok(Module { path, ast })
+                })
+                .collect::<Result<_, LibraryError>>()?;
 
             let dependencies =
                 dependencies_set.into_iter().filter(|dep| dep != &namespace).collect();
@@ -218,15 +270,14 @@ mod use_std {
     // HELPER FUNCTIONS
     // --------------------------------------------------------------------------------------------
 
-    /// Read a directory and recursively feed the state map with path->ast tuples.
+    /// Read a directory and recursively feed the state map with path->source tuples.
     ///
     /// Helper for [`Self::read_from_dir`].
     fn read_from_dir_helper<P>(
-        mut state: BTreeMap<LibraryPath, ModuleAst>,
+        mut state: BTreeMap<LibraryPath, String>,
         dir: P,
         module_path: &LibraryPath,
-        deps: &mut BTreeSet<LibraryNamespace>,
-    ) -> io::Result<BTreeMap<LibraryPath, ModuleAst>>
+    ) -> io::Result<BTreeMap<LibraryPath, String>>
     where
         P: AsRef<Path>,
     {
@@ -243,8 +294,8 @@ mod use_std {
                 let module_path = module_path
                     .append(name)
                     .map_err(|err| io::Error::new(io::ErrorKind::Other, format!("{err}")))?;
-                state = read_from_dir_helper(state, path, &module_path, deps)?;
-            // if file, check if `masm`, parse & append; skip otherwise
+                state = read_from_dir_helper(state, path, &module_path)?;
+            // if file, check if `masm`, read & append; skip otherwise
             } else if ty.is_file() {
                 let path = entry.path();
 
@@ -267,15 +318,8 @@ mod use_std {
                         ));
                     }
 
-                    // read & parse file
+                    // read file
                     let contents = fs::read_to_string(&path)?;
-                    let ast = ModuleAst::parse(&contents)?;
-
-                    // add dependencies of this module to the dependencies of this library
-                    for path in ast.import_paths() {
-                        let ns = LibraryNamespace::new(path.first())?;
-                        deps.insert(ns);
-                    }
 
                     // build module path and add it to the map of modules
                     let module = if name == MaslLibrary::MOD {
@@ -286,7 +330,7 @@ mod use_std {
                             .map_err(|err| io::Error::new(io::ErrorKind::Other, format!("{err}")))?
                     };
 
-                    if state.insert(module, ast).is_some() {
+                    if state.insert(module, contents).is_some() {
                         unreachable!(
                             "the filesystem is inconsistent as it produced duplicated module paths"
                         );
@@ -296,12 +340,71 @@ mod use_std {
         }
         Ok(state)
     }
+
+    // MODULE PARSER
+    // --------------------------------------------------------------------------------------------
+
+    /// Parses the modules of a library from their sources.
+    ///
+    /// When a module imports constants from another module of the same library, the imported
+    /// module is parsed first. Constants cannot be imported via cyclic module dependencies.
+    struct ModuleParser {
+        sources: BTreeMap<LibraryPath, String>,
+        parsed: RefCell<BTreeMap<LibraryPath, ModuleAst>>,
+        in_progress: RefCell<BTreeSet<LibraryPath>>,
+    }
+
+    impl ModuleParser {
+        fn new(sources: BTreeMap<LibraryPath, String>) -> Self {
+            Self {
+                sources,
+                parsed: Default::default(),
+                in_progress: Default::default(),
+            }
+        }
+
+        /// Parses the module with the specified path, unless it has been parsed already.
+        fn parse(&self, path: &LibraryPath) -> Result<(), ParsingError> {
+            if self.parsed.borrow().contains_key(path) {
+                return Ok(());
+            }
+
+            self.in_progress.borrow_mut().insert(path.clone());
+            let result = ModuleAst::parse_with_constants(&self.sources[path], self);
+            self.in_progress.borrow_mut().remove(path);
+
+            self.parsed.borrow_mut().insert(path.clone(), result?);
+            Ok(())
+        }
+    }
+
+    impl ConstantResolver for ModuleParser {
+        fn get_exported_constants(&self, module_path: &LibraryPath) -> Option<ExportedConstMap> {
+            if !self.sources.contains_key(module_path)
+                || self.in_progress.borrow().contains(module_path)
+            {
+                return None;
+            }
+
+            // if the imported module fails to parse, the error is reported when the module
+            // itself is parsed
+            self.parse(module_path).ok()?;
+            self.parsed
+                .borrow()
+                .get(module_path)
+                .map(|ast| ast.exported_constants().clone())
+        }
+    }
 }
 
 impl Serializable for MaslLibrary {
     fn write_into<W: ByteWriter>(&self, target: &mut W) {
+        target.write_bytes(&MASL_MAGIC);
+        target.write_u8(MASL_VERSION);
+
         self.namespace.write_into(target);
         self.version.write_into(target);
+        self.optimization_level.write_into(target);
 
         let modules = self.modules();
 
@@ -331,8 +434,28 @@ impl Serializable for MaslLibrary {
 
 impl Deserializable for MaslLibrary {
     fn read_from<R: ByteReader>(source: &mut R) -> Result<Self, DeserializationError> {
+        let magic = source.read_array::<4>()?;
+        if magic != MASL_MAGIC {
+            return Err(DeserializationError::InvalidValue("invalid masl magic bytes".into()));
+        }
+        let format_version = source.read_u8()?;
+        if format_version == 0 || format_version > MASL_VERSION {
+            return Err(DeserializationError::InvalidValue(format!(
+                "unsupported masl version {format_version}, expected at most version {MASL_VERSION}"
+            )));
+        }
+        let ast_serde_options = AstSerdeOptions {
+            serialize_signatures: format_version >= MASL_SIGNATURES_VERSION,
+            ..AST_DEFAULT_SERDE_OPTIONS
+        };
+
         let namespace = LibraryNamespace::read_from(source)?;
         let version = Version::read_from(source)?;
+        let optimization_level = if format_version >= MASL_OPTIMIZATION_LEVEL_VERSION {
+            OptimizationLevel::read_from(source)?
+        } else {
+            OptimizationLevel::None
+        };
 
         // read dependencies
         let num_deps = source.read_u16()? as usize;
@@ -348,7 +471,7 @@ impl Deserializable for MaslLibrary {
             let path = LibraryPath::read_from(source)?
                 .prepend(&namespace)
                 .map_err(|err| DeserializationError::InvalidValue(format!("{err}")))?;
-            let ast = ModuleAst::read_from(source, AST_DEFAULT_SERDE_OPTIONS)?;
+            let ast = ModuleAst::read_from(source, ast_serde_options)?;
             modules.push(Module { path, ast });
         }
 
@@ -360,6 +483,7 @@ impl Deserializable for MaslLibrary {
 
         let deps = deps_set.into_iter().collect();
         Self::new(namespace, version, has_source_locations, modules, deps)
+            .map(|library| library.with_optimization_level(optimization_level))
             .map_err(|err| DeserializationError::InvalidValue(format!("{err}")))
     }
 }
@@ -1,12 +1,12 @@
 use super::{
     ast::{AstSerdeOptions, ModuleAst},
-    ByteReader, ByteWriter, Deserializable, DeserializationError, LibraryError, PathError,
-    Serializable, String, ToString, Vec, MAX_LABEL_LEN, NAMESPACE_LABEL_PARSER,
+    ByteReader, ByteWriter, Deserializable, DeserializationError, LibraryError, OptimizationLevel,
+    PathError, Serializable, String, ToString, Vec, MAX_LABEL_LEN, NAMESPACE_LABEL_PARSER,
 };
 use core::{cmp::Ordering, fmt, ops::Deref, str::from_utf8};
 
 mod masl;
-pub use masl::MaslLibrary;
+pub use masl::{MaslLibrary, MASL_MAGIC, MASL_VERSION};
 
 mod path;
 pub use path::LibraryPath;
@@ -41,6 +41,14 @@ pub trait Library {
 
     /// Returns the dependency libraries of this library.
     fn dependencies(&self) -> &[LibraryNamespace];
+
+    /// Returns the optimization level with which the modules of this library are compiled.
+    ///
+    /// Optimizations change MAST roots of the compiled procedures; thus, the level is a property
+    /// of the library rather than of the assembler compiling it.
+    fn optimization_level(&self) -> OptimizationLevel {
+        OptimizationLevel::None
+    }
 }
 
 impl<T> Library for &T
@@ -66,6 +74,10 @@ where
     fn dependencies(&self) -> &[LibraryNamespace] {
         T::dependencies(self)
     }
+
+    fn optimization_level(&self) -> OptimizationLevel {
+        T::optimization_level(self)
+    }
 }
 
 // MODULE
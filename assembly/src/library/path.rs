@@ -1,6 +1,6 @@
 use super::{
-    ByteReader, ByteWriter, Deserializable, DeserializationError, PathError, Serializable, String,
-    ToString, MAX_LABEL_LEN,
+    ByteReader, ByteWriter, Deserializable, DeserializationError, MaslLibrary, PathError,
+    Serializable, String, ToString, MAX_LABEL_LEN,
 };
 use core::{fmt, ops::Deref, str::from_utf8};
 
@@ -118,6 +118,12 @@ impl LibraryPath {
         self.path.split(Self::PATH_DELIM)
     }
 
+    /// Returns the path of the source file of the module at this path, relative to the root
+    /// directory of its library (e.g., `std/math/u64.masm` for `std::math::u64`).
+    pub fn to_source_path(&self) -> String {
+        format!("{}.{}", self.path.replace(Self::PATH_DELIM, "/"), MaslLibrary::MODULE_EXTENSION)
+    }
+
     /// Returns true if this path is for a kernel module.
     pub fn is_kernel_path(&self) -> bool {
         self.path == Self::KERNEL_PATH
@@ -1,5 +1,9 @@
-use super::{LibraryNamespace, LibraryPath, MaslLibrary, Module, ModuleAst, Version};
-use vm_core::utils::{Deserializable, Serializable, SliceReader};
+use super::{
+    Library, LibraryNamespace, LibraryPath, MaslLibrary, Module, ModuleAst, OptimizationLevel,
+    Version, MASL_MAGIC, MASL_VERSION,
+};
+use crate::ast::AstSerdeOptions;
+use vm_core::utils::{ByteWriter, Deserializable, Serializable, SliceReader};
 
 #[test]
 fn masl_locations_serialization() {
@@ -56,3 +60,137 @@ fn masl_locations_serialization() {
     bundle.clear_locations();
     assert_eq!(bundle, deserialized);
 }
+
+#[test]
+fn masl_optimization_level_serialization() {
+    let path = LibraryPath::new("test::foo").unwrap();
+    let ast = ModuleAst::parse("export.foo\n    push.1 push.2 add\nend").unwrap();
+    let modules = vec![Module::new(path, ast)];
+    let namespace = LibraryNamespace::new("test").unwrap();
+    let bundle = MaslLibrary::new(namespace, Version::MIN, false, modules, Vec::new()).unwrap();
+    assert_eq!(OptimizationLevel::None, bundle.optimization_level());
+
+    // the optimization level is preserved across serialization
+    let bundle = bundle.with_optimization_level(OptimizationLevel::Basic);
+    let mut bytes = Vec::new();
+    bundle.write_into(&mut bytes);
+    let deserialized = MaslLibrary::read_from(&mut SliceReader::new(&bytes)).unwrap();
+    assert_eq!(OptimizationLevel::Basic, deserialized.optimization_level());
+    assert_eq!(bundle, deserialized);
+
+    // libraries of the versions of the format preceding the optimization level are not optimized
+    let mut bytes = Vec::new();
+    bytes.write_bytes(&MASL_MAGIC);
+    bytes.write_u8(2);
+    bundle.root_ns().write_into(&mut bytes);
+    Version::MIN.write_into(&mut bytes);
+    bytes.write_u16(0);
+    bytes.write_u16(1);
+    let module = bundle.modules().next().unwrap();
+    module.path.strip_first().unwrap().write_into(&mut bytes);
+    module.ast.write_into(&mut bytes, AstSerdeOptions::new(true));
+    bytes.write_bool(false);
+    let deserialized = MaslLibrary::read_from(&mut SliceReader::new(&bytes)).unwrap();
+    assert_eq!(OptimizationLevel::None, deserialized.optimization_level());
+}
+
+#[test]
+fn masl_format_version() {
+    let path = LibraryPath::new("test::foo").unwrap();
+    let ast = ModuleAst::parse("export.foo\n    push.1\nend").unwrap();
+    let modules = vec![Module::new(path, ast)];
+    let namespace = LibraryNamespace::new("test").unwrap();
+    let bundle = MaslLibrary::new(namespace, Version::MIN, false, modules, Vec::new()).unwrap();
+
+    // serialized libraries start with the magic bytes followed by the format version
+    let mut bytes = Vec::new();
+    bundle.write_into(&mut bytes);
+    assert_eq!(MASL_MAGIC, bytes[..4]);
+    assert_eq!(MASL_VERSION, bytes[4]);
+
+    // libraries without the magic bytes or with an unknown version are rejected
+    let mut invalid = bytes.clone();
+    invalid[0] = b'X';
+    assert!(MaslLibrary::read_from(&mut SliceReader::new(&invalid)).is_err());
+    let mut invalid = bytes.clone();
+    invalid[4] = MASL_VERSION + 1;
+    assert!(MaslLibrary::read_from(&mut SliceReader::new(&invalid)).is_err());
+    assert!(MaslLibrary::read_from(&mut SliceReader::new(&bytes[5..])).is_err());
+}
+
+#[test]
+fn masl_signatures_serialization() {
+    let source = "export.foo # (a b -- c)\n    add\nend\nexport.bar\n    push.1\nend";
+    let path = LibraryPath::new("test::foo").unwrap();
+    let ast = ModuleAst::parse(source).unwrap();
+    let modules = vec![Module::new(path.clone(), ast.clone())];
+    let namespace = LibraryNamespace::new("test").unwrap();
+    let bundle =
+        MaslLibrary::new(namespace.clone(), Version::MIN, false, modules, Vec::new()).unwrap();
+
+    // procedures with and without signatures survive the round trip
+    let mut bytes = Vec::new();
+    bundle.write_into(&mut bytes);
+    let deserialized = MaslLibrary::read_from(&mut SliceReader::new(&bytes)).unwrap();
+    let signatures = |library: &MaslLibrary| {
+        let module = library.modules().next().unwrap();
+        module.ast.procs().iter().map(|proc| proc.signature).collect::<Vec<_>>()
+    };
+    assert_eq!(signatures(&bundle), signatures(&deserialized));
+    assert!(signatures(&deserialized)[0].is_some());
+    assert!(signatures(&deserialized)[1].is_none());
+    let mut expected = bundle.clone();
+    expected.clear_locations();
+    assert_eq!(expected, deserialized);
+
+    // libraries of the first version of the format do not contain signatures
+    let mut bytes = Vec::new();
+    bytes.write_bytes(&MASL_MAGIC);
+    bytes.write_u8(1);
+    namespace.write_into(&mut bytes);
+    Version::MIN.write_into(&mut bytes);
+    bytes.write_u16(0);
+    bytes.write_u16(1);
+    path.strip_first().unwrap().write_into(&mut bytes);
+    let options = AstSerdeOptions {
+        serialize_imports: true,
+        serialize_signatures: false,
+    };
+    ast.write_into(&mut bytes, options);
+    bytes.write_bool(false);
+    let deserialized = MaslLibrary::read_from(&mut SliceReader::new(&bytes)).unwrap();
+    assert_eq!(vec![None, None], signatures(&deserialized));
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn masl_read_from_dir_with_imported_constants() {
+    use super::Library;
+    use crate::ast::ConstantValue;
+    use std::fs;
+
+    // modules are parsed in the order of their paths, so `test::a` imports constants from a module
+    // which has not been parsed yet
+    let nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_nanos();
+    let dir_name = format!("miden_masl_imported_constants_{}_{nanos}", std::process::id());
+    let dir = std::env::temp_dir().join(dir_name);
+    fs::create_dir_all(&dir).unwrap();
+    fs::write(
+        dir.join("a.masm"),
+        "use.test::b\nexport.const.PTR=b::BASE+4\nexport.foo\n    push.b::ROOT\nend\n",
+    )
+    .unwrap();
+    fs::write(dir.join("b.masm"), "export.const.BASE=1000\nexport.const.ROOT=[1,2,3,4]\n").unwrap();
+
+    let namespace = LibraryNamespace::new("test").unwrap();
+    let result = MaslLibrary::read_from_dir(&dir, namespace, false, Version::MIN);
+    fs::remove_dir_all(&dir).unwrap();
+    let library = result.unwrap();
+
+    let path = LibraryPath::new("test::a").unwrap();
+    let a = library.modules().find(|m| m.path == path).unwrap();
+    assert_eq!(Some(&ConstantValue::Scalar(1004)), a.ast.exported_constants().get("PTR"));
+}
@@ -0,0 +1,273 @@
+use super::{
+    ast::{
+        AdviceInjectorNode, CodeBody, Instruction, ModuleAst, ModuleImports, Node, ProcedureAst,
+        ProgramAst, SourceLocation,
+    },
+    BTreeMap, BTreeSet, Felt, StarkField, String, ToString, Vec,
+};
+use core::fmt;
+
+mod rules;
+
+mod u32_tracker;
+use u32_tracker::U32Tracker;
+
+#[cfg(test)]
+mod tests;
+
+// LINT RULES
+// ================================================================================================
+
+/// Rules checked by the [Linter].
+///
+/// Each rule has a stable identifier (e.g., `unused-import`) which is attached to every finding
+/// reported for this rule, and which can be used to suppress the rule via [LintConfig].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum LintRule {
+    /// An internal procedure is not invoked from the program body or from any exported procedure,
+    /// either directly or transitively.
+    UnreachableProcedure,
+    /// A module is imported via a `use` statement but none of its items are referenced.
+    UnusedImport,
+    /// A procedure declares memory locals which it never accesses.
+    UnusedLocal,
+    /// A `u32unchecked_*` instruction is applied to values which are not proven to be u32 values.
+    UncheckedU32Operand,
+    /// A memory local or a memory address is written but never read.
+    DeadMemoryWrite,
+    /// A `while.true` loop always leaves a non-zero constant as the condition for the next
+    /// iteration, and thus never exits.
+    InfiniteLoop,
+    /// An `adv_push` instruction reads from the advice stack without a preceding advice injector
+    /// or procedure invocation which could have put data onto the advice stack.
+    AdvicePushWithoutInjector,
+}
+
+impl LintRule {
+    /// All rules checked by the linter.
+    pub const ALL: [Self; 7] = [
+        Self::UnreachableProcedure,
+        Self::UnusedImport,
+        Self::UnusedLocal,
+        Self::UncheckedU32Operand,
+        Self::DeadMemoryWrite,
+        Self::InfiniteLoop,
+        Self::AdvicePushWithoutInjector,
+    ];
+
+    /// Returns the identifier of this rule.
+    pub const fn id(&self) -> &'static str {
+        match self {
+            Self::UnreachableProcedure => "unreachable-proc",
+            Self::UnusedImport => "unused-import",
+            Self::UnusedLocal => "unused-local",
+            Self::UncheckedU32Operand => "unchecked-u32-operand",
+            Self::DeadMemoryWrite => "dead-memory-write",
+            Self::InfiniteLoop => "infinite-loop",
+            Self::AdvicePushWithoutInjector => "adv-push-without-injector",
+        }
+    }
+
+    /// Returns the rule with the specified identifier, or None if there is no such rule.
+    pub fn from_id(id: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|rule| rule.id() == id)
+    }
+}
+
+impl fmt::Display for LintRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.id())
+    }
+}
+
+// LINT FINDING
+// ================================================================================================
+
+/// A potential bug reported by the [Linter].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LintFinding {
+    rule: LintRule,
+    location: SourceLocation,
+    procedure: Option<String>,
+    message: String,
+}
+
+impl LintFinding {
+    /// Returns a new [LintFinding] for the specified rule.
+    pub fn new(
+        rule: LintRule,
+        location: SourceLocation,
+        procedure: Option<&str>,
+        message: String,
+    ) -> Self {
+        Self {
+            rule,
+            location,
+            procedure: procedure.map(|name| name.to_string()),
+            message,
+        }
+    }
+
+    /// Returns the rule which produced this finding.
+    pub fn rule(&self) -> LintRule {
+        self.rule
+    }
+
+    /// Returns the location of the source code this finding refers to.
+    pub fn location(&self) -> &SourceLocation {
+        &self.location
+    }
+
+    /// Returns the name of the procedure this finding refers to, or None if the finding refers to
+    /// the program body or to the module as a whole.
+    pub fn procedure(&self) -> Option<&str> {
+        self.procedure.as_deref()
+    }
+
+    /// Returns the description of this finding.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+impl fmt::Display for LintFinding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {} [{}]", self.location, self.message, self.rule)
+    }
+}
+
+// LINT CONFIG
+// ================================================================================================
+
+/// Configuration of the [Linter] specifying which findings should be suppressed.
+///
+/// A rule can be suppressed either everywhere or only within the procedures with the specified
+/// names; the latter can be used to silence findings which were reviewed and deemed intentional.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LintConfig {
+    suppressed: BTreeSet<LintRule>,
+    suppressed_in_procs: BTreeMap<String, BTreeSet<LintRule>>,
+}
+
+impl LintConfig {
+    /// Returns a new [LintConfig] with all rules enabled.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Suppresses all findings of the specified rule.
+    pub fn suppress(mut self, rule: LintRule) -> Self {
+        self.suppressed.insert(rule);
+        self
+    }
+
+    /// Suppresses the findings of the specified rule within the procedure with the specified name.
+    pub fn suppress_in(mut self, rule: LintRule, proc_name: &str) -> Self {
+        self.suppressed_in_procs.entry(proc_name.to_string()).or_default().insert(rule);
+        self
+    }
+
+    /// Returns true if the provided finding is suppressed by this config.
+    pub fn is_suppressed(&self, finding: &LintFinding) -> bool {
+        if self.suppressed.contains(&finding.rule) {
+            return true;
+        }
+        finding
+            .procedure()
+            .and_then(|name| self.suppressed_in_procs.get(name))
+            .map(|rules| rules.contains(&finding.rule))
+            .unwrap_or(false)
+    }
+}
+
+// LINTER
+// ================================================================================================
+
+/// Static analysis pass over program and module ASTs which flags common bugs in Miden assembly.
+///
+/// The linter reports the following potential issues (see [LintRule] for rule identifiers):
+/// - Internal procedures which are never invoked.
+/// - Imported modules which are never referenced.
+/// - Memory locals which are declared by a procedure but never accessed.
+/// - `u32unchecked_*` instructions applied to values which are not proven to be u32 values.
+/// - Memory locals, as well as absolute memory addresses of a program, which are written but never
+///   read.
+/// - `while.true` loops which never exit.
+/// - `adv_push` instructions which are not preceded by an advice injector.
+///
+/// All checks are heuristic: they are conservative about what constitutes a bug, but a finding
+/// does not necessarily mean that the code is incorrect.
+///
+/// Locations of imports, as well as references to imported constants, are available only for ASTs
+/// parsed from source; thus, ASTs should not be cleared of source locations or deserialized before
+/// linting.
+#[derive(Debug, Clone, Default)]
+pub struct Linter {
+    config: LintConfig,
+}
+
+impl Linter {
+    // CONSTRUCTOR
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns a new [Linter] instantiated with the specified config.
+    pub fn new(config: LintConfig) -> Self {
+        Self { config }
+    }
+
+    // PUBLIC METHODS
+    // --------------------------------------------------------------------------------------------
+
+    /// Checks the provided program and returns all findings not suppressed by the config of this
+    /// linter, ordered by their source location.
+    pub fn lint_program(&self, program: &ProgramAst) -> Vec<LintFinding> {
+        let procs = program.procedures();
+        let program_start = program.source_locations().next().copied().unwrap_or_default();
+        let body_scope = rules::Scope::new(None, program_start);
+
+        let mut findings = Vec::new();
+        let reachable = rules::reachable_procs(procs, [program.body()]);
+        findings.extend(rules::unreachable_procs(procs, &reachable));
+        if let Some(import_info) = program.import_info() {
+            findings.extend(rules::unused_imports(import_info));
+        }
+        findings.extend(rules::check_body(program.body(), &body_scope));
+        for proc in procs.iter() {
+            findings.extend(rules::check_procedure(proc));
+        }
+        findings.extend(rules::dead_memory_writes(program.body(), procs, program_start));
+
+        self.finalize(findings)
+    }
+
+    /// Checks the provided module and returns all findings not suppressed by the config of this
+    /// linter, ordered by their source location.
+    ///
+    /// Exported procedures of the module are assumed to be reachable.
+    pub fn lint_module(&self, module: &ModuleAst) -> Vec<LintFinding> {
+        let procs = module.procs();
+
+        let mut findings = Vec::new();
+        let exported = procs.iter().filter(|proc| proc.is_export).map(|proc| &proc.body);
+        let reachable = rules::reachable_procs(procs, exported);
+        findings.extend(rules::unreachable_procs(procs, &reachable));
+        if let Some(import_info) = module.import_info() {
+            findings.extend(rules::unused_imports(import_info));
+        }
+        for proc in procs.iter() {
+            findings.extend(rules::check_procedure(proc));
+        }
+
+        self.finalize(findings)
+    }
+
+    // HELPER METHODS
+    // --------------------------------------------------------------------------------------------
+
+    /// Removes suppressed findings and sorts the remaining ones by their location.
+    fn finalize(&self, mut findings: Vec<LintFinding>) -> Vec<LintFinding> {
+        findings.retain(|finding| !self.config.is_suppressed(finding));
+        findings.sort_by(|a, b| a.location.cmp(&b.location).then(a.rule.cmp(&b.rule)));
+        findings
+    }
+}
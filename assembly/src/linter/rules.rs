@@ -0,0 +1,473 @@
+use super::{
+    u32_tracker::{is_unchecked_u32_op, u32_op_arity},
+    AdviceInjectorNode, BTreeMap, BTreeSet, CodeBody, Instruction, LintFinding, LintRule,
+    ModuleImports, Node, ProcedureAst, SourceLocation, StarkField, String, ToString, U32Tracker,
+    Vec,
+};
+
+// SCOPE
+// ================================================================================================
+
+/// A code body being checked: either the body of a program, or the body of a procedure.
+pub struct Scope<'a> {
+    procedure: Option<&'a str>,
+    start: SourceLocation,
+}
+
+impl<'a> Scope<'a> {
+    /// Returns a new [Scope] for the procedure with the specified name, or for the program body if
+    /// the name is None.
+    pub fn new(procedure: Option<&'a str>, start: SourceLocation) -> Self {
+        Self { procedure, start }
+    }
+
+    /// Returns the location of the node at the specified index in the provided body, or the start
+    /// of the scope if the body has no source locations.
+    fn node_location(&self, body: &CodeBody, index: usize) -> SourceLocation {
+        body.source_locations().get(index).copied().unwrap_or(self.start)
+    }
+
+    /// Returns a new finding for the specified rule located within this scope.
+    fn finding(&self, rule: LintRule, location: SourceLocation, message: String) -> LintFinding {
+        LintFinding::new(rule, location, self.procedure, message)
+    }
+}
+
+// MODULE-LEVEL RULES
+// ================================================================================================
+
+/// Returns the indexes of the local procedures invoked, directly or transitively, from any of the
+/// provided code bodies.
+pub fn reachable_procs<'a, I>(procs: &[ProcedureAst], roots: I) -> BTreeSet<u16>
+where
+    I: IntoIterator<Item = &'a CodeBody>,
+{
+    let mut reachable = BTreeSet::new();
+    let mut pending = Vec::new();
+    for body in roots {
+        collect_local_invocations(body, &mut pending);
+    }
+
+    while let Some(index) = pending.pop() {
+        if reachable.insert(index) {
+            if let Some(proc) = procs.get(index as usize) {
+                collect_local_invocations(&proc.body, &mut pending);
+            }
+        }
+    }
+
+    reachable
+}
+
+/// Reports internal procedures which are not in the set of reachable procedures.
+pub fn unreachable_procs(procs: &[ProcedureAst], reachable: &BTreeSet<u16>) -> Vec<LintFinding> {
+    procs
+        .iter()
+        .enumerate()
+        .filter(|(index, proc)| !proc.is_export && !reachable.contains(&(*index as u16)))
+        .map(|(_, proc)| {
+            let message = format!("procedure '{}' is never invoked", proc.name);
+            LintFinding::new(
+                LintRule::UnreachableProcedure,
+                proc.start,
+                Some(proc.name.as_ref()),
+                message,
+            )
+        })
+        .collect()
+}
+
+/// Reports imported modules which are not referenced anywhere in the source.
+pub fn unused_imports(import_info: &ModuleImports) -> Vec<LintFinding> {
+    import_info
+        .imported_modules()
+        .filter(|(name, _)| !import_info.is_referenced(name))
+        .map(|(name, path)| {
+            let location = import_info.import_location(name).copied().unwrap_or_default();
+            let message = format!("module '{path}' is imported but never used");
+            LintFinding::new(LintRule::UnusedImport, location, None, message)
+        })
+        .collect()
+}
+
+/// Reports absolute memory addresses which are written by a program via instructions with
+/// immediate addresses, but are never read.
+///
+/// Since any address can be read by an instruction which takes the address from the stack, or by
+/// an imported procedure or a kernel procedure executing in the same memory context, no findings
+/// are reported for programs containing such instructions.
+pub fn dead_memory_writes(
+    body: &CodeBody,
+    procs: &[ProcedureAst],
+    program_start: SourceLocation,
+) -> Vec<LintFinding> {
+    let mut accesses = MemoryAccesses::default();
+    accesses.collect(body, &Scope::new(None, program_start));
+    for proc in procs.iter() {
+        accesses.collect(&proc.body, &Scope::new(Some(proc.name.as_ref()), proc.start));
+    }
+
+    if accesses.has_dynamic_reads {
+        return Vec::new();
+    }
+
+    accesses
+        .writes
+        .into_iter()
+        .filter(|(addr, _)| !accesses.reads.contains(addr))
+        .map(|(addr, (location, procedure))| {
+            let message = format!("memory address {addr} is written but never read");
+            LintFinding::new(LintRule::DeadMemoryWrite, location, procedure, message)
+        })
+        .collect()
+}
+
+// BODY RULES
+// ================================================================================================
+
+/// Checks the rules which apply to the body of a procedure, as well as the rules specific to
+/// procedures.
+pub fn check_procedure(proc: &ProcedureAst) -> Vec<LintFinding> {
+    let scope = Scope::new(Some(proc.name.as_ref()), proc.start);
+    let mut findings = check_body(&proc.body, &scope);
+    findings.extend(check_locals(proc, &scope));
+    findings
+}
+
+/// Checks the rules which apply to any code body.
+pub fn check_body(body: &CodeBody, scope: &Scope) -> Vec<LintFinding> {
+    let mut findings = Vec::new();
+    check_u32_operands(body, &mut U32Tracker::default(), scope, &mut findings);
+    check_loops(body, scope, &mut findings);
+    check_advice_reads(body, &mut false, scope, &mut findings);
+    findings
+}
+
+/// Reports unused memory locals of the procedure, as well as the locals which are written but
+/// never read.
+///
+/// Local addresses obtained via `locaddr` can be used to access any local, and thus procedures
+/// which use `locaddr` are not checked.
+fn check_locals(proc: &ProcedureAst, scope: &Scope) -> Vec<LintFinding> {
+    let mut accesses = LocalAccesses::default();
+    accesses.collect(&proc.body, scope);
+    if accesses.has_locaddr {
+        return Vec::new();
+    }
+
+    let mut findings = Vec::new();
+    let unused: Vec<_> = (0..proc.num_locals)
+        .filter(|index| !accesses.reads.contains(index) && !accesses.writes.contains_key(index))
+        .map(|index| index.to_string())
+        .collect();
+    if unused.len() == proc.num_locals as usize && !unused.is_empty() {
+        let message = format!(
+            "procedure '{}' declares {} locals but never accesses them",
+            proc.name, proc.num_locals
+        );
+        findings.push(scope.finding(LintRule::UnusedLocal, proc.start, message));
+    } else if !unused.is_empty() {
+        let message = format!(
+            "procedure '{}' declares {} locals but never accesses locals {}",
+            proc.name,
+            proc.num_locals,
+            unused.join(", ")
+        );
+        findings.push(scope.finding(LintRule::UnusedLocal, proc.start, message));
+    }
+
+    for (index, location) in accesses.writes.iter() {
+        if !accesses.reads.contains(index) {
+            let message = format!("local {index} is written but never read");
+            findings.push(scope.finding(LintRule::DeadMemoryWrite, *location, message));
+        }
+    }
+
+    findings
+}
+
+/// Reports `u32unchecked_*` instructions applied to values which are not proven to be u32 values.
+///
+/// Values are tracked only within straight-line code: the stack is assumed to be unknown at the
+/// start of loop bodies and after control flow blocks.
+fn check_u32_operands(
+    body: &CodeBody,
+    tracker: &mut U32Tracker,
+    scope: &Scope,
+    findings: &mut Vec<LintFinding>,
+) {
+    for (idx, node) in body.nodes().iter().enumerate() {
+        match node {
+            Node::Instruction(instruction) => {
+                if is_unchecked_u32_op(instruction) {
+                    let (num_inputs, _) = u32_op_arity(instruction).expect("not a u32 operation");
+                    if !tracker.is_u32(num_inputs) {
+                        let message = format!(
+                            "'{instruction}' is applied to values which are not proven to be u32"
+                        );
+                        let location = scope.node_location(body, idx);
+                        findings.push(scope.finding(
+                            LintRule::UncheckedU32Operand,
+                            location,
+                            message,
+                        ));
+                    }
+                }
+                tracker.apply(instruction);
+            }
+            Node::IfElse {
+                true_case,
+                false_case,
+            } => {
+                tracker.pop(1);
+                check_u32_operands(true_case, &mut tracker.clone(), scope, findings);
+                check_u32_operands(false_case, &mut tracker.clone(), scope, findings);
+                tracker.reset();
+            }
+            Node::Repeat { body, .. } | Node::While { body } => {
+                check_u32_operands(body, &mut U32Tracker::default(), scope, findings);
+                tracker.reset();
+            }
+        }
+    }
+}
+
+/// Reports `while.true` loops which never exit because their bodies always end by pushing a
+/// non-zero constant onto the stack.
+fn check_loops(body: &CodeBody, scope: &Scope, findings: &mut Vec<LintFinding>) {
+    for (idx, node) in body.nodes().iter().enumerate() {
+        match node {
+            Node::Instruction(_) => (),
+            Node::IfElse {
+                true_case,
+                false_case,
+            } => {
+                check_loops(true_case, scope, findings);
+                check_loops(false_case, scope, findings);
+            }
+            Node::Repeat {
+                body: repeat_body, ..
+            } => check_loops(repeat_body, scope, findings),
+            Node::While { body: loop_body } => {
+                if ends_with_nonzero_constant(loop_body) {
+                    let message = "while loop never exits: its body always ends by pushing a \
+                        non-zero constant"
+                        .to_string();
+                    let location = scope.node_location(body, idx);
+                    findings.push(scope.finding(LintRule::InfiniteLoop, location, message));
+                }
+                check_loops(loop_body, scope, findings);
+            }
+        }
+    }
+}
+
+/// Reports `adv_push` instructions which are not preceded by an advice injector or by a procedure
+/// invocation which could have pushed data onto the advice stack.
+///
+/// Only the first such instruction is reported within a code body, since the following reads are
+/// likely to consume the same advice data.
+fn check_advice_reads(
+    body: &CodeBody,
+    injected: &mut bool,
+    scope: &Scope,
+    findings: &mut Vec<LintFinding>,
+) {
+    for (idx, node) in body.nodes().iter().enumerate() {
+        match node {
+            Node::Instruction(Instruction::AdvPush(n)) => {
+                if !*injected {
+                    let message = format!(
+                        "'adv_push.{n}' reads from the advice stack, but no preceding instruction \
+                        injects data into it"
+                    );
+                    let location = scope.node_location(body, idx);
+                    findings.push(scope.finding(
+                        LintRule::AdvicePushWithoutInjector,
+                        location,
+                        message,
+                    ));
+                    *injected = true;
+                }
+            }
+            Node::Instruction(instruction) => {
+                *injected |= is_advice_source(instruction);
+            }
+            Node::IfElse {
+                true_case,
+                false_case,
+            } => {
+                let mut true_injected = *injected;
+                check_advice_reads(true_case, &mut true_injected, scope, findings);
+                let mut false_injected = *injected;
+                check_advice_reads(false_case, &mut false_injected, scope, findings);
+                *injected = true_injected || false_injected;
+            }
+            Node::Repeat { body, .. } | Node::While { body } => {
+                // an injector at the end of a loop body supplies data to the next iteration
+                *injected |= contains_advice_source(body);
+                check_advice_reads(body, injected, scope, findings);
+            }
+        }
+    }
+}
+
+// MEMORY ACCESSES
+// ================================================================================================
+
+/// Accesses to procedure locals.
+#[derive(Default)]
+struct LocalAccesses {
+    reads: BTreeSet<u16>,
+    writes: BTreeMap<u16, SourceLocation>,
+    has_locaddr: bool,
+}
+
+impl LocalAccesses {
+    fn collect(&mut self, body: &CodeBody, scope: &Scope) {
+        for (idx, node) in body.nodes().iter().enumerate() {
+            match node {
+                Node::Instruction(instruction) => match instruction {
+                    Instruction::LocLoad(index) | Instruction::LocLoadW(index) => {
+                        self.reads.insert(*index);
+                    }
+                    Instruction::LocStore(index) | Instruction::LocStoreW(index) => {
+                        self.writes.entry(*index).or_insert_with(|| scope.node_location(body, idx));
+                    }
+                    Instruction::Locaddr(_) => self.has_locaddr = true,
+                    _ => (),
+                },
+                Node::IfElse {
+                    true_case,
+                    false_case,
+                } => {
+                    self.collect(true_case, scope);
+                    self.collect(false_case, scope);
+                }
+                Node::Repeat { body, .. } | Node::While { body } => self.collect(body, scope),
+            }
+        }
+    }
+}
+
+/// Accesses to absolute memory addresses.
+#[derive(Default)]
+struct MemoryAccesses<'a> {
+    reads: BTreeSet<u32>,
+    writes: BTreeMap<u32, (SourceLocation, Option<&'a str>)>,
+    has_dynamic_reads: bool,
+}
+
+impl<'a> MemoryAccesses<'a> {
+    fn collect(&mut self, body: &CodeBody, scope: &Scope<'a>) {
+        use Instruction::*;
+
+        for (idx, node) in body.nodes().iter().enumerate() {
+            match node {
+                Node::Instruction(instruction) => match instruction {
+                    MemLoadImm(addr) | MemLoadWImm(addr) => {
+                        self.reads.insert(*addr);
+                    }
+                    MemStoreImm(addr) | MemStoreWImm(addr) => {
+                        let location = scope.node_location(body, idx);
+                        self.writes.entry(*addr).or_insert((location, scope.procedure));
+                    }
+                    MemLoad | MemLoadW | MemStream | ExecImported(_) | DynExec | SysCall(_) => {
+                        self.has_dynamic_reads = true
+                    }
+                    _ => (),
+                },
+                Node::IfElse {
+                    true_case,
+                    false_case,
+                } => {
+                    self.collect(true_case, scope);
+                    self.collect(false_case, scope);
+                }
+                Node::Repeat { body, .. } | Node::While { body } => self.collect(body, scope),
+            }
+        }
+    }
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Appends the indexes of the local procedures invoked from the provided body to the list.
+fn collect_local_invocations(body: &CodeBody, result: &mut Vec<u16>) {
+    for node in body.nodes() {
+        match node {
+            Node::Instruction(Instruction::ExecLocal(index))
+            | Node::Instruction(Instruction::CallLocal(index)) => result.push(*index),
+            Node::Instruction(_) => (),
+            Node::IfElse {
+                true_case,
+                false_case,
+            } => {
+                collect_local_invocations(true_case, result);
+                collect_local_invocations(false_case, result);
+            }
+            Node::Repeat { body, .. } | Node::While { body } => {
+                collect_local_invocations(body, result)
+            }
+        }
+    }
+}
+
+/// Returns true if the last node of the provided body always pushes a non-zero constant onto the
+/// stack.
+fn ends_with_nonzero_constant(body: &CodeBody) -> bool {
+    use Instruction::*;
+
+    match body.nodes().last() {
+        Some(Node::Instruction(instruction)) => match instruction {
+            PushU8(value) => *value != 0,
+            PushU16(value) => *value != 0,
+            PushU32(value) => *value != 0,
+            PushFelt(value) => value.as_int() != 0,
+            PushU8List(values) => values.last().map(|v| *v != 0).unwrap_or(false),
+            PushU16List(values) => values.last().map(|v| *v != 0).unwrap_or(false),
+            PushU32List(values) => values.last().map(|v| *v != 0).unwrap_or(false),
+            PushFeltList(values) => values.last().map(|v| v.as_int() != 0).unwrap_or(false),
+            _ => false,
+        },
+        Some(Node::IfElse {
+            true_case,
+            false_case,
+        }) => ends_with_nonzero_constant(true_case) && ends_with_nonzero_constant(false_case),
+        Some(Node::Repeat { times, body }) => *times > 0 && ends_with_nonzero_constant(body),
+        Some(Node::While { .. }) | None => false,
+    }
+}
+
+/// Returns true if the provided instruction could push data onto the advice stack.
+///
+/// Invoked procedures are assumed to inject advice data, since their bodies are not inspected.
+fn is_advice_source(instruction: &Instruction) -> bool {
+    use Instruction::*;
+
+    match instruction {
+        AdvInject(injector) => !matches!(
+            injector,
+            AdviceInjectorNode::InsertMem
+                | AdviceInjectorNode::InsertHdword
+                | AdviceInjectorNode::InsertHdwordImm { .. }
+                | AdviceInjectorNode::InsertHperm
+        ),
+        ExecLocal(_) | ExecImported(_) | CallLocal(_) | CallMastRoot(_) | CallImported(_)
+        | SysCall(_) | DynExec | DynCall => true,
+        _ => false,
+    }
+}
+
+/// Returns true if any instruction in the provided body could push data onto the advice stack.
+fn contains_advice_source(body: &CodeBody) -> bool {
+    body.nodes().iter().any(|node| match node {
+        Node::Instruction(instruction) => is_advice_source(instruction),
+        Node::IfElse {
+            true_case,
+            false_case,
+        } => contains_advice_source(true_case) || contains_advice_source(false_case),
+        Node::Repeat { body, .. } | Node::While { body } => contains_advice_source(body),
+    })
+}
@@ -0,0 +1,247 @@
+use super::{LintConfig, LintFinding, LintRule, Linter};
+use crate::{
+    ast::{ConstantResolver, ConstantValue, ExportedConstMap, ModuleAst, ProgramAst},
+    LibraryPath,
+};
+
+// HELPERS
+// ================================================================================================
+
+/// Returns the rules and the source lines of the findings reported for the provided findings.
+fn summarize(findings: &[LintFinding]) -> Vec<(LintRule, u32)> {
+    findings
+        .iter()
+        .map(|finding| (finding.rule(), finding.location().line()))
+        .collect()
+}
+
+fn lint_program(source: &str) -> Vec<(LintRule, u32)> {
+    let program = ProgramAst::parse(source).unwrap();
+    summarize(&Linter::default().lint_program(&program))
+}
+
+fn lint_module(source: &str) -> Vec<(LintRule, u32)> {
+    let module = ModuleAst::parse(source).unwrap();
+    summarize(&Linter::default().lint_module(&module))
+}
+
+/// Resolves every imported module to a module exporting a single constant `ZERO`.
+struct ZeroConstantResolver;
+
+impl ConstantResolver for ZeroConstantResolver {
+    fn get_exported_constants(&self, _module_path: &LibraryPath) -> Option<ExportedConstMap> {
+        Some([("ZERO".to_string(), ConstantValue::Scalar(0))].into())
+    }
+}
+
+// TESTS
+// ================================================================================================
+
+#[test]
+fn lint_clean_program() {
+    let source = "\
+    use.std::math::u64
+    const.ADDR=u64::ZERO
+    proc.foo.1
+        loc_store.0
+        loc_load.0
+    end
+    begin
+        push.1.2 u32assert2 u32unchecked_div
+        exec.foo
+        push.5 mem_store.7 mem_load.7
+        adv.push_u64div adv_push.2
+        push.ADDR
+        while.true
+            sub.1 dup neq.0
+        end
+    end";
+    let program = ProgramAst::parse_with_constants(source, &ZeroConstantResolver).unwrap();
+    assert!(Linter::default().lint_program(&program).is_empty());
+}
+
+#[test]
+fn lint_unreachable_procs() {
+    let source = "\
+    proc.foo
+        add
+    end
+    proc.bar
+        exec.foo
+    end
+    proc.baz
+        mul
+    end
+    begin
+        call.bar
+    end";
+    assert_eq!(vec![(LintRule::UnreachableProcedure, 7)], lint_program(source));
+
+    let source = "\
+    proc.foo
+        add
+    end
+    proc.bar
+        mul
+    end
+    export.baz
+        exec.foo
+    end";
+    assert_eq!(vec![(LintRule::UnreachableProcedure, 4)], lint_module(source));
+}
+
+#[test]
+fn lint_unused_imports() {
+    let source = "\
+    use.std::math::u64
+    use.std::crypto::hashes::blake3
+    use.std::sys
+    export.foo
+        exec.u64::checked_add
+    end
+    export.blake3::hash";
+    assert_eq!(vec![(LintRule::UnusedImport, 3)], lint_module(source));
+}
+
+#[test]
+fn lint_unused_locals() {
+    let source = "\
+    export.foo.2
+        loc_storew.0 loc_loadw.0
+    end
+    export.bar.1
+        add
+    end
+    export.baz.4
+        locaddr.0 mem_load
+    end";
+    let findings = lint_module(source);
+    assert_eq!(vec![(LintRule::UnusedLocal, 1), (LintRule::UnusedLocal, 4)], findings);
+}
+
+#[test]
+fn lint_dead_memory_writes() {
+    let source = "\
+    export.foo.2
+        loc_store.0
+        loc_store.1
+        loc_load.1
+    end";
+    assert_eq!(vec![(LintRule::DeadMemoryWrite, 2)], lint_module(source));
+
+    let source = "\
+    begin
+        push.1 mem_store.1
+        push.2 mem_store.2
+        mem_load.2
+    end";
+    assert_eq!(vec![(LintRule::DeadMemoryWrite, 2)], lint_program(source));
+
+    // an address may be read by an instruction which takes the address from the stack
+    let source = "\
+    begin
+        push.1 mem_store.1
+        push.1 mem_load
+    end";
+    assert!(lint_program(source).is_empty());
+}
+
+#[test]
+fn lint_unchecked_u32_operands() {
+    let source = "\
+    export.foo
+        u32unchecked_shr.2
+        dup.5 u32unchecked_shr.2
+        dup.5 u32assert u32unchecked_shr.2
+        push.3 swap u32unchecked_min
+        mem_load u32unchecked_lt
+        push.4.5 if.true u32unchecked_lt else mul end
+        u32checked_add push.1 u32unchecked_lt
+    end";
+    let expected = vec![
+        (LintRule::UncheckedU32Operand, 2),
+        (LintRule::UncheckedU32Operand, 3),
+        (LintRule::UncheckedU32Operand, 6),
+    ];
+    assert_eq!(expected, lint_module(source));
+}
+
+#[test]
+fn lint_infinite_loops() {
+    let source = "\
+    begin
+        push.1
+        while.true
+            push.2 drop
+            push.1
+        end
+        push.1
+        while.true
+            if.true push.1 else push.0 end
+        end
+        push.1
+        while.true
+            if.true push.1 else push.2 end
+        end
+    end";
+    let expected = vec![(LintRule::InfiniteLoop, 3), (LintRule::InfiniteLoop, 12)];
+    assert_eq!(expected, lint_program(source));
+}
+
+#[test]
+fn lint_advice_push_without_injector() {
+    let source = "\
+    proc.foo
+        adv_push.1 adv_push.1
+    end
+    proc.bar
+        push.1
+        if.true
+            adv.push_mapval
+        end
+        adv_push.1
+    end
+    proc.baz
+        adv.insert_mem adv_push.4
+    end
+    begin
+        exec.foo exec.bar exec.baz
+    end";
+    let expected = vec![
+        (LintRule::AdvicePushWithoutInjector, 2),
+        (LintRule::AdvicePushWithoutInjector, 12),
+    ];
+    assert_eq!(expected, lint_program(source));
+}
+
+#[test]
+fn lint_suppressed_findings() {
+    let source = "\
+    proc.foo.1
+        adv_push.1
+    end
+    proc.bar.1
+        adv_push.1
+    end
+    begin
+        exec.foo exec.bar
+    end";
+    let program = ProgramAst::parse(source).unwrap();
+
+    let config = LintConfig::new()
+        .suppress(LintRule::UnusedLocal)
+        .suppress_in(LintRule::AdvicePushWithoutInjector, "foo");
+    let findings = Linter::new(config).lint_program(&program);
+    assert_eq!(vec![(LintRule::AdvicePushWithoutInjector, 5)], summarize(&findings));
+    assert_eq!(Some("bar"), findings[0].procedure());
+    assert_eq!(
+        "[5:9] 'adv_push.1' reads from the advice stack, but no preceding instruction injects \
+        data into it [adv-push-without-injector]",
+        findings[0].to_string()
+    );
+
+    for rule in LintRule::ALL {
+        assert_eq!(Some(rule), LintRule::from_id(rule.id()));
+    }
+    assert_eq!(None, LintRule::from_id("unknown-rule"));
+}
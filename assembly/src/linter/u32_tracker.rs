@@ -0,0 +1,314 @@
+use super::{Felt, Instruction, StarkField, Vec};
+
+// CONSTANTS
+// ================================================================================================
+
+/// Maximum number of top stack elements tracked; deeper elements are always considered unknown.
+const MAX_TRACKED_DEPTH: usize = 32;
+
+// U32 TRACKER
+// ================================================================================================
+
+/// Tracks which of the top stack elements are proven to be u32 values over a straight-line
+/// sequence of instructions.
+///
+/// An element is proven to be a u32 value if it was pushed as a u32 constant, asserted via one of
+/// the `u32assert` instructions, or produced by an instruction which always outputs u32 values
+/// (e.g., a comparison or a u32 operation). Instructions whose effect on the stack is not modeled
+/// reset the tracker, making all elements unknown.
+#[derive(Debug, Clone, Default)]
+pub struct U32Tracker {
+    /// Flags of the tracked stack elements; the last flag belongs to the top of the stack.
+    stack: Vec<bool>,
+}
+
+impl U32Tracker {
+    // PUBLIC ACCESSORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns true if the top `n` elements of the stack are proven to be u32 values.
+    pub fn is_u32(&self, n: usize) -> bool {
+        self.stack.len() >= n && self.stack[self.stack.len() - n..].iter().all(|&flag| flag)
+    }
+
+    // STATE MUTATORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Forgets everything known about the stack.
+    pub fn reset(&mut self) {
+        self.stack.clear();
+    }
+
+    /// Removes the top `n` elements from the stack.
+    pub fn pop(&mut self, n: usize) {
+        self.stack.truncate(self.stack.len().saturating_sub(n));
+    }
+
+    /// Updates the tracker with the effect of the provided instruction.
+    pub fn apply(&mut self, instruction: &Instruction) {
+        use Instruction::*;
+
+        match instruction {
+            // ----- constants ------------------------------------------------------------------
+            PushU8(_) | PushU16(_) | PushU32(_) | Locaddr(_) | Sdepth | Clk => self.push(true),
+            PushFelt(value) => self.push(is_u32_felt(value)),
+            PushWord(values) => values.iter().for_each(|value| self.push(is_u32_felt(value))),
+            PushU8List(values) => values.iter().for_each(|_| self.push(true)),
+            PushU16List(values) => values.iter().for_each(|_| self.push(true)),
+            PushU32List(values) => values.iter().for_each(|_| self.push(true)),
+            PushFeltList(values) => values.iter().for_each(|value| self.push(is_u32_felt(value))),
+            PadW => (0..4).for_each(|_| self.push(true)),
+
+            // ----- u32 assertions -------------------------------------------------------------
+            U32Assert | U32AssertWithError(_) | U32Cast => self.assert_u32(1),
+            U32Assert2 | U32Assert2WithError(_) => self.assert_u32(2),
+            U32AssertW | U32AssertWWithError(_) => self.assert_u32(4),
+            U32Test | U32TestW => self.push(true),
+            U32Split => {
+                self.pop(1);
+                self.push(true);
+                self.push(true);
+            }
+
+            // ----- operations with boolean outputs --------------------------------------------
+            Eq | Neq | Lt | Lte | Gt | Gte | And | Or | Xor => self.replace(2, 1),
+            EqImm(_) | NeqImm(_) | Not | IsOdd => self.replace(1, 1),
+            Eqw => self.push(true),
+
+            // ----- stack manipulation ---------------------------------------------------------
+            Drop => self.pop(1),
+            DropW => self.pop(4),
+            Dup0 => self.dup(0),
+            Dup1 => self.dup(1),
+            Dup2 => self.dup(2),
+            Dup3 => self.dup(3),
+            Dup4 => self.dup(4),
+            Dup5 => self.dup(5),
+            Dup6 => self.dup(6),
+            Dup7 => self.dup(7),
+            Dup8 => self.dup(8),
+            Dup9 => self.dup(9),
+            Dup10 => self.dup(10),
+            Dup11 => self.dup(11),
+            Dup12 => self.dup(12),
+            Dup13 => self.dup(13),
+            Dup14 => self.dup(14),
+            Dup15 => self.dup(15),
+            DupW0 => (0..4).for_each(|_| self.dup(3)),
+            DupW1 => (0..4).for_each(|_| self.dup(7)),
+            DupW2 => (0..4).for_each(|_| self.dup(11)),
+            DupW3 => (0..4).for_each(|_| self.dup(15)),
+            Swap1 => self.swap(0, 1, 1),
+            Swap2 => self.swap(0, 2, 1),
+            Swap3 => self.swap(0, 3, 1),
+            Swap4 => self.swap(0, 4, 1),
+            Swap5 => self.swap(0, 5, 1),
+            Swap6 => self.swap(0, 6, 1),
+            Swap7 => self.swap(0, 7, 1),
+            Swap8 => self.swap(0, 8, 1),
+            Swap9 => self.swap(0, 9, 1),
+            Swap10 => self.swap(0, 10, 1),
+            Swap11 => self.swap(0, 11, 1),
+            Swap12 => self.swap(0, 12, 1),
+            Swap13 => self.swap(0, 13, 1),
+            Swap14 => self.swap(0, 14, 1),
+            Swap15 => self.swap(0, 15, 1),
+            SwapW1 => self.swap(0, 4, 4),
+            SwapW2 => self.swap(0, 8, 4),
+            SwapW3 => self.swap(0, 12, 4),
+            SwapDw => self.swap(0, 8, 8),
+            MovUp2 => self.movup(2),
+            MovUp3 => self.movup(3),
+            MovUp4 => self.movup(4),
+            MovUp5 => self.movup(5),
+            MovUp6 => self.movup(6),
+            MovUp7 => self.movup(7),
+            MovUp8 => self.movup(8),
+            MovUp9 => self.movup(9),
+            MovUp10 => self.movup(10),
+            MovUp11 => self.movup(11),
+            MovUp12 => self.movup(12),
+            MovUp13 => self.movup(13),
+            MovUp14 => self.movup(14),
+            MovUp15 => self.movup(15),
+            MovUpW2 => (0..4).for_each(|_| self.movup(11)),
+            MovUpW3 => (0..4).for_each(|_| self.movup(15)),
+            MovDn2 => self.movdn(2),
+            MovDn3 => self.movdn(3),
+            MovDn4 => self.movdn(4),
+            MovDn5 => self.movdn(5),
+            MovDn6 => self.movdn(6),
+            MovDn7 => self.movdn(7),
+            MovDn8 => self.movdn(8),
+            MovDn9 => self.movdn(9),
+            MovDn10 => self.movdn(10),
+            MovDn11 => self.movdn(11),
+            MovDn12 => self.movdn(12),
+            MovDn13 => self.movdn(13),
+            MovDn14 => self.movdn(14),
+            MovDn15 => self.movdn(15),
+            MovDnW2 => (0..4).for_each(|_| self.movdn(11)),
+            MovDnW3 => (0..4).for_each(|_| self.movdn(15)),
+
+            // ----- u32 operations and everything else -----------------------------------------
+            _ => match u32_op_arity(instruction) {
+                Some((num_inputs, num_outputs)) => self.replace(num_inputs, num_outputs),
+                None => self.reset(),
+            },
+        }
+    }
+
+    // HELPER METHODS
+    // --------------------------------------------------------------------------------------------
+
+    /// Pushes an element onto the stack.
+    fn push(&mut self, is_u32: bool) {
+        self.stack.push(is_u32);
+        if self.stack.len() > MAX_TRACKED_DEPTH {
+            self.stack.remove(0);
+        }
+    }
+
+    /// Replaces the top `num_inputs` elements with `num_outputs` u32 values.
+    fn replace(&mut self, num_inputs: usize, num_outputs: usize) {
+        self.pop(num_inputs);
+        (0..num_outputs).for_each(|_| self.push(true));
+    }
+
+    /// Marks the top `n` elements of the stack as u32 values.
+    fn assert_u32(&mut self, n: usize) {
+        self.extend_to(n);
+        let len = self.stack.len();
+        self.stack[len - n..].iter_mut().for_each(|flag| *flag = true);
+    }
+
+    /// Pushes a copy of the element at the specified depth onto the stack.
+    fn dup(&mut self, depth: usize) {
+        let is_u32 = self.stack.len() > depth && self.stack[self.stack.len() - 1 - depth];
+        self.push(is_u32);
+    }
+
+    /// Swaps `n` elements starting at depth `a` with `n` elements starting at depth `b`.
+    fn swap(&mut self, a: usize, b: usize, n: usize) {
+        self.extend_to(b + n);
+        let len = self.stack.len();
+        for i in 0..n {
+            self.stack.swap(len - 1 - a - i, len - 1 - b - i);
+        }
+    }
+
+    /// Moves the element at the specified depth to the top of the stack.
+    fn movup(&mut self, depth: usize) {
+        self.extend_to(depth + 1);
+        let is_u32 = self.stack.remove(self.stack.len() - 1 - depth);
+        self.stack.push(is_u32);
+    }
+
+    /// Moves the top element of the stack to the specified depth.
+    fn movdn(&mut self, depth: usize) {
+        self.extend_to(depth + 1);
+        let is_u32 = self.stack.pop().expect("stack extended");
+        self.stack.insert(self.stack.len() - depth, is_u32);
+    }
+
+    /// Makes sure at least `depth` top elements are tracked by padding the bottom of the tracked
+    /// stack with unknown elements.
+    fn extend_to(&mut self, depth: usize) {
+        while self.stack.len() < depth {
+            self.stack.insert(0, false);
+        }
+    }
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Returns true if the provided instruction is one of the `u32unchecked_*` instructions.
+pub fn is_unchecked_u32_op(instruction: &Instruction) -> bool {
+    use Instruction::*;
+
+    matches!(
+        instruction,
+        U32UncheckedDiv
+            | U32UncheckedDivImm(_)
+            | U32UncheckedMod
+            | U32UncheckedModImm(_)
+            | U32UncheckedDivMod
+            | U32UncheckedDivModImm(_)
+            | U32UncheckedShr
+            | U32UncheckedShrImm(_)
+            | U32UncheckedShl
+            | U32UncheckedShlImm(_)
+            | U32UncheckedRotr
+            | U32UncheckedRotrImm(_)
+            | U32UncheckedRotl
+            | U32UncheckedRotlImm(_)
+            | U32UncheckedPopcnt
+            | U32UncheckedLt
+            | U32UncheckedLte
+            | U32UncheckedGt
+            | U32UncheckedGte
+            | U32UncheckedMin
+            | U32UncheckedMax
+    )
+}
+
+/// Returns the number of stack elements consumed and produced by the provided u32 operation, or
+/// None if the instruction is not a u32 operation.
+///
+/// All outputs of u32 operations are u32 values.
+pub fn u32_op_arity(instruction: &Instruction) -> Option<(usize, usize)> {
+    use Instruction::*;
+
+    let arity = match instruction {
+        U32CheckedAdd | U32WrappingAdd | U32CheckedSub | U32WrappingSub | U32CheckedMul
+        | U32WrappingMul | U32CheckedDiv | U32UncheckedDiv | U32CheckedMod | U32UncheckedMod
+        | U32CheckedAnd | U32CheckedOr | U32CheckedXor | U32CheckedShr | U32UncheckedShr
+        | U32CheckedShl | U32UncheckedShl | U32CheckedRotr | U32UncheckedRotr | U32CheckedRotl
+        | U32UncheckedRotl | U32CheckedEq | U32CheckedNeq | U32CheckedLt | U32UncheckedLt
+        | U32CheckedLte | U32UncheckedLte | U32CheckedGt | U32UncheckedGt | U32CheckedGte
+        | U32UncheckedGte | U32CheckedMin | U32UncheckedMin | U32CheckedMax | U32UncheckedMax => {
+            (2, 1)
+        }
+        U32CheckedAddImm(_)
+        | U32WrappingAddImm(_)
+        | U32CheckedSubImm(_)
+        | U32WrappingSubImm(_)
+        | U32CheckedMulImm(_)
+        | U32WrappingMulImm(_)
+        | U32CheckedDivImm(_)
+        | U32UncheckedDivImm(_)
+        | U32CheckedModImm(_)
+        | U32UncheckedModImm(_)
+        | U32CheckedNot
+        | U32CheckedShrImm(_)
+        | U32UncheckedShrImm(_)
+        | U32CheckedShlImm(_)
+        | U32UncheckedShlImm(_)
+        | U32CheckedRotrImm(_)
+        | U32UncheckedRotrImm(_)
+        | U32CheckedRotlImm(_)
+        | U32UncheckedRotlImm(_)
+        | U32CheckedPopcnt
+        | U32UncheckedPopcnt
+        | U32CheckedEqImm(_)
+        | U32CheckedNeqImm(_) => (1, 1),
+        U32OverflowingAdd | U32OverflowingSub | U32OverflowingMul | U32CheckedDivMod
+        | U32UncheckedDivMod => (2, 2),
+        U32OverflowingAddImm(_)
+        | U32OverflowingSubImm(_)
+        | U32OverflowingMulImm(_)
+        | U32CheckedDivModImm(_)
+        | U32UncheckedDivModImm(_) => (1, 2),
+        U32OverflowingAdd3 | U32OverflowingMadd => (3, 2),
+        U32WrappingAdd3 | U32WrappingMadd => (3, 1),
+        _ => return None,
+    };
+
+    Some(arity)
+}
+
+/// Returns true if the provided field element is a valid u32 value.
+fn is_u32_felt(value: &Felt) -> bool {
+    value.as_int() <= u32::MAX as u64
+}
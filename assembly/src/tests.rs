@@ -1,8 +1,10 @@
 use crate::{
-    ast::{ModuleAst, ProgramAst},
-    Assembler, AssemblyContext, Library, LibraryNamespace, LibraryPath, Module, Version,
+    ast::{AstSerdeOptions, ModuleAst, ProgramAst},
+    Assembler, AssemblyContext, Library, LibraryNamespace, LibraryPath, Module, OptimizationLevel,
+    Version,
 };
 use core::slice::Iter;
+use vm_core::{code_blocks::CodeBlock, Decorator, Operation};
 
 // SIMPLE PROGRAMS
 // ================================================================================================
@@ -592,6 +594,141 @@ fn const_conversion_failed_to_u32() {
     assert_eq!(expected_error, err.to_string());
 }
 
+#[test]
+fn array_constants_push() {
+    let assembler = Assembler::default();
+    let source = "\
+    const.ROOT=[1,2,3,0x100000000] \
+    const.PAIR=[5,2*3] \
+    const.ALIAS=PAIR \
+    begin \
+    push.ROOT \
+    push.ALIAS.7 \
+    end";
+    let expected = "\
+    begin \
+        span \
+            pad incr push(2) push(3) push(4294967296) \
+            push(5) push(6) push(7) \
+        end \
+    end";
+    let program = assembler.compile(source).unwrap();
+    assert_eq!(expected, format!("{program}"));
+}
+
+#[test]
+fn constant_hex_expression() {
+    let assembler = Assembler::default();
+    let source = "\
+    const.BASE=0x1000 \
+    const.OFFSET=BASE+0x10*2 \
+    begin \
+    push.OFFSET \
+    end";
+    let expected = "\
+    begin \
+        span \
+            push(4128) \
+        end \
+    end";
+    let program = assembler.compile(source).unwrap();
+    assert_eq!(expected, format!("{program}"));
+}
+
+#[test]
+fn array_constant_errors() {
+    let assembler = Assembler::default();
+
+    // array constants cannot be used where a single value is expected
+    let source = "\
+    const.ROOT=[1,2,3,4] \
+    begin \
+    mem_load.ROOT \
+    end";
+    let err = assembler.compile(source).unwrap_err();
+    let expected_error =
+        "array constant 'ROOT' used in `mem_load.ROOT` where a single value is expected";
+    assert_eq!(expected_error, err.to_string());
+
+    // push cannot take more than 16 values
+    let source = "\
+    const.ROOT=[1,2,3,4,5,6,7,8,9,10,11,12,13,14,15,16] \
+    begin \
+    push.ROOT.17 \
+    end";
+    let err = assembler.compile(source).unwrap_err();
+    let expected_error = "malformed instruction 'push.ROOT.17', parameter ROOT is invalid: \
+        push cannot take more than 16 values";
+    assert_eq!(expected_error, err.to_string());
+
+    // constants cannot be exported from programs
+    let source = "\
+    export.const.ROOT=[1,2,3,4] \
+    begin \
+    push.ROOT \
+    end";
+    let err = assembler.compile(source).unwrap_err();
+    let expected_error =
+        "exported constants not allowed in this context: `export.const.ROOT=[1,2,3,4]`";
+    assert_eq!(expected_error, err.to_string());
+}
+
+#[test]
+fn program_with_imported_constants() {
+    const NAMESPACE: &str = "dummy";
+    const MODULE: &str = "layout";
+    const MODULE_BODY: &str = "\
+    export.const.BASE=1000
+    export.const.ROOT=[1,2,3,4]
+    const.PRIVATE=3
+
+    export.foo
+        push.BASE
+    end";
+
+    let namespace = LibraryNamespace::try_from(NAMESPACE.to_string()).unwrap();
+    let path = LibraryPath::try_from(MODULE.to_string()).unwrap().prepend(&namespace).unwrap();
+    let ast = ModuleAst::parse(MODULE_BODY).unwrap();
+    assert_eq!(2, ast.exported_constants().len());
+    let modules = vec![Module { path, ast }];
+    let assembler = Assembler::default()
+        .with_library(&DummyLibrary::new(namespace, modules))
+        .unwrap();
+
+    let source = format!(
+        "\
+    use.{NAMESPACE}::{MODULE}->mem
+    const.PTR=mem::BASE+4
+    begin
+        push.mem::ROOT
+        push.PTR
+        mem_load.mem::BASE
+    end"
+    );
+    let program = assembler.compile(source).unwrap();
+    let expected = "\
+    begin \
+        span \
+            pad incr push(2) push(3) push(4) \
+            push(1004) \
+            push(1000) mload \
+        end \
+    end";
+    assert_eq!(expected, format!("{program}"));
+
+    // constants which are not exported cannot be referenced
+    let source = format!(
+        "\
+    use.{NAMESPACE}::{MODULE}
+    begin
+        push.{MODULE}::PRIVATE
+    end"
+    );
+    let err = assembler.compile(source).unwrap_err();
+    let expected_error = "constant used in operation `push.layout::PRIVATE` not found";
+    assert_eq!(expected_error, err.to_string());
+}
+
 // ASSERTIONS
 // ================================================================================================
 
@@ -893,6 +1030,352 @@ fn program_with_dynamic_code_execution_in_new_context() {
     assert_eq!(expected, format!("{program}"));
 }
 
+// EVENTS
+// ================================================================================================
+
+#[test]
+fn program_with_events() {
+    use vm_core::{code_blocks::CodeBlock, Decorator};
+
+    let assembler = Assembler::default();
+    let program = assembler.compile("begin push.1 emit.5 push.2 emit.6 end").unwrap();
+
+    // events are compiled into decorators and do not affect the program hash
+    let expected = assembler.compile("begin push.1 push.2 end").unwrap();
+    assert_eq!(expected.hash(), program.hash());
+
+    let CodeBlock::Span(span) = program.root() else {
+        panic!("expected a span block");
+    };
+    let events: Vec<_> = span
+        .decorators()
+        .iter()
+        .map(|(idx, decorator)| (*idx, decorator.clone()))
+        .collect();
+    assert_eq!(vec![(2, Decorator::Event(5)), (3, Decorator::Event(6))], events);
+}
+
+#[test]
+fn program_with_events_without_following_operations() {
+    let assembler = Assembler::default();
+
+    // events which are not followed by any operation are attached to the preceding block, and
+    // thus do not affect the program hash
+    let sources = [
+        (
+            "proc.foo push.1 drop end begin call.foo emit.7 end",
+            "proc.foo push.1 drop end begin call.foo end",
+        ),
+        (
+            "begin push.1 if.true push.2 else push.3 end emit.7 end",
+            "begin push.1 if.true push.2 else push.3 end end",
+        ),
+        (
+            "begin push.1 while.true push.0 end emit.7 end",
+            "begin push.1 while.true push.0 end end",
+        ),
+        (
+            "proc.foo push.1 drop end begin call.foo emit.7 call.foo end",
+            "proc.foo push.1 drop end begin call.foo call.foo end",
+        ),
+        (
+            "proc.foo push.1 drop end begin emit.7 call.foo end",
+            "proc.foo push.1 drop end begin call.foo end",
+        ),
+        (
+            "begin push.1 emit.7 if.true push.2 end emit.8 end",
+            "begin push.1 if.true push.2 end end",
+        ),
+        (
+            "begin push.1 if.true emit.7 else push.2 end end",
+            "begin push.1 if.true else push.2 end end",
+        ),
+    ];
+    for (source, expected) in sources {
+        let program = assembler.compile(source).unwrap();
+        let expected = assembler.compile(expected).unwrap();
+        assert_eq!(expected.hash(), program.hash(), "{source}");
+    }
+
+    // the same applies to debug decorators in debug mode
+    let assembler = Assembler::default().with_debug_mode(true);
+    let program = assembler
+        .compile("proc.foo push.1 drop end begin call.foo debug.stack end")
+        .unwrap();
+    let expected = Assembler::default()
+        .compile("proc.foo push.1 drop end begin call.foo end")
+        .unwrap();
+    assert_eq!(expected.hash(), program.hash());
+
+    // decorators are attached to the end of the preceding block, or before the following block
+    // if no block precedes them
+    let source = "proc.foo push.1 drop end begin emit.1 call.foo emit.2 end";
+    let program = Assembler::default().compile(source).unwrap();
+    assert_eq!(&[Decorator::Event(1)], program.root().before_enter());
+    assert_eq!(&[Decorator::Event(2)], program.root().after_exit());
+}
+
+// MACROS
+// ================================================================================================
+
+#[test]
+fn program_with_macros() {
+    let assembler = Assembler::default();
+    let source = "\
+    const.BASE=100
+    macro.load_pair(ptr)
+        mem_load.$ptr
+        mem_load.$ptr
+    end
+    macro.sum_at(ptr,k)
+        load_pair($ptr)
+        add
+        push.$k
+        mul
+    end
+    proc.foo
+        sum_at(BASE,3)
+    end
+    begin
+        exec.foo
+        sum_at(7,2)
+    end";
+    let program = assembler.compile(source).unwrap();
+
+    // macro invocations produce the same MAST as the hand-written code
+    let expected = assembler
+        .compile(
+            "\
+    proc.foo
+        mem_load.100 mem_load.100 add push.3 mul
+    end
+    begin
+        exec.foo
+        mem_load.7 mem_load.7 add push.2 mul
+    end",
+        )
+        .unwrap();
+    assert_eq!(expected.hash(), program.hash());
+    assert_eq!(format!("{expected}"), format!("{program}"));
+}
+
+#[test]
+fn module_with_macros() {
+    let source = "\
+    macro.square()
+        dup
+        mul
+    end
+    export.foo
+        square()
+        square()
+    end";
+    let module = ModuleAst::parse(source).unwrap();
+    let expected = ModuleAst::parse("export.foo dup mul dup mul end").unwrap();
+    assert_eq!(expected.procs()[0].body.nodes(), module.procs()[0].body.nodes());
+}
+
+// STACK SIGNATURES
+// ================================================================================================
+
+#[test]
+fn stack_signatures_match() {
+    let assembler = Assembler::default();
+    let source = "\
+    proc.double # (a -- b)
+        dup add
+    end
+    proc.sum3 # (3 -- 1)
+        add add
+    end
+    proc.pick.1 # (a b c -- c a)
+        loc_store.0 drop loc_load.0 swap
+        if.true
+            push.1
+        else
+            push.2
+        end
+    end
+    proc.countdown # (n -- )
+        dup neq.0
+        while.true
+            sub.1 dup neq.0
+        end
+        drop
+    end
+    proc.helper
+        push.1 push.2
+    end
+    proc.with_helper # ( -- a b c d)
+        exec.helper
+        repeat.2
+            push.0
+        end
+    end
+    begin
+        push.1.2.3
+        exec.sum3
+        exec.double
+        exec.pick
+        exec.countdown
+        exec.with_helper
+    end";
+    assembler.compile(source).unwrap();
+}
+
+#[test]
+fn stack_signature_mismatch() {
+    let assembler = Assembler::default();
+
+    // the body leaves an extra element on the stack
+    let source = "\
+    proc.foo # (a b -- c)
+        add
+        push.1
+    end
+    begin
+        exec.foo
+    end";
+    let err = assembler.compile(source).unwrap_err();
+    let expected_error = "procedure 'foo' ending at [4:5] changes the stack depth by 0, \
+        which does not match its signature (2 -- 1)";
+    assert_eq!(expected_error, err.to_string());
+
+    // signatures of invoked procedures are taken into account
+    let source = "\
+    proc.bar # (a -- b c)
+        dup
+    end
+    proc.foo # (a -- b)
+        exec.bar
+    end
+    begin
+        exec.foo
+    end";
+    let err = assembler.compile(source).unwrap_err();
+    let expected_error = "procedure 'foo' ending at [6:5] changes the stack depth by 1, \
+        which does not match its signature (1 -- 1)";
+    assert_eq!(expected_error, err.to_string());
+
+    // procedures executed in a new context do not change the depth of the caller's stack
+    let source = "\
+    proc.bar
+        push.1
+    end
+    proc.foo # (a -- )
+        call.bar
+    end
+    begin
+        exec.foo
+    end";
+    let err = assembler.compile(source).unwrap_err();
+    let expected_error = "procedure 'foo' ending at [6:5] changes the stack depth by 0, \
+        which does not match its signature (1 -- 0)";
+    assert_eq!(expected_error, err.to_string());
+}
+
+#[test]
+fn stack_signature_unbalanced_blocks() {
+    let assembler = Assembler::default();
+
+    let source = "\
+    proc.foo # (a b -- c)
+        if.true
+            push.1
+        else
+            push.1 push.2
+        end
+    end
+    begin
+        exec.foo
+    end";
+    let err = assembler.compile(source).unwrap_err();
+    let expected_error = "branches of if.true statement at [2:9] in procedure 'foo' change the \
+        stack depth differently: by 1 and by 2";
+    assert_eq!(expected_error, err.to_string());
+
+    let source = "\
+    proc.foo # (a -- )
+        push.1
+        while.true
+            push.1 push.1
+        end
+    end
+    begin
+        exec.foo
+    end";
+    let err = assembler.compile(source).unwrap_err();
+    let expected_error = "body of while.true loop at [3:9] in procedure 'foo' changes the stack \
+        depth by 1 on each iteration";
+    assert_eq!(expected_error, err.to_string());
+
+    // procedures without signatures are not checked
+    let source = "\
+    proc.foo
+        if.true
+            push.1
+        end
+    end
+    begin
+        exec.foo
+    end";
+    assert!(assembler.compile(source).is_ok());
+
+    // signatures cannot be verified for procedures executing dynamic code
+    let source = "\
+    proc.foo # (a -- a)
+        dynexec
+        push.1
+    end
+    begin
+        exec.foo
+    end";
+    assert!(assembler.compile(source).is_ok());
+}
+
+#[test]
+fn stack_signature_imported_procedures() {
+    const NAMESPACE: &str = "dummy";
+    const MODULE: &str = "math";
+    const MODULE_BODY: &str = "\
+    proc.helper
+        dup mul
+    end
+    export.square # (a -- b)
+        exec.helper
+    end
+    export.cube
+        dup exec.square mul
+    end";
+
+    let namespace = LibraryNamespace::try_from(NAMESPACE.to_string()).unwrap();
+    let path = LibraryPath::try_from(MODULE.to_string()).unwrap().prepend(&namespace).unwrap();
+    let ast = ModuleAst::parse(MODULE_BODY).unwrap();
+    let modules = vec![Module { path, ast }];
+    let assembler = Assembler::default()
+        .with_library(&DummyLibrary::new(namespace, modules))
+        .unwrap();
+
+    // the effect of `cube` is inferred from its body
+    let source = format!(
+        "\
+    use.{NAMESPACE}::{MODULE}
+    proc.foo # (a -- b c)
+        dup
+        exec.{MODULE}::cube
+        exec.{MODULE}::square
+        drop
+    end
+    begin
+        exec.foo
+    end"
+    );
+    let err = assembler.compile(source).unwrap_err();
+    let expected_error = "procedure 'foo' ending at [7:5] changes the stack depth by 0, \
+        which does not match its signature (1 -- 2)";
+    assert_eq!(expected_error, err.to_string());
+}
+
 // MAST ROOT CALLS
 // ================================================================================================
 
@@ -1327,6 +1810,354 @@ fn program_with_import_errors() {
     );
 }
 
+// SOURCE MAPS
+// ================================================================================================
+
+#[test]
+fn program_with_source_map() {
+    let namespace = LibraryNamespace::try_from("dummy".to_string()).unwrap();
+    let path = LibraryPath::try_from("math".to_string()).unwrap().prepend(&namespace).unwrap();
+    let ast = ModuleAst::parse("export.foo\n    mul\nend").unwrap();
+    let modules = vec![Module { path, ast }];
+    let assembler = Assembler::default()
+        .with_library(&DummyLibrary::new(namespace, modules))
+        .unwrap();
+
+    let source = "\
+    use.dummy::math
+    proc.bar
+        add
+    end
+    begin
+        push.1 push.2
+        exec.math::foo
+        call.bar
+    end";
+    let program = ProgramAst::parse(source).unwrap();
+    let (program, source_map) = assembler.compile_with_source_map(&program, "main.masm").unwrap();
+    assert_eq!(program.hash(), source_map.program_hash());
+
+    let main_span = match program.root() {
+        CodeBlock::Join(join) => join.first().hash(),
+        _ => panic!("expected a JOIN block"),
+    };
+    let bar_span = CodeBlock::new_span(vec![Operation::Add]).hash();
+    let location = |block_hash, op_idx| {
+        source_map
+            .get(block_hash, op_idx)
+            .map(|location| format!("{} in {}", location, location.procedure()))
+    };
+    let expected = [
+        "main.masm:6:9 in #exec::#main",
+        "main.masm:6:9 in #exec::#main",
+        "main.masm:6:16 in #exec::#main",
+        "dummy/math.masm:2:5 in dummy::math::foo",
+    ];
+    for (op_idx, expected) in expected.into_iter().enumerate() {
+        assert_eq!(Some(expected.to_string()), location(main_span, op_idx));
+    }
+    assert_eq!(None, location(main_span, 4));
+    assert_eq!(Some("main.masm:3:9 in #exec::bar".to_string()), location(bar_span, 0));
+
+    // deserialized ASTs have no source locations; thus, only the library procedure which was
+    // cached with its source locations by the previous compilation remains in the source map
+    let bytes = ProgramAst::parse(source).unwrap().to_bytes(AstSerdeOptions::new(true));
+    let program = ProgramAst::from_bytes(&bytes).unwrap();
+    let (_, source_map) = assembler.compile_with_source_map(&program, "main.masm").unwrap();
+    assert_eq!(1, source_map.len());
+    let location = source_map.get(main_span, 3).map(|location| location.to_string());
+    assert_eq!(Some("dummy/math.masm:2:5".to_string()), location);
+}
+
+#[test]
+fn source_map_branches() {
+    let source = "\
+    begin
+        push.1
+        if.true
+            push.2
+        end
+        while.true
+            push.0
+        end
+    end";
+    let program = ProgramAst::parse(source).unwrap();
+    let assembler = Assembler::default();
+    let (program, source_map) = assembler.compile_with_source_map(&program, "main.masm").unwrap();
+
+    let CodeBlock::Join(join) = program.root() else {
+        panic!("expected a JOIN block");
+    };
+    let CodeBlock::Join(inner) = join.first() else {
+        panic!("expected a JOIN block");
+    };
+    let locations = |block: &CodeBlock| {
+        let locations = source_map.get_branches(block.hash());
+        locations.iter().map(|l| l.to_string()).collect::<Vec<_>>()
+    };
+    assert_eq!(["main.masm:3:9"], locations(inner.second()).as_slice());
+    assert_eq!(["main.masm:6:9"], locations(join.second()).as_slice());
+    assert!(locations(inner.first()).is_empty());
+
+    // identical statements are compiled into the same block, which maps to all their locations
+    let source = "\
+    proc.foo
+        if.true
+            push.2
+        end
+    end
+    begin
+        exec.foo
+        if.true
+            push.2
+        end
+    end";
+    let program = ProgramAst::parse(source).unwrap();
+    let (program, source_map) = assembler.compile_with_source_map(&program, "main.masm").unwrap();
+
+    let CodeBlock::Join(join) = program.root() else {
+        panic!("expected a JOIN block");
+    };
+    let locations = source_map.get_branches(join.second().hash());
+    let locations = locations.iter().map(|l| l.to_string()).collect::<Vec<_>>();
+    assert_eq!(["main.masm:2:9", "main.masm:8:9"], locations.as_slice());
+}
+
+// OPTIMIZATIONS
+// ================================================================================================
+
+#[test]
+fn optimized_programs() {
+    let assembler = Assembler::default().with_optimization_level(OptimizationLevel::Basic);
+    let compile = |source: &str| assembler.compile(source).unwrap().to_string();
+
+    // redundant stack manipulations are removed
+    let source = "begin swap swap dup.0 drop movup.2 movdn.2 push.0 drop mul end";
+    assert_eq!("begin span mul end end", compile(source));
+    assert_eq!("begin span add end end", compile("begin swap add end"));
+
+    // arithmetic identities are eliminated and constant expressions are folded
+    assert_eq!("begin span incr end end", compile("begin add.0 mul.1 push.1 add end"));
+    assert_eq!("begin span push(20) end end", compile("begin push.2 push.3 add push.4 mul end"));
+    assert_eq!("begin span push(5) end end", compile("begin push.5 neg neg push.0 add end"));
+
+    // operations which may fail are preserved
+    assert_eq!("begin span pad inv end end", compile("begin push.0 inv end"));
+    assert_eq!("begin span push(2) not end end", compile("begin push.2 not end"));
+
+    // bodies left empty execute a single NOOP
+    assert_eq!("begin span noop end end", compile("begin swap swap end"));
+
+    // without optimizations, operations are emitted as written
+    let program = Assembler::default().compile("begin swap swap end").unwrap();
+    assert_eq!("begin span swap swap end end", program.to_string());
+}
+
+#[test]
+fn optimized_program_decorators() {
+    let assembler = Assembler::default()
+        .with_debug_mode(true)
+        .with_optimization_level(OptimizationLevel::Basic);
+    let span = |source: &str| match assembler.compile(source).unwrap().root() {
+        CodeBlock::Span(span) => span.clone(),
+        block => panic!("expected a SPAN block, got {block}"),
+    };
+
+    // decorators which observe the state of the VM prevent rewrites across them
+    let program = assembler.compile("begin push.1 swap debug.stack swap drop end").unwrap();
+    assert_eq!("begin span pad incr swap swap drop end end", program.to_string());
+    let program = assembler.compile("begin push.1 swap swap drop end").unwrap();
+    assert_eq!("begin span noop end end", program.to_string());
+
+    // AsmOp decorators are attributed to the remaining operations
+    let span = span("begin push.2 push.3 add swap swap end");
+    assert_eq!("span push(5) end", span.to_string());
+    let decorators = span
+        .decorators()
+        .iter()
+        .map(|(pos, decorator)| match decorator {
+            Decorator::AsmOp(assembly_op) => (*pos, assembly_op.op(), assembly_op.num_cycles()),
+            decorator => panic!("unexpected decorator {decorator}"),
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(vec![(0, "push.2", 1)], decorators);
+}
+
+#[test]
+fn optimization_report() {
+    let assembler = Assembler::default().with_optimization_level(OptimizationLevel::Basic);
+    let source = "\
+    proc.foo
+        swap swap
+    end
+    begin
+        repeat.3
+            push.0 add
+        end
+        exec.foo
+    end";
+    assembler.compile(source).unwrap();
+
+    // bodies left empty by optimizations still execute a NOOP; repeated bodies are counted once
+    // per repetition
+    let report = assembler.optimization_report();
+    assert_eq!(Some(1), report.cycles_saved("#exec::foo"));
+    assert_eq!(Some(3), report.cycles_saved("#exec::#main"));
+    assert_eq!(4, report.total_cycles_saved());
+    assert!(Assembler::default().optimization_report().is_empty());
+}
+
+#[test]
+fn optimized_library_modules() {
+    let namespace = LibraryNamespace::try_from("dummy".to_string()).unwrap();
+    let path = LibraryPath::try_from("math".to_string()).unwrap().prepend(&namespace).unwrap();
+    let ast = ModuleAst::parse("export.foo\n    push.1 push.2 add\nend").unwrap();
+    let modules = vec![Module { path, ast }];
+    let source = "use.dummy::math begin exec.math::foo end";
+
+    // library modules are compiled with the optimization level of their library
+    let library = DummyLibrary::new(namespace.clone(), modules.clone())
+        .with_optimization_level(OptimizationLevel::Basic);
+    let assembler = Assembler::default().with_library(&library).unwrap();
+    let program = assembler.compile(source).unwrap();
+    assert_eq!("begin span push(3) end end", program.to_string());
+    let report = assembler.optimization_report();
+    assert_eq!(Some(3), report.cycles_saved("dummy::math::foo"));
+    assert_eq!(None, report.cycles_saved("#exec::#main"));
+
+    let assembler = Assembler::default()
+        .with_optimization_level(OptimizationLevel::Basic)
+        .with_library(&DummyLibrary::new(namespace, modules))
+        .unwrap();
+    let program = assembler.compile(source).unwrap();
+    assert_eq!("begin span pad incr push(2) add end end", program.to_string());
+    let report = assembler.optimization_report();
+    assert_eq!(None, report.cycles_saved("dummy::math::foo"));
+    assert_eq!(Some(0), report.cycles_saved("#exec::#main"));
+}
+
+// CALL GRAPH
+// ================================================================================================
+
+#[test]
+fn unreachable_procedures_are_not_called() {
+    let assembler = Assembler::default();
+    let source = "\
+    proc.foo
+        push.1
+    end
+    proc.bar
+        push.2
+    end
+    proc.baz
+        call.bar
+    end
+    begin
+        call.foo
+    end";
+    let program = assembler.compile(source).unwrap();
+
+    // procedures called only from procedures which are never invoked are not included in the
+    // code block table
+    let foo = Assembler::default().compile("begin push.1 end").unwrap().hash();
+    let bar = Assembler::default().compile("begin push.2 end").unwrap().hash();
+    assert!(program.cb_table().get(foo).is_some());
+    assert!(program.cb_table().get(bar).is_none());
+}
+
+#[test]
+fn call_graph_report() {
+    let namespace = LibraryNamespace::try_from("dummy".to_string()).unwrap();
+    let path = LibraryPath::try_from("math".to_string()).unwrap().prepend(&namespace).unwrap();
+    let module = "\
+    proc.helper
+        mul mul
+    end
+    export.foo
+        add
+    end
+    export.bar
+        exec.helper swap
+    end
+    export.baz
+        exec.helper drop
+    end
+    export.qux
+        add add add
+    end";
+    let ast = ModuleAst::parse(module).unwrap();
+    let library = DummyLibrary::new(namespace, vec![Module { path, ast }]);
+    let assembler = Assembler::default().with_library(&library).unwrap();
+
+    let source = "\
+    use.dummy::math
+    proc.twice
+        exec.math::bar exec.math::bar
+    end
+    begin
+        exec.math::foo
+        repeat.3
+            exec.twice
+        end
+        call.math::baz
+    end";
+    let program = ProgramAst::parse(source).unwrap();
+    let (_, report) = assembler.compile_with_call_report(&program).unwrap();
+
+    // exports invoked neither directly nor transitively are reported
+    assert_eq!(["dummy::math::qux".to_string()], report.unused_exports());
+
+    // inlined copies are counted once per repetition and through other inlined procedures; the
+    // called procedure is a separate tree of the MAST
+    let inlined = report
+        .inlined_procedures()
+        .iter()
+        .map(|proc| (proc.name(), proc.size(), proc.copies()))
+        .collect::<Vec<_>>();
+    let expected = vec![
+        ("#exec::twice", 6, 3),
+        ("dummy::math::bar", 3, 6),
+        ("dummy::math::helper", 2, 7),
+        ("dummy::math::foo", 1, 1),
+    ];
+    assert_eq!(expected, inlined);
+    assert_eq!(15, report.inlined_procedures()[1].call_size_savings());
+
+    // programs without imports have no unused exports
+    let program = ProgramAst::parse("proc.foo add end begin exec.foo exec.foo end").unwrap();
+    let (_, report) = assembler.compile_with_call_report(&program).unwrap();
+    assert!(report.unused_exports().is_empty());
+    assert_eq!(2, report.inlined_procedures()[0].copies());
+}
+
+#[test]
+fn call_graph_report_with_identical_mast_roots() {
+    let assembler = Assembler::default();
+    let inlined = |source: &str| {
+        let program = ProgramAst::parse(source).unwrap();
+        let (_, report) = assembler.compile_with_call_report(&program).unwrap();
+        report
+            .inlined_procedures()
+            .iter()
+            .map(|proc| (proc.name().to_string(), proc.copies()))
+            .collect::<Vec<_>>()
+    };
+
+    // the program body has the same MAST root as the procedure it consists of
+    let source = "proc.y push.1 add end begin exec.y end";
+    assert_eq!(vec![("#exec::y".to_string(), 1)], inlined(source));
+
+    // a procedure has the same MAST root as the procedure it consists of
+    let source = "proc.y push.1 add end proc.x exec.y end begin exec.x end";
+    let expected = vec![("#exec::x".to_string(), 1), ("#exec::y".to_string(), 1)];
+    assert_eq!(expected, inlined(source));
+
+    // identical procedures are reported separately
+    let source = "proc.a push.1 add end proc.b push.1 add end begin exec.a exec.b exec.b end";
+    let expected = vec![("#exec::b".to_string(), 2), ("#exec::a".to_string(), 1)];
+    assert_eq!(expected, inlined(source));
+}
+
 // COMMENTS
 // ================================================================================================
 
@@ -1584,6 +2415,7 @@ struct DummyLibrary {
     namespace: LibraryNamespace,
     modules: Vec<Module>,
     dependencies: Vec<LibraryNamespace>,
+    optimization_level: OptimizationLevel,
 }
 
 impl DummyLibrary {
@@ -1592,8 +2424,14 @@ impl DummyLibrary {
             namespace,
             modules,
             dependencies: Vec::new(),
+            optimization_level: OptimizationLevel::None,
         }
     }
+
+    fn with_optimization_level(mut self, optimization_level: OptimizationLevel) -> Self {
+        self.optimization_level = optimization_level;
+        self
+    }
 }
 
 impl Library for DummyLibrary {
@@ -1614,4 +2452,8 @@ impl Library for DummyLibrary {
     fn dependencies(&self) -> &[LibraryNamespace] {
         &self.dependencies
     }
+
+    fn optimization_level(&self) -> OptimizationLevel {
+        self.optimization_level
+    }
 }
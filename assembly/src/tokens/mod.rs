@@ -37,6 +37,7 @@ impl<'a> Token<'a> {
     pub const CONST: &'static str = "const";
     pub const END: &'static str = "end";
     pub const EXPORT: &'static str = "export";
+    pub const MACRO: &'static str = "macro";
     pub const PROC: &'static str = "proc";
     pub const USE: &'static str = "use";
 
@@ -14,6 +14,7 @@ pub struct TokenStream<'a> {
     pos: usize,
     temp: Token<'a>,
     proc_comments: BTreeMap<usize, Option<String>>,
+    proc_signatures: BTreeMap<usize, &'a str>,
     module_comment: Option<String>,
 }
 
@@ -26,6 +27,7 @@ impl<'a> TokenStream<'a> {
         let mut tokens = Vec::new();
         let mut locations = Vec::new();
         let mut proc_comments = BTreeMap::new();
+        let mut proc_signatures = BTreeMap::new();
         let mut module_comment = None;
 
         for line_info in LinesStream::from(source) {
@@ -35,6 +37,9 @@ impl<'a> TokenStream<'a> {
                     if line.starts_with(Token::EXPORT) || line.starts_with(Token::PROC) {
                         let doc_comment = build_comment(line_info.docs());
                         proc_comments.insert(tokens.len(), doc_comment);
+                        if let Some(signature) = extract_signature(line) {
+                            proc_signatures.insert(tokens.len(), signature);
+                        }
                     } else if !line_info.docs().is_empty() {
                         return Err(ParsingError::dangling_procedure_comment(line_info.into()));
                     }
@@ -79,10 +84,34 @@ impl<'a> TokenStream<'a> {
             pos: 0,
             temp: Token::default(),
             proc_comments,
+            proc_signatures,
             module_comment,
         })
     }
 
+    /// Returns a new stream of the provided tokens bound to the specified source locations.
+    ///
+    /// This is used to parse tokens which do not come directly from a source file, such as the
+    /// tokens of an expanded macro.
+    ///
+    /// # Panics
+    /// Panics if no tokens are provided, or if the number of tokens and locations differs.
+    pub fn from_tokens(tokens: Vec<&'a str>, locations: Vec<SourceLocation>) -> Self {
+        assert!(!tokens.is_empty(), "token stream cannot be empty");
+        assert_eq!(tokens.len(), locations.len(), "each token must have a location");
+        let current = Token::new(tokens[0], locations[0]);
+        Self {
+            tokens,
+            locations,
+            current,
+            pos: 0,
+            temp: Token::default(),
+            proc_comments: BTreeMap::new(),
+            proc_signatures: BTreeMap::new(),
+            module_comment: None,
+        }
+    }
+
     // PUBLIC ACCESSORS
     // --------------------------------------------------------------------------------------------
 
@@ -102,6 +131,12 @@ impl<'a> TokenStream<'a> {
         self.pos == self.tokens.len()
     }
 
+    /// Returns the tokens of this stream which have not been read yet, starting with the token at
+    /// the current position.
+    pub fn remaining_tokens(&self) -> &[&'a str] {
+        &self.tokens[self.pos..]
+    }
+
     // TOKEN READERS
     // --------------------------------------------------------------------------------------------
 
@@ -144,6 +179,12 @@ impl<'a> TokenStream<'a> {
         self.proc_comments.remove(&pos)?
     }
 
+    /// Takes the stack signature declared in the comment of the procedure header at the specified
+    /// position, if present.
+    pub fn take_signature_at(&mut self, pos: usize) -> Option<&'a str> {
+        self.proc_signatures.remove(&pos)
+    }
+
     pub fn take_module_comments(self) -> Option<String> {
         self.module_comment
     }
@@ -158,6 +199,14 @@ impl<'a> fmt::Display for TokenStream<'a> {
 // HELPERS
 // ================================================================================================
 
+/// Returns the stack signature (e.g., `(a b -- c)`) declared in the trailing comment of a line, if
+/// present. Comments which do not look like stack signatures are ignored.
+fn extract_signature(line: &str) -> Option<&str> {
+    let (_, comment) = line.split_once(Token::COMMENT_PREFIX)?;
+    let comment = comment.trim();
+    (comment.starts_with('(') && comment.contains("--")).then_some(comment)
+}
+
 fn build_comment(docs: &[&str]) -> Option<String> {
     let last = docs.len().saturating_sub(1);
     let docs: String = docs.iter().enumerate().fold(String::new(), |mut res, (i, l)| {
@@ -40,12 +40,12 @@ pub use math::{
 };
 
 mod program;
-pub use program::{blocks as code_blocks, CodeBlockTable, Kernel, Program, ProgramInfo};
+pub use program::{blocks as code_blocks, CodeBlockTable, Kernel, Program, ProgramInfo, SourceMap};
 
 mod operations;
 pub use operations::{
-    AdviceInjector, AssemblyOp, DebugOptions, Decorator, DecoratorIterator, DecoratorList,
-    Operation, SignatureKind,
+    AdviceInjector, AsmOpLocation, AssemblyOp, DebugOptions, Decorator, DecoratorIterator,
+    DecoratorList, Operation, SignatureKind,
 };
 
 pub mod stack;
@@ -1,4 +1,7 @@
-use crate::utils::string::String;
+use crate::utils::{
+    collections::Vec,
+    string::{String, ToString},
+};
 use core::fmt;
 
 // ASSEMBLY OP
@@ -11,6 +14,8 @@ pub struct AssemblyOp {
     num_cycles: u8,
     op: String,
     should_break: bool,
+    location: Option<AsmOpLocation>,
+    exec_path: Vec<String>,
 }
 
 impl AssemblyOp {
@@ -22,14 +27,41 @@ impl AssemblyOp {
             num_cycles,
             op,
             should_break,
+            location: None,
+            exec_path: Vec::new(),
         }
     }
 
+    /// Returns this [AssemblyOp] with the location of its assembly instruction in the source code
+    /// set to the specified location.
+    pub fn with_location(mut self, location: AsmOpLocation) -> Self {
+        self.location = Some(location);
+        self
+    }
+
+    /// Returns this [AssemblyOp] with the procedures through which its instruction was inlined
+    /// set to the specified procedures (see [AssemblyOp::exec_path()]).
+    pub fn with_exec_path(mut self, exec_path: Vec<String>) -> Self {
+        self.exec_path = exec_path;
+        self
+    }
+
     /// Returns the context name for this operation.
     pub fn context_name(&self) -> &str {
         &self.context_name
     }
 
+    /// Returns the context names of the procedures through which the instruction of this
+    /// decorator was inlined via `exec`, starting from the outermost one.
+    ///
+    /// For example, for an instruction of procedure `bar` executed via `exec.bar` from procedure
+    /// `foo`, which was in turn executed via `exec.foo` from the program body, the exec path is
+    /// `["#main", "foo"]`. The path is empty for instructions of procedures which were not
+    /// inlined (e.g., the program body and procedures invoked via `call`).
+    pub fn exec_path(&self) -> &[String] {
+        &self.exec_path
+    }
+
     /// Returns the number of VM cycles taken to execute the assembly instruction of this decorator.
     pub const fn num_cycles(&self) -> u8 {
         self.num_cycles
@@ -45,6 +77,12 @@ impl AssemblyOp {
         self.should_break
     }
 
+    /// Returns the location of the assembly instruction of this decorator in the source code, if
+    /// the location was recorded by the assembler.
+    pub fn location(&self) -> Option<&AsmOpLocation> {
+        self.location.as_ref()
+    }
+
     // STATE MUTATORS
     // --------------------------------------------------------------------------------------------
 
@@ -52,6 +90,12 @@ impl AssemblyOp {
     pub fn set_num_cycles(&mut self, num_cycles: u8) {
         self.num_cycles = num_cycles;
     }
+
+    /// Records that the instruction of this decorator was inlined via `exec` into the procedure
+    /// with the specified context name.
+    pub fn inline_into(&mut self, context_name: String) {
+        self.exec_path.insert(0, context_name);
+    }
 }
 
 impl fmt::Display for AssemblyOp {
@@ -63,3 +107,85 @@ impl fmt::Display for AssemblyOp {
         )
     }
 }
+
+// ASSEMBLY OP LOCATION
+// ================================================================================================
+
+/// Location of an assembly instruction in the source code.
+///
+/// The location consists of the path of the source file, the line and the column at which the
+/// instruction starts (both starting at 1), and the fully-qualified path of the procedure
+/// containing the instruction.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AsmOpLocation {
+    path: String,
+    line: u32,
+    column: u32,
+    procedure: String,
+}
+
+impl AsmOpLocation {
+    /// Returns a new [AsmOpLocation] instantiated with the specified file path, line, column and
+    /// procedure path.
+    pub fn new(path: String, line: u32, column: u32, procedure: String) -> Self {
+        Self {
+            path,
+            line,
+            column,
+            procedure,
+        }
+    }
+
+    /// Returns the path of the source file containing the instruction.
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    /// Returns the line at which the instruction starts.
+    pub const fn line(&self) -> u32 {
+        self.line
+    }
+
+    /// Returns the column at which the instruction starts.
+    pub const fn column(&self) -> u32 {
+        self.column
+    }
+
+    /// Returns the fully-qualified path of the procedure containing the instruction.
+    pub fn procedure(&self) -> &str {
+        &self.procedure
+    }
+
+    /// Returns an excerpt of the provided source code of the file at this location.
+    ///
+    /// The excerpt consists of the line at this location followed by a marker pointing to the
+    /// column of the instruction. Returns None if the source does not contain the line at this
+    /// location.
+    pub fn excerpt(&self, source: &str) -> Option<String> {
+        let line = source.lines().nth(self.line.checked_sub(1)? as usize)?;
+        let line_number = self.line.to_string();
+        let padding = " ".repeat(line_number.len());
+
+        // the marker is aligned with the instruction by re-using the whitespace preceding it in
+        // the source line, so that tabs are preserved
+        let indent = line
+            .chars()
+            .take(self.column.saturating_sub(1) as usize)
+            .map(|c| if c == '\t' { '\t' } else { ' ' })
+            .collect::<String>();
+
+        let lines = [
+            format!("{padding}--> {self}"),
+            format!("{padding} |"),
+            format!("{line_number} | {line}"),
+            format!("{padding} | {indent}^"),
+        ];
+        Some(lines.join("\n"))
+    }
+}
+
+impl fmt::Display for AsmOpLocation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}:{}", self.path, self.line, self.column)
+    }
+}
@@ -5,7 +5,7 @@ mod advice;
 pub use advice::AdviceInjector;
 
 mod assembly_op;
-pub use assembly_op::AssemblyOp;
+pub use assembly_op::{AsmOpLocation, AssemblyOp};
 
 mod debug;
 pub use debug::DebugOptions;
@@ -30,6 +30,9 @@ pub enum Decorator {
     /// Prints out information about the state of the VM based on the specified options. This
     /// decorator is executed only in debug mode.
     Debug(DebugOptions),
+    /// Signals an application-defined event with the specified ID to the host. Unlike debug
+    /// decorators, events are emitted regardless of whether the VM runs in debug mode.
+    Event(u32),
 }
 
 impl fmt::Display for Decorator {
@@ -40,6 +43,7 @@ impl fmt::Display for Decorator {
                 write!(f, "asmOp({}, {})", assembly_op.op(), assembly_op.num_cycles())
             }
             Self::Debug(options) => write!(f, "debug({options})"),
+            Self::Event(event_id) => write!(f, "event({event_id})"),
         }
     }
 }
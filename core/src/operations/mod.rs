@@ -2,8 +2,8 @@ use super::Felt;
 use core::fmt;
 mod decorators;
 pub use decorators::{
-    AdviceInjector, AssemblyOp, DebugOptions, Decorator, DecoratorIterator, DecoratorList,
-    SignatureKind,
+    AdviceInjector, AsmOpLocation, AssemblyOp, DebugOptions, Decorator, DecoratorIterator,
+    DecoratorList, SignatureKind,
 };
 
 // OPERATIONS
@@ -1,4 +1,4 @@
-use super::{fmt, hasher, Digest, Felt, Operation};
+use super::{fmt, hasher, BlockDecorators, Digest, Felt, Operation};
 use crate::utils::to_hex;
 
 // CALL BLOCK
@@ -19,6 +19,7 @@ pub struct Call {
     hash: Digest,
     fn_hash: Digest,
     is_syscall: bool,
+    decorators: BlockDecorators,
 }
 
 impl Call {
@@ -38,6 +39,7 @@ impl Call {
             hash,
             fn_hash,
             is_syscall: false,
+            decorators: BlockDecorators::default(),
         }
     }
 
@@ -49,6 +51,7 @@ impl Call {
             hash,
             fn_hash,
             is_syscall: true,
+            decorators: BlockDecorators::default(),
         }
     }
 
@@ -77,6 +80,17 @@ impl Call {
             false => Self::CALL_DOMAIN,
         }
     }
+
+    /// Returns the decorators executed before this block is entered and after it is exited.
+    pub(super) fn decorators(&self) -> &BlockDecorators {
+        &self.decorators
+    }
+
+    /// Returns a mutable reference to the decorators executed before this block is entered and
+    /// after it is exited.
+    pub(super) fn decorators_mut(&mut self) -> &mut BlockDecorators {
+        &mut self.decorators
+    }
 }
 
 impl fmt::Display for Call {
@@ -1,4 +1,4 @@
-use super::{fmt, Digest, Felt, Operation};
+use super::{fmt, BlockDecorators, Digest, Felt, Operation};
 
 // CONSTANTS
 // ================================================================================================
@@ -25,7 +25,9 @@ const DYN_CONSTANT: Digest = Digest::new([
 /// constant (rather than by unique hashes), which is computed as an RPO hash of two empty words
 /// ([ZERO, ZERO, ZERO, ZERO]) with a domain value of `DYN_DOMAIN`.
 #[derive(Clone, Debug)]
-pub struct Dyn {}
+pub struct Dyn {
+    decorators: BlockDecorators,
+}
 
 impl Dyn {
     // CONSTANTS
@@ -37,7 +39,9 @@ impl Dyn {
     // --------------------------------------------------------------------------------------------
     /// Returns a new [Dyn] block instantiated with the specified function body hash.
     pub fn new() -> Self {
-        Self {}
+        Self {
+            decorators: BlockDecorators::default(),
+        }
     }
 
     // PUBLIC ACCESSORS
@@ -52,6 +56,17 @@ impl Dyn {
     pub fn dyn_hash() -> Digest {
         DYN_CONSTANT
     }
+
+    /// Returns the decorators executed before this block is entered and after it is exited.
+    pub(super) fn decorators(&self) -> &BlockDecorators {
+        &self.decorators
+    }
+
+    /// Returns a mutable reference to the decorators executed before this block is entered and
+    /// after it is exited.
+    pub(super) fn decorators_mut(&mut self) -> &mut BlockDecorators {
+        &mut self.decorators
+    }
 }
 
 impl Default for Dyn {
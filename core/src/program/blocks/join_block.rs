@@ -1,4 +1,4 @@
-use super::{fmt, hasher, Box, CodeBlock, Digest, Felt, Operation};
+use super::{fmt, hasher, BlockDecorators, Box, CodeBlock, Digest, Felt, Operation};
 
 // JOIN BLOCKS
 // ================================================================================================
@@ -15,6 +15,7 @@ use super::{fmt, hasher, Box, CodeBlock, Digest, Felt, Operation};
 pub struct Join {
     body: Box<[CodeBlock; 2]>,
     hash: Digest,
+    decorators: BlockDecorators,
 }
 
 impl Join {
@@ -31,6 +32,7 @@ impl Join {
         Self {
             body: Box::new(body),
             hash,
+            decorators: BlockDecorators::default(),
         }
     }
 
@@ -53,6 +55,23 @@ impl Join {
     pub fn second(&self) -> &CodeBlock {
         &self.body[1]
     }
+
+    /// Returns mutable references to the code blocks of this join block; this must not be used to
+    /// change the hashes of the code blocks.
+    pub(super) fn body_mut(&mut self) -> &mut [CodeBlock; 2] {
+        &mut self.body
+    }
+
+    /// Returns the decorators executed before this block is entered and after it is exited.
+    pub(super) fn decorators(&self) -> &BlockDecorators {
+        &self.decorators
+    }
+
+    /// Returns a mutable reference to the decorators executed before this block is entered and
+    /// after it is exited.
+    pub(super) fn decorators_mut(&mut self) -> &mut BlockDecorators {
+        &mut self.decorators
+    }
 }
 
 impl fmt::Display for Join {
@@ -1,4 +1,4 @@
-use super::{fmt, hasher, Box, CodeBlock, Digest, Felt, Operation};
+use super::{fmt, hasher, BlockDecorators, Box, CodeBlock, Digest, Felt, Operation};
 
 // LOOP BLOCK
 // ================================================================================================
@@ -16,6 +16,7 @@ use super::{fmt, hasher, Box, CodeBlock, Digest, Felt, Operation};
 pub struct Loop {
     body: Box<CodeBlock>,
     hash: Digest,
+    decorators: BlockDecorators,
 }
 
 impl Loop {
@@ -32,6 +33,7 @@ impl Loop {
         Self {
             body: Box::new(body),
             hash,
+            decorators: BlockDecorators::default(),
         }
     }
 
@@ -47,6 +49,23 @@ impl Loop {
     pub fn body(&self) -> &CodeBlock {
         &self.body
     }
+
+    /// Returns a mutable reference to the body of this loop block; this must not be used to change
+    /// the hash of the body.
+    pub(super) fn body_mut(&mut self) -> &mut CodeBlock {
+        &mut self.body
+    }
+
+    /// Returns the decorators executed before this block is entered and after it is exited.
+    pub(super) fn decorators(&self) -> &BlockDecorators {
+        &self.decorators
+    }
+
+    /// Returns a mutable reference to the decorators executed before this block is entered and
+    /// after it is exited.
+    pub(super) fn decorators_mut(&mut self) -> &mut BlockDecorators {
+        &mut self.decorators
+    }
 }
 
 impl fmt::Display for Loop {
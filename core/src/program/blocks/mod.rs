@@ -1,5 +1,5 @@
 use super::{hasher, Box, Digest, Felt, Operation, Vec};
-use crate::DecoratorList;
+use crate::{Decorator, DecoratorList};
 use core::fmt;
 
 mod call_block;
@@ -122,6 +122,126 @@ impl CodeBlock {
             CodeBlock::Proxy(_) => panic!("Can't fetch `domain` for a `Proxy` block!"),
         }
     }
+
+    /// Returns the decorators which are executed before this block is entered.
+    ///
+    /// Decorators of SPAN blocks are attached to their operations, thus the returned list is
+    /// always empty for SPAN blocks.
+    pub fn before_enter(&self) -> &[Decorator] {
+        match self.decorators() {
+            Some(decorators) => &decorators.before_enter,
+            None => &[],
+        }
+    }
+
+    /// Returns the decorators which are executed after this block is exited.
+    ///
+    /// Decorators of SPAN blocks are attached to their operations, thus the returned list is
+    /// always empty for SPAN blocks.
+    pub fn after_exit(&self) -> &[Decorator] {
+        match self.decorators() {
+            Some(decorators) => &decorators.after_exit,
+            None => &[],
+        }
+    }
+
+    // DECORATOR MUTATORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Attaches the specified decorators to this block so that they are executed before any
+    /// other decorators and operations of this block.
+    ///
+    /// For SPAN blocks, the decorators are executed before the first operation of the block; for
+    /// all other blocks, the decorators are executed before the block is entered. Decorators do
+    /// not contribute to the hash of a code block, thus the hash of this block is not affected.
+    ///
+    /// # Panics
+    /// Panics if this block is a [Proxy] block.
+    pub fn prepend_decorators(&mut self, decorators: Vec<Decorator>) {
+        match self {
+            CodeBlock::Span(block) => block.prepend_decorators(decorators),
+            _ => {
+                let block_decorators =
+                    self.decorators_mut().expect("proxy blocks have no decorators");
+                block_decorators.before_enter.splice(0..0, decorators);
+            }
+        }
+    }
+
+    /// Attaches the specified decorators to this block so that they are executed after all
+    /// other decorators and operations of this block.
+    ///
+    /// For SPAN blocks, the decorators are executed after the last operation of the block; for
+    /// all other blocks, the decorators are executed after the block is exited. Decorators do not
+    /// contribute to the hash of a code block, thus the hash of this block is not affected.
+    ///
+    /// # Panics
+    /// Panics if this block is a [Proxy] block.
+    pub fn append_decorators(&mut self, decorators: Vec<Decorator>) {
+        match self {
+            CodeBlock::Span(block) => block.append_decorators(decorators),
+            _ => {
+                let block_decorators =
+                    self.decorators_mut().expect("proxy blocks have no decorators");
+                block_decorators.after_exit.extend(decorators);
+            }
+        }
+    }
+
+    /// Applies the provided function to all decorators in this code block.
+    ///
+    /// Decorators do not contribute to the hash of a code block, thus the hash of this block is
+    /// not affected. Code executed via CALL, SYSCALL, or DYN blocks is not a part of this block
+    /// and is not visited.
+    pub fn update_decorators<F>(&mut self, f: &mut F)
+    where
+        F: FnMut(&mut Decorator),
+    {
+        if let Some(decorators) = self.decorators_mut() {
+            let decorators = decorators.before_enter.iter_mut().chain(&mut decorators.after_exit);
+            decorators.for_each(&mut *f);
+        }
+        match self {
+            CodeBlock::Span(block) => block.decorators_mut().iter_mut().for_each(|(_, d)| f(d)),
+            CodeBlock::Join(block) => {
+                block.body_mut().iter_mut().for_each(|b| b.update_decorators(f))
+            }
+            CodeBlock::Split(block) => {
+                block.branches_mut().iter_mut().for_each(|b| b.update_decorators(f))
+            }
+            CodeBlock::Loop(block) => block.body_mut().update_decorators(f),
+            CodeBlock::Call(_) | CodeBlock::Dyn(_) | CodeBlock::Proxy(_) => (),
+        }
+    }
+
+    // HELPER METHODS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the decorators executed before this block is entered and after it is exited, or
+    /// None for SPAN and PROXY blocks.
+    fn decorators(&self) -> Option<&BlockDecorators> {
+        match self {
+            CodeBlock::Join(block) => Some(block.decorators()),
+            CodeBlock::Split(block) => Some(block.decorators()),
+            CodeBlock::Loop(block) => Some(block.decorators()),
+            CodeBlock::Call(block) => Some(block.decorators()),
+            CodeBlock::Dyn(block) => Some(block.decorators()),
+            CodeBlock::Span(_) | CodeBlock::Proxy(_) => None,
+        }
+    }
+
+    /// Returns a mutable reference to the decorators executed before this block is entered and
+    /// after it is exited, or None for SPAN and PROXY blocks.
+    fn decorators_mut(&mut self) -> Option<&mut BlockDecorators> {
+        match self {
+            CodeBlock::Join(block) => Some(block.decorators_mut()),
+            CodeBlock::Split(block) => Some(block.decorators_mut()),
+            CodeBlock::Loop(block) => Some(block.decorators_mut()),
+            CodeBlock::Call(block) => Some(block.decorators_mut()),
+            CodeBlock::Dyn(block) => Some(block.decorators_mut()),
+            CodeBlock::Span(_) | CodeBlock::Proxy(_) => None,
+        }
+    }
 }
 
 impl fmt::Display for CodeBlock {
@@ -137,3 +257,19 @@ impl fmt::Display for CodeBlock {
         }
     }
 }
+
+// BLOCK DECORATORS
+// ================================================================================================
+
+/// Decorators attached to a control block (i.e., a block other than SPAN or PROXY).
+///
+/// Unlike decorators of SPAN blocks, these decorators are not attached to operations: the
+/// decorators in `before_enter` are executed before the block is entered, and the decorators in
+/// `after_exit` are executed after the block is exited. This allows decorators which are not
+/// followed by any operations (e.g., an `emit` at the end of a procedure which ends with a
+/// `call`) to be executed without adding operations to the program.
+#[derive(Clone, Debug, Default)]
+struct BlockDecorators {
+    before_enter: Vec<Decorator>,
+    after_exit: Vec<Decorator>,
+}
@@ -1,5 +1,5 @@
 use super::{fmt, hasher, Digest, Felt, Operation, Vec};
-use crate::{DecoratorIterator, DecoratorList, ZERO};
+use crate::{Decorator, DecoratorIterator, DecoratorList, ZERO};
 use winter_utils::flatten_slice_elements;
 
 // CONSTANTS
@@ -132,6 +132,27 @@ impl Span {
         &self.decorators
     }
 
+    /// Returns a mutable reference to the list of decorators in this span block; this must not be
+    /// used to change the positions of the decorators.
+    pub(super) fn decorators_mut(&mut self) -> &mut DecoratorList {
+        &mut self.decorators
+    }
+
+    /// Inserts the specified decorators before all other decorators of this span block; the
+    /// decorators are executed before the first operation of this block.
+    pub(super) fn prepend_decorators(&mut self, decorators: Vec<Decorator>) {
+        let decorators = decorators.into_iter().map(|decorator| (0, decorator));
+        self.decorators.splice(0..0, decorators);
+    }
+
+    /// Appends the specified decorators after all other decorators of this span block; the
+    /// decorators are executed after the last operation of this block.
+    pub(super) fn append_decorators(&mut self, decorators: Vec<Decorator>) {
+        let num_ops = self.op_batches.iter().map(|batch| batch.ops.len()).sum::<usize>();
+        self.decorators
+            .extend(decorators.into_iter().map(|decorator| (num_ops, decorator)));
+    }
+
     /// Returns a [DecoratorIterator] which allows us to iterate through the decorator list of this span
     /// block while executing operation batches of this span block
     pub fn decorator_iter(&self) -> DecoratorIterator {
@@ -1,4 +1,4 @@
-use super::{fmt, hasher, Box, CodeBlock, Digest, Felt, Operation};
+use super::{fmt, hasher, BlockDecorators, Box, CodeBlock, Digest, Felt, Operation};
 
 // SPLIT BLOCK
 // ================================================================================================
@@ -16,6 +16,7 @@ use super::{fmt, hasher, Box, CodeBlock, Digest, Felt, Operation};
 pub struct Split {
     branches: Box<[CodeBlock; 2]>,
     hash: Digest,
+    decorators: BlockDecorators,
 }
 
 impl Split {
@@ -32,6 +33,7 @@ impl Split {
         Self {
             branches: Box::new([t_branch, f_branch]),
             hash,
+            decorators: BlockDecorators::default(),
         }
     }
 
@@ -54,6 +56,23 @@ impl Split {
     pub fn on_false(&self) -> &CodeBlock {
         &self.branches[1]
     }
+
+    /// Returns mutable references to the branches of this split block; this must not be used to
+    /// change the hashes of the branches.
+    pub(super) fn branches_mut(&mut self) -> &mut [CodeBlock; 2] {
+        &mut self.branches
+    }
+
+    /// Returns the decorators executed before this block is entered and after it is exited.
+    pub(super) fn decorators(&self) -> &BlockDecorators {
+        &self.decorators
+    }
+
+    /// Returns a mutable reference to the decorators executed before this block is entered and
+    /// after it is exited.
+    pub(super) fn decorators_mut(&mut self) -> &mut BlockDecorators {
+        &mut self.decorators
+    }
 }
 
 impl fmt::Display for Split {
@@ -1,6 +1,7 @@
 use super::{
     chiplets::hasher::{self, Digest},
     utils::{
+        self,
         collections::{BTreeMap, Vec},
         Box,
     },
@@ -15,6 +16,9 @@ use blocks::CodeBlock;
 mod info;
 pub use info::ProgramInfo;
 
+mod source_map;
+pub use source_map::SourceMap;
+
 #[cfg(test)]
 mod tests;
 
@@ -114,6 +118,11 @@ impl CodeBlockTable {
     pub fn is_empty(&self) -> bool {
         self.0.is_empty()
     }
+
+    /// Returns an iterator over the code blocks in this table.
+    pub fn blocks(&self) -> impl Iterator<Item = &CodeBlock> {
+        self.0.values()
+    }
 }
 
 // KERNEL
@@ -0,0 +1,265 @@
+use super::{
+    blocks::CodeBlock,
+    utils::{
+        collections::{BTreeMap, BTreeSet, Vec},
+        string::String,
+    },
+    ByteReader, ByteWriter, Deserializable, DeserializationError, Digest, Program, Serializable,
+};
+use crate::{AsmOpLocation, Decorator};
+
+// SOURCE MAP
+// ================================================================================================
+
+/// A map from operations of a program's MAST to the locations of the assembly instructions these
+/// operations were compiled from.
+///
+/// Operations are identified by the hash of the SPAN block containing them and by their index
+/// within the block (not counting the NOOPs inserted to align operation batches). The map is built
+/// from the locations carried by the AsmOp decorators of the program. Since the hashes of SPAN
+/// blocks do not depend on decorators, the map can be used to look up locations of operations of
+/// any program with the same MAST root, including a program compiled without decorators.
+///
+/// The map can also hold locations of the conditional and loop statements which SPLIT and LOOP
+/// blocks were compiled from. Control blocks do not carry decorators; thus, these locations are
+/// added to the map by the assembler (see [SourceMap::add_branch()]). Since identical statements
+/// are compiled into blocks with the same hash, a block hash may map to several locations.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SourceMap {
+    program_hash: Digest,
+    entries: BTreeMap<([u8; 32], usize), SourceMapEntry>,
+    branches: BTreeMap<[u8; 32], Vec<AsmOpLocation>>,
+}
+
+/// Location of an assembly instruction together with the number of operations it was compiled
+/// into.
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct SourceMapEntry {
+    num_ops: usize,
+    location: AsmOpLocation,
+}
+
+impl SourceMap {
+    // CONSTRUCTOR
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns a [SourceMap] built from the AsmOp decorators of the specified program.
+    ///
+    /// Operations of instructions whose decorators do not carry a source location are not
+    /// present in the returned map.
+    pub fn from_program(program: &Program) -> Self {
+        let mut source_map = Self {
+            program_hash: program.hash(),
+            entries: BTreeMap::new(),
+            branches: BTreeMap::new(),
+        };
+
+        let mut visited = BTreeSet::new();
+        source_map.add_block(program.root(), &mut visited);
+        for block in program.cb_table().blocks() {
+            source_map.add_block(block, &mut visited);
+        }
+
+        source_map
+    }
+
+    // PUBLIC ACCESSORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the hash of the program this map was built for.
+    pub fn program_hash(&self) -> Digest {
+        self.program_hash
+    }
+
+    /// Returns the location of the assembly instruction which the operation at the specified index
+    /// of the specified SPAN block was compiled from, or None if the location of the operation is
+    /// not known.
+    pub fn get(&self, block_hash: Digest, op_idx: usize) -> Option<&AsmOpLocation> {
+        let block_key: [u8; 32] = block_hash.into();
+        let ((key, start), entry) = self.entries.range(..=(block_key, op_idx)).next_back()?;
+        (*key == block_key && op_idx < start + entry.num_ops).then_some(&entry.location)
+    }
+
+    /// Returns the locations of the conditional or loop statements which the SPLIT or LOOP block
+    /// with the specified hash was compiled from, in the order they were added to this map.
+    ///
+    /// The returned slice is empty if the location of the block is not known, and contains more
+    /// than one location if several identical statements were compiled into the same block.
+    pub fn get_branches(&self, block_hash: Digest) -> &[AsmOpLocation] {
+        let block_key: [u8; 32] = block_hash.into();
+        self.branches.get(&block_key).map_or(&[], Vec::as_slice)
+    }
+
+    /// Returns the number of assembly instructions with known locations in this map.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns true if this map does not contain any locations.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    // STATE MUTATORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Records the location of the conditional or loop statement which the SPLIT or LOOP block
+    /// with the specified hash was compiled from.
+    ///
+    /// Blocks with the same hash are indistinguishable; if several statements were compiled into
+    /// the same block, all of their locations are retained (see [SourceMap::get_branches()]).
+    pub fn add_branch(&mut self, block_hash: Digest, location: AsmOpLocation) {
+        let locations = self.branches.entry(block_hash.into()).or_default();
+        if !locations.contains(&location) {
+            locations.push(location);
+        }
+    }
+
+    // HELPER METHODS
+    // --------------------------------------------------------------------------------------------
+
+    /// Adds locations of the operations of all SPAN blocks in the MAST rooted at the specified
+    /// block to this map.
+    fn add_block(&mut self, block: &CodeBlock, visited: &mut BTreeSet<[u8; 32]>) {
+        if !visited.insert(block.hash().into()) {
+            return;
+        }
+
+        match block {
+            CodeBlock::Span(span) => {
+                let block_key: [u8; 32] = span.hash().into();
+                for (op_idx, decorator) in span.decorators() {
+                    if let Decorator::AsmOp(asmop) = decorator {
+                        if let Some(location) = asmop.location() {
+                            let entry = SourceMapEntry {
+                                num_ops: asmop.num_cycles() as usize,
+                                location: location.clone(),
+                            };
+                            self.entries.insert((block_key, *op_idx), entry);
+                        }
+                    }
+                }
+            }
+            CodeBlock::Join(join) => {
+                self.add_block(join.first(), visited);
+                self.add_block(join.second(), visited);
+            }
+            CodeBlock::Split(split) => {
+                self.add_block(split.on_true(), visited);
+                self.add_block(split.on_false(), visited);
+            }
+            CodeBlock::Loop(loop_block) => self.add_block(loop_block.body(), visited),
+            CodeBlock::Call(_) | CodeBlock::Dyn(_) | CodeBlock::Proxy(_) => (),
+        }
+    }
+}
+
+// SERIALIZATION
+// ------------------------------------------------------------------------------------------------
+
+impl Serializable for SourceMap {
+    fn write_into<W: ByteWriter>(&self, target: &mut W) {
+        self.program_hash.write_into(target);
+
+        // file and procedure paths are shared by many locations; thus, they are written once
+        // into a table of strings, and the locations refer to them by their index in the table
+        let mut strings = BTreeMap::<&str, u32>::new();
+        let locations = self.entries.values().map(|entry| &entry.location);
+        for location in locations.chain(self.branches.values().flatten()) {
+            for path in [location.path(), location.procedure()] {
+                let next_idx = strings.len() as u32;
+                strings.entry(path).or_insert(next_idx);
+            }
+        }
+        let mut string_table = vec![""; strings.len()];
+        for (&path, &idx) in strings.iter() {
+            string_table[idx as usize] = path;
+        }
+
+        target.write_u32(string_table.len() as u32);
+        for path in string_table {
+            target.write_u32(path.len() as u32);
+            target.write_bytes(path.as_bytes());
+        }
+
+        target.write_u32(self.entries.len() as u32);
+        for ((block_key, op_idx), entry) in self.entries.iter() {
+            target.write_bytes(block_key);
+            target.write_u32(*op_idx as u32);
+            target.write_u32(entry.num_ops as u32);
+            target.write_u32(strings[entry.location.path()]);
+            target.write_u32(entry.location.line());
+            target.write_u32(entry.location.column());
+            target.write_u32(strings[entry.location.procedure()]);
+        }
+
+        target.write_u32(self.branches.len() as u32);
+        for (block_key, locations) in self.branches.iter() {
+            target.write_bytes(block_key);
+            target.write_u32(locations.len() as u32);
+            for location in locations {
+                target.write_u32(strings[location.path()]);
+                target.write_u32(location.line());
+                target.write_u32(location.column());
+                target.write_u32(strings[location.procedure()]);
+            }
+        }
+    }
+}
+
+impl Deserializable for SourceMap {
+    fn read_from<R: ByteReader>(source: &mut R) -> Result<Self, DeserializationError> {
+        let program_hash = source.read()?;
+
+        let num_strings = source.read_u32()?;
+        let mut string_table = Vec::new();
+        for _ in 0..num_strings {
+            let len = source.read_u32()? as usize;
+            let path = String::from_utf8(source.read_vec(len)?)
+                .map_err(|err| DeserializationError::InvalidValue(format!("{err}")))?;
+            string_table.push(path);
+        }
+        let read_string = |source: &mut R| {
+            let idx = source.read_u32()? as usize;
+            string_table.get(idx).cloned().ok_or_else(|| {
+                DeserializationError::InvalidValue(format!("invalid string index {idx}"))
+            })
+        };
+
+        let num_entries = source.read_u32()?;
+        let mut entries = BTreeMap::new();
+        for _ in 0..num_entries {
+            let block_key = source.read_array::<32>()?;
+            let op_idx = source.read_u32()? as usize;
+            let num_ops = source.read_u32()? as usize;
+            let path = read_string(source)?;
+            let line = source.read_u32()?;
+            let column = source.read_u32()?;
+            let procedure = read_string(source)?;
+            let location = AsmOpLocation::new(path, line, column, procedure);
+            entries.insert((block_key, op_idx), SourceMapEntry { num_ops, location });
+        }
+
+        let num_branches = source.read_u32()?;
+        let mut branches = BTreeMap::new();
+        for _ in 0..num_branches {
+            let block_key = source.read_array::<32>()?;
+            let num_locations = source.read_u32()?;
+            let mut locations = Vec::new();
+            for _ in 0..num_locations {
+                let path = read_string(source)?;
+                let line = source.read_u32()?;
+                let column = source.read_u32()?;
+                let procedure = read_string(source)?;
+                locations.push(AsmOpLocation::new(path, line, column, procedure));
+            }
+            branches.insert(block_key, locations);
+        }
+
+        Ok(Self {
+            program_hash,
+            entries,
+            branches,
+        })
+    }
+}
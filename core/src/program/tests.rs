@@ -1,5 +1,8 @@
-use super::{blocks::Dyn, Deserializable, Digest, Felt, Kernel, ProgramInfo, Serializable};
-use crate::{chiplets::hasher, Word};
+use super::{
+    blocks::{CodeBlock, Dyn},
+    Deserializable, Digest, Felt, Kernel, Program, ProgramInfo, Serializable, SourceMap,
+};
+use crate::{chiplets::hasher, AsmOpLocation, AssemblyOp, Decorator, Operation, Word};
 use proptest::prelude::*;
 use rand_utils::prng_array;
 
@@ -10,6 +13,51 @@ fn dyn_hash_is_correct() {
     assert_eq!(expected_constant, Dyn::new().hash());
 }
 
+#[test]
+fn source_map_locations() {
+    let asmop = |op: &str, num_cycles, line, column| {
+        let location = AsmOpLocation::new("main.masm".into(), line, column, "#exec::#main".into());
+        let asmop = AssemblyOp::new("#main".into(), num_cycles, op.into(), false);
+        Decorator::AsmOp(asmop.with_location(location))
+    };
+    let ops = vec![Operation::Pad, Operation::Incr, Operation::Add, Operation::Mul];
+    let decorators = vec![(0, asmop("push.1", 2, 2, 5)), (2, asmop("add", 1, 3, 5))];
+    let span = CodeBlock::new_span_with_decorators(ops, decorators);
+    let program = Program::new(CodeBlock::new_loop(span.clone()));
+
+    // operations of an instruction map to its location; undecorated operations are not mapped
+    let source_map = SourceMap::from_program(&program);
+    assert_eq!(program.hash(), source_map.program_hash());
+    assert_eq!(2, source_map.len());
+    let location = |op_idx| source_map.get(span.hash(), op_idx).map(|l| l.to_string());
+    assert_eq!(Some("main.masm:2:5".to_string()), location(0));
+    assert_eq!(Some("main.masm:2:5".to_string()), location(1));
+    assert_eq!(Some("main.masm:3:5".to_string()), location(2));
+    assert_eq!(None, location(3));
+    assert_eq!(None, source_map.get(program.hash(), 0));
+
+    // locations of control blocks are added explicitly
+    let mut source_map = source_map;
+    assert!(source_map.get_branches(program.hash()).is_empty());
+    let location = AsmOpLocation::new("main.masm".into(), 1, 1, "#exec::#main".into());
+    source_map.add_branch(program.hash(), location.clone());
+    source_map.add_branch(program.hash(), location.clone());
+    assert_eq!(1, source_map.get_branches(program.hash()).len());
+
+    // identical statements compiled into the same block retain all of their locations
+    let other = AsmOpLocation::new("main.masm".into(), 5, 1, "#exec::foo".into());
+    source_map.add_branch(program.hash(), other.clone());
+    assert_eq!([location, other], source_map.get_branches(program.hash()));
+
+    let bytes = source_map.to_bytes();
+    assert_eq!(source_map, SourceMap::read_from_bytes(&bytes).unwrap());
+
+    let source = "begin\n    push.1\n    add\n    mul\nend";
+    let expected = " --> main.masm:3:5\n  |\n3 |     add\n  |     ^";
+    let excerpt = source_map.get(span.hash(), 2).and_then(|l| l.excerpt(source));
+    assert_eq!(Some(expected.to_string()), excerpt);
+}
+
 proptest! {
     #[test]
     fn arbitrary_program_info_serialization_works(
@@ -1,4 +1,7 @@
-use super::{vec, ByteWriter, Felt, InputError, Serializable, ToElements, Vec};
+use super::{
+    vec, ByteReader, ByteWriter, Deserializable, DeserializationError, Felt, InputError,
+    Serializable, ToElements, Vec,
+};
 use core::slice;
 use serde::{Deserialize, Serialize};
 
@@ -79,6 +82,15 @@ impl Serializable for StackInputs {
     }
 }
 
+impl Deserializable for StackInputs {
+    fn read_from<R: ByteReader>(source: &mut R) -> Result<Self, DeserializationError> {
+        // values are serialized in the order in which they are stored, which is already reversed
+        let num_values = source.read_u32()? as usize;
+        let values = (0..num_values).map(|_| source.read()).collect::<Result<_, _>>()?;
+        Ok(Self { values })
+    }
+}
+
 impl ToElements<Felt> for StackInputs {
     fn to_elements(&self) -> Vec<Felt> {
         self.values.to_vec()
@@ -4,7 +4,7 @@ use super::{
 };
 use winter_utils::{
     collections::{vec, Vec},
-    ByteWriter, Serializable,
+    ByteReader, ByteWriter, Deserializable, DeserializationError, Serializable,
 };
 
 mod inputs;
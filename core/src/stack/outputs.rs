@@ -1,7 +1,8 @@
 use super::{
-    ByteWriter, Felt, OutputError, Serializable, StackTopState, StarkField, ToElements, Vec,
-    STACK_TOP_SIZE,
+    ByteReader, ByteWriter, Deserializable, DeserializationError, Felt, OutputError, Serializable,
+    StackTopState, StarkField, ToElements, Vec, STACK_TOP_SIZE,
 };
+use crate::utils::string::ToString;
 
 // STACK OUTPUTS
 // ================================================================================================
@@ -182,6 +183,20 @@ impl Serializable for StackOutputs {
     }
 }
 
+impl Deserializable for StackOutputs {
+    fn read_from<R: ByteReader>(source: &mut R) -> Result<Self, DeserializationError> {
+        let num_stack_values = source.read_u32()? as usize;
+        let stack = (0..num_stack_values).map(|_| source.read_u64()).collect::<Result<_, _>>()?;
+
+        let num_overflow_addrs = source.read_u32()? as usize;
+        let overflow_addrs =
+            (0..num_overflow_addrs).map(|_| source.read_u64()).collect::<Result<_, _>>()?;
+
+        Self::new(stack, overflow_addrs)
+            .map_err(|err| DeserializationError::InvalidValue(err.to_string()))
+    }
+}
+
 impl ToElements<Felt> for StackOutputs {
     fn to_elements(&self) -> Vec<Felt> {
         // infallible conversion from u64 to Felt is OK here because we check validity of u64
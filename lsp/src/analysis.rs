@@ -0,0 +1,222 @@
+use super::libraries::LibraryIndex;
+use assembly::{
+    ast::{ModuleAst, ModuleImports, ProcedureAst, ProgramAst, SourceLocation},
+    AssemblyContext, Diagnostic,
+};
+use lsp_types::{
+    Diagnostic as LspDiagnostic, DiagnosticRelatedInformation, DiagnosticSeverity, Location,
+    Position, Range, Url,
+};
+
+// DOCUMENT
+// ================================================================================================
+
+/// A Miden assembly source file opened by the client.
+#[derive(Debug)]
+pub struct Document {
+    uri: Url,
+    text: String,
+    /// AST of the last version of the document which could be parsed. It is retained while the
+    /// document is being edited so that navigation and completion keep working while the source
+    /// is temporarily invalid.
+    ast: Option<SourceAst>,
+    /// Diagnostics of the current version of the document.
+    diagnostics: Vec<LspDiagnostic>,
+}
+
+impl Document {
+    /// Returns a new document with the specified contents analyzed against the provided
+    /// libraries.
+    pub fn new(uri: Url, text: String, libraries: &LibraryIndex) -> Self {
+        let mut document = Self {
+            uri,
+            text: String::new(),
+            ast: None,
+            diagnostics: Vec::new(),
+        };
+        document.update(text, libraries);
+        document
+    }
+
+    /// Replaces the contents of this document and analyzes them against the provided libraries.
+    pub fn update(&mut self, text: String, libraries: &LibraryIndex) {
+        let (ast, diagnostic) = analyze(&text, libraries);
+        self.diagnostics = diagnostic
+            .map(|diagnostic| vec![to_lsp_diagnostic(&diagnostic, &self.uri, &text)])
+            .unwrap_or_default();
+        if ast.is_some() {
+            self.ast = ast;
+        }
+        self.text = text;
+    }
+
+    /// Returns the URI of this document.
+    pub fn uri(&self) -> &Url {
+        &self.uri
+    }
+
+    /// Returns the contents of this document.
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Returns the AST of the last version of this document which could be parsed.
+    pub fn ast(&self) -> Option<&SourceAst> {
+        self.ast.as_ref()
+    }
+
+    /// Returns the diagnostics of the current version of this document.
+    pub fn diagnostics(&self) -> &[LspDiagnostic] {
+        &self.diagnostics
+    }
+}
+
+// SOURCE AST
+// ================================================================================================
+
+/// The AST of a source file, which is either an executable program or a library module.
+#[derive(Debug)]
+pub enum SourceAst {
+    Program(ProgramAst),
+    Module(ModuleAst),
+}
+
+impl SourceAst {
+    /// Returns the procedures defined in the source.
+    pub fn procedures(&self) -> &[ProcedureAst] {
+        match self {
+            Self::Program(program) => program.procedures(),
+            Self::Module(module) => module.procs(),
+        }
+    }
+
+    /// Returns the imports of the source.
+    pub fn import_info(&self) -> Option<&ModuleImports> {
+        match self {
+            Self::Program(program) => program.import_info(),
+            Self::Module(module) => module.import_info(),
+        }
+    }
+}
+
+// ANALYSIS
+// ================================================================================================
+
+/// Parses and compiles the provided source, and returns its AST (if the source could be parsed)
+/// and the diagnostic of the first error found in the source (if any).
+///
+/// Sources containing a `begin` token are treated as programs, and all other sources as library
+/// modules.
+fn analyze(text: &str, libraries: &LibraryIndex) -> (Option<SourceAst>, Option<Diagnostic>) {
+    let assembler = libraries.assembler();
+    if is_program(text) {
+        let program = match ProgramAst::parse_with_constants(text, &assembler) {
+            Ok(program) => program,
+            Err(err) => return (None, Some(err.diagnostic())),
+        };
+        let diagnostic = assembler.compile_ast(&program).err().map(|err| {
+            err.diagnostic()
+                .unwrap_or_else(|| Diagnostic::new(&err.to_string(), *program.start()))
+        });
+        (Some(SourceAst::Program(program)), diagnostic)
+    } else {
+        let module = match ModuleAst::parse_with_constants(text, &assembler) {
+            Ok(module) => module,
+            Err(err) => return (None, Some(err.diagnostic())),
+        };
+        let mut context = AssemblyContext::for_module(false);
+        let diagnostic = assembler.compile_module(&module, None, &mut context).err().map(|err| {
+            err.diagnostic()
+                .unwrap_or_else(|| Diagnostic::new(&err.to_string(), SourceLocation::default()))
+        });
+        (Some(SourceAst::Module(module)), diagnostic)
+    }
+}
+
+/// Returns true if the provided source contains a `begin` token outside of comments.
+fn is_program(text: &str) -> bool {
+    text.lines().any(|line| {
+        line.split_whitespace()
+            .take_while(|word| !word.starts_with('#'))
+            .any(|word| word == "begin")
+    })
+}
+
+/// Converts the provided diagnostic into an LSP diagnostic of the document with the specified URI
+/// and contents.
+///
+/// Labels of the diagnostic are converted into related information, and the help message is
+/// appended to the message of the diagnostic.
+fn to_lsp_diagnostic(diagnostic: &Diagnostic, uri: &Url, text: &str) -> LspDiagnostic {
+    let mut message = diagnostic.message().to_string();
+    if let Some(help) = diagnostic.help() {
+        message.push_str(&format!("\nhelp: {help}"));
+    }
+    let related_information = diagnostic
+        .labels()
+        .iter()
+        .map(|label| DiagnosticRelatedInformation {
+            location: Location::new(uri.clone(), token_range(text, label.location())),
+            message: label.message().to_string(),
+        })
+        .collect::<Vec<_>>();
+
+    LspDiagnostic {
+        range: token_range(text, diagnostic.location()),
+        severity: Some(DiagnosticSeverity::ERROR),
+        source: Some("miden-assembly".to_string()),
+        message,
+        related_information: (!related_information.is_empty()).then_some(related_information),
+        ..Default::default()
+    }
+}
+
+// POSITIONS
+// ================================================================================================
+
+/// Returns the range of the token at the specified location in the provided source, i.e., the
+/// range from the location up to the next whitespace character.
+pub fn token_range(text: &str, location: &SourceLocation) -> Range {
+    let line = location.line().saturating_sub(1);
+    let start = location.column().saturating_sub(1);
+    let len = text
+        .lines()
+        .nth(line as usize)
+        .map(|line| line.chars().skip(start as usize).take_while(|c| !c.is_whitespace()).count())
+        .unwrap_or_default();
+    Range::new(Position::new(line, start), Position::new(line, start + len as u32))
+}
+
+/// Returns the whitespace-delimited word containing the specified position (or ending at it)
+/// together with the position at which the word starts, or None if there is no such word.
+pub fn word_at(text: &str, position: Position) -> Option<(&str, Position)> {
+    let line = text.lines().nth(position.line as usize)?;
+    let offset = char_offset(line, position.character);
+    let start = line[..offset].rfind(|c: char| c.is_ascii_whitespace()).map_or(0, |idx| idx + 1);
+    let end = line[offset..]
+        .find(|c: char| c.is_ascii_whitespace())
+        .map_or(line.len(), |idx| offset + idx);
+    if start == end {
+        return None;
+    }
+    let column = line[..start].chars().count() as u32;
+    Some((&line[start..end], Position::new(position.line, column)))
+}
+
+/// Returns the part of the word containing the specified position which precedes the position,
+/// together with the position at which the word starts.
+pub fn word_prefix_at(text: &str, position: Position) -> (&str, Position) {
+    let line = text.lines().nth(position.line as usize).unwrap_or_default();
+    let offset = char_offset(line, position.character);
+    let start = line[..offset].rfind(|c: char| c.is_ascii_whitespace()).map_or(0, |idx| idx + 1);
+    let column = line[..start].chars().count() as u32;
+    (&line[start..offset], Position::new(position.line, column))
+}
+
+/// Returns the byte offset of the character at the specified index in the line, clamped to the
+/// length of the line.
+fn char_offset(line: &str, character: u32) -> usize {
+    line.char_indices()
+        .nth(character as usize)
+        .map_or(line.len(), |(offset, _)| offset)
+}
@@ -0,0 +1,349 @@
+use super::{
+    analysis::{token_range, word_at, word_prefix_at, Document, SourceAst},
+    libraries::LibraryIndex,
+};
+use assembly::{
+    ast::{ProcedureAst, SourceLocation, INSTRUCTION_NAMES},
+    LibraryPath,
+};
+use lsp_types::{
+    CompletionItem, CompletionItemKind, CompletionTextEdit, DocumentSymbol, Documentation, Hover,
+    HoverContents, Location, MarkupContent, MarkupKind, Position, Range, SymbolKind, TextEdit, Url,
+};
+
+// CONSTANTS
+// ================================================================================================
+
+/// Instructions which take the name of a procedure as their argument.
+const INVOCATION_INSTRUCTIONS: [&str; 4] = ["exec", "call", "syscall", "procref"];
+
+/// Headers of procedure declarations.
+const PROC_HEADERS: [&str; 2] = ["proc", "export"];
+
+// HOVER
+// ================================================================================================
+
+/// Returns the description of the procedure referenced at the specified position of the
+/// document, including the doc comments of the procedure and the number of its locals.
+pub fn hover(document: &Document, libraries: &LibraryIndex, position: Position) -> Option<Hover> {
+    let (word, start) = word_at(document.text(), position)?;
+    let target = ProcTarget::parse(word)?;
+    let (contents, _) = resolve(document, libraries, &target)?;
+    let end = Position::new(start.line, start.character + word.chars().count() as u32);
+
+    Some(Hover {
+        contents: HoverContents::Markup(MarkupContent {
+            kind: MarkupKind::Markdown,
+            value: contents.describe(),
+        }),
+        range: Some(Range::new(start, end)),
+    })
+}
+
+// GO TO DEFINITION
+// ================================================================================================
+
+/// Returns the location of the declaration of the procedure referenced at the specified position
+/// of the document.
+///
+/// Procedures imported from other modules are resolved via the libraries; for procedures
+/// re-exported by the imported module, the location of the module itself is returned.
+pub fn definition(
+    document: &Document,
+    libraries: &LibraryIndex,
+    position: Position,
+) -> Option<Location> {
+    let (word, _) = word_at(document.text(), position)?;
+    let target = ProcTarget::parse(word)?;
+    let (_, location) = resolve(document, libraries, &target)?;
+    Some(location)
+}
+
+// COMPLETION
+// ================================================================================================
+
+/// Returns the completions for the word preceding the specified position of the document.
+///
+/// Arguments of invocation instructions (e.g., `exec.`) are completed with the names of the local
+/// procedures and of the procedures exported from the imported modules (e.g., `u64::add`); all
+/// other words are completed with the names of instructions.
+pub fn completion(
+    document: &Document,
+    libraries: &LibraryIndex,
+    position: Position,
+) -> Vec<CompletionItem> {
+    let (prefix, start) = word_prefix_at(document.text(), position);
+    let invocation = prefix
+        .split_once('.')
+        .filter(|(instruction, _)| INVOCATION_INSTRUCTIONS.contains(instruction));
+
+    // complete instruction names
+    let Some((instruction, target)) = invocation else {
+        let range = Range::new(start, position);
+        return INSTRUCTION_NAMES
+            .iter()
+            .map(|name| completion_item(name, CompletionItemKind::KEYWORD, range))
+            .collect();
+    };
+
+    let Some(ast) = document.ast() else {
+        return Vec::new();
+    };
+    let imports = ast.import_info();
+    let target_column = start.character + instruction.len() as u32 + 1;
+
+    // complete the names of procedures exported from the module with the specified alias
+    if let Some((module_name, _)) = target.split_once("::") {
+        let name_column = target_column + module_name.len() as u32 + 2;
+        let range = Range::new(Position::new(position.line, name_column), position);
+        let Some(path) = imports.and_then(|imports| imports.get_module_path(module_name)) else {
+            return Vec::new();
+        };
+        return exported_procs(libraries, path)
+            .into_iter()
+            .map(|proc| proc.completion_item(proc.name.clone(), range))
+            .collect();
+    }
+
+    // complete the names of local procedures and of all imported procedures
+    let range = Range::new(Position::new(position.line, target_column), position);
+    let mut items = ast
+        .procedures()
+        .iter()
+        .map(|proc| {
+            let proc = ProcInfo::from_ast(proc, None);
+            proc.completion_item(proc.name.clone(), range)
+        })
+        .collect::<Vec<_>>();
+    for (module_name, path) in imports.into_iter().flat_map(|imports| imports.imported_modules()) {
+        for proc in exported_procs(libraries, path) {
+            items.push(proc.completion_item(format!("{module_name}::{}", proc.name), range));
+        }
+    }
+    items
+}
+
+// DOCUMENT SYMBOLS
+// ================================================================================================
+
+/// Returns the procedures of the document and, for programs, the program body.
+pub fn document_symbols(document: &Document) -> Vec<DocumentSymbol> {
+    let Some(ast) = document.ast() else {
+        return Vec::new();
+    };
+    let text = document.text();
+
+    let mut symbols = ast
+        .procedures()
+        .iter()
+        .map(|proc| {
+            let detail = proc.signature.map(|signature| signature.to_string());
+            let end = proc.body.source_locations().last();
+            symbol(text, proc.name.to_string(), detail, SymbolKind::FUNCTION, &proc.start, end)
+        })
+        .collect::<Vec<_>>();
+    if let SourceAst::Program(program) = ast {
+        let end = program.body().source_locations().last();
+        let start = program.start();
+        symbols.push(symbol(text, "begin".to_string(), None, SymbolKind::MODULE, start, end));
+    }
+    symbols
+}
+
+// PROCEDURE TARGETS
+// ================================================================================================
+
+/// A reference to a procedure in the source code.
+#[derive(Debug, PartialEq, Eq)]
+enum ProcTarget<'a> {
+    /// A procedure defined in the current source.
+    Local(&'a str),
+    /// A procedure exported from the imported module with the specified name.
+    Imported { module: &'a str, name: &'a str },
+}
+
+impl<'a> ProcTarget<'a> {
+    /// Returns the procedure referenced by the specified token, which may be an invocation (e.g.,
+    /// `exec.foo` or `call.u64::add`), a procedure declaration (e.g., `proc.foo.2`), or a
+    /// re-export (e.g., `export.u64::add->add64`).
+    fn parse(token: &'a str) -> Option<Self> {
+        let (instruction, target) = token.split_once('.')?;
+        let target = if INVOCATION_INSTRUCTIONS.contains(&instruction) {
+            target
+        } else if PROC_HEADERS.contains(&instruction) {
+            // strip the number of locals, or the alias of a re-exported procedure
+            let target = target.split('.').next().unwrap_or_default();
+            target.split("->").next().unwrap_or_default()
+        } else {
+            return None;
+        };
+
+        match target.split_once("::") {
+            Some((module, name)) => Some(Self::Imported { module, name }),
+            // MAST roots cannot be resolved to a declaration
+            None if target.starts_with("0x") || target.is_empty() => None,
+            None => Some(Self::Local(target)),
+        }
+    }
+}
+
+/// Resolves the specified target to the description and location of the referenced procedure.
+fn resolve(
+    document: &Document,
+    libraries: &LibraryIndex,
+    target: &ProcTarget,
+) -> Option<(ProcInfo, Location)> {
+    let ast = document.ast()?;
+    match *target {
+        ProcTarget::Local(name) => {
+            let proc = ast.procedures().iter().find(|proc| proc.name.as_ref() == name)?;
+            let range = token_range(document.text(), &proc.start);
+            Some((ProcInfo::from_ast(proc, None), Location::new(document.uri().clone(), range)))
+        }
+        ProcTarget::Imported { module, name } => {
+            let path = ast.import_info()?.get_module_path(module)?;
+            let (module_ast, file) = libraries.module(path)?;
+            let uri = Url::from_file_path(file).ok()?;
+            let proc =
+                exported_procs(libraries, path).into_iter().find(|proc| proc.name == name)?;
+
+            let source = std::fs::read_to_string(file).unwrap_or_default();
+            let range = match module_ast.procs().iter().find(|proc| proc.name.as_ref() == name) {
+                Some(proc) => token_range(&source, &proc.start),
+                None => Range::default(),
+            };
+            Some((proc, Location::new(uri, range)))
+        }
+    }
+}
+
+/// Returns the procedures exported from the library module at the specified path.
+fn exported_procs(libraries: &LibraryIndex, path: &LibraryPath) -> Vec<ProcInfo> {
+    let Some((module, _)) = libraries.module(path) else {
+        return Vec::new();
+    };
+    let mut procs = module
+        .procs()
+        .iter()
+        .filter(|proc| proc.is_export)
+        .map(|proc| ProcInfo::from_ast(proc, Some(path)))
+        .collect::<Vec<_>>();
+    procs.extend(module.reexported_procs().iter().map(|proc| ProcInfo {
+        header: format!("export.{}", proc.name()),
+        name: proc.name().to_string(),
+        module: Some(path.to_string()),
+        num_locals: None,
+        signature: None,
+        docs: proc.docs().map(|docs| docs.to_string()),
+    }));
+    procs
+}
+
+// PROCEDURE INFO
+// ================================================================================================
+
+/// Description of a procedure shown on hover and in completions.
+#[derive(Debug)]
+struct ProcInfo {
+    header: String,
+    name: String,
+    module: Option<String>,
+    num_locals: Option<u16>,
+    signature: Option<String>,
+    docs: Option<String>,
+}
+
+impl ProcInfo {
+    /// Returns the description of the specified procedure defined in the module at the specified
+    /// path, or in the current source if the path is None.
+    fn from_ast(proc: &ProcedureAst, module: Option<&LibraryPath>) -> Self {
+        let keyword = if proc.is_export { "export" } else { "proc" };
+        let header = match proc.num_locals {
+            0 => format!("{keyword}.{}", proc.name),
+            num_locals => format!("{keyword}.{}.{num_locals}", proc.name),
+        };
+        Self {
+            header,
+            name: proc.name.to_string(),
+            module: module.map(|path| path.to_string()),
+            num_locals: Some(proc.num_locals),
+            signature: proc.signature.map(|signature| signature.to_string()),
+            docs: proc.docs.clone(),
+        }
+    }
+
+    /// Returns the Markdown description of this procedure.
+    fn describe(&self) -> String {
+        let mut description = format!("```masm\n{}\n```", self.header);
+        if let Some(ref module) = self.module {
+            description.push_str(&format!("\n\nDefined in `{module}`"));
+        }
+
+        let mut details = Vec::new();
+        if let Some(num_locals) = self.num_locals {
+            details.push(format!("Locals: {num_locals}"));
+        }
+        if let Some(ref signature) = self.signature {
+            details.push(format!("Stack signature: `{signature}`"));
+        }
+        if !details.is_empty() {
+            description.push_str(&format!("\n\n{}", details.join(" | ")));
+        }
+
+        if let Some(ref docs) = self.docs {
+            description.push_str(&format!("\n\n---\n\n{docs}"));
+        }
+        description
+    }
+
+    /// Returns a completion item which inserts the specified label over the specified range.
+    fn completion_item(&self, label: String, range: Range) -> CompletionItem {
+        CompletionItem {
+            detail: Some(self.header.clone()),
+            documentation: self.docs.clone().map(Documentation::String),
+            ..completion_item(&label, CompletionItemKind::FUNCTION, range)
+        }
+    }
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Returns a completion item which inserts the specified label over the specified range.
+fn completion_item(label: &str, kind: CompletionItemKind, range: Range) -> CompletionItem {
+    CompletionItem {
+        label: label.to_string(),
+        kind: Some(kind),
+        text_edit: Some(CompletionTextEdit::Edit(TextEdit::new(range, label.to_string()))),
+        ..Default::default()
+    }
+}
+
+/// Returns a symbol spanning from the token at the start location to the token at the end
+/// location, or only the start token if the end location is unknown.
+fn symbol(
+    text: &str,
+    name: String,
+    detail: Option<String>,
+    kind: SymbolKind,
+    start: &SourceLocation,
+    end: Option<&SourceLocation>,
+) -> DocumentSymbol {
+    let selection_range = token_range(text, start);
+    let range = match end {
+        Some(end) => Range::new(selection_range.start, token_range(text, end).end),
+        None => selection_range,
+    };
+
+    #[allow(deprecated)]
+    DocumentSymbol {
+        name,
+        detail,
+        kind,
+        tags: None,
+        deprecated: None,
+        range,
+        selection_range,
+        children: None,
+    }
+}
@@ -0,0 +1,21 @@
+//! A language server for Miden assembly.
+//!
+//! The server speaks the Language Server Protocol over the provided connection (usually stdio),
+//! and provides the following features for `.masm` files:
+//! - diagnostics for parsing and compilation errors,
+//! - go-to-definition for invoked procedures, including procedures imported from libraries,
+//! - hover with the doc comments, the number of locals, and the stack signature of procedures,
+//! - completion of instructions and of local and imported procedure names,
+//! - document symbols for procedures and the program body.
+
+mod analysis;
+mod features;
+
+mod libraries;
+pub use libraries::{LibraryOptions, ServerOptions};
+
+mod server;
+pub use server::{run, ServerError};
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,113 @@
+use assembly::{
+    ast::ModuleAst, Assembler, Library, LibraryNamespace, LibraryPath, MaslLibrary, Version,
+};
+use serde::Deserialize;
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
+
+// SERVER OPTIONS
+// ================================================================================================
+
+/// Options of the server passed by the client as `initializationOptions` of the `initialize`
+/// request, e.g.:
+/// ```json
+/// { "libraries": [{ "namespace": "std", "path": "/path/to/miden-vm/stdlib/asm" }] }
+/// ```
+#[derive(Debug, Default, Clone, Deserialize)]
+pub struct ServerOptions {
+    /// Libraries which can be imported by the edited sources.
+    #[serde(default)]
+    pub libraries: Vec<LibraryOptions>,
+}
+
+/// Location of a library of Miden assembly modules.
+#[derive(Debug, Clone, Deserialize)]
+pub struct LibraryOptions {
+    /// Root namespace of the library (e.g., `std`).
+    pub namespace: String,
+    /// Directory containing the `.masm` files of the library.
+    pub path: PathBuf,
+}
+
+// LIBRARY INDEX
+// ================================================================================================
+
+/// Libraries available to the edited sources, together with an index of their modules.
+#[derive(Debug, Default)]
+pub struct LibraryIndex {
+    libraries: Vec<MaslLibrary>,
+    modules: BTreeMap<LibraryPath, IndexedModule>,
+}
+
+/// A library module together with the path of the file it was parsed from.
+#[derive(Debug)]
+struct IndexedModule {
+    ast: ModuleAst,
+    file: PathBuf,
+}
+
+impl LibraryIndex {
+    /// Loads the specified libraries from their directories.
+    ///
+    /// Libraries which cannot be loaded are skipped; the returned list contains a message for
+    /// each of them.
+    pub fn load(options: &[LibraryOptions]) -> (Self, Vec<String>) {
+        let mut index = Self::default();
+        let mut errors = Vec::new();
+        for library in options {
+            match read_library(library) {
+                Ok(masl) if index.libraries.iter().any(|lib| lib.root_ns() == masl.root_ns()) => {
+                    errors.push(format!("duplicate library namespace '{}'", library.namespace))
+                }
+                Ok(masl) => {
+                    for module in masl.modules() {
+                        let file = module_file(&library.path, &module.path);
+                        let ast = module.ast.clone();
+                        index.modules.insert(module.path.clone(), IndexedModule { ast, file });
+                    }
+                    index.libraries.push(masl);
+                }
+                Err(err) => errors.push(format!(
+                    "failed to load library '{}' from {}: {err}",
+                    library.namespace,
+                    library.path.display()
+                )),
+            }
+        }
+        (index, errors)
+    }
+
+    /// Returns a new assembler with all libraries of this index.
+    pub fn assembler(&self) -> Assembler {
+        Assembler::default()
+            .with_libraries(self.libraries.iter())
+            .expect("libraries with duplicate modules")
+    }
+
+    /// Returns the AST of the module at the specified path and the file the module was parsed
+    /// from, or None if the module is not provided by any of the libraries.
+    pub fn module(&self, path: &LibraryPath) -> Option<(&ModuleAst, &Path)> {
+        self.modules.get(path).map(|module| (&module.ast, module.file.as_path()))
+    }
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Reads the library described by the provided options.
+fn read_library(options: &LibraryOptions) -> Result<MaslLibrary, String> {
+    let namespace = LibraryNamespace::new(&options.namespace).map_err(|err| err.to_string())?;
+    MaslLibrary::read_from_dir(&options.path, namespace, true, Version::MIN)
+        .map_err(|err| err.to_string())
+}
+
+/// Returns the file of the module at the specified path within the library at the specified
+/// directory (e.g., `<dir>/math/u64.masm` for `std::math::u64`).
+fn module_file(dir: &Path, path: &LibraryPath) -> PathBuf {
+    let mut file = dir.to_path_buf();
+    file.extend(path.components().skip(1));
+    file.set_extension(MaslLibrary::MODULE_EXTENSION);
+    file
+}
@@ -0,0 +1,9 @@
+use lsp_server::Connection;
+use miden_lsp::ServerError;
+
+fn main() -> Result<(), ServerError> {
+    let (connection, io_threads) = Connection::stdio();
+    miden_lsp::run(connection)?;
+    io_threads.join()?;
+    Ok(())
+}
@@ -0,0 +1,235 @@
+use super::{
+    analysis::Document,
+    features,
+    libraries::{LibraryIndex, ServerOptions},
+};
+use lsp_server::{Connection, ErrorCode, Message, Notification, Request, Response};
+use lsp_types::{
+    notification::{
+        DidChangeTextDocument, DidCloseTextDocument, DidOpenTextDocument,
+        Notification as NotificationTrait, PublishDiagnostics, ShowMessage,
+    },
+    request::{Completion, DocumentSymbolRequest, GotoDefinition, HoverRequest, Request as _},
+    CompletionOptions, CompletionResponse, DocumentSymbolResponse, GotoDefinitionResponse,
+    HoverProviderCapability, InitializeParams, InitializeResult, MessageType, OneOf,
+    PublishDiagnosticsParams, ServerCapabilities, ServerInfo, ShowMessageParams,
+    TextDocumentSyncCapability, TextDocumentSyncKind, Url,
+};
+use serde::de::DeserializeOwned;
+use std::{collections::BTreeMap, error::Error};
+
+// TYPE ALIASES
+// ================================================================================================
+
+/// The error type of the server.
+pub type ServerError = Box<dyn Error + Send + Sync>;
+
+// SERVER
+// ================================================================================================
+
+/// Runs the language server on the specified connection until the client shuts it down.
+///
+/// The server handles the `initialize` handshake, after which it analyzes the documents opened
+/// by the client and answers requests about them. Libraries available to the documents are
+/// configured via the `initializationOptions` of the `initialize` request (see
+/// [ServerOptions]).
+pub fn run(connection: Connection) -> Result<(), ServerError> {
+    let (id, params) = connection.initialize_start()?;
+    let params: InitializeParams = serde_json::from_value(params)?;
+    let options = match params.initialization_options {
+        Some(options) => serde_json::from_value::<ServerOptions>(options)?,
+        None => ServerOptions::default(),
+    };
+
+    let result = InitializeResult {
+        capabilities: capabilities(),
+        server_info: Some(ServerInfo {
+            name: env!("CARGO_PKG_NAME").to_string(),
+            version: Some(env!("CARGO_PKG_VERSION").to_string()),
+        }),
+    };
+    connection.initialize_finish(id, serde_json::to_value(result)?)?;
+
+    let (libraries, errors) = LibraryIndex::load(&options.libraries);
+    let mut server = Server {
+        connection: &connection,
+        libraries,
+        documents: BTreeMap::new(),
+    };
+    for message in errors {
+        server.notify::<ShowMessage>(ShowMessageParams {
+            typ: MessageType::ERROR,
+            message,
+        })?;
+    }
+    server.main_loop()
+}
+
+/// Returns the capabilities of the server announced to the client.
+fn capabilities() -> ServerCapabilities {
+    ServerCapabilities {
+        text_document_sync: Some(TextDocumentSyncCapability::Kind(TextDocumentSyncKind::FULL)),
+        hover_provider: Some(HoverProviderCapability::Simple(true)),
+        definition_provider: Some(OneOf::Left(true)),
+        completion_provider: Some(CompletionOptions {
+            trigger_characters: Some(vec![".".to_string(), ":".to_string()]),
+            ..Default::default()
+        }),
+        document_symbol_provider: Some(OneOf::Left(true)),
+        ..Default::default()
+    }
+}
+
+/// State of a running language server.
+struct Server<'a> {
+    connection: &'a Connection,
+    libraries: LibraryIndex,
+    documents: BTreeMap<Url, Document>,
+}
+
+impl Server<'_> {
+    /// Handles messages from the client until the client shuts the server down or closes the
+    /// connection.
+    fn main_loop(&mut self) -> Result<(), ServerError> {
+        for message in &self.connection.receiver {
+            match message {
+                Message::Request(request) => {
+                    if self.connection.handle_shutdown(&request)? {
+                        return Ok(());
+                    }
+                    let response = self.handle_request(request);
+                    self.connection.sender.send(response.into())?;
+                }
+                Message::Notification(notification) => self.handle_notification(notification)?,
+                Message::Response(_) => (),
+            }
+        }
+        Ok(())
+    }
+
+    // REQUESTS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the response to the specified request.
+    fn handle_request(&self, request: Request) -> Response {
+        let id = request.id.clone();
+        let response = match request.method.as_str() {
+            HoverRequest::METHOD => self.respond::<HoverRequest>(request, |server, params| {
+                let position = params.text_document_position_params;
+                let document = server.documents.get(&position.text_document.uri)?;
+                features::hover(document, &server.libraries, position.position)
+            }),
+            GotoDefinition::METHOD => self.respond::<GotoDefinition>(request, |server, params| {
+                let position = params.text_document_position_params;
+                let document = server.documents.get(&position.text_document.uri)?;
+                features::definition(document, &server.libraries, position.position)
+                    .map(GotoDefinitionResponse::Scalar)
+            }),
+            Completion::METHOD => self.respond::<Completion>(request, |server, params| {
+                let position = params.text_document_position;
+                let document = server.documents.get(&position.text_document.uri)?;
+                let items = features::completion(document, &server.libraries, position.position);
+                Some(CompletionResponse::Array(items))
+            }),
+            DocumentSymbolRequest::METHOD => {
+                self.respond::<DocumentSymbolRequest>(request, |server, params| {
+                    let document = server.documents.get(&params.text_document.uri)?;
+                    Some(DocumentSymbolResponse::Nested(features::document_symbols(document)))
+                })
+            }
+            method => Err((ErrorCode::MethodNotFound, format!("unsupported request: {method}"))),
+        };
+
+        match response {
+            Ok(result) => Response::new_ok(id, result),
+            Err((code, message)) => Response::new_err(id, code as i32, message),
+        }
+    }
+
+    /// Extracts the parameters of the specified request and returns the serialized result of the
+    /// provided handler.
+    fn respond<R>(
+        &self,
+        request: Request,
+        handler: impl FnOnce(&Self, R::Params) -> R::Result,
+    ) -> Result<serde_json::Value, (ErrorCode, String)>
+    where
+        R: lsp_types::request::Request,
+        R::Params: DeserializeOwned,
+    {
+        let (_, params) = request
+            .extract::<R::Params>(R::METHOD)
+            .map_err(|err| (ErrorCode::InvalidParams, format!("{err:?}")))?;
+        serde_json::to_value(handler(self, params))
+            .map_err(|err| (ErrorCode::InternalError, err.to_string()))
+    }
+
+    // NOTIFICATIONS
+    // --------------------------------------------------------------------------------------------
+
+    /// Updates the state of the server according to the specified notification.
+    fn handle_notification(&mut self, notification: Notification) -> Result<(), ServerError> {
+        match notification.method.as_str() {
+            DidOpenTextDocument::METHOD => {
+                let params = extract::<DidOpenTextDocument>(notification)?;
+                let uri = params.text_document.uri;
+                let document =
+                    Document::new(uri.clone(), params.text_document.text, &self.libraries);
+                self.documents.insert(uri.clone(), document);
+                self.publish_diagnostics(&uri)
+            }
+            DidChangeTextDocument::METHOD => {
+                let params = extract::<DidChangeTextDocument>(notification)?;
+                let uri = params.text_document.uri;
+                // the server requests full document synchronization, thus the last change holds
+                // the complete contents of the document
+                let Some(change) = params.content_changes.into_iter().last() else {
+                    return Ok(());
+                };
+                if let Some(document) = self.documents.get_mut(&uri) {
+                    document.update(change.text, &self.libraries);
+                }
+                self.publish_diagnostics(&uri)
+            }
+            DidCloseTextDocument::METHOD => {
+                let params = extract::<DidCloseTextDocument>(notification)?;
+                let uri = params.text_document.uri;
+                self.documents.remove(&uri);
+                self.notify::<PublishDiagnostics>(PublishDiagnosticsParams::new(uri, vec![], None))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Sends the diagnostics of the document with the specified URI to the client.
+    fn publish_diagnostics(&self, uri: &Url) -> Result<(), ServerError> {
+        let Some(document) = self.documents.get(uri) else {
+            return Ok(());
+        };
+        let diagnostics = document.diagnostics().to_vec();
+        self.notify::<PublishDiagnostics>(PublishDiagnosticsParams::new(
+            uri.clone(),
+            diagnostics,
+            None,
+        ))
+    }
+
+    /// Sends a notification with the specified parameters to the client.
+    fn notify<N: NotificationTrait>(&self, params: N::Params) -> Result<(), ServerError> {
+        let notification = Notification::new(N::METHOD.to_string(), params);
+        self.connection.sender.send(notification.into())?;
+        Ok(())
+    }
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Extracts the parameters of the specified notification.
+fn extract<N>(notification: Notification) -> Result<N::Params, ServerError>
+where
+    N: NotificationTrait,
+    N::Params: DeserializeOwned,
+{
+    notification.extract(N::METHOD).map_err(|err| format!("{err:?}").into())
+}
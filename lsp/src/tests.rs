@@ -0,0 +1,330 @@
+use super::run;
+use lsp_server::{Connection, Message, Notification, Request, RequestId};
+use lsp_types::{
+    notification::{
+        DidChangeTextDocument, DidOpenTextDocument, Exit, Initialized,
+        Notification as NotificationTrait, PublishDiagnostics,
+    },
+    request::{
+        Completion, DocumentSymbolRequest, GotoDefinition, HoverRequest, Initialize,
+        Request as RequestTrait, Shutdown,
+    },
+    CompletionParams, CompletionResponse, DidChangeTextDocumentParams, DidOpenTextDocumentParams,
+    DocumentSymbolParams, DocumentSymbolResponse, GotoDefinitionParams, GotoDefinitionResponse,
+    HoverContents, HoverParams, InitializeParams, Position, PublishDiagnosticsParams, Range,
+    TextDocumentContentChangeEvent, TextDocumentIdentifier, TextDocumentItem,
+    TextDocumentPositionParams, Url, VersionedTextDocumentIdentifier,
+};
+use serde_json::json;
+use std::{path::Path, thread::JoinHandle};
+
+// TEST CLIENT
+// ================================================================================================
+
+/// A scripted client which runs the server in a separate thread and talks to it over an
+/// in-memory connection.
+struct TestClient {
+    connection: Connection,
+    server: JoinHandle<()>,
+    next_id: i32,
+}
+
+impl TestClient {
+    /// Starts the server with the standard library available under the `std` namespace.
+    fn start() -> Self {
+        let (connection, server_connection) = Connection::memory();
+        let server = std::thread::spawn(move || run(server_connection).unwrap());
+        let mut client = Self {
+            connection,
+            server,
+            next_id: 0,
+        };
+
+        let stdlib = Path::new(env!("CARGO_MANIFEST_DIR")).join("../stdlib/asm");
+        let params = InitializeParams {
+            initialization_options: Some(json!({
+                "libraries": [{ "namespace": "std", "path": stdlib }]
+            })),
+            ..Default::default()
+        };
+        client.request::<Initialize>(params);
+        client.notify::<Initialized>(lsp_types::InitializedParams {});
+        client
+    }
+
+    /// Sends the specified request and returns the result of the response to it.
+    fn request<R: RequestTrait>(&mut self, params: R::Params) -> R::Result {
+        self.next_id += 1;
+        let id = RequestId::from(self.next_id);
+        let request = Request::new(id.clone(), R::METHOD.to_string(), params);
+        self.connection.sender.send(request.into()).unwrap();
+
+        loop {
+            match self.connection.receiver.recv().unwrap() {
+                Message::Response(response) if response.id == id => {
+                    assert!(response.error.is_none(), "{} failed: {:?}", R::METHOD, response.error);
+                    let result = response.result.unwrap_or_default();
+                    return serde_json::from_value(result).unwrap();
+                }
+                _ => (),
+            }
+        }
+    }
+
+    /// Sends the specified notification.
+    fn notify<N: NotificationTrait>(&self, params: N::Params) {
+        let notification = Notification::new(N::METHOD.to_string(), params);
+        self.connection.sender.send(notification.into()).unwrap();
+    }
+
+    /// Waits for the diagnostics published by the server.
+    fn diagnostics(&self) -> PublishDiagnosticsParams {
+        loop {
+            if let Message::Notification(notification) = self.connection.receiver.recv().unwrap() {
+                if notification.method == PublishDiagnostics::METHOD {
+                    return serde_json::from_value(notification.params).unwrap();
+                }
+            }
+        }
+    }
+
+    /// Opens a document with the specified contents and returns its diagnostics.
+    fn open(&self, uri: &Url, text: &str) -> PublishDiagnosticsParams {
+        let document = TextDocumentItem::new(uri.clone(), "masm".to_string(), 1, text.to_string());
+        self.notify::<DidOpenTextDocument>(DidOpenTextDocumentParams {
+            text_document: document,
+        });
+        self.diagnostics()
+    }
+
+    /// Replaces the contents of a document and returns its diagnostics.
+    fn change(&self, uri: &Url, text: &str) -> PublishDiagnosticsParams {
+        self.notify::<DidChangeTextDocument>(DidChangeTextDocumentParams {
+            text_document: VersionedTextDocumentIdentifier::new(uri.clone(), 2),
+            content_changes: vec![TextDocumentContentChangeEvent {
+                range: None,
+                range_length: None,
+                text: text.to_string(),
+            }],
+        });
+        self.diagnostics()
+    }
+
+    /// Shuts the server down and waits for it to exit.
+    fn shutdown(mut self) {
+        self.request::<Shutdown>(());
+        self.notify::<Exit>(());
+        self.server.join().unwrap();
+    }
+}
+
+/// Returns the parameters of a request about the specified position of a document.
+fn position(uri: &Url, line: u32, character: u32) -> TextDocumentPositionParams {
+    TextDocumentPositionParams::new(
+        TextDocumentIdentifier::new(uri.clone()),
+        Position::new(line, character),
+    )
+}
+
+// TESTS
+// ================================================================================================
+
+const PROGRAM: &str = "\
+use.std::math::u64
+
+proc.foo.2 # (2 -- 1)
+    add
+end
+
+begin
+    push.1 push.2 exec.foo
+    push.1.2.3.4 exec.u64::checked_add
+end
+";
+
+#[test]
+fn diagnostics() {
+    let client = TestClient::start();
+    let uri = Url::parse("file:///main.masm").unwrap();
+
+    let diagnostics = client.open(&uri, PROGRAM);
+    assert_eq!(uri, diagnostics.uri);
+    assert!(diagnostics.diagnostics.is_empty());
+
+    // parsing errors carry the help message and the secondary labels
+    let source = "proc.foo add end\nproc.foo mul end\nbegin\n    exec.foo\n    dupp\nend";
+    let diagnostics = client.change(&uri, source).diagnostics;
+    assert_eq!(1, diagnostics.len());
+    assert_eq!("duplicate procedure name: foo", diagnostics[0].message);
+    assert_eq!(Range::new(Position::new(1, 0), Position::new(1, 8)), diagnostics[0].range);
+    let related = diagnostics[0].related_information.as_ref().unwrap();
+    assert_eq!("first defined here", related[0].message);
+    assert_eq!(Range::new(Position::new(0, 0), Position::new(0, 8)), related[0].location.range);
+
+    let diagnostics = client.change(&uri, "begin\n    push.1\n    dupp\nend").diagnostics;
+    assert_eq!(
+        "instruction 'dupp' is invalid\nhelp: did you mean `dup`?",
+        diagnostics[0].message
+    );
+    assert_eq!(Range::new(Position::new(2, 4), Position::new(2, 8)), diagnostics[0].range);
+
+    // compilation errors are reported as well
+    let source = "proc.foo # (1 -- 1)\n    drop\nend\nbegin\n    exec.foo\nend";
+    let diagnostics = client.change(&uri, source).diagnostics;
+    assert_eq!(1, diagnostics.len());
+    assert!(diagnostics[0]
+        .message
+        .starts_with("procedure 'foo' ending at [3:1] changes the stack"));
+    assert_eq!(Range::new(Position::new(2, 0), Position::new(2, 3)), diagnostics[0].range);
+
+    // modules are compiled as library modules
+    let module_uri = Url::parse("file:///module.masm").unwrap();
+    let diagnostics =
+        client.open(&module_uri, "use.std::math::u64\nexport.foo\n    exec.u64::nope\nend");
+    assert_eq!(1, diagnostics.diagnostics.len());
+    let diagnostics = client.change(&module_uri, "export.foo\n    add\nend");
+    assert!(diagnostics.diagnostics.is_empty());
+
+    client.shutdown();
+}
+
+#[test]
+fn hover_and_definition() {
+    let mut client = TestClient::start();
+    let uri = Url::parse("file:///main.masm").unwrap();
+    client.open(&uri, PROGRAM);
+
+    // local procedures
+    let hover = client.request::<HoverRequest>(HoverParams {
+        text_document_position_params: position(&uri, 7, 25),
+        work_done_progress_params: Default::default(),
+    });
+    let HoverContents::Markup(contents) = hover.unwrap().contents else {
+        panic!("unexpected hover contents");
+    };
+    assert_eq!(
+        "```masm\nproc.foo.2\n```\n\nLocals: 2 | Stack signature: `(2 -- 1)`",
+        contents.value
+    );
+
+    let definition = client.request::<GotoDefinition>(GotoDefinitionParams {
+        text_document_position_params: position(&uri, 7, 25),
+        work_done_progress_params: Default::default(),
+        partial_result_params: Default::default(),
+    });
+    let Some(GotoDefinitionResponse::Scalar(location)) = definition else {
+        panic!("unexpected definition: {definition:?}");
+    };
+    assert_eq!(uri, location.uri);
+    assert_eq!(Range::new(Position::new(2, 0), Position::new(2, 10)), location.range);
+
+    // imported procedures
+    let hover = client.request::<HoverRequest>(HoverParams {
+        text_document_position_params: position(&uri, 8, 20),
+        work_done_progress_params: Default::default(),
+    });
+    let HoverContents::Markup(contents) = hover.unwrap().contents else {
+        panic!("unexpected hover contents");
+    };
+    assert!(contents
+        .value
+        .starts_with("```masm\nexport.checked_add\n```\n\nDefined in `std::math::u64`"));
+    assert!(contents
+        .value
+        .contains("Performs addition of two unsigned 64 bit integers, fails"));
+
+    let definition = client.request::<GotoDefinition>(GotoDefinitionParams {
+        text_document_position_params: position(&uri, 8, 20),
+        work_done_progress_params: Default::default(),
+        partial_result_params: Default::default(),
+    });
+    let Some(GotoDefinitionResponse::Scalar(location)) = definition else {
+        panic!("unexpected definition: {definition:?}");
+    };
+    assert!(location.uri.path().ends_with("stdlib/asm/math/u64.masm"));
+    let source = std::fs::read_to_string(location.uri.to_file_path().unwrap()).unwrap();
+    let line = source.lines().nth(location.range.start.line as usize).unwrap();
+    assert_eq!("export.checked_add", line);
+
+    // instructions are not described
+    let hover = client.request::<HoverRequest>(HoverParams {
+        text_document_position_params: position(&uri, 7, 4),
+        work_done_progress_params: Default::default(),
+    });
+    assert_eq!(None, hover);
+
+    client.shutdown();
+}
+
+#[test]
+fn completion() {
+    let mut client = TestClient::start();
+    let uri = Url::parse("file:///main.masm").unwrap();
+    client.open(&uri, PROGRAM);
+
+    let mut complete = |text: &str, line: u32, character: u32| {
+        client.change(&uri, text);
+        let response = client.request::<Completion>(CompletionParams {
+            text_document_position: position(&uri, line, character),
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+            context: None,
+        });
+        let Some(CompletionResponse::Array(items)) = response else {
+            panic!("unexpected completion: {response:?}");
+        };
+        items.into_iter().map(|item| item.label).collect::<Vec<_>>()
+    };
+
+    // instructions
+    let labels = complete(PROGRAM, 7, 5);
+    assert!(labels.contains(&"push".to_string()));
+    assert!(labels.contains(&"u32checked_add".to_string()));
+
+    // local and imported procedures; the document is temporarily invalid
+    let source = PROGRAM.replace("exec.foo\n", "exec.\n");
+    let labels = complete(&source, 7, 23);
+    assert!(labels.contains(&"foo".to_string()));
+    assert!(labels.contains(&"u64::checked_add".to_string()));
+
+    // procedures exported from a module
+    let source = PROGRAM.replace("exec.foo\n", "exec.u64::\n");
+    let labels = complete(&source, 7, 28);
+    assert!(labels.contains(&"checked_add".to_string()));
+    assert!(!labels.contains(&"foo".to_string()));
+
+    client.shutdown();
+}
+
+#[test]
+fn document_symbols() {
+    let mut client = TestClient::start();
+    let uri = Url::parse("file:///main.masm").unwrap();
+    client.open(&uri, PROGRAM);
+
+    let response = client.request::<DocumentSymbolRequest>(DocumentSymbolParams {
+        text_document: TextDocumentIdentifier::new(uri.clone()),
+        work_done_progress_params: Default::default(),
+        partial_result_params: Default::default(),
+    });
+    let Some(DocumentSymbolResponse::Nested(symbols)) = response else {
+        panic!("unexpected symbols: {response:?}");
+    };
+    let symbols = symbols
+        .into_iter()
+        .map(|symbol| (symbol.name, symbol.detail, symbol.range))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        vec![
+            (
+                "foo".to_string(),
+                Some("(2 -- 1)".to_string()),
+                Range::new(Position::new(2, 0), Position::new(4, 3))
+            ),
+            ("begin".to_string(), None, Range::new(Position::new(6, 0), Position::new(9, 3))),
+        ],
+        symbols
+    );
+
+    client.shutdown();
+}
@@ -1,4 +1,4 @@
-use assembly::{LibraryNamespace, MaslLibrary, Version};
+use assembly::{LibraryNamespace, MaslLibrary, OptimizationLevel, Version};
 use clap::Parser;
 use std::path::PathBuf;
 
@@ -17,6 +17,10 @@ pub struct BundleCmd {
     /// Version of the library, defaults to `0.1.0`.
     #[clap(short, long, default_value = "0.1.0")]
     version: String,
+    /// Compile the modules of the library with peephole optimizations; this changes the MAST
+    /// roots of the library procedures.
+    #[clap(long)]
+    optimize: bool,
 }
 
 impl BundleCmd {
@@ -46,6 +50,11 @@ impl BundleCmd {
             version,
         )
         .map_err(|e| e.to_string())?;
+        let optimization_level = match self.optimize {
+            true => OptimizationLevel::Basic,
+            false => OptimizationLevel::None,
+        };
+        let stdlib = stdlib.with_optimization_level(optimization_level);
 
         // write the masl output
         stdlib.write_to_dir(self.dir.clone()).map_err(|e| e.to_string())?;
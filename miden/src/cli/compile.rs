@@ -1,6 +1,7 @@
+use assembly::CallGraphReport;
 use clap::Parser;
 
-use super::data::{Debug, Libraries, ProgramFile};
+use super::data::{Debug, Libraries, ProgramFile, SourceMapFile};
 use std::path::PathBuf;
 
 #[derive(Debug, Clone, Parser)]
@@ -15,6 +16,12 @@ pub struct CompileCmd {
     /// Path to output file
     #[clap(short = 'o', long = "output", value_parser)]
     output_file: Option<PathBuf>,
+    /// Path to which the source map of the compiled program is written
+    #[clap(long = "source-map", value_parser)]
+    source_map_file: Option<PathBuf>,
+    /// Report unused exports of imported modules and the procedures inlined into the program
+    #[clap(long = "call-report", conflicts_with = "source_map_file")]
+    call_report: bool,
 }
 
 impl CompileCmd {
@@ -23,14 +30,28 @@ impl CompileCmd {
         println!("Compile program");
         println!("============================================================");
 
-        // load the program from file and parse it
-        let program = ProgramFile::read(&self.assembly_file)?;
-
         // load libraries from files
         let libraries = Libraries::new(&self.library_paths)?;
 
-        // compile the program
-        let compiled_program = program.compile(&Debug::Off, libraries.libraries)?;
+        // load the program from file and parse it
+        let program = ProgramFile::read(&self.assembly_file, &libraries.libraries)?;
+
+        // compile the program; if requested, write the source map of the program to file
+        let compiled_program = match &self.source_map_file {
+            Some(source_map_path) => {
+                let (compiled_program, source_map) =
+                    program.compile_with_source_map(&Debug::Off, libraries.libraries)?;
+                SourceMapFile::write(&source_map, source_map_path)?;
+                compiled_program
+            }
+            None if self.call_report => {
+                let (compiled_program, report) =
+                    program.compile_with_call_report(&Debug::Off, libraries.libraries)?;
+                print_call_report(&report);
+                compiled_program
+            }
+            None => program.compile(&Debug::Off, libraries.libraries)?,
+        };
 
         // report program hash to user
         let program_hash: [u8; 32] = compiled_program.hash().into();
@@ -40,3 +61,33 @@ impl CompileCmd {
         program.write(self.output_file.clone())
     }
 }
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Prints the unused exports and the inlined procedures listed in the specified report.
+fn print_call_report(report: &CallGraphReport) {
+    for name in report.unused_exports() {
+        println!("warning: procedure `{name}` is exported but never invoked by the program");
+    }
+
+    if report.inlined_procedures().is_empty() {
+        return;
+    }
+    println!("------------------------------------------------------------");
+    println!(
+        "{:<32} {:>8} {:>8} {:>10} {:>10}",
+        "inlined procedure", "size", "copies", "total", "call saves"
+    );
+    for proc in report.inlined_procedures() {
+        println!(
+            "{:<32} {:>8} {:>8} {:>10} {:>10}",
+            proc.name(),
+            proc.size(),
+            proc.copies(),
+            proc.total_size(),
+            proc.call_size_savings()
+        );
+    }
+    println!("------------------------------------------------------------");
+}
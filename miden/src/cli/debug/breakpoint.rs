@@ -0,0 +1,292 @@
+use miden::{math::StarkField, VmState, Word};
+use std::fmt;
+
+// BREAKPOINT
+// ================================================================================================
+
+/// A breakpoint set by the user of the debugger.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Breakpoint {
+    /// Breaks when the execution reaches the specified location, if the condition (if any) holds
+    /// at that point.
+    Location(BreakLocation, Option<StackCondition>),
+    /// Breaks when the specified condition starts to hold.
+    Condition(StackCondition),
+    /// Breaks when the word at the specified memory address of the context being executed
+    /// changes.
+    Watch(u64),
+}
+
+impl Breakpoint {
+    /// Returns `true` if the execution should break at the current state, given the previous
+    /// state and the procedure stacks at both states.
+    pub fn is_hit(
+        &self,
+        prev_state: &VmState,
+        prev_frames: &[String],
+        state: &VmState,
+        frames: &[String],
+    ) -> bool {
+        match self {
+            Self::Location(location, condition) => {
+                location.is_reached(prev_state, prev_frames, state, frames)
+                    && condition.as_ref().map_or(true, |condition| condition.holds(state))
+            }
+            Self::Condition(condition) => condition.holds(state) && !condition.holds(prev_state),
+            // states hold only the memory of their own context, so memories of different contexts
+            // are not compared when the execution enters or leaves a context
+            Self::Watch(address) => {
+                prev_state.ctx == state.ctx
+                    && read_memory(prev_state, *address) != read_memory(state, *address)
+            }
+        }
+    }
+}
+
+impl fmt::Display for Breakpoint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Location(location, None) => write!(f, "break {location}"),
+            Self::Location(location, Some(condition)) => {
+                write!(f, "break {location} if {condition}")
+            }
+            Self::Condition(condition) => write!(f, "break if {condition}"),
+            Self::Watch(address) => write!(f, "watch mem {address}"),
+        }
+    }
+}
+
+// BREAK LOCATION
+// ================================================================================================
+
+/// A location in the program at which the execution breaks.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum BreakLocation {
+    /// Entry into the procedure with the specified name (e.g., `foo` or `std::math::u64::add`).
+    Procedure(String),
+    /// Start of the first instruction executed at the specified line of the specified file.
+    Line { file: String, line: u32 },
+}
+
+impl BreakLocation {
+    /// Returns a new location parsed from the specified string, either `file.masm:line` or a
+    /// procedure name.
+    ///
+    /// # Errors
+    /// Returns an error if the location is malformed.
+    pub fn parse(location: &str) -> Result<Self, String> {
+        match location.rsplit_once(':') {
+            Some((file, line)) if !file.is_empty() && !file.ends_with(':') => {
+                let line = line.parse::<u32>().map_err(|err| {
+                    format!("malformed location - failed to parse line number: `{line}` {err}")
+                })?;
+                Ok(Self::Line {
+                    file: file.to_string(),
+                    line,
+                })
+            }
+            _ => Ok(Self::Procedure(location.to_string())),
+        }
+    }
+
+    /// Returns `true` if the execution reached this location at the current state.
+    fn is_reached(
+        &self,
+        prev_state: &VmState,
+        prev_frames: &[String],
+        state: &VmState,
+        frames: &[String],
+    ) -> bool {
+        match self {
+            Self::Procedure(name) => {
+                // procedures entered at this state are the frames not shared with the previous
+                // stack; the fully-qualified path is known only for the innermost one
+                let shared = frames.iter().zip(prev_frames).take_while(|(a, b)| a == b).count();
+                let entered = &frames[shared..];
+                let location = state.asmop.as_ref().and_then(|asmop| asmop.location());
+                entered.iter().enumerate().any(|(i, frame)| match location {
+                    Some(location) if i == entered.len() - 1 => {
+                        frame == name || procedure_matches(name, location.procedure())
+                    }
+                    _ => procedure_matches(name, frame),
+                })
+            }
+            Self::Line { file, line } => {
+                let Some(location) = state.asmop.as_ref().and_then(|asmop| asmop.location()) else {
+                    return false;
+                };
+                if location.line() != *line || !path_matches(file, location.path()) {
+                    return false;
+                }
+
+                // instructions spanning several cycles, and instructions located at the same
+                // line, break only once
+                let prev_location = prev_state.asmop.as_ref().and_then(|asmop| asmop.location());
+                prev_location.map_or(true, |prev| {
+                    prev.line() != location.line() || prev.path() != location.path()
+                })
+            }
+        }
+    }
+}
+
+impl fmt::Display for BreakLocation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Procedure(name) => write!(f, "{name}"),
+            Self::Line { file, line } => write!(f, "{file}:{line}"),
+        }
+    }
+}
+
+// STACK CONDITION
+// ================================================================================================
+
+/// A condition on the value of a stack item, e.g., `s0==5`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct StackCondition {
+    index: usize,
+    value: u64,
+}
+
+impl StackCondition {
+    /// Returns a new condition parsed from the specified string of the form `s<index>==<value>`.
+    ///
+    /// # Errors
+    /// Returns an error if the condition is malformed.
+    pub fn parse(condition: &str) -> Result<Self, String> {
+        let malformed =
+            || format!("malformed condition - expected `s<index>==<value>`, found: `{condition}`");
+
+        let (index, value) = condition.split_once("==").ok_or_else(malformed)?;
+        let index = index.strip_prefix('s').ok_or_else(malformed)?;
+        let index = index.parse::<usize>().map_err(|_| malformed())?;
+        let value = value.parse::<u64>().map_err(|_| malformed())?;
+
+        Ok(Self { index, value })
+    }
+
+    /// Returns `true` if this condition holds at the specified state.
+    fn holds(&self, state: &VmState) -> bool {
+        state.stack.get(self.index).is_some_and(|item| item.as_int() == self.value)
+    }
+}
+
+impl fmt::Display for StackCondition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "s{}=={}", self.index, self.value)
+    }
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Returns `true` if the specified procedure name refers to the procedure with the specified name
+/// or fully-qualified path, i.e., if one of them is a suffix of the other made of whole path
+/// components.
+fn procedure_matches(name: &str, procedure: &str) -> bool {
+    procedure == name
+        || procedure.strip_suffix(name).is_some_and(|prefix| prefix.ends_with("::"))
+        || name.strip_suffix(procedure).is_some_and(|prefix| prefix.ends_with("::"))
+}
+
+/// Returns `true` if the specified file name refers to the source file at the specified path.
+fn path_matches(file: &str, path: &str) -> bool {
+    path == file || path.strip_suffix(file).is_some_and(|prefix| prefix.ends_with('/'))
+}
+
+/// Returns the word at the specified memory address at the specified state, or None if the
+/// address has never been accessed.
+fn read_memory(state: &VmState, address: u64) -> Option<Word> {
+    state.memory.iter().find(|(addr, _)| *addr == address).map(|(_, word)| *word)
+}
+
+// TESTS
+// ================================================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::{path_matches, procedure_matches, BreakLocation, Breakpoint, StackCondition};
+    use miden::{math::Felt, VmState, Word};
+
+    #[test]
+    fn parse_break_location() {
+        let line = |file: &str, line| BreakLocation::Line {
+            file: file.to_string(),
+            line,
+        };
+        assert_eq!(Ok(line("main.masm", 12)), BreakLocation::parse("main.masm:12"));
+        assert_eq!(Ok(line("std/math/u64.masm", 3)), BreakLocation::parse("std/math/u64.masm:3"));
+
+        // procedure paths are not mistaken for file locations
+        let procedure = |name: &str| BreakLocation::Procedure(name.to_string());
+        assert_eq!(Ok(procedure("foo")), BreakLocation::parse("foo"));
+        assert_eq!(Ok(procedure("foo::bar")), BreakLocation::parse("foo::bar"));
+        assert_eq!(
+            Ok(procedure("std::math::u64::add")),
+            BreakLocation::parse("std::math::u64::add")
+        );
+
+        // line numbers must be valid
+        assert!(BreakLocation::parse("main.masm:").is_err());
+        assert!(BreakLocation::parse("main.masm:x").is_err());
+        assert!(BreakLocation::parse("main.masm:-1").is_err());
+    }
+
+    #[test]
+    fn parse_stack_condition() {
+        let condition = StackCondition::parse("s3==18446744069414584320").unwrap();
+        assert_eq!(
+            StackCondition {
+                index: 3,
+                value: 18446744069414584320
+            },
+            condition
+        );
+        assert_eq!("s3==18446744069414584320", condition.to_string());
+
+        // malformed conditions are rejected
+        for condition in ["", "s0", "s0=5", "s0==", "0==5", "x0==5", "s==5", "s0==-1", "s0==5==6"] {
+            assert!(StackCondition::parse(condition).is_err(), "accepted `{condition}`");
+        }
+    }
+
+    #[test]
+    fn match_procedures_and_paths() {
+        assert!(procedure_matches("foo", "foo"));
+        assert!(procedure_matches("bar", "foo::bar"));
+        assert!(procedure_matches("foo::bar", "bar"));
+        assert!(procedure_matches("u64::add", "std::math::u64::add"));
+        assert!(!procedure_matches("ar", "foo::bar"));
+        assert!(!procedure_matches("math::u64", "std::math::u64::add"));
+
+        assert!(path_matches("main.masm", "main.masm"));
+        assert!(path_matches("u64.masm", "std/math/u64.masm"));
+        assert!(path_matches("math/u64.masm", "std/math/u64.masm"));
+        assert!(!path_matches("64.masm", "std/math/u64.masm"));
+        assert!(!path_matches("std/math/u64.masm", "u64.masm"));
+    }
+
+    #[test]
+    fn watch_ignores_context_switches() {
+        let state = |ctx, memory: Vec<(u64, Word)>| VmState {
+            clk: 0,
+            ctx,
+            op: None,
+            asmop: None,
+            fmp: Felt::new(0),
+            stack: Vec::new(),
+            memory,
+        };
+        let word = [Felt::new(1); 4];
+        let watch = Breakpoint::Watch(8);
+
+        // entering and leaving a context does not change the memory of any context
+        assert!(!watch.is_hit(&state(0, vec![(8, word)]), &[], &state(1, Vec::new()), &[]));
+        assert!(!watch.is_hit(&state(1, Vec::new()), &[], &state(0, vec![(8, word)]), &[]));
+
+        // changes within a context are detected
+        assert!(watch.is_hit(&state(1, Vec::new()), &[], &state(1, vec![(8, word)]), &[]));
+        assert!(!watch.is_hit(&state(0, vec![(8, word)]), &[], &state(0, vec![(8, word)]), &[]));
+    }
+}
@@ -1,8 +1,12 @@
+use super::breakpoint::{BreakLocation, Breakpoint, StackCondition};
+
 /// debug commands supported by the debugger
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum DebugCommand {
     Continue,
     Next(usize),
+    Over,
+    Finish,
     Rewind,
     Back(usize),
     PrintState,
@@ -11,6 +15,10 @@ pub enum DebugCommand {
     PrintMem,
     PrintMemAddress(u64),
     Clock,
+    Backtrace,
+    Break(Breakpoint),
+    Breakpoints,
+    Delete(usize),
     Quit,
     Help,
 }
@@ -35,10 +43,17 @@ impl DebugCommand {
         let command = match identifier {
             "n" | "next" => Self::parse_next(tokens.by_ref())?,
             "c" | "continue" => Self::Continue,
+            "o" | "over" => Self::Over,
+            "f" | "finish" => Self::Finish,
             "b" | "back" => Self::parse_back(tokens.by_ref())?,
             "r" | "rewind" => Self::Rewind,
             "p" | "print" => Self::parse_print(tokens.by_ref())?,
             "l" | "clock" => Self::Clock,
+            "bt" | "backtrace" => Self::Backtrace,
+            "br" | "break" => Self::parse_break(tokens.by_ref())?,
+            "w" | "watch" => Self::parse_watch(tokens.by_ref())?,
+            "bl" | "breakpoints" => Self::Breakpoints,
+            "d" | "delete" => Self::parse_delete(tokens.by_ref())?,
             "h" | "?" | "help" => Self::Help,
             "q" | "quit" => Self::Quit,
             _ => {
@@ -121,11 +136,157 @@ impl DebugCommand {
                 format!("malformed command - failed to parse print argument: {err}")
             })?;
 
-        match (command, argument) {
+        match (&command, argument) {
             (Self::PrintMem, Some(arg)) => Ok(Self::PrintMemAddress(arg)),
             (Self::PrintStack, Some(arg)) => Ok(Self::PrintStackItem(arg as usize)),
             (_, Some(_)) => unreachable!("the command was previously parsed within this block"),
             (_, None) => Ok(command),
         }
     }
+
+    /// parse break command - break <location> [if <condition>] | break if <condition>
+    fn parse_break<'a, I>(mut tokens: I) -> Result<Self, String>
+    where
+        I: Iterator<Item = &'a str>,
+    {
+        let location = match tokens.next() {
+            Some("if") => None,
+            Some(location) => {
+                let location = BreakLocation::parse(location)
+                    .map_err(|err| format!("malformed `break` command - {err}"))?;
+                match tokens.next() {
+                    Some("if") => Some(location),
+                    Some(t) => {
+                        return Err(format!("malformed `break` command - unexpected token `{t}`"))
+                    }
+                    None => return Ok(Self::Break(Breakpoint::Location(location, None))),
+                }
+            }
+            None => {
+                return Err(
+                    "malformed `break` command - expected a location or a condition".to_string()
+                )
+            }
+        };
+
+        // the condition may contain whitespaces, thus all remaining tokens are consumed
+        let condition = tokens.collect::<String>();
+        let condition = StackCondition::parse(&condition)
+            .map_err(|err| format!("malformed `break` command - {err}"))?;
+
+        match location {
+            Some(location) => Ok(Self::Break(Breakpoint::Location(location, Some(condition)))),
+            None => Ok(Self::Break(Breakpoint::Condition(condition))),
+        }
+    }
+
+    /// parse watch command - watch mem <addr>
+    fn parse_watch<'a, I>(mut tokens: I) -> Result<Self, String>
+    where
+        I: Iterator<Item = &'a str>,
+    {
+        match tokens.next() {
+            Some("m" | "mem") => (),
+            Some(t) => {
+                return Err(format!("malformed `watch` command - unexpected subcommand: `{t}`"))
+            }
+            None => return Err("malformed `watch` command - expected `mem <addr>`".to_string()),
+        }
+
+        let address = match tokens.next() {
+            Some(addr) => addr.parse::<u64>().map_err(|err| {
+                format!("malformed `watch` command - failed to parse address: `{addr}` {err}")
+            })?,
+            None => return Err("malformed `watch` command - expected an address".to_string()),
+        };
+        Ok(Self::Break(Breakpoint::Watch(address)))
+    }
+
+    /// parse delete command - delete <id>
+    fn parse_delete<'a, I>(mut tokens: I) -> Result<Self, String>
+    where
+        I: Iterator<Item = &'a str>,
+    {
+        let id = match tokens.next() {
+            Some(id) => id.parse::<usize>().map_err(|err| {
+                format!("malformed `delete` command - failed to parse breakpoint id: `{id}` {err}")
+            })?,
+            None => return Err("malformed `delete` command - expected a breakpoint id".to_string()),
+        };
+        Ok(Self::Delete(id))
+    }
+}
+
+// TESTS
+// ================================================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::{BreakLocation, Breakpoint, DebugCommand, StackCondition};
+
+    #[test]
+    fn parse_break_command() {
+        let location = |location| BreakLocation::parse(location).unwrap();
+        let condition = |condition| Some(StackCondition::parse(condition).unwrap());
+        let parse = |command| match DebugCommand::parse(command) {
+            Ok(Some(DebugCommand::Break(breakpoint))) => Ok(breakpoint),
+            Ok(command) => panic!("unexpected command {command:?}"),
+            Err(err) => Err(err),
+        };
+
+        assert_eq!(Ok(Breakpoint::Location(location("foo::bar"), None)), parse("break foo::bar"));
+        assert_eq!(
+            Ok(Breakpoint::Location(location("main.masm:12"), None)),
+            parse("br main.masm:12")
+        );
+        assert_eq!(
+            Ok(Breakpoint::Location(location("foo"), condition("s0==5"))),
+            parse("break foo if s0==5")
+        );
+        assert_eq!(
+            Ok(Breakpoint::Condition(StackCondition::parse("s0==5").unwrap())),
+            parse("break if s0==5")
+        );
+
+        // conditions may contain whitespaces
+        assert_eq!(
+            Ok(Breakpoint::Location(location("main.masm:12"), condition("s1==2"))),
+            parse("break main.masm:12 if s1 == 2")
+        );
+
+        // malformed commands are rejected
+        for command in [
+            "break",
+            "break if",
+            "break foo if",
+            "break foo s0==5",
+            "break foo if s0=5",
+            "break foo if x0==5",
+            "break main.masm:x",
+            "break main.masm:x if s0==5",
+        ] {
+            assert!(parse(command).is_err(), "accepted `{command}`");
+        }
+    }
+
+    #[test]
+    fn parse_watch_command() {
+        let expected = Ok(Some(DebugCommand::Break(Breakpoint::Watch(8))));
+        assert_eq!(expected, DebugCommand::parse("watch mem 8"));
+        assert_eq!(expected, DebugCommand::parse("w m 8"));
+
+        for command in ["watch", "watch 8", "watch mem", "watch mem x", "watch mem 8 9"] {
+            assert!(DebugCommand::parse(command).is_err(), "accepted `{command}`");
+        }
+    }
+
+    #[test]
+    fn parse_delete_command() {
+        assert_eq!(Ok(Some(DebugCommand::Delete(2))), DebugCommand::parse("delete 2"));
+        assert_eq!(Ok(Some(DebugCommand::Delete(1))), DebugCommand::parse("d 1"));
+
+        for command in ["delete", "delete x", "delete -1", "delete 1 2"] {
+            assert!(DebugCommand::parse(command).is_err(), "accepted `{command}`");
+        }
+    }
 }
@@ -1,13 +1,22 @@
-use super::DebugCommand;
+use super::{breakpoint::Breakpoint, DebugCommand};
 use miden::{
     math::{Felt, StarkField},
     DefaultHost, MemAdviceProvider, Program, StackInputs, VmState, VmStateIterator,
 };
+use processor::CallStack;
+use std::{collections::BTreeMap, fs, rc::Rc};
 
 /// Holds debugger state and iterator used for debugging.
 pub struct DebugExecutor {
     vm_state_iter: VmStateIterator,
     vm_state: VmState,
+    /// Stack of procedures at the latest clock cycle executed so far.
+    call_stack: CallStack,
+    /// Procedure frames at each clock cycle executed so far, indexed by clock cycle.
+    frames: Vec<Rc<[String]>>,
+    /// Breakpoints set by the user, indexed by their ids.
+    breakpoints: BTreeMap<usize, Breakpoint>,
+    next_breakpoint_id: usize,
 }
 
 impl DebugExecutor {
@@ -31,9 +40,17 @@ impl DebugExecutor {
             ))?
             .expect("initial state of vm must be healthy!");
 
+        let mut call_stack = CallStack::default();
+        call_stack.update(&vm_state);
+        let frames = vec![call_stack.frames().into()];
+
         Ok(Self {
             vm_state_iter,
             vm_state,
+            call_stack,
+            frames,
+            breakpoints: BTreeMap::new(),
+            next_breakpoint_id: 1,
         })
     }
 
@@ -44,28 +61,35 @@ impl DebugExecutor {
     pub fn execute(&mut self, command: DebugCommand) -> bool {
         match command {
             DebugCommand::Continue => {
-                while let Some(new_vm_state) = self.next_vm_state() {
-                    self.vm_state = new_vm_state;
-                    if self.should_break() {
-                        break;
-                    }
-                }
+                self.run_until(|_| false);
                 self.print_vm_state();
             }
             DebugCommand::Next(cycles) => {
-                for _cycle in 0..cycles {
-                    match self.next_vm_state() {
-                        Some(next_vm_state) => {
-                            self.vm_state = next_vm_state;
-                            if self.should_break() {
-                                break;
-                            }
-                        }
-                        None => break,
-                    }
+                let mut remaining = cycles;
+                if remaining > 0 {
+                    self.run_until(|_| {
+                        remaining -= 1;
+                        remaining == 0
+                    });
                 }
                 self.print_vm_state();
             }
+            DebugCommand::Over => {
+                // execute the current instruction together with the procedures it invokes
+                let depth = self.current_frames().len();
+                self.run_until(|executor| {
+                    executor.at_instruction_start() && executor.current_frames().len() <= depth
+                });
+                self.print_vm_state();
+            }
+            DebugCommand::Finish => {
+                // execute until the current procedure returns to its caller
+                let depth = self.current_frames().len();
+                self.run_until(|executor| {
+                    executor.at_instruction_start() && executor.current_frames().len() < depth
+                });
+                self.print_vm_state();
+            }
             DebugCommand::Rewind => {
                 while let Some(new_vm_state) = self.vm_state_iter.back() {
                     self.vm_state = new_vm_state;
@@ -92,17 +116,46 @@ impl DebugExecutor {
             DebugCommand::PrintMem => self.print_memory(),
             DebugCommand::PrintMemAddress(address) => self.print_memory_entry(address),
             DebugCommand::Clock => println!("{}", self.vm_state.clk),
+            DebugCommand::Backtrace => self.print_backtrace(),
+            DebugCommand::Break(breakpoint) => {
+                let id = self.next_breakpoint_id;
+                self.next_breakpoint_id += 1;
+                println!("Breakpoint {id}: {breakpoint}");
+                self.breakpoints.insert(id, breakpoint);
+            }
+            DebugCommand::Breakpoints => self.print_breakpoints(),
+            DebugCommand::Delete(id) => match self.breakpoints.remove(&id) {
+                Some(breakpoint) => println!("Deleted breakpoint {id}: {breakpoint}"),
+                None => println!("breakpoint '{id}' not found"),
+            },
             DebugCommand::Help => Self::print_help(),
             DebugCommand::Quit => return false,
         }
         true
     }
 
+    /// Executes clock cycles until the provided predicate holds for the executor, a breakpoint
+    /// is hit, or the execution ends.
+    fn run_until<F>(&mut self, mut stop: F)
+    where
+        F: FnMut(&Self) -> bool,
+    {
+        while let Some(new_vm_state) = self.next_vm_state() {
+            let prev_vm_state = std::mem::replace(&mut self.vm_state, new_vm_state);
+            if self.should_break() || self.hit_breakpoint(&prev_vm_state) || stop(self) {
+                break;
+            }
+        }
+    }
+
     /// iterates to the next clock cycle.
     fn next_vm_state(&mut self) -> Option<VmState> {
         match self.vm_state_iter.next() {
             Some(next_vm_state_result) => match next_vm_state_result {
-                Ok(vm_state) => Some(vm_state),
+                Ok(vm_state) => {
+                    self.record_frames(&vm_state);
+                    Some(vm_state)
+                }
                 Err(err) => {
                     println!("Execution error: {err:?}");
                     None
@@ -115,12 +168,35 @@ impl DebugExecutor {
         }
     }
 
+    /// Records the procedure frames at the specified state, unless the state was already
+    /// executed before rewinding the execution.
+    fn record_frames(&mut self, vm_state: &VmState) {
+        if vm_state.clk as usize != self.frames.len() {
+            return;
+        }
+
+        self.call_stack.update(vm_state);
+        let frames = match self.frames.last() {
+            Some(frames) if **frames == *self.call_stack.frames() => frames.clone(),
+            _ => self.call_stack.frames().into(),
+        };
+        self.frames.push(frames);
+    }
+
     // ACCESSORS
     // --------------------------------------------------------------------------------------------
 
     /// print general VM state information.
     fn print_vm_state(&self) {
-        println!("{}", self.vm_state)
+        println!("{}", self.vm_state);
+
+        // print the source code of the current instruction, if it is available
+        if let Some(location) = self.vm_state.asmop.as_ref().and_then(|asmop| asmop.location()) {
+            let excerpt = fs::read_to_string(location.path())
+                .ok()
+                .and_then(|source| location.excerpt(&source));
+            println!("{}", excerpt.unwrap_or_else(|| format!("--> {location}")));
+        }
     }
 
     /// print all stack items.
@@ -168,9 +244,48 @@ impl DebugExecutor {
         }
     }
 
+    /// print the stack of procedures being executed, starting from the innermost one.
+    pub fn print_backtrace(&self) {
+        let frames = self.current_frames();
+        if frames.is_empty() {
+            println!("no procedure is being executed");
+            return;
+        }
+
+        // the location is known only for the instruction being executed by the innermost
+        // procedure
+        let location = self.vm_state.asmop.as_ref().and_then(|asmop| asmop.location());
+        for (i, frame) in frames.iter().rev().enumerate() {
+            match location {
+                Some(location) if i == 0 => println!("#{i} {} at {location}", location.procedure()),
+                _ => println!("#{i} {frame}"),
+            }
+        }
+    }
+
+    /// print all breakpoints.
+    pub fn print_breakpoints(&self) {
+        if self.breakpoints.is_empty() {
+            println!("no breakpoints");
+        }
+        for (id, breakpoint) in self.breakpoints.iter() {
+            println!("{id} {breakpoint}");
+        }
+    }
+
     // HELPERS
     // --------------------------------------------------------------------------------------------
 
+    /// Returns the procedure frames at the current clock cycle.
+    fn current_frames(&self) -> &[String] {
+        &self.frames[self.vm_state.clk as usize]
+    }
+
+    /// Returns `true` if the current state executes the first operation of an instruction.
+    fn at_instruction_start(&self) -> bool {
+        self.vm_state.asmop.as_ref().is_some_and(|asmop| asmop.cycle_idx() == 1)
+    }
+
     /// print memory data.
     fn print_memory_data(address: &u64, memory: &[Felt]) {
         let mem_int = memory.iter().map(|&x| x.as_int()).collect::<Vec<_>>();
@@ -184,7 +299,9 @@ impl DebugExecutor {
             ---------------------------------------------------------------------\n\
             next               moves to the next clock cycle\n\
             next <c>           moves `c` clock cycles forward\n\
-            continue           executes program until completion or failure\n\
+            over               executes the current instruction and the procedures it invokes\n\
+            finish             executes until the current procedure returns\n\
+            continue           executes program until completion, failure, or a breakpoint\n\
             back               rewinds `1` clock cycles\n\
             back <c>           rewinds `c` clock cycles\n\
             rewind             rewinds program until beginning\n\
@@ -194,11 +311,21 @@ impl DebugExecutor {
             print stack        displays the complete state of the stack\n\
             print stack <i>    displays the stack element at index `i`\n\
             clock              displays the current clock cycle\n\
+            backtrace          displays the stack of procedures being executed\n\
+            break <p>          breaks when procedure `p` (e.g. `foo` or `u64::add`) is entered\n\
+            break <f>:<l>      breaks when line `l` of file `f` is reached\n\
+            break <b> if <c>   breaks at `b` only if condition `c` (e.g. `s0==5`) holds\n\
+            break if <c>       breaks when condition `c` starts to hold\n\
+            watch mem <a>      breaks when memory at address `a` changes\n\
+            breakpoints        displays all breakpoints\n\
+            delete <i>         deletes breakpoint `i`\n\
             quit               quits the debugger\n\
             help               displays this message\n\
             \n\
             The following mappings are also available:\n\
             n -> next\n\
+            o -> over\n\
+            f -> finish\n\
             c -> continue\n\
             b -> back\n\
             r -> rewind\n\
@@ -206,6 +333,11 @@ impl DebugExecutor {
             m -> mem\n\
             s -> stack\n\
             l -> clock\n\
+            bt -> backtrace\n\
+            br -> break\n\
+            w -> watch\n\
+            bl -> breakpoints\n\
+            d -> delete\n\
             q -> quit\n\
             h -> help\n\
             ? -> help";
@@ -217,4 +349,22 @@ impl DebugExecutor {
     fn should_break(&self) -> bool {
         self.vm_state.asmop.as_ref().map(|asm| asm.should_break()).unwrap_or(false)
     }
+
+    /// Returns `true` if a breakpoint set by the user is hit at the current state, given the
+    /// previous state; the hit breakpoint is printed.
+    fn hit_breakpoint(&self, prev_vm_state: &VmState) -> bool {
+        let prev_frames = &self.frames[prev_vm_state.clk as usize];
+        let frames = self.current_frames();
+        let hit = self.breakpoints.iter().find(|(_, breakpoint)| {
+            breakpoint.is_hit(prev_vm_state, prev_frames, &self.vm_state, frames)
+        });
+
+        match hit {
+            Some((id, breakpoint)) => {
+                println!("Breakpoint {id} hit: {breakpoint}");
+                true
+            }
+            None => false,
+        }
+    }
 }
@@ -3,6 +3,8 @@ use clap::Parser;
 use rustyline::{error::ReadlineError, Config, DefaultEditor, EditMode};
 use std::path::PathBuf;
 
+mod breakpoint;
+
 mod command;
 use command::DebugCommand;
 
@@ -35,9 +37,10 @@ impl DebugCmd {
         // load libraries from files
         let libraries = Libraries::new(&self.library_paths)?;
 
-        // load program from file and compile
-        let program =
-            ProgramFile::read(&self.assembly_file)?.compile(&Debug::On, libraries.libraries)?;
+        // load program from file and compile; source locations of the instructions are tracked
+        // so that breakpoints can be set at source lines
+        let (program, _) = ProgramFile::read(&self.assembly_file, &libraries.libraries)?
+            .compile_with_source_map(&Debug::On, libraries.libraries)?;
 
         let program_hash: [u8; 32] = program.hash().into();
         println!("Debugging program with hash {}... ", hex::encode(program_hash));
@@ -0,0 +1,86 @@
+use assembly::format_source;
+use clap::Parser;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+#[derive(Debug, Clone, Parser)]
+#[clap(about = "Format miden assembly files")]
+pub struct FormatCmd {
+    /// Paths to .masm assembly files, or to directories which are searched for such files
+    #[clap(required = true, value_parser)]
+    paths: Vec<PathBuf>,
+    /// Check whether the files are formatted instead of formatting them
+    #[clap(long = "check")]
+    check: bool,
+}
+
+impl FormatCmd {
+    pub fn execute(&self) -> Result<(), String> {
+        let mut files = Vec::new();
+        for path in self.paths.iter() {
+            collect_masm_files(path, &mut files)?;
+        }
+
+        let mut unformatted = Vec::new();
+        for path in files.iter() {
+            let source = fs::read_to_string(path)
+                .map_err(|err| format!("Failed to open file `{}` - {}", path.display(), err))?;
+            let formatted = format_source(&source).map_err(|err| {
+                let diagnostic = err.diagnostic().render(&path.display().to_string(), &source);
+                format!("Failed to format file `{}`\n{}", path.display(), diagnostic)
+            })?;
+            if formatted == source {
+                continue;
+            }
+
+            if self.check {
+                println!("{} is not formatted", path.display());
+            } else {
+                fs::write(path, formatted).map_err(|err| {
+                    format!("Failed to write file `{}` - {}", path.display(), err)
+                })?;
+                println!("Formatted {}", path.display());
+            }
+            unformatted.push(path);
+        }
+
+        if self.check && !unformatted.is_empty() {
+            return Err(format!(
+                "{} of {} file(s) are not formatted",
+                unformatted.len(),
+                files.len()
+            ));
+        }
+        if unformatted.is_empty() {
+            println!("All {} file(s) are formatted", files.len());
+        }
+        Ok(())
+    }
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Adds the specified file, or all .masm files in the specified directory and its subdirectories,
+/// to the list of files. Files in a directory are added in the order of their paths.
+fn collect_masm_files(path: &Path, files: &mut Vec<PathBuf>) -> Result<(), String> {
+    if !path.is_dir() {
+        files.push(path.to_path_buf());
+        return Ok(());
+    }
+
+    let mut entries = fs::read_dir(path)
+        .and_then(|entries| entries.map(|entry| entry.map(|entry| entry.path())).collect())
+        .map_err(|err: std::io::Error| {
+            format!("Failed to read directory `{}` - {}", path.display(), err)
+        })?;
+    entries.sort();
+    for entry in entries {
+        if entry.is_dir() || entry.extension().is_some_and(|ext| ext == "masm") {
+            collect_masm_files(&entry, files)?;
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,98 @@
+use super::data::{build_assembler, Debug, Libraries, ProgramFile};
+use assembly::{LintConfig, LintFinding, LintRule, Linter};
+use clap::Parser;
+use miden::ModuleAst;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+#[derive(Debug, Clone, Parser)]
+#[clap(about = "Check a miden program or module for common bugs")]
+pub struct LintCmd {
+    /// Path to .masm assembly file
+    #[clap(short = 'a', long = "assembly", value_parser)]
+    assembly_file: PathBuf,
+    /// Paths to .masl library files
+    #[clap(short = 'l', long = "libraries", value_parser)]
+    library_paths: Vec<PathBuf>,
+    /// Treat the assembly file as a library module rather than an executable program
+    #[clap(short = 'm', long = "module")]
+    is_module: bool,
+    /// IDs of the rules to suppress (e.g., `unused-import`)
+    #[clap(long = "allow")]
+    allowed_rules: Vec<String>,
+    /// Rules to suppress within a single procedure, specified as `<rule>:<procedure>`
+    #[clap(long = "allow-in")]
+    allowed_in_procs: Vec<String>,
+}
+
+impl LintCmd {
+    pub fn execute(&self) -> Result<(), String> {
+        println!("============================================================");
+        println!("Lint {}", if self.is_module { "module" } else { "program" });
+        println!("============================================================");
+
+        let linter = Linter::new(self.build_config()?);
+
+        // load libraries from files
+        let libraries = Libraries::new(&self.library_paths)?;
+
+        // parse the source file and check it
+        let findings = if self.is_module {
+            let source = fs::read_to_string(&self.assembly_file).map_err(|err| {
+                format!("Failed to open module file `{}` - {}", self.assembly_file.display(), err)
+            })?;
+            let assembler = build_assembler(&Debug::Off, libraries.libraries)?;
+            let module = ModuleAst::parse_with_constants(&source, &assembler).map_err(|err| {
+                format!("Failed to parse module file `{}` - {}", self.assembly_file.display(), err)
+            })?;
+            linter.lint_module(&module)
+        } else {
+            let program = ProgramFile::read(&self.assembly_file, &libraries.libraries)?;
+            linter.lint_program(program.ast())
+        };
+
+        report_findings(&self.assembly_file, &findings)
+    }
+
+    /// Builds the linter config from the suppressed rules specified via command line arguments.
+    fn build_config(&self) -> Result<LintConfig, String> {
+        let mut config = LintConfig::new();
+        for rule_id in self.allowed_rules.iter() {
+            config = config.suppress(parse_rule(rule_id)?);
+        }
+        for entry in self.allowed_in_procs.iter() {
+            let (rule_id, proc_name) = entry.split_once(':').ok_or_else(|| {
+                format!("Invalid suppression `{entry}` - expected `<rule>:<procedure>`")
+            })?;
+            config = config.suppress_in(parse_rule(rule_id)?, proc_name);
+        }
+        Ok(config)
+    }
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Returns the lint rule with the specified ID.
+fn parse_rule(rule_id: &str) -> Result<LintRule, String> {
+    LintRule::from_id(rule_id).ok_or_else(|| {
+        let known_ids: Vec<_> = LintRule::ALL.iter().map(|rule| rule.id()).collect();
+        format!("Unknown lint rule `{rule_id}` - expected one of: {}", known_ids.join(", "))
+    })
+}
+
+/// Prints the findings and returns an error if there are any.
+fn report_findings(path: &Path, findings: &[LintFinding]) -> Result<(), String> {
+    for finding in findings {
+        println!("{}:{}", path.display(), finding);
+    }
+
+    if findings.is_empty() {
+        println!("No issues found");
+        Ok(())
+    } else {
+        Err(format!("Found {} issue(s) in `{}`", findings.len(), path.display()))
+    }
+}
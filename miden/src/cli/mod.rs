@@ -2,6 +2,8 @@ mod bundle;
 mod compile;
 mod data;
 mod debug;
+mod fmt;
+mod lint;
 mod prove;
 mod repl;
 mod run;
@@ -11,6 +13,8 @@ pub use bundle::BundleCmd;
 pub use compile::CompileCmd;
 pub use data::InputFile;
 pub use debug::DebugCmd;
+pub use fmt::FormatCmd;
+pub use lint::LintCmd;
 pub use prove::ProveCmd;
 pub use repl::ReplCmd;
 pub use run::RunCmd;
@@ -1,6 +1,6 @@
 use super::data::{Debug, InputFile, Libraries, OutputFile, ProgramFile, ProofFile};
 use clap::Parser;
-use miden::ProvingOptions;
+use miden::{ProofEncoding, ProofEnvelope, ProvingOptions};
 use processor::{DefaultHost, ExecutionOptions, ExecutionOptionsError};
 use std::{io::Write, path::PathBuf, time::Instant};
 
@@ -40,6 +40,10 @@ pub struct ProveCmd {
     #[clap(short = 'p', long = "proof", value_parser)]
     proof_file: Option<PathBuf>,
 
+    /// Encoding of the proof file: binary, hex or base64
+    #[clap(short = 'f', long = "proof-format", default_value = "binary")]
+    proof_format: String,
+
     /// Enable generation of proofs suitable for recursive verification
     #[clap(short = 'r', long = "recursive")]
     recursive: bool,
@@ -75,8 +79,8 @@ impl ProveCmd {
         let libraries = Libraries::new(&self.library_paths)?;
 
         // load program from file and compile
-        let program =
-            ProgramFile::read(&self.assembly_file)?.compile(&Debug::Off, libraries.libraries)?;
+        let program = ProgramFile::read(&self.assembly_file, &libraries.libraries)?
+            .compile(&Debug::Off, libraries.libraries)?;
 
         // load input data from file
         let input_data = InputFile::read(&self.input_file, &self.assembly_file)?;
@@ -90,10 +94,11 @@ impl ProveCmd {
         let host = DefaultHost::new(input_data.parse_advice_provider()?);
 
         let proving_options = self.get_proof_options().map_err(|err| format!("{err}"))?;
+        let proof_encoding: ProofEncoding = self.proof_format.parse()?;
 
         // execute program and generate proof
         let (stack_outputs, proof) =
-            prover::prove(&program, stack_inputs, host, proving_options)
+            prover::prove(&program, stack_inputs.clone(), host, proving_options)
                 .map_err(|err| format!("Failed to prove program - {:?}", err))?;
 
         println!(
@@ -102,8 +107,10 @@ impl ProveCmd {
             now.elapsed().as_millis()
         );
 
-        // write proof to file
-        ProofFile::write(proof, &self.proof_file, &self.assembly_file)?;
+        // write proof to file together with the program hash and the public inputs and outputs
+        let envelope =
+            ProofEnvelope::new(program.hash(), stack_inputs, stack_outputs.clone(), proof);
+        ProofFile::write(&envelope, proof_encoding, &self.proof_file, &self.assembly_file)?;
 
         // provide outputs
         if let Some(output_path) = &self.output_file {
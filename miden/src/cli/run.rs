@@ -1,7 +1,8 @@
-use super::data::{Debug, InputFile, Libraries, OutputFile, ProgramFile};
+use super::data::{AdviceSnapshotFile, Debug, InputFile, Libraries, OutputFile, ProgramFile};
 use clap::Parser;
-use processor::{DefaultHost, ExecutionOptions};
-use std::{path::PathBuf, time::Instant};
+use miden::{ExecutionError, Program, SourceMap, StackInputs};
+use processor::{AdviceProvider, DefaultHost, ExecutionOptions, MemAdviceProvider};
+use std::{fs, path::PathBuf, time::Instant};
 
 #[derive(Debug, Clone, Parser)]
 #[clap(about = "Run a miden program")]
@@ -33,6 +34,15 @@ pub struct RunCmd {
     /// Path to output file
     #[clap(short = 'o', long = "output", value_parser)]
     output_file: Option<PathBuf>,
+
+    /// Path to an advice snapshot file used as advice inputs instead of the advice data of the
+    /// input file
+    #[clap(long = "advice-in", value_parser)]
+    advice_snapshot_in: Option<PathBuf>,
+
+    /// Path to which the state of the advice provider is written after execution
+    #[clap(long = "advice-out", value_parser)]
+    advice_snapshot_out: Option<PathBuf>,
 }
 
 impl RunCmd {
@@ -44,9 +54,10 @@ impl RunCmd {
         // load libraries from files
         let libraries = Libraries::new(&self.library_paths)?;
 
-        // load program from file and compile
-        let program =
-            ProgramFile::read(&self.assembly_file)?.compile(&Debug::Off, libraries.libraries)?;
+        // load program from file and compile; the source map is used to report the location of
+        // the failing instruction if execution fails
+        let (program, source_map) = ProgramFile::read(&self.assembly_file, &libraries.libraries)?
+            .compile_with_source_map(&Debug::Off, libraries.libraries)?;
 
         // load input data from file
         let input_data = InputFile::read(&self.input_file, &self.assembly_file)?;
@@ -57,18 +68,40 @@ impl RunCmd {
 
         // fetch the stack and program inputs from the arguments
         let stack_inputs = input_data.parse_stack_inputs()?;
-        let host = DefaultHost::new(input_data.parse_advice_provider()?);
+        let advice_provider = match &self.advice_snapshot_in {
+            Some(path) => MemAdviceProvider::from(AdviceSnapshotFile::read(path)?),
+            None => input_data.parse_advice_provider()?,
+        };
+        let mut host = DefaultHost::new(advice_provider.clone());
 
         let program_hash: [u8; 32] = program.hash().into();
         print!("Executing program with hash {}... ", hex::encode(program_hash));
         let now = Instant::now();
 
         // execute program and generate outputs
-        let trace = processor::execute(&program, stack_inputs, host, execution_options)
-            .map_err(|err| format!("Failed to generate execution trace = {:?}", err))?;
+        let trace =
+            processor::execute(&program, stack_inputs.clone(), &mut host, execution_options)
+                .map_err(|err| {
+                    let location =
+                        locate_failure(&err, &program, &source_map, stack_inputs, advice_provider);
+                    format!(
+                        "Failed to generate execution trace = {:?}{}",
+                        err,
+                        location.map(|location| format!("\n{location}")).unwrap_or_default()
+                    )
+                })?;
 
         println!("done ({} ms)", now.elapsed().as_millis());
 
+        // write the state of the advice provider to file if one was specified
+        if let Some(snapshot_path) = &self.advice_snapshot_out {
+            let snapshot = host
+                .advice_provider()
+                .snapshot()
+                .ok_or("The advice provider does not support snapshots")?;
+            AdviceSnapshotFile::write(&snapshot, snapshot_path)?;
+        }
+
         if let Some(output_path) = &self.output_file {
             // write outputs to file if one was specified
             OutputFile::write(trace.stack_outputs(), output_path)?;
@@ -107,3 +140,38 @@ impl RunCmd {
         Ok(())
     }
 }
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Returns the source location of the instruction at which the execution failed with the
+/// provided error, together with an excerpt of its source code if the source file is available.
+///
+/// The location is determined by re-executing the program in debug mode to find the operation
+/// executed at the clock cycle of the failure; None is returned if the error does not carry a
+/// clock cycle or if the location of the operation is not present in the source map.
+fn locate_failure(
+    err: &ExecutionError,
+    program: &Program,
+    source_map: &SourceMap,
+    stack_inputs: StackInputs,
+    advice_provider: MemAdviceProvider,
+) -> Option<String> {
+    let clk = match err {
+        ExecutionError::AdviceStackReadFailed(clk)
+        | ExecutionError::DivideByZero(clk)
+        | ExecutionError::FailedAssertion(clk, _)
+        | ExecutionError::HostRequestFailed(clk, _) => *clk,
+        _ => return None,
+    };
+
+    let vm_states =
+        processor::execute_iter(program, stack_inputs, DefaultHost::new(advice_provider));
+    let (block_hash, op_idx) = vm_states.span_op_at(clk)?;
+    let location = source_map.get(block_hash, op_idx)?;
+
+    let excerpt = fs::read_to_string(location.path())
+        .ok()
+        .and_then(|source| location.excerpt(&source));
+    Some(excerpt.unwrap_or_else(|| location.to_string()))
+}
@@ -12,6 +12,14 @@ pub struct VerifyCmd {
     /// Path to output file
     #[clap(short = 'o', long = "output", value_parser)]
     output_file: Option<PathBuf>,
+    /// Verify against the inputs and outputs recorded in the proof file by the prover instead of
+    /// reading them from the input and output files; the inputs and outputs are not checked
+    /// against any values known to the verifier
+    #[clap(
+        long = "use-envelope-io",
+        conflicts_with_all = ["input_file", "output_file"]
+    )]
+    use_envelope_io: bool,
     /// Path to proof file
     #[clap(short = 'p', long = "proof", value_parser)]
     proof_file: PathBuf,
@@ -29,17 +37,21 @@ impl VerifyCmd {
         // read program hash from input
         let program_hash = ProgramHash::read(&self.program_hash)?;
 
-        // load input data from file
-        let input_data = InputFile::read(&self.input_file, &self.proof_file)?;
-
-        // fetch the stack inputs from the arguments
-        let stack_inputs = input_data.parse_stack_inputs()?;
-
-        // load outputs data from file
-        let outputs_data = OutputFile::read(&self.output_file, &self.proof_file)?;
-
         // load proof from file
-        let proof = ProofFile::read(&Some(self.proof_file.clone()), &self.proof_file)?;
+        let envelope = ProofFile::read(&Some(self.proof_file.clone()), &self.proof_file)?;
+        if envelope.program_hash() != program_hash {
+            return Err("Proof file was generated for a different program".to_string());
+        }
+
+        // fetch the stack inputs and outputs from the proof file if requested, or from the input
+        // and output files otherwise
+        let (stack_inputs, stack_outputs) = if self.use_envelope_io {
+            (envelope.stack_inputs().clone(), envelope.stack_outputs().clone())
+        } else {
+            let input_data = InputFile::read(&self.input_file, &self.proof_file)?;
+            let outputs_data = OutputFile::read(&self.output_file, &self.proof_file)?;
+            (input_data.parse_stack_inputs()?, outputs_data.stack_outputs()?)
+        };
 
         println!("verifying program...");
         let now = Instant::now();
@@ -49,7 +61,7 @@ impl VerifyCmd {
         let program_info = ProgramInfo::new(program_hash, kernel);
 
         // verify proof
-        verifier::verify(program_info, stack_inputs, outputs_data.stack_outputs()?, proof)
+        verifier::verify(program_info, stack_inputs, stack_outputs, envelope.proof().clone())
             .map_err(|err| format!("Program failed verification! - {}", err))?;
 
         println!("Verification complete in {} ms", now.elapsed().as_millis());
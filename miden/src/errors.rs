@@ -0,0 +1,192 @@
+use super::{AssemblyError, ExecutionError, InputError, ParsingError, VerificationError};
+use crate::utils::string::{String, ToString};
+use assembly::ast::SourceLocation;
+use core::fmt;
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::JsValue;
+
+// VM ERROR KIND
+// ================================================================================================
+
+/// Describes the stage at which a request made through the wasm API failed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum VmErrorKind {
+    /// The source code could not be parsed into an AST.
+    Parsing,
+    /// The AST could not be compiled into a program MAST.
+    Assembly,
+    /// The provided stack or advice inputs were malformed.
+    Input,
+    /// The program failed while being executed by the processor.
+    Execution,
+    /// The program was executed, but STARK proof generation failed.
+    Prover,
+    /// The proof does not prove a correct execution of the program.
+    Verification,
+    /// A value could not be serialized or deserialized.
+    Serialization,
+}
+
+impl fmt::Display for VmErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use VmErrorKind::*;
+        match self {
+            Parsing => write!(f, "parsing error"),
+            Assembly => write!(f, "assembly error"),
+            Input => write!(f, "input error"),
+            Execution => write!(f, "execution error"),
+            Prover => write!(f, "prover error"),
+            Verification => write!(f, "verification error"),
+            Serialization => write!(f, "serialization error"),
+        }
+    }
+}
+
+// ERROR LOCATION
+// ================================================================================================
+
+/// Line and column in the source code at which a parsing error was detected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ErrorLocation {
+    pub line: u32,
+    pub column: u32,
+}
+
+impl From<&SourceLocation> for ErrorLocation {
+    fn from(location: &SourceLocation) -> Self {
+        Self {
+            line: location.line(),
+            column: location.column(),
+        }
+    }
+}
+
+// VM ERROR
+// ================================================================================================
+
+/// A structured error returned by the wasm API.
+///
+/// The error is serialized as a JSON object so that JavaScript callers can inspect the kind of
+/// failure, and, when available, the source location or the clock cycle at which it occurred.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VmError {
+    /// The stage at which the failure occurred.
+    pub kind: VmErrorKind,
+    /// A human-readable description of the failure.
+    pub message: String,
+    /// Source location of the failure; only set for parsing errors.
+    pub location: Option<ErrorLocation>,
+    /// Clock cycle at which execution failed; only set for execution errors which track it.
+    pub clk: Option<u32>,
+}
+
+impl VmError {
+    // CONSTRUCTORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns a new error of the specified kind without location or clock cycle information.
+    pub fn new(kind: VmErrorKind, message: impl ToString) -> Self {
+        Self {
+            kind,
+            message: message.to_string(),
+            location: None,
+            clk: None,
+        }
+    }
+
+    pub fn input(message: impl ToString) -> Self {
+        Self::new(VmErrorKind::Input, message)
+    }
+
+    pub fn serialization(message: impl ToString) -> Self {
+        Self::new(VmErrorKind::Serialization, message)
+    }
+
+    // PUBLIC ACCESSORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the JSON representation of this error.
+    pub fn to_json(&self) -> String {
+        // serializing a struct of strings and integers cannot fail
+        serde_json::to_string(self).expect("failed to serialize error")
+    }
+}
+
+impl fmt::Display for VmError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (&self.location, self.clk) {
+            (Some(location), _) => write!(
+                f,
+                "{} at [{}:{}]: {}",
+                self.kind, location.line, location.column, self.message
+            ),
+            (None, Some(clk)) => {
+                write!(f, "{} at clock cycle {}: {}", self.kind, clk, self.message)
+            }
+            (None, None) => write!(f, "{}: {}", self.kind, self.message),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for VmError {}
+
+impl From<ParsingError> for VmError {
+    fn from(err: ParsingError) -> Self {
+        Self {
+            kind: VmErrorKind::Parsing,
+            message: err.message().clone(),
+            location: Some(err.location().into()),
+            clk: None,
+        }
+    }
+}
+
+impl From<AssemblyError> for VmError {
+    fn from(err: AssemblyError) -> Self {
+        Self::new(VmErrorKind::Assembly, err)
+    }
+}
+
+impl From<InputError> for VmError {
+    fn from(err: InputError) -> Self {
+        Self::new(VmErrorKind::Input, err)
+    }
+}
+
+impl From<ExecutionError> for VmError {
+    fn from(err: ExecutionError) -> Self {
+        use ExecutionError::*;
+        let (kind, clk) = match &err {
+            ProverError(_) => (VmErrorKind::Prover, None),
+            AdviceStackReadFailed(clk)
+            | DivideByZero(clk)
+            | FailedAssertion(clk, _)
+            | HostRequestFailed(clk, _) => (VmErrorKind::Execution, Some(*clk)),
+            _ => (VmErrorKind::Execution, None),
+        };
+        Self {
+            kind,
+            message: err.to_string(),
+            location: None,
+            clk,
+        }
+    }
+}
+
+impl From<VerificationError> for VmError {
+    fn from(err: VerificationError) -> Self {
+        use VerificationError::*;
+        match err {
+            InputNotFieldElement(_) | OutputNotFieldElement(_) => Self::input(err),
+            VerifierError(_) => Self::new(VmErrorKind::Verification, err),
+        }
+    }
+}
+
+impl From<VmError> for JsValue {
+    fn from(err: VmError) -> Self {
+        JsValue::from_str(&err.to_json())
+    }
+}
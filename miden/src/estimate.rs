@@ -0,0 +1,155 @@
+use super::{HashFunction, ProvingOptions, StackOutputsString};
+use air::trace::{AUX_TRACE_WIDTH, TRACE_WIDTH};
+use processor::TraceLenSummary;
+use serde::{Deserialize, Serialize};
+
+// CONSTANTS
+// ================================================================================================
+
+/// Number of columns of the constraint composition polynomial; this is equal to the blowup factor
+/// required by the highest-degree transition constraint of the VM (degree 9 → blowup of 8).
+const NUM_COMPOSITION_COLUMNS: usize = 8;
+
+/// Number of trace commitments (main trace, auxiliary trace and constraint composition) built by
+/// the prover.
+const NUM_COMMITMENTS: u64 = 3;
+
+/// Size in bytes of a base field element.
+const ELEMENT_SIZE: u64 = 8;
+
+/// Size in bytes of a node in the Merkle trees built over the LDE domain.
+const DIGEST_SIZE: u64 = 32;
+
+/// Approximate time (in nanoseconds) spent by a single thread on every cell of the extended
+/// execution trace when BLAKE3 is used for commitments.
+const BLAKE3_NS_PER_CELL: u64 = 60;
+
+/// Approximate time (in nanoseconds) spent by a single thread on every cell of the extended
+/// execution trace when RPO is used for commitments.
+const RPO_NS_PER_CELL: u64 = 250;
+
+// TRACE SUMMARY
+// ================================================================================================
+
+/// Lengths of the execution trace and of its segments, as reported by a [TraceLenSummary].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TraceSummary {
+    /// Number of rows of the main trace (system, decoder and stack columns).
+    pub main_trace_len: usize,
+    /// Number of rows required by the range checker.
+    pub range_trace_len: usize,
+    /// Number of rows of the hash chiplet.
+    pub hash_chiplet_len: usize,
+    /// Number of rows of the bitwise chiplet.
+    pub bitwise_chiplet_len: usize,
+    /// Number of rows of the memory chiplet.
+    pub memory_chiplet_len: usize,
+    /// Number of rows of the kernel ROM chiplet.
+    pub kernel_rom_len: usize,
+    /// Number of rows of the longest trace segment.
+    pub trace_len: usize,
+    /// Number of rows of the execution trace after padding to a power of two.
+    pub padded_trace_len: usize,
+}
+
+impl From<&TraceLenSummary> for TraceSummary {
+    fn from(summary: &TraceLenSummary) -> Self {
+        let chiplets = summary.chiplets_trace_len();
+        Self {
+            main_trace_len: summary.main_trace_len(),
+            range_trace_len: summary.range_trace_len(),
+            hash_chiplet_len: chiplets.hash_chiplet_len(),
+            bitwise_chiplet_len: chiplets.bitwise_chiplet_len(),
+            memory_chiplet_len: chiplets.memory_chiplet_len(),
+            kernel_rom_len: chiplets.kernel_rom_len(),
+            trace_len: summary.trace_len(),
+            padded_trace_len: summary.padded_trace_len(),
+        }
+    }
+}
+
+// PROVING ESTIMATE
+// ================================================================================================
+
+/// A rough estimate of the resources required to prove an execution trace.
+///
+/// The estimate is derived from the number of cells in the low-degree extension of the trace
+/// (main trace, auxiliary trace and constraint composition columns) and is meant to tell small
+/// proofs apart from prohibitively large ones, rather than to predict exact figures. The time is
+/// estimated for a single thread running natively; proving in a browser is typically a few times
+/// slower.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProvingEstimate {
+    /// Number of rows of the low-degree extension of the trace.
+    pub lde_domain_size: usize,
+    /// Number of base field elements in the low-degree extension of all committed columns.
+    pub lde_cells: u64,
+    /// Estimated peak memory consumption of the prover, in bytes.
+    pub memory_bytes: u64,
+    /// Estimated proving time, in milliseconds.
+    pub time_ms: u64,
+}
+
+impl ProvingEstimate {
+    /// Returns the estimate for proving a trace with the specified lengths using the specified
+    /// options.
+    pub fn new(summary: &TraceLenSummary, options: &ProvingOptions) -> Self {
+        let trace_len = summary.padded_trace_len() as u64;
+        let lde_domain_size = trace_len * options.proof_options.blowup_factor() as u64;
+        let extension_degree = options.proof_options.field_extension().degree() as u64;
+
+        let num_columns = TRACE_WIDTH as u64
+            + (AUX_TRACE_WIDTH + NUM_COMPOSITION_COLUMNS) as u64 * extension_degree;
+        let lde_cells = num_columns * lde_domain_size;
+
+        // the prover keeps the trace polynomials and their extensions in memory, as well as a
+        // Merkle tree over the extended domain for every commitment
+        let merkle_tree_bytes = NUM_COMMITMENTS * 2 * lde_domain_size * DIGEST_SIZE;
+        let memory_bytes = (lde_cells + num_columns * trace_len) * ELEMENT_SIZE + merkle_tree_bytes;
+
+        let ns_per_cell = match options.hash_fn() {
+            HashFunction::Blake3_192 | HashFunction::Blake3_256 => BLAKE3_NS_PER_CELL,
+            HashFunction::Rpo256 => RPO_NS_PER_CELL,
+        };
+        let time_ms = lde_cells * ns_per_cell / 1_000_000;
+
+        Self {
+            lde_domain_size: lde_domain_size as usize,
+            lde_cells,
+            memory_bytes,
+            time_ms,
+        }
+    }
+}
+
+// EXECUTION SUMMARY
+// ================================================================================================
+
+/// Result of executing a program without generating a proof.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExecutionSummary {
+    /// The stack outputs of the program.
+    pub outputs: StackOutputsString,
+    /// Lengths of the execution trace.
+    pub trace: TraceSummary,
+    /// Estimated cost of proving the execution.
+    pub estimate: ProvingEstimate,
+}
+
+/// Cost of proving a program, as returned by the estimate-only wasm export.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct CostEstimate {
+    /// Lengths of the execution trace.
+    pub trace: TraceSummary,
+    /// Estimated cost of proving the execution.
+    pub estimate: ProvingEstimate,
+}
+
+impl From<ExecutionSummary> for CostEstimate {
+    fn from(summary: ExecutionSummary) -> Self {
+        Self {
+            trace: summary.trace,
+            estimate: summary.estimate,
+        }
+    }
+}
@@ -0,0 +1,368 @@
+use super::{
+    crypto::{MerklePath, RpoDigest},
+    math::{Felt, StarkField},
+    utils::{
+        collections::Vec,
+        string::{String, ToString},
+        Box,
+    },
+    AdviceProvider, AdviceSource, ExecutionError, Host, InputFile, MemAdviceProvider, Word,
+};
+use js_sys::Function;
+use processor::{AdviceExtractor, AdviceInjector, DebugOptions, HostResponse, ProcessState};
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::prelude::*;
+
+// TYPE ALIASES
+// ================================================================================================
+
+/// A callback which receives a JSON-encoded [HostRequest] and returns either a JSON-encoded
+/// [HostReply], or None if the request should be handled by the default advice provider.
+pub type HostCallback = Box<dyn FnMut(&str) -> Result<Option<String>, String>>;
+
+// HOST REQUEST
+// ================================================================================================
+
+/// A request made by the VM to a [JsHost] callback.
+///
+/// Requests are passed to the callbacks as JSON objects, for example:
+/// `{ "request": "get_merkle_path", "clk": 12, "ctx": 0, "stack": ["3", "1", ...] }`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HostRequest {
+    /// Name of the request: the name of the advice extractor for `get_advice` callbacks (e.g.
+    /// `pop_stack`), of the advice injector for `set_advice` callbacks (e.g.
+    /// `merkle_node_to_stack`), the debug options for `on_debug` callbacks (e.g. `stack.4`), and
+    /// `event.<event_id>` for `on_event` callbacks.
+    pub request: String,
+    /// Clock cycle at which the request was made.
+    pub clk: u32,
+    /// Execution context in which the request was made.
+    pub ctx: u32,
+    /// Elements on the operand stack, starting from the top; for `stack.<n>` debug requests,
+    /// only the top n elements are included.
+    pub stack: Vec<String>,
+}
+
+impl HostRequest {
+    /// Returns a request with the specified name for the current state of the process.
+    fn new<S: ProcessState>(process: &S, request: String, stack_depth: Option<usize>) -> Self {
+        let mut stack = process.get_stack_state();
+        if let Some(depth) = stack_depth {
+            stack.truncate(depth);
+        }
+        Self {
+            request,
+            clk: process.clk(),
+            ctx: process.ctx(),
+            stack: stack.iter().map(|v| v.to_string()).collect(),
+        }
+    }
+}
+
+// HOST REPLY
+// ================================================================================================
+
+/// A reply returned by a [JsHost] callback.
+///
+/// Replies are JSON objects with a single field; all elements are encoded as decimal strings and
+/// Merkle path nodes as `0x`-prefixed hex strings, for example: `{ "element": "5" }` or
+/// `{ "merkle_path": ["0x1400...", "0x1500..."] }`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HostReply {
+    /// Reply to a `pop_stack` request.
+    Element(String),
+    /// Reply to a `pop_stack_word` request.
+    Word([String; 4]),
+    /// Reply to a `pop_stack_dword` request.
+    DoubleWord([[String; 4]; 2]),
+    /// Reply to a `get_merkle_path` request; nodes are listed from the leaf level upwards.
+    MerklePath(Vec<String>),
+    /// Reply to a `set_advice` request: the values are pushed onto the advice stack instead of
+    /// executing the advice injector, so that the first value ends up at the top of the stack.
+    AdviceStack(Vec<String>),
+}
+
+impl HostReply {
+    /// Converts this reply into a response to the specified advice extractor.
+    fn into_advice(self, extractor: AdviceExtractor) -> Result<HostResponse, String> {
+        match (extractor, self) {
+            (AdviceExtractor::PopStack, Self::Element(value)) => {
+                parse_element(&value).map(HostResponse::Element)
+            }
+            (AdviceExtractor::PopStackWord, Self::Word(word)) => {
+                parse_word(&word).map(HostResponse::Word)
+            }
+            (AdviceExtractor::PopStackDWord, Self::DoubleWord([word0, word1])) => {
+                Ok(HostResponse::DoubleWord([parse_word(&word0)?, parse_word(&word1)?]))
+            }
+            (AdviceExtractor::GetMerklePath, Self::MerklePath(nodes)) => {
+                let nodes =
+                    nodes.iter().map(|node| parse_digest(node)).collect::<Result<_, _>>()?;
+                Ok(HostResponse::MerklePath(MerklePath::new(nodes)))
+            }
+            (extractor, reply) => {
+                Err(format!("reply {reply:?} does not match request {extractor}"))
+            }
+        }
+    }
+}
+
+// JS HOST
+// ================================================================================================
+
+/// A [Host] which delegates requests made by the VM to callbacks supplied by the embedding
+/// application.
+///
+/// Each of the `get_advice`, `set_advice`, `on_debug` and `on_event` callbacks is optional:
+/// - `get_advice` and `set_advice` callbacks may return a [HostReply] to serve the request, or
+///   nothing to let the default advice provider (initialized from the program inputs) handle it.
+///   This allows advice, such as Merkle paths, to be supplied lazily.
+/// - `on_debug` callbacks receive the stack state requested by `debug` instructions, instead of
+///   it being printed to stdout; their return value is ignored. Without an `on_debug` callback,
+///   `debug` instructions are ignored.
+/// - `on_event` callbacks receive the events emitted by `emit` instructions; their return value is
+///   ignored.
+///
+/// Requests and replies are exchanged as JSON strings; a callback which fails (or throws in
+/// JavaScript) aborts the execution of the program.
+#[wasm_bindgen]
+#[derive(Default)]
+pub struct JsHost {
+    get_advice: Option<HostCallback>,
+    set_advice: Option<HostCallback>,
+    on_debug: Option<HostCallback>,
+    on_event: Option<HostCallback>,
+    adv_provider: MemAdviceProvider,
+}
+
+impl JsHost {
+    // CONSTRUCTORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns a new host without any callbacks and with an empty advice provider.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the callback which handles advice extraction requests.
+    pub fn with_get_advice<F>(mut self, callback: F) -> Self
+    where
+        F: FnMut(&str) -> Result<Option<String>, String> + 'static,
+    {
+        self.get_advice = Some(Box::new(callback));
+        self
+    }
+
+    /// Sets the callback which handles advice injection requests.
+    pub fn with_set_advice<F>(mut self, callback: F) -> Self
+    where
+        F: FnMut(&str) -> Result<Option<String>, String> + 'static,
+    {
+        self.set_advice = Some(Box::new(callback));
+        self
+    }
+
+    /// Sets the callback which handles debug requests.
+    pub fn with_on_debug<F>(mut self, callback: F) -> Self
+    where
+        F: FnMut(&str) -> Result<Option<String>, String> + 'static,
+    {
+        self.on_debug = Some(Box::new(callback));
+        self
+    }
+
+    /// Sets the callback which handles events emitted by the program.
+    pub fn with_on_event<F>(mut self, callback: F) -> Self
+    where
+        F: FnMut(&str) -> Result<Option<String>, String> + 'static,
+    {
+        self.on_event = Some(Box::new(callback));
+        self
+    }
+
+    /// Sets the advice provider which handles requests not served by the callbacks.
+    pub fn with_advice_provider(mut self, adv_provider: MemAdviceProvider) -> Self {
+        self.adv_provider = adv_provider;
+        self
+    }
+
+    // PUBLIC ACCESSORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns true if this host handles debug requests.
+    pub fn handles_debug(&self) -> bool {
+        self.on_debug.is_some()
+    }
+
+    /// Returns the advice provider of this host.
+    pub fn into_inner(self) -> MemAdviceProvider {
+        self.adv_provider
+    }
+}
+
+impl Host for JsHost {
+    fn get_advice<S: ProcessState>(
+        &mut self,
+        process: &S,
+        extractor: AdviceExtractor,
+    ) -> Result<HostResponse, ExecutionError> {
+        let request = HostRequest::new(process, extractor.to_string(), None);
+        match call(&mut self.get_advice, &request)? {
+            Some(reply) => reply
+                .into_advice(extractor)
+                .map_err(|err| ExecutionError::HostRequestFailed(request.clk, err)),
+            None => self.adv_provider.get_advice(process, &extractor),
+        }
+    }
+
+    fn set_advice<S: ProcessState>(
+        &mut self,
+        process: &S,
+        injector: AdviceInjector,
+    ) -> Result<HostResponse, ExecutionError> {
+        let request = HostRequest::new(process, injector.to_string(), None);
+        match call(&mut self.set_advice, &request)? {
+            Some(HostReply::AdviceStack(values)) => {
+                let values = values
+                    .iter()
+                    .map(|value| parse_element(value))
+                    .collect::<Result<Vec<_>, _>>()
+                    .map_err(|err| ExecutionError::HostRequestFailed(request.clk, err))?;
+                for value in values.into_iter().rev() {
+                    self.adv_provider.push_stack(AdviceSource::Value(value))?;
+                }
+                Ok(HostResponse::None)
+            }
+            Some(reply) => Err(ExecutionError::HostRequestFailed(
+                request.clk,
+                format!("reply {reply:?} does not match request {injector}"),
+            )),
+            None => self.adv_provider.set_advice(process, &injector),
+        }
+    }
+
+    fn on_debug<S: ProcessState>(
+        &mut self,
+        process: &S,
+        options: &DebugOptions,
+    ) -> Result<HostResponse, ExecutionError> {
+        if let Some(callback) = self.on_debug.as_mut() {
+            let depth = match options {
+                DebugOptions::StackAll => None,
+                DebugOptions::StackTop(n) => Some(*n as usize),
+            };
+            let request = HostRequest::new(process, options.to_string(), depth);
+            callback(&to_json(&request))
+                .map_err(|err| ExecutionError::HostRequestFailed(request.clk, err))?;
+        }
+        Ok(HostResponse::None)
+    }
+
+    fn on_event<S: ProcessState>(
+        &mut self,
+        process: &S,
+        event_id: u32,
+    ) -> Result<HostResponse, ExecutionError> {
+        if let Some(callback) = self.on_event.as_mut() {
+            let request = HostRequest::new(process, format!("event.{event_id}"), None);
+            callback(&to_json(&request))
+                .map_err(|err| ExecutionError::HostRequestFailed(request.clk, err))?;
+        }
+        Ok(HostResponse::None)
+    }
+}
+
+// WASM EXPORTS
+// ================================================================================================
+
+#[wasm_bindgen]
+impl JsHost {
+    /// Creates a host from optional JavaScript callbacks.
+    ///
+    /// Each callback is invoked with a JSON-encoded request and may return a JSON-encoded reply,
+    /// or `null`/`undefined` to let the default advice provider handle the request.
+    #[wasm_bindgen(constructor)]
+    pub fn create(
+        get_advice: Option<Function>,
+        set_advice: Option<Function>,
+        on_debug: Option<Function>,
+        on_event: Option<Function>,
+    ) -> JsHost {
+        Self {
+            get_advice: get_advice.map(js_callback),
+            set_advice: set_advice.map(js_callback),
+            on_debug: on_debug.map(js_callback),
+            on_event: on_event.map(js_callback),
+            adv_provider: MemAdviceProvider::default(),
+        }
+    }
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Invokes the provided callback, if any, with the specified request and parses its reply.
+fn call(
+    callback: &mut Option<HostCallback>,
+    request: &HostRequest,
+) -> Result<Option<HostReply>, ExecutionError> {
+    let callback = match callback {
+        Some(callback) => callback,
+        None => return Ok(None),
+    };
+    let reply = callback(&to_json(request))
+        .map_err(|err| ExecutionError::HostRequestFailed(request.clk, err))?;
+    reply
+        .map(|reply| {
+            serde_json::from_str(&reply).map_err(|err| {
+                ExecutionError::HostRequestFailed(request.clk, format!("malformed reply - {err}"))
+            })
+        })
+        .transpose()
+}
+
+/// Wraps a JavaScript function into a [HostCallback].
+fn js_callback(function: Function) -> HostCallback {
+    Box::new(move |request| {
+        let reply = function
+            .call1(&JsValue::NULL, &JsValue::from_str(request))
+            .map_err(|err| err.as_string().unwrap_or_else(|| format!("{err:?}")))?;
+        if reply.is_null() || reply.is_undefined() {
+            return Ok(None);
+        }
+        reply
+            .as_string()
+            .map(Some)
+            .ok_or_else(|| "callback must return a string, null or undefined".to_string())
+    })
+}
+
+fn to_json(request: &HostRequest) -> String {
+    serde_json::to_string(request).expect("host requests are always serializable")
+}
+
+fn parse_element(value: &str) -> Result<Felt, String> {
+    let value = value
+        .parse::<u64>()
+        .map_err(|e| format!("failed to parse element `{value}` - {e}"))?;
+    if value >= Felt::MODULUS {
+        return Err(format!("element {value} is not a valid field element"));
+    }
+    Ok(Felt::new(value))
+}
+
+fn parse_word(word: &[String; 4]) -> Result<Word, String> {
+    let mut result = Word::default();
+    for (element, value) in result.iter_mut().zip(word) {
+        *element = parse_element(value)?;
+    }
+    Ok(result)
+}
+
+fn parse_digest(node: &str) -> Result<RpoDigest, String> {
+    if !node.starts_with("0x") {
+        return Err(format!("Merkle path node `{node}` is not a 0x-prefixed hex string"));
+    }
+    InputFile::parse_word(node).map(RpoDigest::from)
+}
@@ -0,0 +1,390 @@
+use super::{
+    crypto::{MerkleStore, MerkleTree, NodeIndex, PartialMerkleTree, RpoDigest, SimpleSmt},
+    math::{Felt, StarkField},
+    utils::{
+        collections::{BTreeMap, Vec},
+        string::{String, ToString},
+    },
+    AdviceInputs, MaslLibrary, MemAdviceProvider, StackInputs, VmError, Word,
+};
+use assembly::utils::{ByteReader, Deserializable, SliceReader};
+use serde::Deserialize;
+#[cfg(feature = "std")]
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+/// Advice map entries keyed by the byte representation of their keys.
+type AdviceMap = BTreeMap<[u8; 32], Vec<Felt>>;
+
+// MERKLE DATA
+// ================================================================================================
+
+/// Struct used to deserialize merkle data from input file. Merkle data can be represented as a
+/// merkle tree or a Sparse Merkle Tree.
+#[derive(Deserialize, Debug)]
+pub enum MerkleData {
+    /// String representation of a merkle tree. The merkle tree is represented as a vector of
+    /// 32 byte hex strings where each string represents a leaf in the tree.
+    #[serde(rename = "merkle_tree")]
+    MerkleTree(Vec<String>),
+    /// String representation of a Sparse Merkle Tree. The Sparse Merkle Tree is represented as a
+    /// vector of tuples where each tuple consists of a u64 node index and a 32 byte hex string
+    /// representing the value of the node.
+    #[serde(rename = "sparse_merkle_tree")]
+    SparseMerkleTree(Vec<(u64, String)>),
+    /// String representation of a Partial Merkle Tree. The Partial Merkle Tree is represented as a
+    /// vector of tuples where each tuple consists of a leaf index tuple (depth, index) and a 32
+    /// byte hex string representing the value of the leaf.
+    #[serde(rename = "partial_merkle_tree")]
+    PartialMerkleTree(Vec<((u8, u64), String)>),
+}
+
+// INPUT FILE
+// ================================================================================================
+
+// TODO consider using final types instead of string representations.
+/// Input file struct that is used to deserialize input data from file. It consists of four
+/// components:
+/// - operand_stack
+/// - advice_stack
+/// - advice_map
+/// - merkle_store
+#[derive(Deserialize, Debug)]
+pub struct InputFile {
+    /// String representation of the initial operand stack, composed of chained field elements.
+    pub operand_stack: Vec<String>,
+    /// Optional string representation of the initial advice stack, composed of chained field
+    /// elements.
+    pub advice_stack: Option<Vec<String>>,
+    /// Optional map of 32 byte hex strings to vectors of u64s representing the initial advice map.
+    pub advice_map: Option<BTreeMap<String, Vec<u64>>>,
+    /// Optional vector of merkle data which will be loaded into the initial merkle store. Merkle
+    /// data is represented as 32 byte hex strings and node indexes are represented as u64s.
+    pub merkle_store: Option<Vec<MerkleData>>,
+}
+
+/// Helper methods to interact with the input file
+impl InputFile {
+    /// Parses the input data from its JSON representation.
+    pub fn from_json(inputs: &str) -> Result<Self, String> {
+        serde_json::from_str(inputs)
+            .map_err(|err| format!("Failed to deserialize input data - {err}"))
+    }
+
+    #[cfg(feature = "std")]
+    pub fn read(inputs_path: &Option<PathBuf>, program_path: &Path) -> Result<Self, String> {
+        // if file not specified explicitly and corresponding file with same name as program_path
+        // with '.inputs' extension does't exist, set operand_stack to empty vector
+        if !inputs_path.is_some() && !program_path.with_extension("inputs").exists() {
+            return Ok(Self {
+                operand_stack: Vec::new(),
+                advice_stack: Some(Vec::new()),
+                advice_map: Some(BTreeMap::new()),
+                merkle_store: None,
+            });
+        }
+
+        // If inputs_path has been provided then use this as path. Alternatively we will
+        // replace the program_path extension with `.inputs` and use this as a default.
+        let path = match inputs_path {
+            Some(path) => path.clone(),
+            None => program_path.with_extension("inputs"),
+        };
+
+        // read input file to string
+        let inputs_file = fs::read_to_string(&path)
+            .map_err(|err| format!("Failed to open input file `{}` - {}", path.display(), err))?;
+
+        // deserialize input data
+        Self::from_json(&inputs_file)
+    }
+
+    /// Parse advice provider data from the input file.
+    pub fn parse_advice_provider(&self) -> Result<MemAdviceProvider, String> {
+        Ok(MemAdviceProvider::from(self.parse_advice_inputs()?))
+    }
+
+    /// Parse advice inputs (advice stack, advice map and Merkle store) from the input file.
+    pub fn parse_advice_inputs(&self) -> Result<AdviceInputs, String> {
+        let mut advice_inputs = AdviceInputs::default();
+
+        let stack = self
+            .parse_advice_stack()
+            .map_err(|e| format!("failed to parse advice provider: {e}"))?;
+        advice_inputs = advice_inputs.with_stack_values(stack).map_err(|e| e.to_string())?;
+
+        if let Some(map) = self
+            .parse_advice_map()
+            .map_err(|e| format!("failed to parse advice provider: {e}"))?
+        {
+            advice_inputs = advice_inputs.with_map(map);
+        }
+
+        if let Some(merkle_store) = self
+            .parse_merkle_store()
+            .map_err(|e| format!("failed to parse advice provider: {e}"))?
+        {
+            advice_inputs = advice_inputs.with_merkle_store(merkle_store);
+        }
+
+        Ok(advice_inputs)
+    }
+
+    /// Parse advice stack data from the input file.
+    fn parse_advice_stack(&self) -> Result<Vec<u64>, String> {
+        self.advice_stack
+            .as_deref()
+            .unwrap_or(&[])
+            .iter()
+            .map(|v| {
+                v.parse::<u64>()
+                    .map_err(|e| format!("failed to parse advice stack value `{v}` - {e}"))
+            })
+            .collect::<Result<Vec<_>, _>>()
+    }
+
+    /// Parse advice map data from the input file.
+    fn parse_advice_map(&self) -> Result<Option<AdviceMap>, String> {
+        let advice_map = match &self.advice_map {
+            Some(advice_map) => advice_map,
+            None => return Ok(None),
+        };
+
+        let map = advice_map
+            .iter()
+            .map(|(k, v)| {
+                // decode hex key
+                let mut key = [0u8; 32];
+                hex::decode_to_slice(k, &mut key)
+                    .map_err(|e| format!("failed to decode advice map key `{k}` - {e}"))?;
+
+                // convert values to Felt
+                let values = v
+                    .iter()
+                    .map(|&v| {
+                        if v < Felt::MODULUS {
+                            Ok(Felt::new(v))
+                        } else {
+                            Err(format!("failed to convert advice map value `{v}` to Felt"))
+                        }
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+                Ok((key, values))
+            })
+            .collect::<Result<AdviceMap, String>>()?;
+
+        Ok(Some(map))
+    }
+
+    /// Parse merkle store data from the input file.
+    fn parse_merkle_store(&self) -> Result<Option<MerkleStore>, String> {
+        let merkle_data = match &self.merkle_store {
+            Some(merkle_data) => merkle_data,
+            None => return Ok(None),
+        };
+
+        let mut merkle_store = MerkleStore::default();
+        for data in merkle_data {
+            match data {
+                MerkleData::MerkleTree(data) => {
+                    let leaves = Self::parse_merkle_tree(data)?;
+                    let tree = MerkleTree::new(leaves)
+                        .map_err(|e| format!("failed to parse a Merkle tree: {e}"))?;
+                    merkle_store.extend(tree.inner_nodes());
+                }
+                MerkleData::SparseMerkleTree(data) => {
+                    let entries = Self::parse_sparse_merkle_tree(data)?;
+                    let tree = SimpleSmt::with_leaves(u64::BITS as u8, entries)
+                        .map_err(|e| format!("failed to parse a Sparse Merkle Tree: {e}"))?;
+                    merkle_store.extend(tree.inner_nodes());
+                }
+                MerkleData::PartialMerkleTree(data) => {
+                    let entries = Self::parse_partial_merkle_tree(data)?;
+                    let tree = PartialMerkleTree::with_leaves(entries)
+                        .map_err(|e| format!("failed to parse a Partial Merkle Tree: {e}"))?;
+                    merkle_store.extend(tree.inner_nodes());
+                }
+            }
+        }
+
+        Ok(Some(merkle_store))
+    }
+
+    /// Parse and return merkle tree leaves.
+    fn parse_merkle_tree(tree: &[String]) -> Result<Vec<Word>, String> {
+        tree.iter()
+            .map(|v| {
+                let leaf = Self::parse_word(v)?;
+                Ok(leaf)
+            })
+            .collect()
+    }
+
+    /// Parse and return Sparse Merkle Tree entries.
+    fn parse_sparse_merkle_tree(tree: &[(u64, String)]) -> Result<Vec<(u64, Word)>, String> {
+        tree.iter()
+            .map(|(index, v)| {
+                let leaf = Self::parse_word(v)?;
+                Ok((*index, leaf))
+            })
+            .collect()
+    }
+
+    /// Parse and return Partial Merkle Tree entries.
+    fn parse_partial_merkle_tree(
+        tree: &[((u8, u64), String)],
+    ) -> Result<Vec<(NodeIndex, RpoDigest)>, String> {
+        tree.iter()
+            .map(|((depth, index), v)| {
+                let node_index = NodeIndex::new(*depth, *index).map_err(|e| {
+                    format!(
+                        "failed to create node index with depth {depth} and index {index} - {e}"
+                    )
+                })?;
+                let leaf = Self::parse_word(v)?;
+                Ok((node_index, RpoDigest::new(leaf)))
+            })
+            .collect()
+    }
+
+    /// Parse a `Word` from a hex string.
+    pub fn parse_word(word_hex: &str) -> Result<Word, String> {
+        let word_value = word_hex
+            .strip_prefix("0x")
+            .ok_or_else(|| format!("`Word` hex {word_hex} is not prefixed with 0x"))?;
+        let mut word_data = [0u8; 32];
+        hex::decode_to_slice(word_value, &mut word_data)
+            .map_err(|e| format!("failed to decode `Word` from hex {word_hex} - {e}"))?;
+        let mut word = Word::default();
+        for (i, value) in word_data.chunks(8).enumerate() {
+            word[i] = Felt::try_from(value).map_err(|e| {
+                format!("failed to convert `Word` data {word_hex} (element {i}) to Felt - {e}")
+            })?;
+        }
+        Ok(word)
+    }
+
+    /// Parse and return the stack inputs for the program.
+    pub fn parse_stack_inputs(&self) -> Result<StackInputs, String> {
+        let stack_inputs = self
+            .operand_stack
+            .iter()
+            .map(|v| v.parse::<u64>().map_err(|e| e.to_string()))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        StackInputs::try_from_values(stack_inputs).map_err(|e| e.to_string())
+    }
+}
+
+// LIBRARIES
+// ================================================================================================
+
+/// Deserializes a sequence of libraries from the concatenation of their `.masl` encodings.
+///
+/// # Errors
+/// Returns an error if the bytes do not contain a sequence of well-formed libraries.
+pub fn parse_libraries(bytes: &[u8]) -> Result<Vec<MaslLibrary>, VmError> {
+    let mut source = SliceReader::new(bytes);
+    let mut libraries = Vec::new();
+    while source.has_more_bytes() {
+        let library = MaslLibrary::read_from(&mut source)
+            .map_err(|e| VmError::input(format!("failed to deserialize library - {e}")))?;
+        libraries.push(library);
+    }
+    Ok(libraries)
+}
+
+// TESTS
+// ================================================================================================
+#[cfg(test)]
+mod tests {
+    use super::{InputFile, StarkField};
+
+    #[test]
+    fn test_merkle_data_parsing() {
+        let program_with_pmt = "
+        {
+            \"operand_stack\": [\"1\"],
+            \"merkle_store\": [
+                {
+                    \"partial_merkle_tree\": [
+                        [
+                            [2, 0],
+                            \"0x1400000000000000000000000000000000000000000000000000000000000000\"
+                        ],
+                        [
+                            [2, 1],
+                            \"0x1500000000000000000000000000000000000000000000000000000000000000\"
+                        ],
+                        [
+                            [1, 1],
+                            \"0x0b00000000000000000000000000000000000000000000000000000000000000\"
+                        ]
+                    ]
+                }
+            ]
+        }";
+        let inputs: InputFile = serde_json::from_str(program_with_pmt).unwrap();
+        let merkle_store = inputs.parse_merkle_store().unwrap();
+        assert!(merkle_store.is_some());
+
+        let program_with_smt = "
+        {
+            \"operand_stack\": [\"1\"],
+            \"merkle_store\": [
+              {
+                \"sparse_merkle_tree\": [
+                  [
+                    0,
+                    \"0x1400000000000000000000000000000000000000000000000000000000000000\"
+                  ],
+                  [
+                    1,
+                    \"0x1500000000000000000000000000000000000000000000000000000000000000\"
+                  ],
+                  [
+                    3,
+                    \"0x1700000000000000000000000000000000000000000000000000000000000000\"
+                  ]
+                ]
+              }
+            ]
+          }";
+        let inputs: InputFile = serde_json::from_str(program_with_smt).unwrap();
+        let merkle_store = inputs.parse_merkle_store().unwrap();
+        assert!(merkle_store.is_some());
+
+        let program_with_merkle_tree = "
+        {
+            \"operand_stack\": [\"1\"],
+            \"merkle_store\": [
+                {
+                    \"merkle_tree\": [
+                        \"0x1400000000000000000000000000000000000000000000000000000000000000\",
+                        \"0x1500000000000000000000000000000000000000000000000000000000000000\",
+                        \"0x1600000000000000000000000000000000000000000000000000000000000000\",
+                        \"0x1700000000000000000000000000000000000000000000000000000000000000\"
+                    ]
+                }
+            ]
+        }";
+        let inputs: InputFile = serde_json::from_str(program_with_merkle_tree).unwrap();
+        let merkle_store = inputs.parse_merkle_store().unwrap();
+        assert!(merkle_store.is_some());
+    }
+
+    #[test]
+    fn test_word_parsing() {
+        let word = "0x1400000000000000000000000000000000000000000000000000000000000000";
+        assert_eq!(20, InputFile::parse_word(word).unwrap()[0].as_int());
+
+        // malformed words are rejected instead of panicking
+        assert!(InputFile::parse_word("").is_err());
+        assert!(InputFile::parse_word("1").is_err());
+        assert!(InputFile::parse_word("é").is_err());
+        assert!(InputFile::parse_word(&word[2..]).is_err());
+        assert!(InputFile::parse_word("0x14").is_err());
+    }
+}
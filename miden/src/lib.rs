@@ -4,28 +4,50 @@
 // EXPORTS
 // ================================================================================================
 use crate::utils::collections::Vec;
-use assembly::utils::string::ToString;
 use crate::utils::string::String;
+use assembly::utils::string::ToString;
 pub use assembly::{
     ast::{ModuleAst, ProgramAst},
-    Assembler, AssemblyError, ParsingError,
+    Assembler, AssemblyError, MaslLibrary, ParsingError,
 };
-use vm_core::Felt;
 use serde::{Deserialize, Serialize};
 
 pub use processor::{
-    crypto, execute, execute_iter, utils, AdviceInputs, AdviceProvider, AsmOpInfo, DefaultHost,
-    ExecutionError, ExecutionTrace, Host, Kernel, MemAdviceProvider, Operation, Program,
-    ProgramInfo, StackInputs, VmState, VmStateIterator, ZERO,
+    crypto, execute, execute_iter, run, utils, AdviceInputs, AdviceProvider, AdviceSource,
+    AsmOpInfo, AsmOpLocation, ChipletsLengths, DefaultHost, ExecutionError, ExecutionOptions,
+    ExecutionOptionsError, ExecutionTrace, FastProcessor, Host, Kernel, MemAdviceProvider,
+    Operation, Program, ProgramInfo, RunOutput, SourceMap, StackInputs, TraceLenSummary, VmState,
+    VmStateIterator, ZERO,
 };
 pub use prover::{
-    math, prove, Digest, ExecutionProof, FieldExtension, HashFunction, InputError, ProvingOptions,
-    StackOutputs, StarkProof, Word,
+    math, prove, Digest, ExecutionProof, ExecutionProofGenerator, FieldExtension, HashFunction,
+    InputError, ProvingOptions, ProvingStage, StackOutputs, StarkProof, Word,
 };
 pub use verifier::{verify, VerificationError};
 
+mod errors;
+pub use errors::{ErrorLocation, VmError, VmErrorKind};
+
+mod estimate;
+pub use estimate::{CostEstimate, ExecutionSummary, ProvingEstimate, TraceSummary};
+
+mod host;
+pub use host::{HostCallback, HostReply, HostRequest, JsHost};
+
+mod inputs;
+pub use inputs::{parse_libraries, InputFile, MerkleData};
+
+mod options;
+use options::parse_proving_options;
+pub use options::{ProvingConfig, SecurityLevel};
+
+mod proof;
+pub use proof::{ProofEncoding, ProofEnvelope, PROOF_ENVELOPE_MAGIC, PROOF_ENVELOPE_VERSION};
+
+mod session;
+pub use session::{ProvingPhase, ProvingSession};
+
 use wasm_bindgen::prelude::*;
-use wasm_bindgen_test::console_log;
 extern crate console_error_panic_hook;
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -34,10 +56,47 @@ pub struct NormalInput {
     pub host: DefaultHost<MemAdviceProvider>,
 }
 
+impl TryFrom<&InputFile> for NormalInput {
+    type Error = VmError;
+
+    fn try_from(inputs: &InputFile) -> Result<Self, Self::Error> {
+        let stack_inputs = inputs.parse_stack_inputs().map_err(VmError::input)?;
+        let advice_provider = inputs.parse_advice_provider().map_err(VmError::input)?;
+        Ok(Self {
+            stack_inputs,
+            host: DefaultHost::new(advice_provider),
+        })
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct VMResult {
     pub outputs: StackOutputsString,
     pub starkproof: ExecutionProof,
+    /// The hash function used to generate the proof.
+    #[serde(default)]
+    pub hash_fn: HashFunction,
+    /// The security level (in bits) of the proof.
+    #[serde(default)]
+    pub security_level: u32,
+}
+
+impl VMResult {
+    /// Returns a new [VMResult] instantiated from the specified outputs and proof.
+    pub fn new(outputs: &StackOutputs, proof: ExecutionProof) -> Self {
+        Self {
+            outputs: StackOutputsString::from(outputs),
+            hash_fn: proof.hash_fn(),
+            security_level: proof.security_level(),
+            starkproof: proof,
+        }
+    }
+}
+
+impl From<ProofEnvelope> for VMResult {
+    fn from(envelope: ProofEnvelope) -> Self {
+        Self::new(envelope.stack_outputs(), envelope.proof().clone())
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -48,86 +107,615 @@ pub struct StackOutputsString {
     pub overflow_addrs: Vec<String>,
 }
 
+impl StackOutputsString {
+    /// Converts the string representation of the outputs back into [StackOutputs].
+    ///
+    /// # Errors
+    /// Returns an error if any of the values is not a valid u64 or field element, or if the
+    /// number of overflow addresses is inconsistent with the stack depth.
+    pub fn to_stack_outputs(&self) -> Result<StackOutputs, VmError> {
+        let stack = parse_u64_values(&self.stack, "stack output")?;
+        let overflow_addrs = parse_u64_values(&self.overflow_addrs, "overflow address")?;
+        StackOutputs::new(stack, overflow_addrs).map_err(VmError::input)
+    }
+}
+
+impl From<&StackOutputs> for StackOutputsString {
+    fn from(outputs: &StackOutputs) -> Self {
+        Self {
+            stack: outputs.stack.iter().map(|v| v.to_string()).collect(),
+            overflow_addrs: outputs.overflow_addrs.iter().map(|v| v.to_string()).collect(),
+        }
+    }
+}
+
+#[wasm_bindgen]
+pub fn execute_zk_program(
+    program_code: String,
+    stack_init: String,
+    advice_tape: String,
+    options: Option<String>,
+) -> Result<String, JsValue> {
+    let options = parse_proving_options(options.as_deref())?;
+    let result = prove_program(&program_code, stack_init, advice_tape, options)?;
+    let final_result = serde_json::to_string(&result).map_err(VmError::serialization)?;
+    Ok(final_result)
+}
+
+#[wasm_bindgen]
+pub fn generate_program_hash(program_in_assembly: String) -> Result<String, JsValue> {
+    Ok(program_hash(&program_in_assembly)?)
+}
+
+/// Compiles, executes and proves the specified program against the provided comma-separated
+/// stack and advice stack inputs using the specified proving options.
+///
+/// # Errors
+/// Returns an error if the program fails to parse or compile, if the inputs are malformed, or if
+/// execution or proof generation fails.
+pub fn prove_program(
+    program_code: &str,
+    stack_init: String,
+    advice_tape: String,
+    options: ProvingOptions,
+) -> Result<VMResult, VmError> {
+    let program = compile_program(program_code)?;
+
+    let inputs: NormalInput = convert_stackinputs(stack_init, advice_tape)?;
+
+    let (outputs, proof) = prove(&program, inputs.stack_inputs, inputs.host, options)?;
+
+    Ok(VMResult::new(&outputs, proof))
+}
+
 #[wasm_bindgen]
-pub fn execute_zk_program(program_code: String, stack_init: String, advice_tape: String) -> String {
-    let options = ProvingOptions::default();
+pub fn execute_zk_program_with_inputs(
+    program_code: String,
+    inputs: String,
+    kernel_code: Option<String>,
+    libraries: Option<Vec<u8>>,
+    options: Option<String>,
+) -> Result<String, JsValue> {
+    let inputs = InputFile::from_json(&inputs).map_err(VmError::input)?;
+    let libraries = parse_libraries(libraries.as_deref().unwrap_or_default())?;
+    let options = parse_proving_options(options.as_deref())?;
+    let result = prove_program_with_inputs(
+        &program_code,
+        &inputs,
+        kernel_code.as_deref(),
+        &libraries,
+        options,
+    )?;
+    let final_result = serde_json::to_string(&result).map_err(VmError::serialization)?;
+    Ok(final_result)
+}
 
-    let assembler = Assembler::default().with_library(&stdlib::StdLibrary::default()).unwrap();
+#[wasm_bindgen]
+pub fn generate_program_hash_with_libraries(
+    program_in_assembly: String,
+    kernel_code: Option<String>,
+    libraries: Option<Vec<u8>>,
+) -> Result<String, JsValue> {
+    let libraries = parse_libraries(libraries.as_deref().unwrap_or_default())?;
+    Ok(program_hash_with(&program_in_assembly, kernel_code.as_deref(), &libraries)?)
+}
 
-    let program = assembler.compile(&program_code).unwrap();
+/// Compiles, executes and proves the specified program against inputs described using the
+/// [InputFile] schema.
+///
+/// If `kernel_code` is provided, the program is compiled against the kernel defined by it; the
+/// specified `libraries` are made available to the program in addition to the standard library.
+/// The proof is generated using the specified proving options.
+///
+/// # Errors
+/// Returns an error if the program or the kernel fail to parse or compile, if the inputs are
+/// malformed, or if execution or proof generation fails.
+pub fn prove_program_with_inputs(
+    program_code: &str,
+    inputs: &InputFile,
+    kernel_code: Option<&str>,
+    libraries: &[MaslLibrary],
+    options: ProvingOptions,
+) -> Result<VMResult, VmError> {
+    prove_program_envelope(program_code, inputs, kernel_code, libraries, options)
+        .map(VMResult::from)
+}
 
-    let inputs: NormalInput = convert_stackinputs(stack_init, advice_tape);
+#[wasm_bindgen]
+pub fn execute_zk_program_to_bytes(
+    program_code: String,
+    inputs: String,
+    kernel_code: Option<String>,
+    libraries: Option<Vec<u8>>,
+    options: Option<String>,
+) -> Result<Vec<u8>, JsValue> {
+    let envelope =
+        prove_envelope_from_json(&program_code, &inputs, kernel_code, libraries, options)?;
+    Ok(envelope.encode(ProofEncoding::Binary))
+}
 
-    let res = prove(&program, inputs.stack_inputs, inputs.host, options);
+#[wasm_bindgen]
+pub fn execute_zk_program_to_string(
+    program_code: String,
+    inputs: String,
+    encoding: String,
+    kernel_code: Option<String>,
+    libraries: Option<Vec<u8>>,
+    options: Option<String>,
+) -> Result<String, JsValue> {
+    let encoding = encoding.parse().map_err(VmError::input)?;
+    if encoding == ProofEncoding::Binary {
+        return Err(VmError::input("binary proof envelopes cannot be returned as a string").into());
+    }
+    let envelope =
+        prove_envelope_from_json(&program_code, &inputs, kernel_code, libraries, options)?;
+    match encoding {
+        ProofEncoding::Hex => Ok(envelope.to_hex()),
+        ProofEncoding::Base64 => Ok(envelope.to_base64()),
+        ProofEncoding::Binary => unreachable!("binary encoding is rejected above"),
+    }
+}
 
-    assert!(res.is_ok(), "The proof generation fails: {:?}", res);
+/// Compiles, executes and proves the specified program in the same way as
+/// [prove_program_with_inputs], and wraps the proof into a [ProofEnvelope] together with the
+/// program hash and the public inputs and outputs.
+///
+/// # Errors
+/// Returns an error if the program or the kernel fail to parse or compile, if the inputs are
+/// malformed, or if execution or proof generation fails.
+pub fn prove_program_envelope(
+    program_code: &str,
+    inputs: &InputFile,
+    kernel_code: Option<&str>,
+    libraries: &[MaslLibrary],
+    options: ProvingOptions,
+) -> Result<ProofEnvelope, VmError> {
+    let program = compile_program_with(program_code, kernel_code, libraries)?;
 
-    let (outputs, proof) = res.unwrap();
+    let inputs = NormalInput::try_from(inputs)?;
+    let stack_inputs = inputs.stack_inputs.clone();
 
-    let stack_string: Vec<String> = outputs.stack.iter().map(|(v)| (v.to_string())).collect();
+    let (outputs, proof) = prove(&program, inputs.stack_inputs, inputs.host, options)?;
 
-    let overflow_addrs_string: Vec<String> =
-        outputs.overflow_addrs.iter().map(|(v)| (v.to_string())).collect();
+    Ok(ProofEnvelope::new(program.hash(), stack_inputs, outputs, proof))
+}
 
-    let outputs_string = StackOutputsString {
-        stack: stack_string,
-        overflow_addrs: overflow_addrs_string,
-    };
+#[wasm_bindgen]
+pub fn execute_zk_program_without_proof(
+    program_code: String,
+    inputs: String,
+    kernel_code: Option<String>,
+    libraries: Option<Vec<u8>>,
+    options: Option<String>,
+) -> Result<String, JsValue> {
+    let summary =
+        execute_program_from_json(&program_code, &inputs, kernel_code, libraries, options)?;
+    Ok(serde_json::to_string(&summary).map_err(VmError::serialization)?)
+}
 
-    let result = VMResult {
-        outputs: outputs_string,
-        starkproof: proof,
-    };
+#[wasm_bindgen]
+pub fn estimate_zk_program(
+    program_code: String,
+    inputs: String,
+    kernel_code: Option<String>,
+    libraries: Option<Vec<u8>>,
+    options: Option<String>,
+) -> Result<String, JsValue> {
+    let summary =
+        execute_program_from_json(&program_code, &inputs, kernel_code, libraries, options)?;
+    let estimate = CostEstimate::from(summary);
+    Ok(serde_json::to_string(&estimate).map_err(VmError::serialization)?)
+}
+
+/// Compiles and executes the specified program in the same way as [prove_program_with_inputs],
+/// but without generating a proof.
+///
+/// Returns the stack outputs of the program together with the lengths of the execution trace and
+/// an estimate of the resources required to prove the execution using the specified options.
+///
+/// # Errors
+/// Returns an error if the program or the kernel fail to parse or compile, if the inputs are
+/// malformed, or if execution fails.
+pub fn execute_program_with_inputs(
+    program_code: &str,
+    inputs: &InputFile,
+    kernel_code: Option<&str>,
+    libraries: &[MaslLibrary],
+    options: ProvingOptions,
+) -> Result<ExecutionSummary, VmError> {
+    let program = compile_program_with(program_code, kernel_code, libraries)?;
+
+    let inputs = NormalInput::try_from(inputs)?;
+
+    execute_with_host(&program, inputs.stack_inputs, inputs.host, &options)
+}
+
+#[wasm_bindgen]
+pub fn execute_zk_program_with_host(
+    program_code: String,
+    inputs: String,
+    host: JsHost,
+    kernel_code: Option<String>,
+    libraries: Option<Vec<u8>>,
+    options: Option<String>,
+) -> Result<String, JsValue> {
+    let inputs = InputFile::from_json(&inputs).map_err(VmError::input)?;
+    let libraries = parse_libraries(libraries.as_deref().unwrap_or_default())?;
+    let options = parse_proving_options(options.as_deref())?;
+    let result = prove_program_with_host(
+        &program_code,
+        &inputs,
+        host,
+        kernel_code.as_deref(),
+        &libraries,
+        options,
+    )?;
+    Ok(serde_json::to_string(&result).map_err(VmError::serialization)?)
+}
+
+#[wasm_bindgen]
+pub fn execute_zk_program_without_proof_with_host(
+    program_code: String,
+    inputs: String,
+    host: JsHost,
+    kernel_code: Option<String>,
+    libraries: Option<Vec<u8>>,
+    options: Option<String>,
+) -> Result<String, JsValue> {
+    let inputs = InputFile::from_json(&inputs).map_err(VmError::input)?;
+    let libraries = parse_libraries(libraries.as_deref().unwrap_or_default())?;
+    let options = parse_proving_options(options.as_deref())?;
+    let summary = execute_program_with_host(
+        &program_code,
+        &inputs,
+        host,
+        kernel_code.as_deref(),
+        &libraries,
+        options,
+    )?;
+    Ok(serde_json::to_string(&summary).map_err(VmError::serialization)?)
+}
+
+/// Compiles, executes and proves the specified program in the same way as
+/// [prove_program_with_inputs], but serves the requests made by the VM through the provided
+/// [JsHost].
+///
+/// The advice inputs described by `inputs` are used to handle the advice requests which are not
+/// served by the callbacks of the host. If the host handles debug requests, the program is
+/// compiled in debug mode so that `debug` instructions are executed.
+///
+/// # Errors
+/// Returns an error if the program or the kernel fail to parse or compile, if the inputs are
+/// malformed, if a host callback fails, or if execution or proof generation fails.
+pub fn prove_program_with_host(
+    program_code: &str,
+    inputs: &InputFile,
+    host: JsHost,
+    kernel_code: Option<&str>,
+    libraries: &[MaslLibrary],
+    options: ProvingOptions,
+) -> Result<VMResult, VmError> {
+    let program = assemble(program_code, kernel_code, libraries, host.handles_debug())?;
+
+    let stack_inputs = inputs.parse_stack_inputs().map_err(VmError::input)?;
+    let host = host.with_advice_provider(inputs.parse_advice_provider().map_err(VmError::input)?);
+
+    let (outputs, proof) = prove(&program, stack_inputs, host, options)?;
+
+    Ok(VMResult::new(&outputs, proof))
+}
 
-    let final_result: String = serde_json::to_string(&result).unwrap();
-    return final_result;
+/// Compiles and executes the specified program in the same way as
+/// [execute_program_with_inputs], but serves the requests made by the VM through the provided
+/// [JsHost].
+///
+/// The advice inputs described by `inputs` are used to handle the advice requests which are not
+/// served by the callbacks of the host. If the host handles debug requests, the program is
+/// compiled in debug mode so that `debug` instructions are executed.
+///
+/// # Errors
+/// Returns an error if the program or the kernel fail to parse or compile, if the inputs are
+/// malformed, if a host callback fails, or if execution fails.
+pub fn execute_program_with_host(
+    program_code: &str,
+    inputs: &InputFile,
+    host: JsHost,
+    kernel_code: Option<&str>,
+    libraries: &[MaslLibrary],
+    options: ProvingOptions,
+) -> Result<ExecutionSummary, VmError> {
+    let program = assemble(program_code, kernel_code, libraries, host.handles_debug())?;
+
+    let stack_inputs = inputs.parse_stack_inputs().map_err(VmError::input)?;
+    let host = host.with_advice_provider(inputs.parse_advice_provider().map_err(VmError::input)?);
+
+    execute_with_host(&program, stack_inputs, host, &options)
 }
 
 #[wasm_bindgen]
-pub fn generate_program_hash(program_in_assembly: String) -> String {
-    let assembler = Assembler::default().with_library(&stdlib::StdLibrary::default()).unwrap();
-    let program = assembler.compile(&program_in_assembly).unwrap();
+pub fn verify_zk_program(
+    program_hash: String,
+    stack_inputs: String,
+    zk_outputs: String,
+) -> Result<u32, JsValue> {
+    let vm_result: VMResult = serde_json::from_str(&zk_outputs).map_err(VmError::serialization)?;
+    Ok(verify_program(&program_hash, &stack_inputs, vm_result)?)
+}
+
+/// Verifies the proof contained in a [VMResult] produced by [prove_program] and returns the
+/// security level of the proof.
+///
+/// The program hash is expected to be hex-encoded (as returned by [program_hash]) and the stack
+/// inputs are expected to be the same comma-separated list used to generate the proof.
+///
+/// # Errors
+/// Returns an error if the program hash or the inputs are malformed, or if the proof does not
+/// prove a correct execution of the program.
+pub fn verify_program(
+    program_hash: &str,
+    stack_inputs: &str,
+    vm_result: VMResult,
+) -> Result<u32, VmError> {
+    let program_info = ProgramInfo::new(parse_program_hash(program_hash)?, Kernel::default());
+    let stack_inputs = StackInputs::try_from_values(parse_u64_list(stack_inputs, "stack input")?)?;
+    let stack_outputs = vm_result.outputs.to_stack_outputs()?;
+
+    Ok(verify(program_info, stack_inputs, stack_outputs, vm_result.starkproof)?)
+}
+
+#[wasm_bindgen]
+pub fn verify_zk_program_with_inputs(
+    program_hash: String,
+    inputs: String,
+    zk_outputs: String,
+    kernel_code: Option<String>,
+) -> Result<u32, JsValue> {
+    let inputs = InputFile::from_json(&inputs).map_err(VmError::input)?;
+    let vm_result: VMResult = serde_json::from_str(&zk_outputs).map_err(VmError::serialization)?;
+    Ok(verify_program_with_inputs(
+        &program_hash,
+        &inputs,
+        kernel_code.as_deref(),
+        vm_result,
+    )?)
+}
+
+/// Verifies the proof contained in a [VMResult] produced by [prove_program_with_inputs] and
+/// returns the security level of the proof.
+///
+/// Only the operand stack of the `inputs` is used for verification as advice inputs are secret.
+/// If the program was compiled against a kernel, the same kernel source must be provided.
+///
+/// # Errors
+/// Returns an error if the program hash, the inputs or the kernel are malformed, or if the proof
+/// does not prove a correct execution of the program.
+pub fn verify_program_with_inputs(
+    program_hash: &str,
+    inputs: &InputFile,
+    kernel_code: Option<&str>,
+    vm_result: VMResult,
+) -> Result<u32, VmError> {
+    let program_info =
+        ProgramInfo::new(parse_program_hash(program_hash)?, parse_kernel(kernel_code)?);
+    let stack_inputs = inputs.parse_stack_inputs().map_err(VmError::input)?;
+    let stack_outputs = vm_result.outputs.to_stack_outputs()?;
+
+    Ok(verify(program_info, stack_inputs, stack_outputs, vm_result.starkproof)?)
+}
+
+#[wasm_bindgen]
+pub fn verify_zk_proof_envelope(
+    program_hash: String,
+    envelope: Vec<u8>,
+    kernel_code: Option<String>,
+) -> Result<u32, JsValue> {
+    let envelope = ProofEnvelope::decode(&envelope)?;
+    Ok(verify_proof_envelope(&program_hash, envelope, kernel_code.as_deref())?)
+}
+
+/// Verifies the proof contained in a [ProofEnvelope] against the public inputs and outputs
+/// recorded in the envelope and returns the security level of the proof.
+///
+/// The envelope is accepted only if it was generated for the program with the specified
+/// hex-encoded hash. If the program was compiled against a kernel, the same kernel source must
+/// be provided.
+///
+/// # Errors
+/// Returns an error if the program hash or the kernel are malformed, if the envelope was
+/// generated for a different program, or if the proof does not prove a correct execution of the
+/// program.
+pub fn verify_proof_envelope(
+    program_hash: &str,
+    envelope: ProofEnvelope,
+    kernel_code: Option<&str>,
+) -> Result<u32, VmError> {
+    let program_hash = parse_program_hash(program_hash)?;
+    if program_hash != envelope.program_hash() {
+        return Err(VmError::new(
+            VmErrorKind::Verification,
+            "proof envelope was generated for a different program",
+        ));
+    }
+    let program_info = ProgramInfo::new(program_hash, parse_kernel(kernel_code)?);
+    let stack_inputs = envelope.stack_inputs().clone();
+    let stack_outputs = envelope.stack_outputs().clone();
+    let proof = envelope.proof().clone();
+
+    Ok(verify(program_info, stack_inputs, stack_outputs, proof)?)
+}
+
+/// Returns the hex-encoded hash of the specified program.
+///
+/// # Errors
+/// Returns an error if the program fails to parse or compile.
+pub fn program_hash(program_in_assembly: &str) -> Result<String, VmError> {
+    program_hash_with(program_in_assembly, None, &[])
+}
+
+/// Returns the hex-encoded hash of the specified program compiled against the specified
+/// libraries and, optionally, a kernel.
+///
+/// # Errors
+/// Returns an error if the program or the kernel fail to parse or compile.
+pub fn program_hash_with(
+    program_in_assembly: &str,
+    kernel_code: Option<&str>,
+    libraries: &[MaslLibrary],
+) -> Result<String, VmError> {
     use vm_core::utils::Serializable;
-    let program_hash = program.hash().to_bytes();
-    let ph = hex::encode(program_hash);
-    return ph;
-}
-
-pub fn convert_stackinputs(stack_init: String, advice_tape: String) -> NormalInput {
-    let mut stack_inita = Vec::new();
-    let mut advice_tapea = Vec::new();
-    if stack_init.len() != 0 {
-        let stack_init: Vec<&str> = stack_init.split(',').collect();
-        stack_inita = stack_init
-            .iter()
-            .map(|stack_init| Felt::new(stack_init.parse::<u64>().unwrap()))
-            .collect();
-    };
+    let program = compile_program_with(program_in_assembly, kernel_code, libraries)?;
+    Ok(hex::encode(program.hash().to_bytes()))
+}
 
-    if advice_tape.len() != 0 {
-        let advice_tape: Vec<&str> = advice_tape.split(',').collect();
-        advice_tapea = advice_tape
-            .iter()
-            .map(|advice_tape| advice_tape.parse::<u64>().unwrap())
-            .collect();
-    };
+/// Compiles the specified source code into a [Program] using an assembler with the standard
+/// library loaded.
+///
+/// The source is parsed before compilation so that parsing errors retain their source location.
+pub fn compile_program(program_code: &str) -> Result<Program, VmError> {
+    compile_program_with(program_code, None, &[])
+}
+
+/// Compiles the specified source code into a [Program] using an assembler with the standard
+/// library, the specified libraries, and optionally a kernel loaded.
+pub fn compile_program_with(
+    program_code: &str,
+    kernel_code: Option<&str>,
+    libraries: &[MaslLibrary],
+) -> Result<Program, VmError> {
+    assemble(program_code, kernel_code, libraries, false)
+}
 
-    let stack_input: StackInputs = StackInputs::new(stack_inita);
+pub fn convert_stackinputs(
+    stack_init: String,
+    advice_tape: String,
+) -> Result<NormalInput, VmError> {
+    let stack_inita = parse_u64_list(&stack_init, "stack input")?;
+    let advice_tapea = parse_u64_list(&advice_tape, "advice stack value")?;
 
-    let advice_inputs = AdviceInputs::default().with_stack_values(advice_tapea).unwrap();
+    let stack_input: StackInputs = StackInputs::try_from_values(stack_inita)?;
+
+    let advice_inputs = AdviceInputs::default().with_stack_values(advice_tapea)?;
 
     let mem_advice_provider: MemAdviceProvider = MemAdviceProvider::from(advice_inputs);
     let host: DefaultHost<MemAdviceProvider> = DefaultHost::new(mem_advice_provider);
     let inputs = NormalInput {
         stack_inputs: stack_input,
-        host: host
+        host,
     };
 
-    return inputs;
+    Ok(inputs)
+}
+
+/// Compiles the specified source code into a [Program] using an assembler with the standard
+/// library, the specified libraries, and optionally a kernel loaded; in debug mode, `debug`
+/// instructions are compiled into the program.
+fn assemble(
+    program_code: &str,
+    kernel_code: Option<&str>,
+    libraries: &[MaslLibrary],
+    in_debug_mode: bool,
+) -> Result<Program, VmError> {
+    let mut assembler = Assembler::default()
+        .with_debug_mode(in_debug_mode)
+        .with_library(&stdlib::StdLibrary::default())?;
+    for library in libraries {
+        assembler = assembler.with_library(library)?;
+    }
+    if let Some(kernel_code) = kernel_code {
+        assembler = assembler.with_kernel(kernel_code)?;
+    }
+    // parse the program separately, so that parsing errors are reported with their locations
+    let ast = ProgramAst::parse_with_constants(program_code, &assembler)?;
+    Ok(assembler.compile_ast(&ast)?)
 }
 
+/// Executes the specified program using the provided host and summarizes the execution.
+fn execute_with_host<H: Host>(
+    program: &Program,
+    stack_inputs: StackInputs,
+    host: H,
+    options: &ProvingOptions,
+) -> Result<ExecutionSummary, VmError> {
+    let trace = execute(program, stack_inputs, host, *options.execution_options())?;
+    let trace_len_summary = trace.trace_len_summary();
+
+    Ok(ExecutionSummary {
+        outputs: StackOutputsString::from(trace.stack_outputs()),
+        trace: TraceSummary::from(trace_len_summary),
+        estimate: ProvingEstimate::new(trace_len_summary, options),
+    })
+}
+
+/// Parses a hex-encoded program hash into a [Digest].
+fn parse_program_hash(program_hash: &str) -> Result<Digest, VmError> {
+    use vm_core::utils::{Deserializable, SliceReader};
+
+    let program_hash_bytes = hex::decode(program_hash)
+        .map_err(|e| VmError::input(format!("failed to decode program hash - {e}")))?;
+    Digest::read_from(&mut SliceReader::new(&program_hash_bytes))
+        .map_err(|e| VmError::input(format!("failed to deserialize program hash - {e}")))
+}
+
+/// Compiles the kernel defined by the specified source; an empty kernel is returned if no source
+/// is provided.
+fn parse_kernel(kernel_code: Option<&str>) -> Result<Kernel, VmError> {
+    match kernel_code {
+        Some(kernel_code) => Ok(Assembler::default().with_kernel(kernel_code)?.kernel().clone()),
+        None => Ok(Kernel::default()),
+    }
+}
+
+/// Parses a comma-separated list of u64 values; an empty string yields an empty list.
+fn parse_u64_list(values: &str, description: &str) -> Result<Vec<u64>, VmError> {
+    if values.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let values: Vec<&str> = values.split(',').collect();
+    parse_u64_values(&values, description)
+}
+
+/// Parses a list of u64 values from their string representations.
+fn parse_u64_values<S: AsRef<str>>(values: &[S], description: &str) -> Result<Vec<u64>, VmError> {
+    values
+        .iter()
+        .map(|v| {
+            let v = v.as_ref().trim();
+            v.parse::<u64>()
+                .map_err(|e| VmError::input(format!("failed to parse {description} `{v}` - {e}")))
+        })
+        .collect()
+}
+
+/// Proves a program against JSON-encoded inputs and options as received through the wasm API.
+fn prove_envelope_from_json(
+    program_code: &str,
+    inputs: &str,
+    kernel_code: Option<String>,
+    libraries: Option<Vec<u8>>,
+    options: Option<String>,
+) -> Result<ProofEnvelope, VmError> {
+    let inputs = InputFile::from_json(inputs).map_err(VmError::input)?;
+    let libraries = parse_libraries(libraries.as_deref().unwrap_or_default())?;
+    let options = parse_proving_options(options.as_deref())?;
+    prove_program_envelope(program_code, &inputs, kernel_code.as_deref(), &libraries, options)
+}
+
+/// Executes a program against JSON-encoded inputs and options as received through the wasm API.
+fn execute_program_from_json(
+    program_code: &str,
+    inputs: &str,
+    kernel_code: Option<String>,
+    libraries: Option<Vec<u8>>,
+    options: Option<String>,
+) -> Result<ExecutionSummary, VmError> {
+    let inputs = InputFile::from_json(inputs).map_err(VmError::input)?;
+    let libraries = parse_libraries(libraries.as_deref().unwrap_or_default())?;
+    let options = parse_proving_options(options.as_deref())?;
+    execute_program_with_inputs(program_code, &inputs, kernel_code.as_deref(), &libraries, options)
+}
 
 #[wasm_bindgen]
 pub fn init_panic_hook() {
     console_error_panic_hook::set_once();
-}
\ No newline at end of file
+}
@@ -23,6 +23,9 @@ pub enum Actions {
     Bundle(cli::BundleCmd),
     Debug(cli::DebugCmd),
     Example(examples::ExampleOptions),
+    Fmt(cli::FormatCmd),
+    Lint(cli::LintCmd),
+    Profile(tools::Profile),
     Prove(cli::ProveCmd),
     Run(cli::RunCmd),
     Verify(cli::VerifyCmd),
@@ -39,6 +42,9 @@ impl Cli {
             Actions::Bundle(compile) => compile.execute(),
             Actions::Debug(debug) => debug.execute(),
             Actions::Example(example) => example.execute(),
+            Actions::Fmt(fmt) => fmt.execute(),
+            Actions::Lint(lint) => lint.execute(),
+            Actions::Profile(profile) => profile.execute(),
             Actions::Prove(prove) => prove.execute(),
             Actions::Run(run) => run.execute(),
             Actions::Verify(verify) => verify.execute(),
@@ -0,0 +1,83 @@
+use super::{ExecutionOptions, ProvingOptions, VmError};
+use serde::{Deserialize, Serialize};
+
+/// The default number of cycles a program is expected to consume; this matches the minimum
+/// length of the execution trace.
+const DEFAULT_EXPECTED_CYCLES: u32 = 64;
+
+// SECURITY LEVEL
+// ================================================================================================
+
+/// Target security level of the proofs generated through the wasm API.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SecurityLevel {
+    #[default]
+    #[serde(rename = "96bits")]
+    Bits96,
+    #[serde(rename = "128bits")]
+    Bits128,
+}
+
+// PROVING CONFIG
+// ================================================================================================
+
+/// Proving options accepted by the wasm API.
+///
+/// The config is deserialized from a JSON object in which every field is optional, for example:
+/// `{ "security": "128bits", "recursive": true, "max_cycles": 1048576, "expected_cycles": 1024 }`.
+/// Missing fields take the same defaults as [ProvingOptions::default] and
+/// [ExecutionOptions::default].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ProvingConfig {
+    /// Target security level of the proof.
+    pub security: SecurityLevel,
+    /// Whether the proof should be generated using RPO so that it can be verified recursively.
+    pub recursive: bool,
+    /// Maximum number of cycles the program is allowed to consume; unlimited if not set.
+    pub max_cycles: Option<u32>,
+    /// Number of cycles the program is expected to consume.
+    pub expected_cycles: u32,
+}
+
+impl Default for ProvingConfig {
+    fn default() -> Self {
+        Self {
+            security: SecurityLevel::default(),
+            recursive: false,
+            max_cycles: None,
+            expected_cycles: DEFAULT_EXPECTED_CYCLES,
+        }
+    }
+}
+
+impl ProvingConfig {
+    /// Parses the config from its JSON representation.
+    pub fn from_json(config: &str) -> Result<Self, VmError> {
+        serde_json::from_str(config)
+            .map_err(|e| VmError::input(format!("failed to deserialize proving options - {e}")))
+    }
+
+    /// Builds [ProvingOptions] described by this config.
+    ///
+    /// # Errors
+    /// Returns an error if the maximum and expected number of cycles are inconsistent.
+    pub fn to_proving_options(&self) -> Result<ProvingOptions, VmError> {
+        let exec_options =
+            ExecutionOptions::new(self.max_cycles, self.expected_cycles).map_err(VmError::input)?;
+        Ok(match self.security {
+            SecurityLevel::Bits96 => ProvingOptions::with_96_bit_security(self.recursive),
+            SecurityLevel::Bits128 => ProvingOptions::with_128_bit_security(self.recursive),
+        }
+        .with_execution_options(exec_options))
+    }
+}
+
+/// Parses optional JSON-encoded proving options into [ProvingOptions], falling back to the
+/// defaults when no options are provided.
+pub(crate) fn parse_proving_options(options: Option<&str>) -> Result<ProvingOptions, VmError> {
+    match options {
+        Some(options) => ProvingConfig::from_json(options)?.to_proving_options(),
+        None => Ok(ProvingOptions::default()),
+    }
+}
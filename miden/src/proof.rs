@@ -0,0 +1,265 @@
+use super::{
+    utils::{
+        collections::Vec,
+        string::{String, ToString},
+        ByteReader, ByteWriter, Deserializable, DeserializationError, Serializable, SliceReader,
+    },
+    Digest, ExecutionProof, HashFunction, StackInputs, StackOutputs, VmError,
+};
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use core::{fmt, str::FromStr};
+
+// CONSTANTS
+// ================================================================================================
+
+/// Magic bytes with which every binary proof envelope starts.
+pub const PROOF_ENVELOPE_MAGIC: [u8; 4] = *b"MPRF";
+
+/// Version of the proof envelope layout produced by this crate. Envelopes with a different
+/// version are rejected during deserialization.
+pub const PROOF_ENVELOPE_VERSION: u8 = 1;
+
+/// Prefix of a hex-encoded proof envelope (the hex encoding of [PROOF_ENVELOPE_MAGIC]).
+const HEX_PREFIX: &str = "4d505246";
+
+/// Prefix of a base64-encoded proof envelope (the base64 encoding of the first three bytes of
+/// [PROOF_ENVELOPE_MAGIC]).
+const BASE64_PREFIX: &str = "TVBS";
+
+// PROOF ENCODING
+// ================================================================================================
+
+/// Encodings in which a [ProofEnvelope] can be written out.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ProofEncoding {
+    /// Raw binary serialization of the envelope.
+    #[default]
+    Binary,
+    /// Lowercase hex encoding of the binary serialization.
+    Hex,
+    /// Standard (padded) base64 encoding of the binary serialization.
+    Base64,
+}
+
+impl FromStr for ProofEncoding {
+    type Err = String;
+
+    fn from_str(encoding: &str) -> Result<Self, Self::Err> {
+        match encoding {
+            "binary" | "bin" => Ok(Self::Binary),
+            "hex" => Ok(Self::Hex),
+            "base64" => Ok(Self::Base64),
+            other => Err(format!("{other} is not a valid proof encoding")),
+        }
+    }
+}
+
+impl fmt::Display for ProofEncoding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Binary => write!(f, "binary"),
+            Self::Hex => write!(f, "hex"),
+            Self::Base64 => write!(f, "base64"),
+        }
+    }
+}
+
+// PROOF ENVELOPE
+// ================================================================================================
+
+/// A self-describing container for a proof of program execution.
+///
+/// The envelope carries everything, except for the kernel, that a verifier needs to check the
+/// proof: the hash of the proven program, its public stack inputs and outputs, and the proof
+/// itself (which records the hash function used to generate it).
+///
+/// The binary layout is as follows:
+/// - 4 magic bytes ([PROOF_ENVELOPE_MAGIC]) followed by a one-byte layout version.
+/// - the program hash, stack inputs and stack outputs in their binary serialization.
+/// - the length of the proof bytes as a u32, followed by [ExecutionProof::to_bytes].
+///
+/// The magic bytes and the version allow envelopes to be recognized and envelopes produced by
+/// incompatible versions of the VM to be rejected, rather than mis-decoded.
+#[derive(Debug, Clone)]
+pub struct ProofEnvelope {
+    program_hash: Digest,
+    stack_inputs: StackInputs,
+    stack_outputs: StackOutputs,
+    proof: ExecutionProof,
+}
+
+impl ProofEnvelope {
+    // CONSTRUCTORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns a new envelope for a proof of execution of the program with the specified hash.
+    pub fn new(
+        program_hash: Digest,
+        stack_inputs: StackInputs,
+        stack_outputs: StackOutputs,
+        proof: ExecutionProof,
+    ) -> Self {
+        Self {
+            program_hash,
+            stack_inputs,
+            stack_outputs,
+            proof,
+        }
+    }
+
+    // PUBLIC ACCESSORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the hash of the program whose execution is proven.
+    pub fn program_hash(&self) -> Digest {
+        self.program_hash
+    }
+
+    /// Returns the stack inputs against which the program was executed.
+    pub fn stack_inputs(&self) -> &StackInputs {
+        &self.stack_inputs
+    }
+
+    /// Returns the stack outputs of the program execution.
+    pub fn stack_outputs(&self) -> &StackOutputs {
+        &self.stack_outputs
+    }
+
+    /// Returns the proof of program execution.
+    pub fn proof(&self) -> &ExecutionProof {
+        &self.proof
+    }
+
+    /// Returns the hash function used to generate the proof.
+    pub fn hash_fn(&self) -> HashFunction {
+        self.proof.hash_fn()
+    }
+
+    // ENCODING / DECODING
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the representation of this envelope in the specified encoding; hex and base64
+    /// encodings are returned as ASCII bytes.
+    pub fn encode(&self, encoding: ProofEncoding) -> Vec<u8> {
+        match encoding {
+            ProofEncoding::Binary => self.to_bytes(),
+            ProofEncoding::Hex => self.to_hex().into_bytes(),
+            ProofEncoding::Base64 => self.to_base64().into_bytes(),
+        }
+    }
+
+    /// Returns the hex encoding of this envelope.
+    pub fn to_hex(&self) -> String {
+        hex::encode(self.to_bytes())
+    }
+
+    /// Returns the base64 encoding of this envelope.
+    pub fn to_base64(&self) -> String {
+        BASE64.encode(self.to_bytes())
+    }
+
+    /// Decodes an envelope from its binary, hex or base64 representation.
+    ///
+    /// The encoding is detected from the magic bytes at the start of the envelope; leading and
+    /// trailing whitespace is ignored for the text encodings.
+    ///
+    /// # Errors
+    /// Returns an error if the encoding cannot be detected, if the envelope was produced by an
+    /// incompatible version of the VM, or if the envelope is malformed.
+    pub fn decode(bytes: &[u8]) -> Result<Self, VmError> {
+        let bytes = match Self::detect_encoding(bytes)? {
+            ProofEncoding::Binary => return Self::from_bytes(bytes),
+            ProofEncoding::Hex => hex::decode(trim_ascii_whitespace(bytes)).map_err(|e| {
+                VmError::serialization(format!("failed to decode hex proof envelope - {e}"))
+            })?,
+            ProofEncoding::Base64 => BASE64.decode(trim_ascii_whitespace(bytes)).map_err(|e| {
+                VmError::serialization(format!("failed to decode base64 proof envelope - {e}"))
+            })?,
+        };
+        Self::from_bytes(&bytes)
+    }
+
+    /// Deserializes an envelope from its binary representation.
+    ///
+    /// # Errors
+    /// Returns an error if the envelope was produced by an incompatible version of the VM, or if
+    /// the envelope is malformed.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, VmError> {
+        let mut source = SliceReader::new(bytes);
+        let envelope = Self::read_from(&mut source).map_err(|e| {
+            VmError::serialization(format!("failed to deserialize proof envelope - {e}"))
+        })?;
+        if source.has_more_bytes() {
+            return Err(VmError::serialization("proof envelope contains trailing bytes"));
+        }
+        Ok(envelope)
+    }
+
+    /// Returns the encoding of the provided envelope representation.
+    pub fn detect_encoding(bytes: &[u8]) -> Result<ProofEncoding, VmError> {
+        if bytes.starts_with(&PROOF_ENVELOPE_MAGIC) {
+            return Ok(ProofEncoding::Binary);
+        }
+
+        let text = trim_ascii_whitespace(bytes);
+        if text.len() >= HEX_PREFIX.len()
+            && text[..HEX_PREFIX.len()].eq_ignore_ascii_case(HEX_PREFIX.as_bytes())
+        {
+            Ok(ProofEncoding::Hex)
+        } else if text.starts_with(BASE64_PREFIX.as_bytes()) {
+            Ok(ProofEncoding::Base64)
+        } else {
+            Err(VmError::serialization("data is not a proof envelope"))
+        }
+    }
+}
+
+impl Serializable for ProofEnvelope {
+    fn write_into<W: ByteWriter>(&self, target: &mut W) {
+        target.write_bytes(&PROOF_ENVELOPE_MAGIC);
+        target.write_u8(PROOF_ENVELOPE_VERSION);
+        self.program_hash.write_into(target);
+        self.stack_inputs.write_into(target);
+        self.stack_outputs.write_into(target);
+
+        let proof_bytes = self.proof.to_bytes();
+        let proof_len =
+            u32::try_from(proof_bytes.len()).expect("proof does not fit into a proof envelope");
+        target.write_u32(proof_len);
+        target.write_bytes(&proof_bytes);
+    }
+}
+
+impl Deserializable for ProofEnvelope {
+    fn read_from<R: ByteReader>(source: &mut R) -> Result<Self, DeserializationError> {
+        let magic: [u8; 4] = source.read_array()?;
+        if magic != PROOF_ENVELOPE_MAGIC {
+            return Err(DeserializationError::InvalidValue("invalid magic bytes".to_string()));
+        }
+        let version = source.read_u8()?;
+        if version != PROOF_ENVELOPE_VERSION {
+            return Err(DeserializationError::InvalidValue(format!(
+                "unsupported envelope version {version}, expected version {PROOF_ENVELOPE_VERSION}"
+            )));
+        }
+
+        let program_hash = Digest::read_from(source)?;
+        let stack_inputs = StackInputs::read_from(source)?;
+        let stack_outputs = StackOutputs::read_from(source)?;
+
+        let proof_len = source.read_u32()? as usize;
+        let proof = ExecutionProof::from_bytes(&source.read_vec(proof_len)?)?;
+
+        Ok(Self::new(program_hash, stack_inputs, stack_outputs, proof))
+    }
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Returns the provided bytes without leading and trailing ASCII whitespace.
+fn trim_ascii_whitespace(bytes: &[u8]) -> &[u8] {
+    let start = bytes.iter().position(|b| !b.is_ascii_whitespace()).unwrap_or(bytes.len());
+    let end = bytes.iter().rposition(|b| !b.is_ascii_whitespace()).map_or(start, |i| i + 1);
+    &bytes[start..end]
+}
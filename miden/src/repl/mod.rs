@@ -1,7 +1,7 @@
 use super::ProgramError;
 use miden::{
     math::{Felt, StarkField},
-    DefaultHost, StackInputs, Word,
+    DefaultHost, ExecutionOptions, StackInputs, Word,
 };
 use rustyline::{error::ReadlineError, DefaultEditor};
 
@@ -269,16 +269,16 @@ fn execute(program: String) -> Result<(Vec<(u64, Word)>, Vec<Felt>), ProgramErro
     let stack_inputs = StackInputs::default();
     let host = DefaultHost::default();
 
-    let state_iter = processor::execute_iter(&program, stack_inputs, host);
-    let (system, _, stack, chiplets, err) = state_iter.into_parts();
-    if let Some(err) = err {
-        return Err(ProgramError::ExecutionError(err));
-    }
+    // the program is executed without generating an execution trace since only the final state
+    // of the VM is displayed
+    let output = miden::run(&program, stack_inputs, host, ExecutionOptions::default())
+        .map_err(ProgramError::ExecutionError)?;
 
-    // loads the memory at the latest clock cycle.
-    let mem_state = chiplets.get_mem_state_at(0, system.clk());
-    // loads the stack along with the overflow values at the latest clock cycle.
-    let stack_state = stack.get_state_at(system.clk());
+    // loads the memory at the end of the execution.
+    let mem_state = output.get_mem_state(0);
+    // loads the stack along with the overflow values at the end of the execution.
+    let stack_state =
+        output.stack_outputs().stack().iter().map(|&value| Felt::new(value)).collect();
 
     Ok((mem_state, stack_state))
 }
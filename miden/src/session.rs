@@ -0,0 +1,324 @@
+use super::{
+    compile_program_with, execute, parse_libraries, parse_proving_options,
+    utils::{
+        collections::Vec,
+        string::{String, ToString},
+        Box,
+    },
+    Digest, InputFile, MaslLibrary, NormalInput, Program, ProofEncoding, ProofEnvelope,
+    ProvingOptions, StackInputs, VMResult, VmError,
+};
+use core::{fmt, mem};
+use prover::{ExecutionProofGenerator, ProvingStage};
+use wasm_bindgen::prelude::*;
+
+// PROVING PHASE
+// ================================================================================================
+
+/// Phases of a [ProvingSession], in the order in which they are executed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProvingPhase {
+    /// Compile the program source code into a program MAST.
+    Assembly,
+    /// Execute the program to generate an execution trace.
+    Execution,
+    /// Execute a single stage of STARK proof generation.
+    Proving(ProvingStage),
+    /// The proof has been generated.
+    Complete,
+}
+
+impl ProvingPhase {
+    /// Number of phases which need to be executed to generate a proof.
+    pub const NUM_PHASES: usize = 2 + ProvingStage::NUM_STAGES;
+
+    /// Returns the number of phases which are executed before this phase.
+    pub const fn index(&self) -> usize {
+        match self {
+            Self::Assembly => 0,
+            Self::Execution => 1,
+            Self::Proving(stage) => 2 + stage.index(),
+            Self::Complete => Self::NUM_PHASES,
+        }
+    }
+}
+
+impl fmt::Display for ProvingPhase {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Assembly => write!(f, "assembly"),
+            Self::Execution => write!(f, "execution"),
+            Self::Proving(stage) => write!(f, "{stage}"),
+            Self::Complete => write!(f, "complete"),
+        }
+    }
+}
+
+// PROVING SESSION
+// ================================================================================================
+
+/// A resumable proof generation request.
+///
+/// Proving a program with [prove_program_with_inputs](super::prove_program_with_inputs) blocks
+/// until the proof is generated, which may take minutes for large programs. A session instead
+/// splits the work into [ProvingPhase]s and executes a single phase on every call to `step()`, so
+/// that a JavaScript caller can report `progress()` and yield to the event loop between phases.
+#[wasm_bindgen]
+pub struct ProvingSession {
+    state: SessionState,
+}
+
+enum SessionState {
+    Assembly {
+        program_code: String,
+        kernel_code: Option<String>,
+        libraries: Vec<MaslLibrary>,
+        inputs: NormalInput,
+        options: ProvingOptions,
+    },
+    Execution {
+        program: Program,
+        inputs: NormalInput,
+        options: ProvingOptions,
+    },
+    Proving {
+        program_hash: Digest,
+        stack_inputs: StackInputs,
+        generator: Box<ExecutionProofGenerator>,
+    },
+    Complete(ProofEnvelope),
+    Failed {
+        phase: ProvingPhase,
+        error: VmError,
+    },
+}
+
+impl ProvingSession {
+    // CONSTRUCTOR
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns a new session which proves the specified program against the provided inputs.
+    ///
+    /// The arguments are the same as for
+    /// [prove_program_with_inputs](super::prove_program_with_inputs); no work is done until
+    /// [step()](Self::step) is called.
+    ///
+    /// # Errors
+    /// Returns an error if the inputs are malformed.
+    pub fn new(
+        program_code: &str,
+        inputs: &InputFile,
+        kernel_code: Option<&str>,
+        libraries: Vec<MaslLibrary>,
+        options: ProvingOptions,
+    ) -> Result<Self, VmError> {
+        let state = SessionState::Assembly {
+            program_code: program_code.into(),
+            kernel_code: kernel_code.map(String::from),
+            libraries,
+            inputs: NormalInput::try_from(inputs)?,
+            options,
+        };
+        Ok(Self { state })
+    }
+
+    // PUBLIC ACCESSORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the phase which will be executed on the next call to [step()](Self::step); for a
+    /// failed session, this is the phase which failed.
+    pub fn phase(&self) -> ProvingPhase {
+        match &self.state {
+            SessionState::Assembly { .. } => ProvingPhase::Assembly,
+            SessionState::Execution { .. } => ProvingPhase::Execution,
+            SessionState::Proving { generator, .. } => ProvingPhase::Proving(generator.stage()),
+            SessionState::Complete(_) => ProvingPhase::Complete,
+            SessionState::Failed { phase, .. } => *phase,
+        }
+    }
+
+    /// Returns the envelope containing the generated proof, or None if the proof has not been
+    /// generated yet.
+    pub fn envelope(&self) -> Option<&ProofEnvelope> {
+        match &self.state {
+            SessionState::Complete(envelope) => Some(envelope),
+            _ => None,
+        }
+    }
+
+    // PROOF GENERATION
+    // --------------------------------------------------------------------------------------------
+
+    /// Executes the next phase of the session and returns the phase which was executed.
+    ///
+    /// # Errors
+    /// Returns an error if the phase fails, in which case the same error is returned from all
+    /// subsequent calls. An error is also returned if the proof has already been generated.
+    pub fn step(&mut self) -> Result<ProvingPhase, VmError> {
+        let phase = self.phase();
+        match &self.state {
+            SessionState::Complete(_) => {
+                return Err(VmError::input("proof generation has already been completed"))
+            }
+            SessionState::Failed { error, .. } => return Err(error.clone()),
+            _ => (),
+        }
+
+        let error = VmError::input("proof generation was interrupted");
+        let state = mem::replace(&mut self.state, SessionState::Failed { phase, error });
+        match Self::advance(state) {
+            Ok(state) => {
+                self.state = state;
+                Ok(phase)
+            }
+            Err(error) => {
+                self.state = SessionState::Failed {
+                    phase,
+                    error: error.clone(),
+                };
+                Err(error)
+            }
+        }
+    }
+
+    // HELPER METHODS
+    // --------------------------------------------------------------------------------------------
+
+    /// Executes the phase corresponding to the provided state and returns the resulting state.
+    fn advance(state: SessionState) -> Result<SessionState, VmError> {
+        match state {
+            SessionState::Assembly {
+                program_code,
+                kernel_code,
+                libraries,
+                inputs,
+                options,
+            } => {
+                let program =
+                    compile_program_with(&program_code, kernel_code.as_deref(), &libraries)?;
+                Ok(SessionState::Execution {
+                    program,
+                    inputs,
+                    options,
+                })
+            }
+            SessionState::Execution {
+                program,
+                inputs,
+                options,
+            } => {
+                let stack_inputs = inputs.stack_inputs.clone();
+                let trace = execute(
+                    &program,
+                    inputs.stack_inputs,
+                    inputs.host,
+                    *options.execution_options(),
+                )?;
+                let generator = ExecutionProofGenerator::new(trace, stack_inputs.clone(), options)?;
+                let generator = Box::new(generator);
+                Ok(SessionState::Proving {
+                    program_hash: program.hash(),
+                    stack_inputs,
+                    generator,
+                })
+            }
+            SessionState::Proving {
+                program_hash,
+                stack_inputs,
+                mut generator,
+            } => {
+                generator.step()?;
+                if !generator.is_complete() {
+                    return Ok(SessionState::Proving {
+                        program_hash,
+                        stack_inputs,
+                        generator,
+                    });
+                }
+
+                let stack_outputs = generator.stack_outputs().clone();
+                let proof = generator.into_proof().expect("proof generation has been completed");
+                let envelope = ProofEnvelope::new(program_hash, stack_inputs, stack_outputs, proof);
+                Ok(SessionState::Complete(envelope))
+            }
+            SessionState::Complete(_) | SessionState::Failed { .. } => {
+                unreachable!("sessions in a final state cannot be advanced")
+            }
+        }
+    }
+
+    /// Returns the envelope containing the generated proof, or an error if the proof has not
+    /// been generated yet.
+    fn completed_envelope(&self) -> Result<&ProofEnvelope, VmError> {
+        self.envelope()
+            .ok_or_else(|| VmError::input("proof generation has not been completed"))
+    }
+}
+
+// WASM EXPORTS
+// ================================================================================================
+
+#[wasm_bindgen]
+impl ProvingSession {
+    /// Creates a session which proves the specified program; the arguments are the same as for
+    /// `execute_zk_program_with_inputs`.
+    #[wasm_bindgen(constructor)]
+    pub fn create(
+        program_code: String,
+        inputs: String,
+        kernel_code: Option<String>,
+        libraries: Option<Vec<u8>>,
+        options: Option<String>,
+    ) -> Result<ProvingSession, JsValue> {
+        let inputs = InputFile::from_json(&inputs).map_err(VmError::input)?;
+        let libraries = parse_libraries(libraries.as_deref().unwrap_or_default())?;
+        let options = parse_proving_options(options.as_deref())?;
+        Ok(Self::new(&program_code, &inputs, kernel_code.as_deref(), libraries, options)?)
+    }
+
+    /// Executes the next phase of the session and returns true if the proof has been generated.
+    #[wasm_bindgen(js_name = step)]
+    pub fn step_js(&mut self) -> Result<bool, JsValue> {
+        self.step()?;
+        Ok(self.is_complete())
+    }
+
+    /// Returns the name of the phase which will be executed on the next call to `step()`.
+    #[wasm_bindgen(js_name = phase)]
+    pub fn phase_name(&self) -> String {
+        self.phase().to_string()
+    }
+
+    /// Returns the fraction of phases which have been executed, as a number between 0 and 1.
+    pub fn progress(&self) -> f64 {
+        self.phase().index() as f64 / ProvingPhase::NUM_PHASES as f64
+    }
+
+    /// Returns true if the proof has been generated.
+    pub fn is_complete(&self) -> bool {
+        self.envelope().is_some()
+    }
+
+    /// Returns the generated proof in the same JSON format as `execute_zk_program_with_inputs`.
+    pub fn result(&self) -> Result<String, JsValue> {
+        let result = VMResult::from(self.completed_envelope()?.clone());
+        Ok(serde_json::to_string(&result).map_err(VmError::serialization)?)
+    }
+
+    /// Returns the binary proof envelope of the generated proof.
+    pub fn envelope_bytes(&self) -> Result<Vec<u8>, JsValue> {
+        Ok(self.completed_envelope()?.encode(ProofEncoding::Binary))
+    }
+
+    /// Returns the proof envelope of the generated proof in the specified text encoding.
+    pub fn envelope_string(&self, encoding: String) -> Result<String, JsValue> {
+        let envelope = self.completed_envelope()?;
+        match encoding.parse().map_err(VmError::input)? {
+            ProofEncoding::Hex => Ok(envelope.to_hex()),
+            ProofEncoding::Base64 => Ok(envelope.to_base64()),
+            ProofEncoding::Binary => {
+                Err(VmError::input("binary proof envelopes cannot be returned as a string").into())
+            }
+        }
+    }
+}
@@ -6,6 +6,9 @@ use processor::{AsmOpInfo, TraceLenSummary};
 use std::{fs, path::PathBuf};
 use stdlib::StdLibrary;
 
+mod profile;
+pub use profile::Profile;
+
 // CLI
 // ================================================================================================
 
@@ -336,7 +339,7 @@ mod tests {
         let stack_inputs = StackInputs::default();
         let host = DefaultHost::default();
         let execution_details = super::analyze(source, stack_inputs, host);
-        let expected_error = "Assembly Error: ParsingError(\"unexpected token: expected 'begin' but was 'mem_storew.1'\")";
+        let expected_error = "Assembly Error: ParsingError(parsing error at [1:28]: unexpected token: expected 'begin' but was 'mem_storew.1')";
         assert_eq!(execution_details.err().unwrap().to_string(), expected_error);
     }
 }
@@ -0,0 +1,76 @@
+use super::{InputFile, ProgramError};
+use clap::Parser;
+use miden::{Assembler, DefaultHost, Host, StackInputs};
+use processor::Profile as ExecutionProfile;
+use std::{fs, path::PathBuf};
+use stdlib::StdLibrary;
+
+// CLI
+// ================================================================================================
+
+/// Defines cli interface
+#[derive(Debug, Clone, Parser)]
+#[clap(about = "Profile cycles spent in each procedure of a miden program")]
+pub struct Profile {
+    /// Path to .masm assembly file
+    #[clap(short = 'a', long = "assembly", value_parser)]
+    assembly_file: PathBuf,
+    /// Path to .inputs file
+    #[clap(short = 'i', long = "input", value_parser)]
+    input_file: Option<PathBuf>,
+    /// Path to a file to write the collapsed stacks to (can be rendered with flamegraph tools)
+    #[clap(short = 'c', long = "collapsed", value_parser)]
+    collapsed_file: Option<PathBuf>,
+}
+
+/// Implements CLI execution logic
+impl Profile {
+    pub fn execute(&self) -> Result<(), String> {
+        let program = fs::read_to_string(&self.assembly_file)
+            .map_err(|e| format!("could not read masm file: {e}"))?;
+
+        // load input data from file
+        let input_data = InputFile::read(&self.input_file, &self.assembly_file)?;
+
+        // fetch the stack and program inputs from the arguments
+        let stack_inputs = input_data.parse_stack_inputs()?;
+        let host = DefaultHost::new(input_data.parse_advice_provider()?);
+
+        let profile = profile(program.as_str(), stack_inputs, host).map_err(|e| e.to_string())?;
+
+        println!("============================================================");
+        println!("Profiled {} program", self.assembly_file.display());
+        println!("{profile}");
+
+        if let Some(collapsed_file) = &self.collapsed_file {
+            fs::write(collapsed_file, profile.collapsed_stacks())
+                .map_err(|e| format!("failed to write collapsed stacks: {e}"))?;
+            println!("Collapsed stacks written to {}", collapsed_file.display());
+        }
+
+        Ok(())
+    }
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Returns the per-procedure profile of executing the provided program in debug mode.
+pub fn profile<H>(
+    program: &str,
+    stack_inputs: StackInputs,
+    host: H,
+) -> Result<ExecutionProfile, ProgramError>
+where
+    H: Host,
+{
+    let program = Assembler::default()
+        .with_debug_mode(true)
+        .with_library(&StdLibrary::default())
+        .map_err(ProgramError::AssemblyError)?
+        .compile(program)
+        .map_err(ProgramError::AssemblyError)?;
+
+    let vm_state_iterator = processor::execute_iter(&program, stack_inputs, host);
+    ExecutionProfile::new(vm_state_iterator).map_err(ProgramError::ExecutionError)
+}
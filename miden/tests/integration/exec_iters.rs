@@ -163,7 +163,8 @@ fn test_exec_iter() {
             ctx: 0,
             op: Some(Operation::Pad),
             asmop: Some(AsmOpInfo::new(
-                AssemblyOp::new("foo".to_string(), 4, "loc_store.0".to_string(), false),
+                AssemblyOp::new("foo".to_string(), 4, "loc_store.0".to_string(), false)
+                    .with_exec_path(vec!["#main".to_string()]),
                 1,
             )),
             stack: [0, 17, 12, 11, 10, 9, 8, 7, 6, 5, 4, 3, 2, 1, 0, 0, 0, 0].to_elements(),
@@ -175,7 +176,8 @@ fn test_exec_iter() {
             ctx: 0,
             op: Some(Operation::FmpAdd),
             asmop: Some(AsmOpInfo::new(
-                AssemblyOp::new("foo".to_string(), 4, "loc_store.0".to_string(), false),
+                AssemblyOp::new("foo".to_string(), 4, "loc_store.0".to_string(), false)
+                    .with_exec_path(vec!["#main".to_string()]),
                 2,
             )),
             stack: [2u64.pow(30) + 1, 17, 12, 11, 10, 9, 8, 7, 6, 5, 4, 3, 2, 1, 0, 0, 0]
@@ -188,7 +190,8 @@ fn test_exec_iter() {
             ctx: 0,
             op: Some(Operation::MStore),
             asmop: Some(AsmOpInfo::new(
-                AssemblyOp::new("foo".to_string(), 4, "loc_store.0".to_string(), false),
+                AssemblyOp::new("foo".to_string(), 4, "loc_store.0".to_string(), false)
+                    .with_exec_path(vec!["#main".to_string()]),
                 3,
             )),
             stack: [17, 12, 11, 10, 9, 8, 7, 6, 5, 4, 3, 2, 1, 0, 0, 0].to_elements(),
@@ -203,7 +206,8 @@ fn test_exec_iter() {
             ctx: 0,
             op: Some(Operation::Drop),
             asmop: Some(AsmOpInfo::new(
-                AssemblyOp::new("foo".to_string(), 4, "loc_store.0".to_string(), false),
+                AssemblyOp::new("foo".to_string(), 4, "loc_store.0".to_string(), false)
+                    .with_exec_path(vec!["#main".to_string()]),
                 4,
             )),
             stack: [12, 11, 10, 9, 8, 7, 6, 5, 4, 3, 2, 1, 0, 0, 0, 0, 0].to_elements(),
@@ -39,6 +39,7 @@ fn conditional_loop() {
 
     let test = build_test!(source, &[10]);
     test.expect_stack(&[55]);
+    test.expect_stack_fast(&[55]);
 
     // --- skipping the loop ----------------------------------------------------------------------
     let source = "begin dup eq.0 while.true add end end";
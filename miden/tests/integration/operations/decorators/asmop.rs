@@ -79,7 +79,8 @@ fn asmop_with_one_procedure() {
         VmStatePartial {
             clk: 2,
             asmop: Some(AsmOpInfo::new(
-                AssemblyOp::new("foo".to_string(), 2, "push.1".to_string(), false),
+                AssemblyOp::new("foo".to_string(), 2, "push.1".to_string(), false)
+                    .with_exec_path(vec!["#main".to_string()]),
                 1,
             )),
             op: Some(Operation::Pad),
@@ -87,7 +88,8 @@ fn asmop_with_one_procedure() {
         VmStatePartial {
             clk: 3,
             asmop: Some(AsmOpInfo::new(
-                AssemblyOp::new("foo".to_string(), 2, "push.1".to_string(), false),
+                AssemblyOp::new("foo".to_string(), 2, "push.1".to_string(), false)
+                    .with_exec_path(vec!["#main".to_string()]),
                 2,
             )),
             op: Some(Operation::Incr),
@@ -95,7 +97,8 @@ fn asmop_with_one_procedure() {
         VmStatePartial {
             clk: 4,
             asmop: Some(AsmOpInfo::new(
-                AssemblyOp::new("foo".to_string(), 1, "push.2".to_string(), false),
+                AssemblyOp::new("foo".to_string(), 1, "push.2".to_string(), false)
+                    .with_exec_path(vec!["#main".to_string()]),
                 1,
             )),
             op: Some(Operation::Push(Felt::new(2))),
@@ -103,7 +106,8 @@ fn asmop_with_one_procedure() {
         VmStatePartial {
             clk: 5,
             asmop: Some(AsmOpInfo::new(
-                AssemblyOp::new("foo".to_string(), 1, "add".to_string(), false),
+                AssemblyOp::new("foo".to_string(), 1, "add".to_string(), false)
+                    .with_exec_path(vec!["#main".to_string()]),
                 1,
             )),
             op: Some(Operation::Add),
@@ -0,0 +1,147 @@
+use assembly::Assembler;
+use processor::{
+    AdviceExtractor, DefaultHost, ExecutionError, ExecutionOptions, Host, HostResponse,
+    MemAdviceProvider, ProcessState,
+};
+use vm_core::{AdviceInjector, StackInputs, StarkField};
+
+// TEST HOST
+// ================================================================================================
+
+/// A host which records the events emitted by the program together with the clock cycle and the
+/// top of the stack at which they were emitted.
+#[derive(Default)]
+struct EventHost {
+    inner: DefaultHost<MemAdviceProvider>,
+    events: Vec<(u32, u32, u64)>,
+}
+
+impl Host for EventHost {
+    fn get_advice<S: ProcessState>(
+        &mut self,
+        process: &S,
+        extractor: AdviceExtractor,
+    ) -> Result<HostResponse, ExecutionError> {
+        self.inner.get_advice(process, extractor)
+    }
+
+    fn set_advice<S: ProcessState>(
+        &mut self,
+        process: &S,
+        injector: AdviceInjector,
+    ) -> Result<HostResponse, ExecutionError> {
+        self.inner.set_advice(process, injector)
+    }
+
+    fn on_event<S: ProcessState>(
+        &mut self,
+        process: &S,
+        event_id: u32,
+    ) -> Result<HostResponse, ExecutionError> {
+        self.events.push((event_id, process.clk(), process.get_stack_item(0).as_int()));
+        Ok(HostResponse::None)
+    }
+}
+
+// TESTS
+// ================================================================================================
+
+#[test]
+fn events_are_passed_to_host() {
+    let source = "
+        const.LOOP_EVENT=7
+        begin
+            push.1 emit.1
+            repeat.2
+                push.2 emit.LOOP_EVENT drop
+            end
+            push.3 emit.3
+        end";
+    let program = Assembler::default().compile(source).unwrap();
+
+    // events are handled by hosts passed by reference as well
+    let mut host = EventHost::default();
+    processor::execute(&program, StackInputs::default(), &mut host, ExecutionOptions::default())
+        .unwrap();
+
+    let events: Vec<(u32, u64)> = host.events.iter().map(|(id, _, top)| (*id, *top)).collect();
+    assert_eq!(vec![(1, 1), (7, 2), (7, 2), (3, 3)], events);
+
+    // events are emitted in the order of execution
+    assert!(host.events.windows(2).all(|w| w[0].1 < w[1].1));
+}
+
+#[test]
+fn events_are_ignored_by_default_host() {
+    let source = "begin push.1 emit.1 push.2 emit.2 add end";
+    let program = Assembler::default().compile(source).unwrap();
+    let trace = processor::execute(
+        &program,
+        StackInputs::default(),
+        DefaultHost::default(),
+        ExecutionOptions::default(),
+    )
+    .unwrap();
+    assert_eq!(3, trace.stack_outputs().stack()[0]);
+}
+
+#[test]
+fn trailing_events_are_passed_to_host() {
+    // both events follow the last operation of the span, and thus are executed after the span
+    // block is closed
+    let source = "begin push.1 emit.1 emit.2 end";
+    let program = Assembler::default().compile(source).unwrap();
+
+    let mut host = EventHost::default();
+    processor::execute(&program, StackInputs::default(), &mut host, ExecutionOptions::default())
+        .unwrap();
+    let events: Vec<(u32, u64)> = host.events.iter().map(|(id, _, top)| (*id, *top)).collect();
+    assert_eq!(vec![(1, 1), (2, 1)], events);
+
+    let mut fast_host = EventHost::default();
+    processor::run(&program, StackInputs::default(), &mut fast_host, ExecutionOptions::default())
+        .unwrap();
+    assert_eq!(host.events, fast_host.events);
+}
+
+#[test]
+fn events_without_following_operations() {
+    let sources = [
+        ("begin emit.1 end", vec![1]),
+        ("begin push.1 if.true emit.1 else push.2 end end", vec![1]),
+        (
+            "begin push.1 if.true push.2 end emit.1 push.0 while.true push.0 end end",
+            vec![1],
+        ),
+        ("begin push.1 while.true push.0 end emit.1 end", vec![1]),
+        ("proc.foo emit.1 end begin push.1 exec.foo drop end", vec![1]),
+        ("proc.foo push.1 drop end begin call.foo emit.1 end", vec![1]),
+        (
+            "proc.foo push.1 drop end begin emit.1 call.foo emit.2 call.foo emit.3 end",
+            vec![1, 2, 3],
+        ),
+    ];
+    for (source, expected) in sources {
+        let program = Assembler::default().compile(source).unwrap();
+        let mut host = EventHost::default();
+        processor::execute(
+            &program,
+            StackInputs::default(),
+            &mut host,
+            ExecutionOptions::default(),
+        )
+        .unwrap();
+        let events: Vec<u32> = host.events.iter().map(|(id, ..)| *id).collect();
+        assert_eq!(expected, events, "{source}");
+
+        let mut fast_host = EventHost::default();
+        processor::run(
+            &program,
+            StackInputs::default(),
+            &mut fast_host,
+            ExecutionOptions::default(),
+        )
+        .unwrap();
+        assert_eq!(host.events, fast_host.events, "{source}");
+    }
+}
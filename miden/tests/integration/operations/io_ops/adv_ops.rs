@@ -1,4 +1,9 @@
 use super::{build_op_test, build_test, TestError};
+use test_utils::{
+    crypto::{init_merkle_leaf, init_merkle_store, MerkleTree},
+    serde::{Deserializable, Serializable},
+    AdviceInputs, AdviceProvider,
+};
 use vm_core::{chiplets::hasher::apply_permutation, utils::ToElements, Felt, StarkField};
 
 // PUSHING VALUES ONTO THE STACK (PUSH)
@@ -112,3 +117,61 @@ fn adv_pipe_with_hperm() {
     let test = build_test!(source, &[], &advice_stack);
     test.expect_stack(&final_stack);
 }
+
+// ADVICE SNAPSHOTS
+// ================================================================================================
+
+#[test]
+fn advice_snapshot_seeds_next_execution() {
+    let index = 5;
+    let (leaves, store) = init_merkle_store(&[1, 2, 3, 4, 5, 6, 7, 8]);
+    let tree = MerkleTree::new(leaves.clone()).unwrap();
+
+    let new_node = init_merkle_leaf(9);
+    let mut new_leaves = leaves;
+    new_leaves[index] = new_node;
+    let new_tree = MerkleTree::new(new_leaves).unwrap();
+
+    // the first program consumes an element of the advice stack, updates a leaf of the Merkle
+    // tree, and inserts two words into the advice map
+    let source = "
+        begin
+            adv_push.1 drop
+            mtree_set dropw dropw
+            push.1.2.3.4 push.5.6.7.8 adv.insert_hdword dropw dropw
+        end";
+    let mut stack_inputs = new_node.iter().map(|v| v.as_int()).collect::<Vec<_>>();
+    stack_inputs.extend(tree.root().iter().map(|v| v.as_int()));
+    stack_inputs.extend([index as u64, tree.depth() as u64]);
+    let test = build_test!(source, &stack_inputs, &[100, 101, 102], store);
+    let process = test.execute_process().unwrap();
+
+    let snapshot = process.host.borrow().advice_provider().snapshot().unwrap();
+    assert_eq!(snapshot.stack(), &[Felt::new(101), Felt::new(102)]);
+
+    // the second program reads the leftover advice stack, the values inserted into the advice map,
+    // and the updated leaf of the Merkle tree
+    let source = "
+        begin
+            mtree_get swapw dropw
+            push.1.2.3.4 push.5.6.7.8 hmerge adv.push_mapval dropw
+            adv_push.10
+        end";
+    let mut stack_inputs = new_tree.root().iter().map(|v| v.as_int()).collect::<Vec<_>>();
+    stack_inputs.extend([index as u64, new_tree.depth() as u64]);
+    let mut test = build_test!(source, &stack_inputs);
+    test.advice_inputs = AdviceInputs::read_from_bytes(&snapshot.to_bytes()).unwrap();
+
+    let mut final_stack = vec![102, 101, 8, 7, 6, 5, 4, 3, 2, 1];
+    final_stack.extend(new_node.iter().rev().map(|v| v.as_int()));
+    test.expect_stack(&final_stack);
+}
+
+#[test]
+fn advice_snapshot_version_mismatch() {
+    let mut bytes = AdviceInputs::default().with_stack([Felt::new(1)]).to_bytes();
+    assert_eq!(bytes[0], AdviceInputs::SERIALIZATION_VERSION);
+
+    bytes[0] += 1;
+    assert!(AdviceInputs::read_from_bytes(&bytes).is_err());
+}
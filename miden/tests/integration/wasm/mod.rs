@@ -0,0 +1,509 @@
+use assembly::{LibraryNamespace, MaslLibrary, Version};
+use miden_vm::{
+    convert_stackinputs,
+    crypto::{MerkleTree, NodeIndex},
+    execute_program_with_host, execute_program_with_inputs, parse_libraries, program_hash,
+    program_hash_with, prove_program, prove_program_envelope, prove_program_with_host,
+    prove_program_with_inputs, verify_program, verify_program_with_inputs, verify_proof_envelope,
+    HashFunction, HostReply, HostRequest, InputFile, JsHost, ProofEncoding, ProofEnvelope,
+    ProvingConfig, ProvingOptions, ProvingPhase, ProvingSession, ProvingStage, VmErrorKind,
+    PROOF_ENVELOPE_VERSION,
+};
+use std::{cell::RefCell, rc::Rc};
+use vm_core::{
+    utils::{IntoBytes, Serializable},
+    Felt, StarkField, Word, ZERO,
+};
+
+// WASM API ERRORS
+// ================================================================================================
+
+#[test]
+fn parsing_error_has_location() {
+    let err = program_hash("begin\n    push.1\n    pushh.2\nend").unwrap_err();
+    assert_eq!(VmErrorKind::Parsing, err.kind);
+    assert_eq!(3, err.location.unwrap().line);
+    assert!(err.clk.is_none());
+}
+
+#[test]
+fn assembly_error() {
+    let err = program_hash("use.std::math::u64\nbegin exec.u64::foo end").unwrap_err();
+    assert_eq!(VmErrorKind::Assembly, err.kind);
+    assert!(err.location.is_none());
+}
+
+#[test]
+fn input_error() {
+    let err = convert_stackinputs("1,a".to_string(), String::new()).unwrap_err();
+    assert_eq!(VmErrorKind::Input, err.kind);
+
+    let err = convert_stackinputs(String::new(), "1,,2".to_string()).unwrap_err();
+    assert_eq!(VmErrorKind::Input, err.kind);
+}
+
+#[test]
+fn execution_error_has_clk() {
+    let err = prove_program(
+        "begin push.1 push.0 div end",
+        String::new(),
+        String::new(),
+        ProvingOptions::default(),
+    )
+    .unwrap_err();
+    assert_eq!(VmErrorKind::Execution, err.kind);
+    assert!(err.clk.is_some());
+
+    let json = err.to_json();
+    assert!(json.contains("\"kind\":\"execution\""));
+}
+
+#[test]
+fn prove_program_ok() {
+    let result =
+        prove_program("begin add end", "1,2".to_string(), String::new(), ProvingOptions::default())
+            .unwrap();
+    assert_eq!("3", result.outputs.stack[0]);
+}
+
+#[test]
+fn verify_program_ok() {
+    let source = "begin push.3 mul end";
+    let result =
+        prove_program(source, "2".to_string(), String::new(), ProvingOptions::default()).unwrap();
+    let hash = program_hash(source).unwrap();
+    assert!(verify_program(&hash, "2", result.clone()).is_ok());
+
+    // verification against different inputs must fail
+    let err = verify_program(&hash, "3", result.clone()).unwrap_err();
+    assert_eq!(VmErrorKind::Verification, err.kind);
+
+    // a malformed program hash is an input error
+    let err = verify_program("abcd", "2", result).unwrap_err();
+    assert_eq!(VmErrorKind::Input, err.kind);
+}
+
+// JSON INPUTS
+// ================================================================================================
+
+#[test]
+fn prove_with_advice_map_and_merkle_store() {
+    let key = [1, 2, 3, 4].map(Felt::new).into_bytes();
+    let key = hex::encode(key);
+    let inputs = InputFile::from_json(
+        &r#"{
+            "operand_stack": ["1", "2", "3", "4"],
+            "advice_map": {
+                "ADVICE_KEY": [7, 8]
+            },
+            "merkle_store": [
+                {
+                    "merkle_tree": [
+                        "0x1400000000000000000000000000000000000000000000000000000000000000",
+                        "0x1500000000000000000000000000000000000000000000000000000000000000"
+                    ]
+                }
+            ]
+        }"#
+        .replace("ADVICE_KEY", &key),
+    )
+    .unwrap();
+
+    // the key of the advice map entry is the top word of the stack
+    let source = "begin adv.push_mapval dropw adv_push.2 end";
+    let result =
+        prove_program_with_inputs(source, &inputs, None, &[], ProvingOptions::default()).unwrap();
+    assert_eq!(&["8", "7"], &result.outputs.stack[..2]);
+
+    let hash = program_hash(source).unwrap();
+    assert!(verify_program_with_inputs(&hash, &inputs, None, result).is_ok());
+}
+
+#[test]
+fn prove_with_kernel() {
+    let kernel = "export.foo\n    add\nend";
+    let source = "begin syscall.foo end";
+    let inputs = InputFile::from_json(r#"{ "operand_stack": ["1", "2"] }"#).unwrap();
+
+    let result =
+        prove_program_with_inputs(source, &inputs, Some(kernel), &[], ProvingOptions::default())
+            .unwrap();
+    assert_eq!("3", result.outputs.stack[0]);
+
+    // the kernel is part of the public inputs, so verification without it must fail
+    let hash = program_hash_with(source, Some(kernel), &[]).unwrap();
+    let err = verify_program_with_inputs(&hash, &inputs, None, result.clone()).unwrap_err();
+    assert_eq!(VmErrorKind::Verification, err.kind);
+    assert!(verify_program_with_inputs(&hash, &inputs, Some(kernel), result).is_ok());
+}
+
+#[test]
+fn prove_with_libraries() {
+    let build_library = |namespace: &str, source: &str| {
+        let dir = std::env::temp_dir().join(format!("miden_wasm_test_{namespace}"));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("math.masm"), source).unwrap();
+        let namespace = LibraryNamespace::new(namespace).unwrap();
+        MaslLibrary::read_from_dir(dir, namespace, false, Version::MIN).unwrap()
+    };
+
+    // libraries are passed as a concatenation of their serialized representations
+    let mut bytes = Vec::new();
+    build_library("foo", "export.triple push.3 mul end").write_into(&mut bytes);
+    build_library("bar", "export.double push.2 mul end").write_into(&mut bytes);
+    let libraries = parse_libraries(&bytes).unwrap();
+    assert_eq!(2, libraries.len());
+
+    let source = "
+        use.foo::math->foo
+        use.bar::math->bar
+        begin exec.foo::triple exec.bar::double end";
+    let inputs = InputFile::from_json(r#"{ "operand_stack": ["5"] }"#).unwrap();
+    let result =
+        prove_program_with_inputs(source, &inputs, None, &libraries, ProvingOptions::default())
+            .unwrap();
+    assert_eq!("30", result.outputs.stack[0]);
+
+    let err = parse_libraries(&bytes[..bytes.len() - 1]).unwrap_err();
+    assert_eq!(VmErrorKind::Input, err.kind);
+}
+
+// PROVING OPTIONS
+// ================================================================================================
+
+#[test]
+fn proving_config() {
+    let config = ProvingConfig::from_json("{}").unwrap();
+    assert_eq!(ProvingConfig::default(), config);
+
+    let config =
+        ProvingConfig::from_json(r#"{ "security": "128bits", "max_cycles": 32 }"#).unwrap();
+    let err = config.to_proving_options().unwrap_err();
+    assert_eq!(VmErrorKind::Input, err.kind);
+
+    let err = ProvingConfig::from_json(r#"{ "security": "64bits" }"#).unwrap_err();
+    assert_eq!(VmErrorKind::Input, err.kind);
+}
+
+#[test]
+fn prove_with_options() {
+    let source = "begin push.3 mul end";
+    let hash = program_hash(source).unwrap();
+
+    // recursive proofs are generated using RPO
+    let options = ProvingConfig::from_json(r#"{ "recursive": true }"#)
+        .unwrap()
+        .to_proving_options()
+        .unwrap();
+    let result = prove_program(source, "2".to_string(), String::new(), options).unwrap();
+    assert_eq!(HashFunction::Rpo256, result.hash_fn);
+    assert_eq!(result.starkproof.security_level(), result.security_level);
+    assert!(verify_program(&hash, "2", result).is_ok());
+
+    // a program exceeding the maximum number of cycles fails to execute
+    let options = ProvingConfig::from_json(r#"{ "max_cycles": 64 }"#)
+        .unwrap()
+        .to_proving_options()
+        .unwrap();
+    let source = "begin repeat.100 push.1 drop end end";
+    let err = prove_program(source, String::new(), String::new(), options).unwrap_err();
+    assert_eq!(VmErrorKind::Execution, err.kind);
+}
+
+// EXECUTION WITHOUT PROOFS
+// ================================================================================================
+
+#[test]
+fn execute_without_proof() {
+    let inputs = InputFile::from_json(r#"{ "operand_stack": ["2", "5"] }"#).unwrap();
+    let source = "begin mul push.7 u32checked_and end";
+    let options = ProvingOptions::default();
+    let summary = execute_program_with_inputs(source, &inputs, None, &[], options.clone()).unwrap();
+
+    // outputs are the same as the ones of a proven execution
+    let result = prove_program_with_inputs(source, &inputs, None, &[], options.clone()).unwrap();
+    assert_eq!(result.outputs.stack, summary.outputs.stack);
+    assert_eq!("2", summary.outputs.stack[0]);
+
+    let trace = summary.trace;
+    assert!(trace.main_trace_len > 0);
+    assert!(trace.bitwise_chiplet_len > 0);
+    assert!(trace.hash_chiplet_len > 0);
+    assert_eq!(0, trace.kernel_rom_len);
+    assert!(trace.trace_len <= trace.padded_trace_len);
+    assert!(trace.padded_trace_len.is_power_of_two());
+    assert_eq!(trace.padded_trace_len * 8, summary.estimate.lde_domain_size);
+
+    // execution errors are reported in the same way as for proving
+    let err = execute_program_with_inputs("begin push.0 assert end", &inputs, None, &[], options)
+        .unwrap_err();
+    assert_eq!(VmErrorKind::Execution, err.kind);
+}
+
+#[test]
+fn proving_estimate() {
+    let inputs = InputFile::from_json(r#"{ "operand_stack": [] }"#).unwrap();
+    let estimate = |source: &str, config: &str| {
+        let options = ProvingConfig::from_json(config).unwrap().to_proving_options().unwrap();
+        execute_program_with_inputs(source, &inputs, None, &[], options)
+            .unwrap()
+            .estimate
+    };
+
+    // longer traces are more expensive to prove
+    let short = estimate("begin push.1 drop end", "{}");
+    let long = estimate("begin repeat.1000 push.1 drop end end", "{}");
+    assert!(long.lde_domain_size > short.lde_domain_size);
+    assert!(long.lde_cells > short.lde_cells);
+    assert!(long.memory_bytes > short.memory_bytes);
+    assert!(long.time_ms >= short.time_ms);
+
+    // proofs with RPO and a higher security level are more expensive to generate
+    let source = "begin repeat.1000 push.1 drop end end";
+    let recursive = estimate(source, r#"{ "recursive": true }"#);
+    assert_eq!(long.lde_cells, recursive.lde_cells);
+    assert!(recursive.time_ms > long.time_ms);
+    let secure = estimate(source, r#"{ "security": "128bits" }"#);
+    assert!(secure.lde_cells > long.lde_cells);
+}
+
+// PROOF ENVELOPES
+// ================================================================================================
+
+#[test]
+fn proof_envelope_encodings() {
+    let source = "begin push.3 mul end";
+    let hash = program_hash(source).unwrap();
+    let inputs = InputFile::from_json(r#"{ "operand_stack": ["2"] }"#).unwrap();
+    let envelope =
+        prove_program_envelope(source, &inputs, None, &[], ProvingOptions::default()).unwrap();
+    assert_eq!(HashFunction::Blake3_192, envelope.hash_fn());
+    assert_eq!(6, envelope.stack_outputs().stack()[0]);
+
+    for encoding in [ProofEncoding::Binary, ProofEncoding::Hex, ProofEncoding::Base64] {
+        let mut bytes = envelope.encode(encoding);
+        if encoding != ProofEncoding::Binary {
+            bytes.push(b'\n');
+        }
+        assert_eq!(encoding, ProofEnvelope::detect_encoding(&bytes).unwrap());
+
+        let decoded = ProofEnvelope::decode(&bytes).unwrap();
+        assert_eq!(envelope.program_hash(), decoded.program_hash());
+        assert_eq!(envelope.stack_outputs(), decoded.stack_outputs());
+        assert!(verify_proof_envelope(&hash, decoded, None).is_ok());
+    }
+
+    // the binary envelope is much more compact than the JSON representation of the proof
+    let json = serde_json::to_string(&miden_vm::VMResult::from(envelope.clone())).unwrap();
+    assert!(envelope.encode(ProofEncoding::Binary).len() * 2 < json.len());
+
+    // envelopes generated for a different program are rejected
+    let other_hash = program_hash("begin push.4 mul end").unwrap();
+    let err = verify_proof_envelope(&other_hash, envelope, None).unwrap_err();
+    assert_eq!(VmErrorKind::Verification, err.kind);
+}
+
+#[test]
+fn proof_envelope_rejects_malformed_data() {
+    let inputs = InputFile::from_json(r#"{ "operand_stack": [] }"#).unwrap();
+    let envelope =
+        prove_program_envelope("begin push.1 end", &inputs, None, &[], ProvingOptions::default())
+            .unwrap();
+    let bytes = envelope.encode(ProofEncoding::Binary);
+
+    // unknown magic bytes
+    let err = ProofEnvelope::decode(&bytes[1..]).unwrap_err();
+    assert_eq!(VmErrorKind::Serialization, err.kind);
+
+    // unsupported version
+    let mut old = bytes.clone();
+    old[4] = PROOF_ENVELOPE_VERSION + 1;
+    let err = ProofEnvelope::decode(&old).unwrap_err();
+    assert_eq!(VmErrorKind::Serialization, err.kind);
+    assert!(err.message.contains("version"));
+
+    // truncated and padded envelopes
+    assert!(ProofEnvelope::decode(&bytes[..bytes.len() - 1]).is_err());
+    let mut padded = bytes;
+    padded.push(0);
+    assert!(ProofEnvelope::decode(&padded).is_err());
+
+    assert!("json".parse::<ProofEncoding>().is_err());
+}
+
+// PROVING SESSIONS
+// ================================================================================================
+
+#[test]
+fn proving_session() {
+    let source = "begin repeat.20 push.3 mul end end";
+    let inputs = InputFile::from_json(r#"{ "operand_stack": ["2"] }"#).unwrap();
+    let options = ProvingConfig::from_json(r#"{ "recursive": true }"#)
+        .unwrap()
+        .to_proving_options()
+        .unwrap();
+
+    let mut session =
+        ProvingSession::new(source, &inputs, None, Vec::new(), options.clone()).unwrap();
+    assert_eq!(ProvingPhase::Assembly, session.phase());
+    assert_eq!(0.0, session.progress());
+
+    // every phase is executed exactly once and in order
+    let mut phases = Vec::new();
+    while !session.is_complete() {
+        let progress = session.progress();
+        phases.push(session.step().unwrap());
+        assert!(session.progress() > progress);
+    }
+    assert_eq!(ProvingPhase::NUM_PHASES, phases.len());
+    assert_eq!(ProvingPhase::Execution, phases[1]);
+    assert_eq!(ProvingPhase::Proving(ProvingStage::MainTraceCommitment), phases[2]);
+    assert_eq!(ProvingPhase::Proving(ProvingStage::Queries), phases[phases.len() - 1]);
+    assert_eq!(ProvingPhase::Complete, session.phase());
+    assert_eq!(1.0, session.progress());
+    assert_eq!(VmErrorKind::Input, session.step().unwrap_err().kind);
+
+    // the proof is the same as the one generated in a single call
+    let envelope = session.envelope().unwrap();
+    assert_eq!(HashFunction::Rpo256, envelope.hash_fn());
+    let expected = prove_program_envelope(source, &inputs, None, &[], options).unwrap();
+    assert_eq!(expected.encode(ProofEncoding::Binary), envelope.encode(ProofEncoding::Binary));
+
+    let hash = program_hash(source).unwrap();
+    assert!(verify_proof_envelope(&hash, envelope.clone(), None).is_ok());
+}
+
+#[test]
+fn proving_session_failure() {
+    let inputs = InputFile::from_json(r#"{ "operand_stack": [] }"#).unwrap();
+    let source = "begin push.1 push.0 div end";
+    let mut session =
+        ProvingSession::new(source, &inputs, None, Vec::new(), ProvingOptions::default()).unwrap();
+    assert_eq!(ProvingPhase::Assembly, session.step().unwrap());
+
+    // a failed session keeps returning the error of the phase which failed
+    let err = session.step().unwrap_err();
+    assert_eq!(VmErrorKind::Execution, err.kind);
+    assert_eq!(ProvingPhase::Execution, session.phase());
+    assert_eq!(err, session.step().unwrap_err());
+    assert!(session.envelope().is_none());
+}
+
+// CUSTOM HOSTS
+// ================================================================================================
+
+#[test]
+fn host_serves_merkle_paths() {
+    let leaves: Vec<Word> = (1..=8).map(|i| [Felt::new(i), ZERO, ZERO, ZERO]).collect();
+    let tree = MerkleTree::new(leaves.clone()).unwrap();
+    let index = 3;
+    let path = tree.get_path(NodeIndex::new(tree.depth(), index).unwrap()).unwrap();
+    let nodes: Vec<String> =
+        path.iter().map(|node| format!("0x{}", hex::encode(node.as_bytes()))).collect();
+
+    let mut operand_stack: Vec<u64> = tree.root().iter().map(|v| v.as_int()).collect();
+    operand_stack.extend([index, tree.depth() as u64]);
+    operand_stack.extend(leaves[index as usize].iter().map(|v| v.as_int()));
+    let operand_stack: Vec<String> = operand_stack.iter().map(|v| format!("\"{v}\"")).collect();
+    let inputs = format!(r#"{{ "operand_stack": [{}] }}"#, operand_stack.join(","));
+    let inputs = InputFile::from_json(&inputs).unwrap();
+    let source = "begin mtree_verify end";
+
+    // the path is not known to the default advice provider
+    let options = ProvingOptions::default();
+    let err = execute_program_with_host(source, &inputs, JsHost::new(), None, &[], options.clone())
+        .unwrap_err();
+    assert_eq!(VmErrorKind::Execution, err.kind);
+
+    // the path is supplied on demand by the host
+    let requests = Rc::new(RefCell::new(Vec::new()));
+    let host = JsHost::new().with_get_advice({
+        let requests = requests.clone();
+        move |request| {
+            let request: HostRequest = serde_json::from_str(request).unwrap();
+            requests.borrow_mut().push(request);
+            let reply = HostReply::MerklePath(nodes.clone());
+            Ok(Some(serde_json::to_string(&reply).unwrap()))
+        }
+    });
+    let result = prove_program_with_host(source, &inputs, host, None, &[], options).unwrap();
+    assert_eq!(1, requests.borrow().len());
+    assert_eq!("get_merkle_path", requests.borrow()[0].request);
+
+    let hash = program_hash(source).unwrap();
+    assert!(verify_program_with_inputs(&hash, &inputs, None, result).is_ok());
+}
+
+#[test]
+fn host_serves_advice_and_captures_debug_output_and_events() {
+    let inputs =
+        InputFile::from_json(r#"{ "operand_stack": ["1"], "advice_stack": ["3"] }"#).unwrap();
+    let source = "
+        begin
+            adv_push.1 adv_push.1
+            adv.push_mapval adv_push.2
+            debug.stack.3
+            add add add add
+            emit.9
+        end";
+
+    // the host serves the first advice request, the next ones fall back to the advice inputs
+    let requests = Rc::new(RefCell::new(Vec::new()));
+    let host = JsHost::new()
+        .with_get_advice(|request| {
+            let request: HostRequest = serde_json::from_str(request).unwrap();
+            assert_eq!("pop_stack", request.request);
+            Ok((request.stack[0] == "1").then(|| r#"{ "element": "7" }"#.to_string()))
+        })
+        .with_set_advice(|request| {
+            let request: HostRequest = serde_json::from_str(request).unwrap();
+            assert_eq!("map_value_to_stack.0", request.request);
+            Ok(Some(r#"{ "advice_stack": ["5", "6"] }"#.to_string()))
+        })
+        .with_on_debug({
+            let requests = requests.clone();
+            move |request| {
+                requests.borrow_mut().push(serde_json::from_str(request).unwrap());
+                Ok(None)
+            }
+        })
+        .with_on_event({
+            let requests = requests.clone();
+            move |request| {
+                requests.borrow_mut().push(serde_json::from_str(request).unwrap());
+                Ok(None)
+            }
+        });
+
+    let summary =
+        execute_program_with_host(source, &inputs, host, None, &[], ProvingOptions::default())
+            .unwrap();
+    assert_eq!("22", summary.outputs.stack[0]);
+
+    let requests: Vec<HostRequest> = requests.take();
+    assert_eq!(2, requests.len());
+    assert_eq!("stack.3", requests[0].request);
+    assert_eq!(["6", "5", "3"], &requests[0].stack[..]);
+    assert_eq!("event.9", requests[1].request);
+    assert_eq!("22", requests[1].stack[0]);
+}
+
+#[test]
+fn host_callback_failure() {
+    let inputs = InputFile::from_json(r#"{ "operand_stack": [] }"#).unwrap();
+    let source = "begin push.1 adv_push.1 end";
+    let options = ProvingOptions::default();
+
+    let host = JsHost::new().with_get_advice(|_| Err("advice is unavailable".to_string()));
+    let err =
+        execute_program_with_host(source, &inputs, host, None, &[], options.clone()).unwrap_err();
+    assert_eq!(VmErrorKind::Execution, err.kind);
+    assert!(err.clk.is_some());
+    assert!(err.message.contains("advice is unavailable"));
+
+    // replies must match the request
+    let host =
+        JsHost::new().with_get_advice(|_| Ok(Some(r#"{ "word": ["1", "2", "3", "4"] }"#.into())));
+    let err = execute_program_with_host(source, &inputs, host, None, &[], options).unwrap_err();
+    assert_eq!(VmErrorKind::Execution, err.kind);
+}
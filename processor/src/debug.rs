@@ -1,11 +1,11 @@
 use crate::{
-    range::RangeChecker, Chiplets, ChipletsLengths, Decoder, ExecutionError, Felt, Host, Process,
-    Stack, StarkField, System, TraceLenSummary, Vec,
+    range::RangeChecker, Chiplets, ChipletsLengths, Decoder, Digest, ExecutionError, Felt, Host,
+    Process, Stack, StarkField, System, TraceLenSummary, Vec,
 };
 use core::fmt;
 use vm_core::{
     utils::string::{String, ToString},
-    AssemblyOp, Operation, StackOutputs, Word,
+    AsmOpLocation, AssemblyOp, Operation, StackOutputs, Word,
 };
 
 /// VmState holds a current process state information at a specific clock cycle.
@@ -181,6 +181,38 @@ impl VmStateIterator {
         &self.trace_len_summary
     }
 
+    /// Returns the hash of the SPAN block and the index of the operation within this block which
+    /// was executed at the specified clock cycle, or None if no operation of a SPAN block was
+    /// executed at this cycle.
+    ///
+    /// The clock cycle is the one at which the operation started executing, and thus, is the same
+    /// as the clock cycle reported by [ExecutionError::FailedAssertion]; the state resulting from
+    /// this operation is the [VmState] at the next clock cycle.
+    pub fn span_op_at(&self, clk: u32) -> Option<(Digest, usize)> {
+        self.decoder.debug_info().span_op_at(clk)
+    }
+
+    /// Returns the hash of the code block whose execution started at the specified clock cycle,
+    /// or None if no JOIN, SPLIT, LOOP, SPAN, CALL, SYSCALL, or DYN operation was executed at this
+    /// cycle.
+    ///
+    /// As for [VmStateIterator::span_op_at()], the clock cycle is the one at which the operation
+    /// started executing; thus, the hash of the block started by the operation of a [VmState] is
+    /// returned for the cycle preceding the cycle of the state.
+    pub fn block_hash_at(&self, clk: u32) -> Option<Digest> {
+        self.decoder.debug_info().block_hash_at(clk)
+    }
+
+    /// Returns the lengths of the chiplet traces at the specified clock cycle, i.e., after the
+    /// operations up to this cycle were executed.
+    ///
+    /// The difference between the lengths at two consecutive clock cycles is the number of
+    /// chiplet rows added by the operation executed between them (i.e., the operation of the
+    /// [VmState] at the later cycle).
+    pub fn chiplets_lengths_at(&self, clk: u32) -> Option<ChipletsLengths> {
+        self.decoder.debug_info().chiplets_lengths_at(clk)
+    }
+
     /// Returns an instance of [TraceLenSummary] based on provided data.
     fn build_trace_len_summary(
         system: &System,
@@ -274,6 +306,12 @@ impl AsmOpInfo {
         self.asmop.context_name()
     }
 
+    /// Returns the context names of the procedures through which the procedure of this operation
+    /// was inlined via `exec`, starting from the outermost one.
+    pub fn exec_path(&self) -> &[String] {
+        self.asmop.exec_path()
+    }
+
     /// Returns the assembly instruction corresponding to this state.
     pub fn op(&self) -> &str {
         self.asmop.op()
@@ -305,6 +343,12 @@ impl AsmOpInfo {
     pub const fn should_break(&self) -> bool {
         self.asmop.should_break()
     }
+
+    /// Returns the location of the assembly instruction in the source code, if it was recorded
+    /// by the assembler.
+    pub fn location(&self) -> Option<&AsmOpLocation> {
+        self.asmop.location()
+    }
 }
 
 impl fmt::Display for AsmOpInfo {
@@ -1,7 +1,7 @@
 use super::{
-    Call, ColMatrix, Dyn, ExecutionError, Felt, FieldElement, Host, Join, Loop, OpBatch, Operation,
-    Process, Span, Split, StarkField, Vec, Word, EMPTY_WORD, MIN_TRACE_LEN, ONE, OP_BATCH_SIZE,
-    ZERO,
+    Call, ChipletsLengths, ColMatrix, Digest, Dyn, ExecutionError, Felt, FieldElement, Host, Join,
+    Loop, OpBatch, Operation, Process, Span, Split, StarkField, Vec, Word, EMPTY_WORD,
+    MIN_TRACE_LEN, ONE, OP_BATCH_SIZE, ZERO,
 };
 use miden_air::trace::{
     chiplets::hasher::DIGEST_LEN,
@@ -59,6 +59,7 @@ where
         // start decoding the JOIN block; this appends a row with JOIN operation to the decoder
         // trace. when JOIN operation is executed, the rest of the VM state does not change
         self.decoder.start_join(child1_hash, child2_hash, addr);
+        self.decoder.append_block_start(self.system.clk(), block.hash());
         self.execute_op(Operation::Noop)
     }
 
@@ -94,6 +95,7 @@ where
         // start decoding the SPLIT block. this appends a row with SPLIT operation to the decoder
         // trace. we also pop the value off the top of the stack and return it.
         self.decoder.start_split(child1_hash, child2_hash, addr, condition);
+        self.decoder.append_block_start(self.system.clk(), block.hash());
         self.execute_op(Operation::Drop)?;
         Ok(condition)
     }
@@ -133,6 +135,7 @@ where
         // basically, if the top of the stack is ZERO, a LOOP operation should be immediately
         // followed by an END operation.
         self.decoder.start_loop(body_hash, addr, condition);
+        self.decoder.append_block_start(self.system.clk(), block.hash());
         self.execute_op(Operation::Drop)?;
         Ok(condition)
     }
@@ -202,6 +205,7 @@ where
             self.system.start_call(fn_hash);
             self.decoder.start_call(fn_hash, addr, ctx_info);
         }
+        self.decoder.append_block_start(self.system.clk(), block.hash());
 
         // the rest of the VM state does not change
         self.execute_op(Operation::Noop)
@@ -255,6 +259,7 @@ where
                 .hash_control_block(EMPTY_WORD, EMPTY_WORD, Dyn::DOMAIN, block.hash());
 
         self.decoder.start_dyn(dyn_hash, addr);
+        self.decoder.append_block_start(self.system.clk(), block.hash());
         self.execute_op(Operation::Noop)
     }
 
@@ -287,6 +292,7 @@ where
         // set the value of the group_count register at the beginning of the SPAN.
         let num_op_groups = get_span_op_group_count(op_batches);
         self.decoder.start_span(&op_batches[0], Felt::new(num_op_groups as u64), addr);
+        self.decoder.append_block_start(self.system.clk(), block.hash());
         self.execute_op(Operation::Noop)
     }
 
@@ -776,6 +782,23 @@ impl Decoder {
         self.debug_info.append_asmop(clk, asmop);
     }
 
+    /// Records that the operation at the specified index of the SPAN block with the specified
+    /// hash is executed at the specified clock cycle. Only applicable in debug mode.
+    pub fn append_span_op(&mut self, clk: u32, span_hash: Digest, op_idx: usize) {
+        self.debug_info.append_span_op(clk, span_hash, op_idx);
+    }
+
+    /// Records the code block started at the specified clock cycle. Only applicable in debug mode.
+    pub fn append_block_start(&mut self, clk: u32, block_hash: Digest) {
+        self.debug_info.append_block_start(clk, block_hash);
+    }
+
+    /// Records the lengths of the chiplet traces at the current clock cycle. Only applicable in
+    /// debug mode.
+    pub fn append_chiplets_lengths(&mut self, lengths: ChipletsLengths) {
+        self.debug_info.append_chiplets_lengths(lengths);
+    }
+
     // TEST METHODS
     // --------------------------------------------------------------------------------------------
 
@@ -856,6 +879,9 @@ pub struct DebugInfo {
     in_debug_mode: bool,
     operations: Vec<Operation>,
     assembly_ops: Vec<(usize, AssemblyOp)>,
+    span_ops: Vec<(u32, Digest, usize)>,
+    block_starts: Vec<(u32, Digest)>,
+    chiplets_lengths: Vec<ChipletsLengths>,
 }
 
 impl DebugInfo {
@@ -864,6 +890,9 @@ impl DebugInfo {
             in_debug_mode,
             operations: Vec::<Operation>::new(),
             assembly_ops: Vec::<(usize, AssemblyOp)>::new(),
+            span_ops: Vec::new(),
+            block_starts: Vec::new(),
+            chiplets_lengths: Vec::new(),
         }
     }
 
@@ -895,4 +924,55 @@ impl DebugInfo {
     pub fn append_asmop(&mut self, clk: u32, asmop: AssemblyOp) {
         self.assembly_ops.push((clk as usize, asmop));
     }
+
+    /// Returns the hash of the SPAN block and the index of the operation within this block which
+    /// was executed at the specified clock cycle, or None if no operation of a SPAN block was
+    /// executed at this cycle. Only applicable in debug mode.
+    ///
+    /// The returned pair can be used to look up the source location of the operation in a
+    /// [SourceMap](vm_core::SourceMap) of the executed program. NOOPs executed to align operation
+    /// batches do not belong to any operation of a SPAN block.
+    pub fn span_op_at(&self, clk: u32) -> Option<(Digest, usize)> {
+        let idx = self.span_ops.binary_search_by_key(&clk, |(op_clk, ..)| *op_clk).ok()?;
+        let (_, span_hash, op_idx) = self.span_ops[idx];
+        Some((span_hash, op_idx))
+    }
+
+    /// Records the SPAN block operation executed at the specified clock cycle in debug mode.
+    #[inline(always)]
+    pub fn append_span_op(&mut self, clk: u32, span_hash: Digest, op_idx: usize) {
+        if self.in_debug_mode {
+            self.span_ops.push((clk, span_hash, op_idx));
+        }
+    }
+
+    /// Returns the hash of the code block whose execution started at the specified clock cycle,
+    /// i.e., the block of the JOIN, SPLIT, LOOP, SPAN, CALL, SYSCALL, or DYN operation executed
+    /// at this cycle.
+    pub fn block_hash_at(&self, clk: u32) -> Option<Digest> {
+        let idx = self.block_starts.binary_search_by_key(&clk, |(block_clk, _)| *block_clk).ok()?;
+        Some(self.block_starts[idx].1)
+    }
+
+    /// Records the code block started at the specified clock cycle in debug mode.
+    #[inline(always)]
+    pub fn append_block_start(&mut self, clk: u32, block_hash: Digest) {
+        if self.in_debug_mode {
+            self.block_starts.push((clk, block_hash));
+        }
+    }
+
+    /// Returns the lengths of the chiplet traces after the operations up to the specified clock
+    /// cycle were executed. Only applicable in debug mode.
+    pub fn chiplets_lengths_at(&self, clk: u32) -> Option<ChipletsLengths> {
+        self.chiplets_lengths.get(clk as usize).copied()
+    }
+
+    /// Records the lengths of the chiplet traces at the current clock cycle in debug mode.
+    #[inline(always)]
+    pub fn append_chiplets_lengths(&mut self, lengths: ChipletsLengths) {
+        if self.in_debug_mode {
+            self.chiplets_lengths.push(lengths);
+        }
+    }
 }
@@ -1655,6 +1655,46 @@ fn set_user_op_helpers_many() {
     assert_eq!(expected, hasher_state);
 }
 
+// DEBUG INFO TESTS
+// ================================================================================================
+
+#[test]
+fn span_ops_in_debug_mode() {
+    let ops = vec![Operation::Push(TWO), Operation::Add, Operation::Mul];
+    let span = Span::new(ops.clone());
+    let program = CodeBlock::new_span(ops);
+
+    let stack_inputs = StackInputs::try_from_values([1, 2]).unwrap();
+    let mut process = Process::new_debug(Kernel::default(), stack_inputs, DefaultHost::default());
+    process.execute_code_block(&program, &CodeBlockTable::default()).unwrap();
+
+    // the SPAN and END operations do not belong to any operation of the span block
+    let debug_info = process.decoder.debug_info();
+    assert_eq!(None, debug_info.span_op_at(0));
+    assert_eq!(Some((span.hash(), 0)), debug_info.span_op_at(1));
+    assert_eq!(Some((span.hash(), 1)), debug_info.span_op_at(2));
+    assert_eq!(Some((span.hash(), 2)), debug_info.span_op_at(3));
+    assert_eq!(None, debug_info.span_op_at(4));
+}
+
+#[test]
+fn block_starts_in_debug_mode() {
+    let span1 = CodeBlock::new_span(vec![Operation::Mul]);
+    let span2 = CodeBlock::new_span(vec![Operation::Add]);
+    let program = CodeBlock::new_split(span1.clone(), span2);
+
+    let stack_inputs = StackInputs::try_from_values([2, 3, 1]).unwrap();
+    let mut process = Process::new_debug(Kernel::default(), stack_inputs, DefaultHost::default());
+    process.execute_code_block(&program, &CodeBlockTable::default()).unwrap();
+
+    // SPLIT, SPAN, MUL, END, END
+    let debug_info = process.decoder.debug_info();
+    assert_eq!(Some(program.hash()), debug_info.block_hash_at(0));
+    assert_eq!(Some(span1.hash()), debug_info.block_hash_at(1));
+    assert_eq!(None, debug_info.block_hash_at(2));
+    assert_eq!(None, debug_info.block_hash_at(3));
+}
+
 // HELPER FUNCTIONS
 // ================================================================================================
 
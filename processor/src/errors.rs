@@ -4,7 +4,10 @@ use super::{
     CodeBlock, Digest, Felt, QuadFelt, Word,
 };
 use core::fmt::{Display, Formatter};
-use vm_core::{stack::STACK_TOP_SIZE, utils::to_hex};
+use vm_core::{
+    stack::STACK_TOP_SIZE,
+    utils::{string::String, to_hex},
+};
 use winter_prover::{math::FieldElement, ProverError};
 
 #[cfg(feature = "std")]
@@ -25,6 +28,7 @@ pub enum ExecutionError {
     DivideByZero(u32),
     Ext2InttError(Ext2InttError),
     FailedAssertion(u32, Felt),
+    HostRequestFailed(u32, String),
     InvalidFmpValue(Felt, Felt),
     InvalidFriDomainSegment(u64),
     InvalidFriLayerFolding(QuadFelt, QuadFelt),
@@ -88,6 +92,9 @@ impl Display for ExecutionError {
             FailedAssertion(clk, err_code) => {
                 write!(f, "Assertion failed at clock cycle {clk} with error code {err_code}")
             }
+            HostRequestFailed(clk, reason) => {
+                write!(f, "Host failed to handle a request at clock cycle {clk}: {reason}")
+            }
             InvalidFmpValue(old, new) => {
                 write!(f, "Updating FMP register from {old} to {new} failed because {new} is outside of {FMP_MIN}..{FMP_MAX}")
             }
@@ -0,0 +1,464 @@
+use crate::{
+    system::{FMP_MIN, SYSCALL_FMP_MIN},
+    ExecutionError, ExecutionOptions, Felt, Host, Kernel, Operation, ProcessState, Program,
+    StackInputs, StackOutputs, Vec, Word, EMPTY_WORD, ONE, ZERO,
+};
+use core::cell::RefCell;
+use vm_core::{
+    code_blocks::{Call, CodeBlock, Dyn, Join, Loop, OpBatch, Span, Split, OP_GROUP_SIZE},
+    utils::collections::BTreeMap,
+    CodeBlockTable, Decorator, DecoratorIterator,
+};
+
+mod operations;
+
+mod stack;
+use stack::OperandStack;
+
+#[cfg(test)]
+mod tests;
+
+// FAST PROCESSOR
+// ================================================================================================
+
+/// A processor which executes programs without generating an execution trace.
+///
+/// The fast processor executes the MAST of a program with the same semantics as the processor used
+/// by [execute()](crate::execute): it executes the same operations (including control flow
+/// operations and NOOPs padding operation batches), and thus, advances the clock by the same number
+/// of cycles, and it makes the same requests to the [Host] at the same cycles. However, it keeps track of the
+/// current state of the VM only; no trace columns, chiplet traces, or auxiliary trace hints are
+/// built, which makes it much faster when only the outputs of a program are needed.
+///
+/// A program executed by the fast processor cannot be proven; use [execute()](crate::execute)
+/// to generate an execution trace of the program.
+pub struct FastProcessor<H>
+where
+    H: Host,
+{
+    clk: u32,
+    ctx: u32,
+    fmp: Felt,
+    in_syscall: bool,
+    fn_hash: Word,
+    stack: OperandStack,
+    memory: BTreeMap<u32, BTreeMap<u32, Word>>,
+    kernel: Kernel,
+    host: RefCell<H>,
+    max_cycles: u32,
+}
+
+impl<H> FastProcessor<H>
+where
+    H: Host,
+{
+    // CONSTRUCTOR
+    // --------------------------------------------------------------------------------------------
+
+    /// Creates a new fast processor with the provided inputs.
+    ///
+    /// Only the maximum number of cycles is used from the provided execution options.
+    pub fn new(
+        kernel: Kernel,
+        stack_inputs: StackInputs,
+        host: H,
+        execution_options: ExecutionOptions,
+    ) -> Self {
+        Self {
+            clk: 0,
+            ctx: 0,
+            fmp: Felt::from(FMP_MIN),
+            in_syscall: false,
+            fn_hash: EMPTY_WORD,
+            stack: OperandStack::new(&stack_inputs),
+            memory: BTreeMap::new(),
+            kernel,
+            host: RefCell::new(host),
+            max_cycles: execution_options.max_cycles(),
+        }
+    }
+
+    // PROGRAM EXECUTOR
+    // --------------------------------------------------------------------------------------------
+
+    /// Executes the provided [Program] in this processor.
+    pub fn execute(&mut self, program: &Program) -> Result<StackOutputs, ExecutionError> {
+        assert_eq!(self.clk, 0, "a program has already been executed in this processor");
+        self.execute_code_block(program.root(), program.cb_table())?;
+
+        Ok(self.stack.build_stack_outputs())
+    }
+
+    // PUBLIC ACCESSORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the kernel of this processor.
+    pub const fn kernel(&self) -> &Kernel {
+        &self.kernel
+    }
+
+    /// Returns the host of this processor.
+    pub fn into_host(self) -> H {
+        self.host.into_inner()
+    }
+
+    /// Returns the outputs of the program executed by this processor.
+    pub(crate) fn into_output(self, stack_outputs: StackOutputs) -> RunOutput {
+        RunOutput {
+            stack_outputs,
+            memory: self.memory,
+            num_cycles: self.clk,
+        }
+    }
+
+    // CODE BLOCK EXECUTORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Executes the specified [CodeBlock].
+    fn execute_code_block(
+        &mut self,
+        block: &CodeBlock,
+        cb_table: &CodeBlockTable,
+    ) -> Result<(), ExecutionError> {
+        // decorators attached to control blocks are executed outside of the blocks; decorators
+        // attached to SPAN blocks are executed together with the operations of the blocks
+        for decorator in block.before_enter() {
+            self.execute_decorator(decorator)?;
+        }
+
+        match block {
+            CodeBlock::Join(block) => self.execute_join_block(block, cb_table)?,
+            CodeBlock::Split(block) => self.execute_split_block(block, cb_table)?,
+            CodeBlock::Loop(block) => self.execute_loop_block(block, cb_table)?,
+            CodeBlock::Call(block) => self.execute_call_block(block, cb_table)?,
+            CodeBlock::Dyn(_) => self.execute_dyn_block(cb_table)?,
+            CodeBlock::Span(block) => self.execute_span_block(block)?,
+            CodeBlock::Proxy(_) => {
+                return Err(ExecutionError::UnexecutableCodeBlock(block.clone()))
+            }
+        }
+
+        for decorator in block.after_exit() {
+            self.execute_decorator(decorator)?;
+        }
+
+        Ok(())
+    }
+
+    /// Executes the specified [Join] block.
+    #[inline(always)]
+    fn execute_join_block(
+        &mut self,
+        block: &Join,
+        cb_table: &CodeBlockTable,
+    ) -> Result<(), ExecutionError> {
+        // JOIN operation
+        self.advance_clock()?;
+
+        self.execute_code_block(block.first(), cb_table)?;
+        self.execute_code_block(block.second(), cb_table)?;
+
+        // END operation
+        self.advance_clock()
+    }
+
+    /// Executes the specified [Split] block.
+    #[inline(always)]
+    fn execute_split_block(
+        &mut self,
+        block: &Split,
+        cb_table: &CodeBlockTable,
+    ) -> Result<(), ExecutionError> {
+        // SPLIT operation pops the condition from the stack
+        let condition = self.stack.get(0);
+        self.execute_op(Operation::Drop)?;
+
+        if condition == ONE {
+            self.execute_code_block(block.on_true(), cb_table)?;
+        } else if condition == ZERO {
+            self.execute_code_block(block.on_false(), cb_table)?;
+        } else {
+            return Err(ExecutionError::NotBinaryValue(condition));
+        }
+
+        // END operation
+        self.advance_clock()
+    }
+
+    /// Executes the specified [Loop] block.
+    #[inline(always)]
+    fn execute_loop_block(
+        &mut self,
+        block: &Loop,
+        cb_table: &CodeBlockTable,
+    ) -> Result<(), ExecutionError> {
+        // LOOP operation pops the condition from the stack
+        let condition = self.stack.get(0);
+        self.execute_op(Operation::Drop)?;
+
+        if condition == ONE {
+            self.execute_code_block(block.body(), cb_table)?;
+
+            // each further iteration is preceded by a REPEAT operation which pops the condition
+            while self.stack.get(0) == ONE {
+                self.execute_op(Operation::Drop)?;
+                self.execute_code_block(block.body(), cb_table)?;
+            }
+
+            // END operation pops the condition of the last iteration
+            debug_assert_eq!(ZERO, self.stack.get(0));
+            self.execute_op(Operation::Drop)
+        } else if condition == ZERO {
+            // END operation
+            self.advance_clock()
+        } else {
+            Err(ExecutionError::NotBinaryValue(condition))
+        }
+    }
+
+    /// Executes the specified [Call] block.
+    #[inline(always)]
+    fn execute_call_block(
+        &mut self,
+        block: &Call,
+        cb_table: &CodeBlockTable,
+    ) -> Result<(), ExecutionError> {
+        // if this is a syscall, make sure the call target exists in the kernel
+        if block.is_syscall() && !self.kernel.contains_proc(block.fn_hash()) {
+            return Err(ExecutionError::SyscallTargetNotInKernel(block.fn_hash()));
+        }
+
+        // CALL or SYSCALL operation starts a new execution context; the operand stack of the new
+        // context has depth 16
+        let parent_ctx = self.ctx;
+        let parent_fmp = self.fmp;
+        let parent_fn_hash = self.fn_hash;
+        let parent_stack = self.stack.start_context();
+        if block.is_syscall() {
+            self.ctx = 0;
+            self.fmp = Felt::from(SYSCALL_FMP_MIN);
+            self.in_syscall = true;
+        } else {
+            self.ctx = self.clk + 1;
+            self.fmp = Felt::from(FMP_MIN);
+            self.fn_hash = block.fn_hash().into();
+        }
+        self.advance_clock()?;
+
+        if block.fn_hash() == Dyn::dyn_hash() {
+            self.execute_dyn_block(cb_table)?;
+        } else {
+            let fn_body = cb_table
+                .get(block.fn_hash())
+                .ok_or_else(|| ExecutionError::CodeBlockNotFound(block.fn_hash()))?;
+            self.execute_code_block(fn_body, cb_table)?;
+        }
+
+        // when a CALL block ends, stack depth must be exactly 16
+        let stack_depth = self.stack.depth();
+        if stack_depth > vm_core::stack::STACK_TOP_SIZE {
+            return Err(ExecutionError::InvalidStackDepthOnReturn(stack_depth));
+        }
+
+        // END operation restores the context of the caller
+        self.ctx = parent_ctx;
+        self.fmp = parent_fmp;
+        self.in_syscall = false;
+        self.fn_hash = parent_fn_hash;
+        self.stack.restore_context(parent_stack);
+        self.advance_clock()
+    }
+
+    /// Executes a [Dyn] block whose target is specified by the word on the top of the stack.
+    #[inline(always)]
+    fn execute_dyn_block(&mut self, cb_table: &CodeBlockTable) -> Result<(), ExecutionError> {
+        let dyn_hash = self.stack.get_word(0);
+
+        // DYN operation
+        self.advance_clock()?;
+
+        let dyn_digest = dyn_hash.into();
+        let dyn_code = cb_table
+            .get(dyn_digest)
+            .ok_or(ExecutionError::DynamicCodeBlockNotFound(dyn_digest))?;
+        self.execute_code_block(dyn_code, cb_table)?;
+
+        // END operation
+        self.advance_clock()
+    }
+
+    /// Executes the specified [Span] block.
+    #[inline(always)]
+    fn execute_span_block(&mut self, block: &Span) -> Result<(), ExecutionError> {
+        // SPAN operation
+        self.advance_clock()?;
+
+        let mut op_offset = 0;
+        let mut decorators = block.decorator_iter();
+
+        // each operation batch after the first one is preceded by a RESPAN operation
+        for (i, op_batch) in block.op_batches().iter().enumerate() {
+            if i > 0 {
+                self.advance_clock()?;
+            }
+            self.execute_op_batch(op_batch, &mut decorators, op_offset)?;
+            op_offset += op_batch.ops().len();
+        }
+
+        // END operation
+        self.advance_clock()?;
+
+        // execute any decorators which have not been executed during span ops execution
+        for decorator in decorators {
+            self.execute_decorator(decorator)?;
+        }
+
+        Ok(())
+    }
+
+    /// Executes all operations in an [OpBatch], together with the NOOPs required to satisfy the
+    /// alignment rules of operation groups.
+    #[inline(always)]
+    fn execute_op_batch(
+        &mut self,
+        batch: &OpBatch,
+        decorators: &mut DecoratorIterator,
+        op_offset: usize,
+    ) -> Result<(), ExecutionError> {
+        let op_counts = batch.op_counts();
+        let mut op_idx = 0;
+        let mut group_idx = 0;
+        let mut next_group_idx = 1;
+        let num_batch_groups = batch.num_groups().next_power_of_two();
+
+        for (i, &op) in batch.ops().iter().enumerate() {
+            while let Some(decorator) = decorators.next_filtered(i + op_offset) {
+                self.execute_decorator(decorator)?;
+            }
+            self.execute_op(op)?;
+
+            // an operation with an immediate value uses the next group for the value, and cannot
+            // be the last operation in a group; a NOOP is executed after it in such a case
+            let has_imm = op.imm_value().is_some();
+            if has_imm {
+                next_group_idx += 1;
+            }
+
+            if op_idx == op_counts[group_idx] - 1 {
+                if has_imm {
+                    debug_assert!(op_idx < OP_GROUP_SIZE - 1, "invalid op index");
+                    self.advance_clock()?;
+                }
+                group_idx = next_group_idx;
+                next_group_idx += 1;
+                op_idx = 0;
+            } else {
+                op_idx += 1;
+            }
+        }
+
+        // a NOOP is executed for each group padding the batch to a power of two groups
+        for _ in group_idx..num_batch_groups {
+            self.advance_clock()?;
+        }
+
+        Ok(())
+    }
+
+    /// Executes the specified decorator.
+    fn execute_decorator(&mut self, decorator: &Decorator) -> Result<(), ExecutionError> {
+        match decorator {
+            Decorator::Advice(injector) => {
+                self.host.borrow_mut().set_advice(self, *injector)?;
+            }
+            Decorator::Debug(options) => {
+                self.host.borrow_mut().on_debug(self, options)?;
+            }
+            Decorator::Event(event_id) => {
+                self.host.borrow_mut().on_event(self, *event_id)?;
+            }
+            Decorator::AsmOp(_) => (),
+        }
+        Ok(())
+    }
+
+    /// Increments the clock cycle.
+    ///
+    /// # Errors
+    /// Returns an error if the maximum number of cycles is exceeded.
+    #[inline(always)]
+    fn advance_clock(&mut self) -> Result<(), ExecutionError> {
+        self.clk += 1;
+        if self.clk > self.max_cycles {
+            return Err(ExecutionError::CycleLimitExceeded(self.max_cycles));
+        }
+        Ok(())
+    }
+}
+
+impl<H: Host> ProcessState for FastProcessor<H> {
+    fn clk(&self) -> u32 {
+        self.clk
+    }
+
+    fn ctx(&self) -> u32 {
+        self.ctx
+    }
+
+    fn get_stack_item(&self, pos: usize) -> Felt {
+        self.stack.get(pos)
+    }
+
+    fn get_stack_word(&self, word_idx: usize) -> Word {
+        self.stack.get_word(word_idx)
+    }
+
+    fn get_stack_state(&self) -> Vec<Felt> {
+        self.stack.get_state()
+    }
+
+    fn get_mem_value(&self, ctx: u32, addr: u32) -> Option<Word> {
+        self.memory.get(&ctx)?.get(&addr).copied()
+    }
+}
+
+// RUN OUTPUT
+// ================================================================================================
+
+/// Outputs of a program executed without generating an execution trace (see [run()](crate::run)).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RunOutput {
+    stack_outputs: StackOutputs,
+    memory: BTreeMap<u32, BTreeMap<u32, Word>>,
+    num_cycles: u32,
+}
+
+impl RunOutput {
+    /// Returns the stack outputs of the program.
+    pub fn stack_outputs(&self) -> &StackOutputs {
+        &self.stack_outputs
+    }
+
+    /// Returns the number of cycles executed by the VM.
+    ///
+    /// This is the same as the length of the execution trace of the program before padding.
+    pub fn num_cycles(&self) -> u32 {
+        self.num_cycles
+    }
+
+    /// Returns a word located at the specified context/address at the end of the execution, or
+    /// None if the address has never been accessed.
+    pub fn get_mem_value(&self, ctx: u32, addr: u32) -> Option<Word> {
+        self.memory.get(&ctx)?.get(&addr).copied()
+    }
+
+    /// Returns the memory state of the specified execution context at the end of the execution as
+    /// a vector of (address, value) tuples sorted by address; addresses which have never been
+    /// accessed are not included.
+    pub fn get_mem_state(&self, ctx: u32) -> Vec<(u64, Word)> {
+        match self.memory.get(&ctx) {
+            Some(memory) => memory.iter().map(|(&addr, &word)| (addr as u64, word)).collect(),
+            None => Vec::new(),
+        }
+    }
+}
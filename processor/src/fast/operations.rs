@@ -0,0 +1,563 @@
+use super::{ExecutionError, FastProcessor, Felt, Host, Operation, Word, ONE, ZERO};
+use crate::{
+    crypto::MerklePath,
+    operations::fri_ops::{
+        compute_evaluation_points, fold4, get_domain_segment_flags, get_tau_factor, DOMAIN_OFFSET,
+    },
+    system::{FMP_MAX, FMP_MIN},
+    utils::split_element,
+    QuadFelt,
+};
+use vm_core::{
+    chiplets::hasher::apply_permutation, AdviceInjector, FieldElement, StarkField, EMPTY_WORD,
+};
+
+// CONSTANTS
+// ================================================================================================
+
+const TWO: Felt = Felt::new(2);
+
+// OPERATION DISPATCHER
+// ================================================================================================
+
+impl<H> FastProcessor<H>
+where
+    H: Host,
+{
+    /// Executes the specified operation and advances the clock.
+    ///
+    /// Control flow operations are executed by the code block executors, except for SPLIT, LOOP,
+    /// REPEAT, and END operations popping the stack, which are executed as DROP operations.
+    pub(super) fn execute_op(&mut self, op: Operation) -> Result<(), ExecutionError> {
+        match op {
+            // ----- system operations ------------------------------------------------------------
+            Operation::Noop => (),
+            Operation::Assert(err_code) => self.op_assert(err_code)?,
+
+            Operation::FmpAdd => self.op_fmpadd(),
+            Operation::FmpUpdate => self.op_fmpupdate()?,
+
+            Operation::SDepth => self.stack.push(Felt::new(self.stack.depth() as u64), self.clk),
+            Operation::Caller => self.op_caller()?,
+
+            Operation::Clk => self.stack.push(Felt::from(self.clk), self.clk),
+
+            // ----- flow control operations ------------------------------------------------------
+            Operation::Join
+            | Operation::Split
+            | Operation::Loop
+            | Operation::Call
+            | Operation::SysCall
+            | Operation::Dyn
+            | Operation::Span
+            | Operation::Repeat
+            | Operation::Respan
+            | Operation::End
+            | Operation::Halt => unreachable!("control flow operation"),
+
+            // ----- field operations -------------------------------------------------------------
+            Operation::Add => self.op_binary(|a, b| a + b),
+            Operation::Neg => self.stack.set(0, -self.stack.get(0)),
+            Operation::Mul => self.op_binary(|a, b| a * b),
+            Operation::Inv => self.op_inv()?,
+            Operation::Incr => self.stack.set(0, self.stack.get(0) + ONE),
+
+            Operation::And => self.op_boolean(|a, b| a && b)?,
+            Operation::Or => self.op_boolean(|a, b| a || b)?,
+            Operation::Not => {
+                let a = assert_binary(self.stack.get(0))?;
+                self.stack.set(0, ONE - a);
+            }
+
+            Operation::Eq => self.op_binary(|a, b| if a == b { ONE } else { ZERO }),
+            Operation::Eqz => {
+                let a = self.stack.get(0);
+                self.stack.set(0, if a == ZERO { ONE } else { ZERO });
+            }
+
+            Operation::Expacc => self.op_expacc(),
+
+            // ----- ext2 operations --------------------------------------------------------------
+            Operation::Ext2Mul => self.op_ext2mul(),
+
+            // ----- u32 operations ---------------------------------------------------------------
+            Operation::U32split => {
+                let (hi, lo) = split_element(self.stack.get(0));
+                self.stack.set(0, lo);
+                self.stack.push(hi, self.clk);
+            }
+            Operation::U32add => {
+                let b = self.stack.get(0);
+                let a = self.stack.get(1);
+                self.set_split(a + b);
+            }
+            Operation::U32add3 => {
+                let c = self.stack.get(0).as_int();
+                let b = self.stack.get(1).as_int();
+                let a = self.stack.get(2).as_int();
+                self.stack.remove(2);
+                self.set_split(Felt::new(a + b + c));
+            }
+            Operation::U32sub => {
+                let b = self.stack.get(0).as_int();
+                let a = self.stack.get(1).as_int();
+                let result = a.wrapping_sub(b);
+                self.stack.set(0, Felt::new(result >> 63));
+                self.stack.set(1, Felt::new((result as u32) as u64));
+            }
+            Operation::U32mul => {
+                let b = self.stack.get(0).as_int();
+                let a = self.stack.get(1).as_int();
+                self.set_split(Felt::new(a * b));
+            }
+            Operation::U32madd => {
+                let b = self.stack.get(0).as_int();
+                let a = self.stack.get(1).as_int();
+                let c = self.stack.get(2).as_int();
+                self.stack.remove(2);
+                self.set_split(Felt::new(a * b + c));
+            }
+            Operation::U32div => self.op_u32div()?,
+
+            Operation::U32and => self.op_bitwise(|a, b| a & b)?,
+            Operation::U32xor => self.op_bitwise(|a, b| a ^ b)?,
+            Operation::U32assert2(err_code) => {
+                for value in [self.stack.get(0), self.stack.get(1)] {
+                    if value.as_int() >> 32 != 0 {
+                        return Err(ExecutionError::NotU32Value(value, err_code));
+                    }
+                }
+            }
+
+            // ----- stack manipulation -----------------------------------------------------------
+            Operation::Pad => self.stack.push(ZERO, self.clk),
+            Operation::Drop => {
+                self.stack.pop();
+            }
+
+            Operation::Dup0 => self.op_dup(0),
+            Operation::Dup1 => self.op_dup(1),
+            Operation::Dup2 => self.op_dup(2),
+            Operation::Dup3 => self.op_dup(3),
+            Operation::Dup4 => self.op_dup(4),
+            Operation::Dup5 => self.op_dup(5),
+            Operation::Dup6 => self.op_dup(6),
+            Operation::Dup7 => self.op_dup(7),
+            Operation::Dup9 => self.op_dup(9),
+            Operation::Dup11 => self.op_dup(11),
+            Operation::Dup13 => self.op_dup(13),
+            Operation::Dup15 => self.op_dup(15),
+
+            Operation::Swap => self.stack.swap(0, 1, 1),
+            Operation::SwapW => self.stack.swap(0, 4, 4),
+            Operation::SwapW2 => self.stack.swap(0, 8, 4),
+            Operation::SwapW3 => self.stack.swap(0, 12, 4),
+            Operation::SwapDW => self.stack.swap(0, 8, 8),
+
+            Operation::MovUp2 => self.stack.move_up(2),
+            Operation::MovUp3 => self.stack.move_up(3),
+            Operation::MovUp4 => self.stack.move_up(4),
+            Operation::MovUp5 => self.stack.move_up(5),
+            Operation::MovUp6 => self.stack.move_up(6),
+            Operation::MovUp7 => self.stack.move_up(7),
+            Operation::MovUp8 => self.stack.move_up(8),
+
+            Operation::MovDn2 => self.stack.move_down(2),
+            Operation::MovDn3 => self.stack.move_down(3),
+            Operation::MovDn4 => self.stack.move_down(4),
+            Operation::MovDn5 => self.stack.move_down(5),
+            Operation::MovDn6 => self.stack.move_down(6),
+            Operation::MovDn7 => self.stack.move_down(7),
+            Operation::MovDn8 => self.stack.move_down(8),
+
+            Operation::CSwap => self.op_cswap(1)?,
+            Operation::CSwapW => self.op_cswap(4)?,
+
+            // ----- input / output ---------------------------------------------------------------
+            Operation::Push(value) => self.stack.push(value, self.clk),
+
+            Operation::AdvPop => {
+                let value = self.host.borrow_mut().pop_adv_stack(self)?;
+                self.stack.push(value, self.clk);
+            }
+            Operation::AdvPopW => {
+                let word = self.host.borrow_mut().pop_adv_stack_word(self)?;
+                self.set_word(0, word);
+            }
+
+            Operation::MLoadW => {
+                let addr = get_valid_address(self.stack.get(0))?;
+                let word = self.read_mem(addr);
+                self.stack.pop();
+                self.set_word(0, word);
+            }
+            Operation::MStoreW => {
+                let addr = get_valid_address(self.stack.get(0))?;
+                self.stack.pop();
+                let word = self.stack.get_word(0);
+                self.write_mem(addr, word);
+            }
+
+            Operation::MLoad => {
+                let addr = get_valid_address(self.stack.get(0))?;
+                let word = self.read_mem(addr);
+                self.stack.set(0, word[0]);
+            }
+            Operation::MStore => {
+                let addr = get_valid_address(self.stack.get(0))?;
+                let value = self.stack.get(1);
+                let mut word = self.read_mem(addr);
+                word[0] = value;
+                self.write_mem(addr, word);
+                self.stack.pop();
+            }
+
+            Operation::MStream => {
+                let addr = get_valid_address(self.stack.get(12))?;
+                let words = [self.read_mem(addr), self.read_mem(addr + 1)];
+                self.set_double_word(addr, words);
+            }
+            Operation::Pipe => {
+                let addr = get_valid_address(self.stack.get(12))?;
+                let words = self.host.borrow_mut().pop_adv_stack_dword(self)?;
+                self.write_mem(addr, words[0]);
+                self.write_mem(addr + 1, words[1]);
+                self.set_double_word(addr, words);
+            }
+
+            // ----- cryptographic operations -----------------------------------------------------
+            Operation::HPerm => {
+                let mut state = [ZERO; 12];
+                for (i, value) in state.iter_mut().enumerate() {
+                    *value = self.stack.get(11 - i);
+                }
+                apply_permutation(&mut state);
+                for (i, &value) in state.iter().rev().enumerate() {
+                    self.stack.set(i, value);
+                }
+            }
+            Operation::MpVerify => self.op_mpverify()?,
+            Operation::MrUpdate => self.op_mrupdate()?,
+            Operation::FriE2F4 => self.op_fri_ext2fold4()?,
+        }
+
+        self.advance_clock()
+    }
+
+    // SYSTEM OPERATIONS
+    // --------------------------------------------------------------------------------------------
+
+    fn op_assert(&mut self, err_code: Felt) -> Result<(), ExecutionError> {
+        if self.stack.get(0) != ONE {
+            return Err(ExecutionError::FailedAssertion(self.clk, err_code));
+        }
+        self.stack.pop();
+        Ok(())
+    }
+
+    fn op_fmpadd(&mut self) {
+        let offset = self.stack.get(0);
+        self.stack.set(0, self.fmp + offset);
+    }
+
+    fn op_fmpupdate(&mut self) -> Result<(), ExecutionError> {
+        let new_fmp = self.fmp + self.stack.get(0);
+        if new_fmp.as_int() < FMP_MIN || new_fmp.as_int() > FMP_MAX {
+            return Err(ExecutionError::InvalidFmpValue(self.fmp, new_fmp));
+        }
+        self.fmp = new_fmp;
+        self.stack.pop();
+        Ok(())
+    }
+
+    fn op_caller(&mut self) -> Result<(), ExecutionError> {
+        if !self.in_syscall {
+            return Err(ExecutionError::CallerNotInSyscall);
+        }
+        self.set_word(0, self.fn_hash);
+        Ok(())
+    }
+
+    // FIELD OPERATIONS
+    // --------------------------------------------------------------------------------------------
+
+    /// Pops two elements off the stack and pushes the result of the specified operation applied
+    /// to them, with the element previously on the top of the stack as the second operand.
+    #[inline(always)]
+    fn op_binary(&mut self, op: impl FnOnce(Felt, Felt) -> Felt) {
+        let b = self.stack.pop();
+        let a = self.stack.get(0);
+        self.stack.set(0, op(a, b));
+    }
+
+    fn op_inv(&mut self) -> Result<(), ExecutionError> {
+        let a = self.stack.get(0);
+        if a == ZERO {
+            return Err(ExecutionError::DivideByZero(self.clk));
+        }
+        self.stack.set(0, a.inv());
+        Ok(())
+    }
+
+    /// Pops two binary elements off the stack and pushes the result of the specified boolean
+    /// operation applied to them.
+    fn op_boolean(&mut self, op: impl FnOnce(bool, bool) -> bool) -> Result<(), ExecutionError> {
+        let b = assert_binary(self.stack.get(0))?;
+        let a = assert_binary(self.stack.get(1))?;
+        self.op_binary(|_, _| if op(a == ONE, b == ONE) { ONE } else { ZERO });
+        Ok(())
+    }
+
+    fn op_expacc(&mut self) {
+        let mut exp = self.stack.get(1);
+        let mut acc = self.stack.get(2);
+        let mut b = self.stack.get(3);
+
+        let bit = b.as_int() & 1;
+        let value = Felt::new((exp.as_int() - 1) * bit + 1);
+        acc *= value;
+        b = Felt::new(b.as_int() >> 1);
+        exp *= exp;
+
+        self.stack.set(0, Felt::new(bit));
+        self.stack.set(1, exp);
+        self.stack.set(2, acc);
+        self.stack.set(3, b);
+    }
+
+    fn op_ext2mul(&mut self) {
+        let [a0, a1, b0, b1] = self.stack.get_word(0);
+        self.stack.set(0, b1);
+        self.stack.set(1, b0);
+        self.stack.set(2, (b0 + b1) * (a1 + a0) - b0 * a0);
+        self.stack.set(3, b0 * a0 - TWO * b1 * a1);
+    }
+
+    // U32 OPERATIONS
+    // --------------------------------------------------------------------------------------------
+
+    /// Replaces the two elements on the top of the stack with the high and the low 32 bits of the
+    /// specified value.
+    #[inline(always)]
+    fn set_split(&mut self, value: Felt) {
+        let (hi, lo) = split_element(value);
+        self.stack.set(0, hi);
+        self.stack.set(1, lo);
+    }
+
+    fn op_u32div(&mut self) -> Result<(), ExecutionError> {
+        let b = self.stack.get(0).as_int();
+        let a = self.stack.get(1).as_int();
+        if b == 0 {
+            return Err(ExecutionError::DivideByZero(self.clk));
+        }
+
+        let q = a / b;
+        let r = a - q * b;
+        self.stack.set(0, Felt::new(r));
+        self.stack.set(1, Felt::new(q));
+        Ok(())
+    }
+
+    /// Pops two u32 elements off the stack and pushes the result of the specified bitwise
+    /// operation applied to them.
+    fn op_bitwise(&mut self, op: impl FnOnce(u64, u64) -> u64) -> Result<(), ExecutionError> {
+        let a = assert_u32(self.stack.get(1))?;
+        let b = assert_u32(self.stack.get(0))?;
+        self.op_binary(|_, _| Felt::new(op(a, b)));
+        Ok(())
+    }
+
+    // STACK OPERATIONS
+    // --------------------------------------------------------------------------------------------
+
+    #[inline(always)]
+    fn op_dup(&mut self, n: usize) {
+        let value = self.stack.get(n);
+        self.stack.push(value, self.clk);
+    }
+
+    /// Pops a binary condition off the stack, and swaps the two groups of the specified number of
+    /// elements below it if the condition is ONE.
+    fn op_cswap(&mut self, num_items: usize) -> Result<(), ExecutionError> {
+        let c = self.stack.get(0);
+        match c.as_int() {
+            0 => (),
+            1 => self.stack.swap(1, 1 + num_items, num_items),
+            _ => return Err(ExecutionError::NotBinaryValue(c)),
+        }
+        self.stack.pop();
+        Ok(())
+    }
+
+    // INPUT / OUTPUT OPERATIONS
+    // --------------------------------------------------------------------------------------------
+
+    /// Sets the elements of the word at the specified word index on the stack; the last element
+    /// of the word is set at the top of the stack.
+    #[inline(always)]
+    fn set_word(&mut self, word_idx: usize, word: Word) {
+        let offset = word_idx * 4;
+        for (i, &value) in word.iter().rev().enumerate() {
+            self.stack.set(offset + i, value);
+        }
+    }
+
+    /// Sets the two provided words at the top of the stack, and the address following them at the
+    /// position 12 of the stack.
+    fn set_double_word(&mut self, addr: u32, words: [Word; 2]) {
+        self.set_word(0, words[1]);
+        self.set_word(1, words[0]);
+        self.stack.set(12, Felt::from(addr + 2));
+    }
+
+    /// Returns the word at the specified address of the current execution context; addresses
+    /// which have never been accessed are initialized with ZERO elements.
+    #[inline(always)]
+    fn read_mem(&mut self, addr: u32) -> Word {
+        *self.memory.entry(self.ctx).or_default().entry(addr).or_insert(EMPTY_WORD)
+    }
+
+    /// Writes the specified word at the specified address of the current execution context.
+    #[inline(always)]
+    fn write_mem(&mut self, addr: u32, word: Word) {
+        self.memory.entry(self.ctx).or_default().insert(addr, word);
+    }
+
+    // CRYPTOGRAPHIC OPERATIONS
+    // --------------------------------------------------------------------------------------------
+
+    fn op_mpverify(&mut self) -> Result<(), ExecutionError> {
+        let node = self.stack.get_word(0);
+        let index = self.stack.get(5);
+        let root = [self.stack.get(9), self.stack.get(8), self.stack.get(7), self.stack.get(6)];
+
+        let path = self.host.borrow_mut().get_adv_merkle_path(self)?;
+        let computed_root = compute_merkle_root(node, &path, index);
+        assert_eq!(root, computed_root, "inconsistent Merkle tree root");
+
+        Ok(())
+    }
+
+    fn op_mrupdate(&mut self) -> Result<(), ExecutionError> {
+        let old_node = self.stack.get_word(0);
+        let depth = self.stack.get(4);
+        let index = self.stack.get(5);
+        let old_root = [self.stack.get(9), self.stack.get(8), self.stack.get(7), self.stack.get(6)];
+        let new_node =
+            [self.stack.get(13), self.stack.get(12), self.stack.get(11), self.stack.get(10)];
+
+        let path: MerklePath = self
+            .host
+            .borrow_mut()
+            .set_advice(self, AdviceInjector::UpdateMerkleNode)?
+            .into();
+        assert_eq!(path.len(), depth.as_int() as usize);
+
+        let computed_old_root = compute_merkle_root(old_node, &path, index);
+        assert_eq!(old_root, computed_old_root, "inconsistent Merkle tree root");
+
+        let new_root = compute_merkle_root(new_node, &path, index);
+        self.set_word(0, new_root);
+
+        Ok(())
+    }
+
+    fn op_fri_ext2fold4(&mut self) -> Result<(), ExecutionError> {
+        let query_values = [
+            QuadFelt::new(self.stack.get(7), self.stack.get(6)),
+            QuadFelt::new(self.stack.get(5), self.stack.get(4)),
+            QuadFelt::new(self.stack.get(3), self.stack.get(2)),
+            QuadFelt::new(self.stack.get(1), self.stack.get(0)),
+        ];
+        let f_pos = self.stack.get(8);
+        let d_seg = self.stack.get(9).as_int();
+        let poe = self.stack.get(10);
+        let prev_value = QuadFelt::new(self.stack.get(12), self.stack.get(11));
+        let alpha = QuadFelt::new(self.stack.get(14), self.stack.get(13));
+        let layer_ptr = self.stack.get(15);
+
+        // make sure the previous folding was done correctly
+        if d_seg > 3 {
+            return Err(ExecutionError::InvalidFriDomainSegment(d_seg));
+        }
+        let d_seg = d_seg as usize;
+        if query_values[d_seg] != prev_value {
+            return Err(ExecutionError::InvalidFriLayerFolding(prev_value, query_values[d_seg]));
+        }
+
+        // fold query values
+        let f_tau = get_tau_factor(d_seg);
+        let x = poe * f_tau * DOMAIN_OFFSET;
+        let (ev, es) = compute_evaluation_points(alpha, x.inv());
+        let (folded_value, tmp0, tmp1) = fold4(query_values, ev, es);
+
+        let tmp0 = tmp0.to_base_elements();
+        let tmp1 = tmp1.to_base_elements();
+        let ds = get_domain_segment_flags(d_seg);
+        let folded_value = folded_value.to_base_elements();
+        let poe2 = poe.square();
+        let poe4 = poe2.square();
+
+        self.stack.remove(15);
+        let values = [
+            tmp0[1],
+            tmp0[0],
+            tmp1[1],
+            tmp1[0],
+            ds[3],
+            ds[2],
+            ds[1],
+            ds[0],
+            poe2,
+            f_tau,
+            layer_ptr + TWO,
+            poe4,
+            f_pos,
+            folded_value[1],
+            folded_value[0],
+        ];
+        for (i, value) in values.into_iter().enumerate() {
+            self.stack.set(i, value);
+        }
+
+        Ok(())
+    }
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+fn assert_binary(value: Felt) -> Result<Felt, ExecutionError> {
+    if value != ZERO && value != ONE {
+        Err(ExecutionError::NotBinaryValue(value))
+    } else {
+        Ok(value)
+    }
+}
+
+fn assert_u32(value: Felt) -> Result<u64, ExecutionError> {
+    let value_u64 = value.as_int();
+    if value_u64 > u32::MAX as u64 {
+        Err(ExecutionError::NotU32Value(value, ZERO))
+    } else {
+        Ok(value_u64)
+    }
+}
+
+fn get_valid_address(addr: Felt) -> Result<u32, ExecutionError> {
+    let addr = addr.as_int();
+    if addr > u32::MAX as u64 {
+        return Err(ExecutionError::MemoryAddressOutOfBounds(addr));
+    }
+    Ok(addr as u32)
+}
+
+/// Returns the root of the Merkle tree computed from the specified node, its index, and the
+/// provided path.
+fn compute_merkle_root(node: Word, path: &MerklePath, index: Felt) -> Word {
+    assert!(!path.is_empty(), "path is empty");
+    path.compute_root(index.as_int(), node.into())
+        .expect("invalid index for the path")
+        .into()
+}
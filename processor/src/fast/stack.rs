@@ -0,0 +1,199 @@
+use crate::{Felt, StackInputs, StackOutputs, Vec, Word, ZERO};
+use vm_core::{stack::STACK_TOP_SIZE, StarkField};
+
+// OPERAND STACK
+// ================================================================================================
+
+/// Operand stack of the fast processor.
+///
+/// All items of the stack are kept in a single vector with the top of the stack at the end; the
+/// items beyond the top 16 slots constitute the overflow table. As for the stack of the VM, the
+/// depth of the stack can never drop below 16: if an item is removed from the stack when the
+/// depth is 16, a ZERO element is inserted into the 16th slot.
+///
+/// When a new execution context is started, the overflow table of the current context is hidden,
+/// and thus, the stack of the new context has depth 16.
+pub(super) struct OperandStack {
+    /// Stack items; the top of the stack is the last item.
+    items: Vec<Felt>,
+    /// Addresses of the rows of the overflow table (i.e., the clock cycles at which the items
+    /// were moved into the overflow table), starting with the deepest item.
+    overflow_addrs: Vec<u64>,
+    /// Index of the deepest item of the stack in the current execution context.
+    bottom: usize,
+}
+
+impl OperandStack {
+    // CONSTRUCTOR
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns a stack initialized with the specified program inputs.
+    pub fn new(inputs: &StackInputs) -> Self {
+        let values = inputs.values();
+        let mut items = Vec::with_capacity(values.len().max(STACK_TOP_SIZE));
+        items.resize(STACK_TOP_SIZE.saturating_sub(values.len()), ZERO);
+        items.extend(values.iter().rev());
+
+        // rows of the overflow table holding the inputs are given "negative" (mod p) addresses,
+        // since they are added before the first execution cycle
+        let num_overflow = items.len() - STACK_TOP_SIZE;
+        let overflow_addrs =
+            (0..num_overflow as u64).map(|i| Felt::MODULUS - num_overflow as u64 + i);
+
+        Self {
+            items,
+            overflow_addrs: overflow_addrs.collect(),
+            bottom: 0,
+        }
+    }
+
+    // PUBLIC ACCESSORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the depth of the stack in the current execution context.
+    #[inline(always)]
+    pub fn depth(&self) -> usize {
+        self.items.len() - self.bottom
+    }
+
+    /// Returns the item at the specified position on the stack.
+    #[inline(always)]
+    pub fn get(&self, pos: usize) -> Felt {
+        debug_assert!(pos < STACK_TOP_SIZE, "stack underflow");
+        self.items[self.items.len() - 1 - pos]
+    }
+
+    /// Returns a word located at the specified word index on the stack; the top element of the
+    /// word is at the last position in the word.
+    #[inline(always)]
+    pub fn get_word(&self, word_idx: usize) -> Word {
+        let offset = word_idx * 4;
+        [
+            self.get(offset + 3),
+            self.get(offset + 2),
+            self.get(offset + 1),
+            self.get(offset),
+        ]
+    }
+
+    /// Returns all items of the stack starting with the top item, including the items hidden in
+    /// the current execution context.
+    pub fn get_state(&self) -> Vec<Felt> {
+        self.items.iter().rev().copied().collect()
+    }
+
+    /// Returns the outputs of the stack.
+    pub fn build_stack_outputs(&self) -> StackOutputs {
+        // the first address is the previous address of the deepest row, which is always ZERO
+        let overflow_addrs = if self.overflow_addrs.is_empty() {
+            Vec::new()
+        } else {
+            core::iter::once(0).chain(self.overflow_addrs.iter().copied()).collect()
+        };
+        let stack = self.items.iter().rev().map(|value| value.as_int()).collect();
+        StackOutputs::new(stack, overflow_addrs).expect("processor stack handling logic is valid")
+    }
+
+    // STATE MUTATORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Sets the item at the specified position on the stack.
+    #[inline(always)]
+    pub fn set(&mut self, pos: usize, value: Felt) {
+        debug_assert!(pos < STACK_TOP_SIZE, "stack underflow");
+        let idx = self.items.len() - 1 - pos;
+        self.items[idx] = value;
+    }
+
+    /// Pushes the specified item onto the stack at the specified clock cycle; the item in the
+    /// 16th slot is moved into the overflow table.
+    #[inline(always)]
+    pub fn push(&mut self, value: Felt, clk: u32) {
+        self.items.push(value);
+        self.overflow_addrs.push(clk as u64);
+    }
+
+    /// Removes the item from the top of the stack and returns it.
+    #[inline(always)]
+    pub fn pop(&mut self) -> Felt {
+        let value = self.items.pop().expect("stack underflow");
+        self.refill();
+        value
+    }
+
+    /// Removes the item at the specified position from the stack; the items below it are shifted
+    /// one slot up.
+    #[inline(always)]
+    pub fn remove(&mut self, pos: usize) {
+        debug_assert!(pos < STACK_TOP_SIZE, "stack underflow");
+        let idx = self.items.len() - 1 - pos;
+        self.items.remove(idx);
+        self.refill();
+    }
+
+    /// Swaps the specified number of items starting at the two specified positions on the stack.
+    #[inline(always)]
+    pub fn swap(&mut self, pos_a: usize, pos_b: usize, num_items: usize) {
+        debug_assert!(pos_a + num_items <= pos_b, "overlapping items");
+        debug_assert!(pos_b + num_items <= STACK_TOP_SIZE, "stack underflow");
+        let top = self.items.len();
+        let (lower, upper) = self.items.split_at_mut(top - pos_b);
+        let lower_start = lower.len() - num_items;
+        let upper_start = upper.len() - pos_a - num_items;
+        lower[lower_start..].swap_with_slice(&mut upper[upper_start..upper_start + num_items]);
+    }
+
+    /// Moves the item at the specified position to the top of the stack; the items above it are
+    /// shifted one slot down.
+    #[inline(always)]
+    pub fn move_up(&mut self, pos: usize) {
+        debug_assert!(pos < STACK_TOP_SIZE, "stack underflow");
+        let top = self.items.len();
+        self.items[top - 1 - pos..].rotate_left(1);
+    }
+
+    /// Moves the item at the top of the stack to the specified position; the items above this
+    /// position are shifted one slot up.
+    #[inline(always)]
+    pub fn move_down(&mut self, pos: usize) {
+        debug_assert!(pos < STACK_TOP_SIZE, "stack underflow");
+        let top = self.items.len();
+        self.items[top - 1 - pos..].rotate_right(1);
+    }
+
+    // CONTEXT MANAGEMENT
+    // --------------------------------------------------------------------------------------------
+
+    /// Starts a new execution context, and returns the information required to restore the
+    /// current context.
+    ///
+    /// This has the effect of hiding the contents of the overflow table such that it appears as
+    /// if the overflow table in the new context is empty.
+    pub fn start_context(&mut self) -> usize {
+        let bottom = self.bottom;
+        self.bottom = self.items.len() - STACK_TOP_SIZE;
+        bottom
+    }
+
+    /// Restores the execution context described by the provided information.
+    ///
+    /// The depth of the stack must be 16.
+    pub fn restore_context(&mut self, bottom: usize) {
+        debug_assert_eq!(self.depth(), STACK_TOP_SIZE, "overflow table not empty");
+        self.bottom = bottom;
+    }
+
+    // HELPER METHODS
+    // --------------------------------------------------------------------------------------------
+
+    /// Fills the 16th slot after an item was removed from the stack, either with the item at the
+    /// top of the overflow table or, if the overflow table is empty, with ZERO.
+    #[inline(always)]
+    fn refill(&mut self) {
+        if self.depth() < STACK_TOP_SIZE {
+            self.items.insert(self.bottom, ZERO);
+        } else {
+            self.overflow_addrs.pop();
+        }
+    }
+}
@@ -0,0 +1,294 @@
+use super::RunOutput;
+use crate::{
+    crypto::{MerkleStore, MerkleTree},
+    run, AdviceInputs, DefaultHost, ExecutionError, ExecutionOptions, MemAdviceProvider, Process,
+    StackInputs, Word, ZERO,
+};
+use miden_assembly::Assembler;
+use vm_core::{
+    utils::{collections::Vec, string::String},
+    Felt,
+};
+
+// TESTS
+// ================================================================================================
+
+#[test]
+fn fast_field_ops() {
+    let source = "
+        begin
+            push.3 push.5 add push.2 mul inv neg add.1
+            push.7 eq not eq.0 and push.1 or
+            push.3 exp.5 push.9 exp.u12
+            push.1.2.3.4 ext2mul
+        end";
+    run_both(Assembler::default(), source, &[], AdviceInputs::default());
+}
+
+#[test]
+fn fast_u32_ops() {
+    let source = "
+        begin
+            push.4294967295 push.17 u32overflowing_add
+            push.3 u32overflowing_add3
+            push.5 push.9 u32overflowing_sub
+            push.100000 push.300000 u32overflowing_mul
+            push.7 push.11 u32overflowing_madd
+            push.1000 push.7 u32checked_divmod
+            push.12 push.10 u32checked_and push.6 u32checked_xor
+            push.18446744069414584320 u32split
+            u32assert2 u32checked_shl.3 u32checked_popcnt
+        end";
+    run_both(Assembler::default(), source, &[], AdviceInputs::default());
+}
+
+#[test]
+fn fast_stack_ops() {
+    let source = "
+        begin
+            push.1.2.3.4.5.6.7.8.9.10.11.12.13.14.15.16 push.17.18.19.20
+            swapw swapw.2 swapw.3 swapdw
+            movup.2 movup.5 movup.8 movdn.3 movdn.7 movdn.8 swap
+            dup.0 dup.3 dup.9 dup.13 dup.15
+            push.1 cswap push.0 cswap push.1 cswapw push.0 cswapw
+            sdepth clk
+            drop drop drop drop drop drop drop drop dropw dropw dropw dropw
+        end";
+    run_both(Assembler::default(), source, &[], AdviceInputs::default());
+}
+
+#[test]
+fn fast_stack_inputs() {
+    let source = "
+        begin
+            dropw dropw push.1 push.2 swapw.3 padw dropw drop drop drop drop drop drop
+        end";
+    let inputs = (1..=24).collect::<Vec<_>>();
+    run_both(Assembler::default(), source, &inputs, AdviceInputs::default());
+}
+
+#[test]
+fn fast_memory_ops() {
+    let source = "
+        proc.foo.3
+            loc_store.0 loc_storew.1 loc_load.0 loc_loadw.2 locaddr.1
+        end
+        begin
+            push.7 mem_store.2 push.1.2.3.4 mem_storew.5 dropw
+            mem_load.2 mem_loadw.5 mem_load.9 push.3 mem_load
+            padw padw push.1 mem_stream
+            push.1.2.3.4.5 exec.foo
+        end";
+    run_both(Assembler::default(), source, &[], AdviceInputs::default());
+}
+
+#[test]
+fn fast_control_flow() {
+    let source = "
+        begin
+            push.3
+            dup.0 push.2 gt
+            while.true
+                push.1 sub
+                dup.0 push.0 eq
+                if.true push.7 else push.8 end
+                drop
+                dup.0 push.0 gt
+            end
+            repeat.3 push.2 add end
+            push.0 while.true push.1 end
+            push.1 if.true push.5 end
+        end";
+    run_both(Assembler::default(), source, &[], AdviceInputs::default());
+}
+
+#[test]
+fn fast_call_ops() {
+    let kernel = "
+        export.baz
+            caller push.1 mem_store.3 sdepth add
+        end";
+    let source = "
+        proc.bar.1
+            loc_store.0 push.7 mem_store.2 syscall.baz dropw drop
+        end
+        proc.foo
+            push.1 push.2 mem_storew.4 dropw
+            push.5 call.bar
+            dropw dropw
+        end
+        begin
+            push.1.2.3.4.5.6.7.8.9.10.11.12.13.14.15.16 push.17
+            call.foo
+            mem_loadw.4
+        end";
+    let assembler = Assembler::default().with_kernel(kernel).unwrap();
+    run_both(assembler, source, &[], AdviceInputs::default());
+}
+
+#[test]
+fn fast_dyn_ops() {
+    let foo_hash = Assembler::default().compile("begin push.1 add end").unwrap().hash();
+    let foo_hash = foo_hash.as_elements().iter().map(|e| format!("push.{e} ")).collect::<String>();
+    let source = format!(
+        "
+        proc.foo
+            push.1 add
+        end
+        begin
+            push.2 call.foo
+            {foo_hash} dynexec dropw
+            {foo_hash} dyncall dropw
+        end"
+    );
+    run_both(Assembler::default(), &source, &[], AdviceInputs::default());
+}
+
+#[test]
+fn fast_advice_ops() {
+    let source = "
+        begin
+            adv_push.2 adv_loadw
+            padw padw push.10 adv_pipe
+            push.3 push.4 adv.push_u64div adv_push.4
+        end";
+    let advice_inputs = AdviceInputs::default().with_stack_values((1..=14).rev()).unwrap();
+    run_both(Assembler::default(), source, &[], advice_inputs);
+}
+
+#[test]
+fn fast_hash_ops() {
+    let source = "
+        begin
+            push.1.2.3.4.5.6.7.8 hmerge
+            push.9.10.11.12 hash
+            padw hperm
+        end";
+    run_both(Assembler::default(), source, &[], AdviceInputs::default());
+}
+
+#[test]
+fn fast_merkle_ops() {
+    let leaves = (0..8).map(|i| [Felt::new(i), Felt::new(i + 1), ZERO, ZERO]);
+    let tree = MerkleTree::new(leaves.collect()).unwrap();
+    let store = MerkleStore::from(&tree);
+    let root = tree.root();
+    let root = root.as_elements().iter().map(|e| format!("push.{e} ")).collect::<String>();
+
+    let source = format!(
+        "
+        begin
+            {root} push.5 push.3 mtree_get
+            push.5 movdn.4 push.3 movdn.4 mtree_verify dropw drop drop
+            push.7.8.9.10 swapw push.2 push.3 mtree_set
+        end"
+    );
+    let advice_inputs = AdviceInputs::default().with_merkle_store(store);
+    run_both(Assembler::default(), &source, &[], advice_inputs);
+}
+
+#[test]
+fn fast_fri_ops() {
+    // query value at position d_seg = 0 is equal to the result of the previous layer folding
+    let source = "
+        begin
+            push.200 push.100 push.5 push.6 push.10 push.11 push.7 push.0 push.3
+            push.10 push.11 push.12 push.13 push.14 push.15 push.16 push.17
+            fri_ext2fold4
+        end";
+    run_both(Assembler::default(), source, &[], AdviceInputs::default());
+}
+
+#[test]
+fn fast_errors() {
+    let sources = [
+        "begin push.2 assert.err=5 end",
+        "begin push.0 inv end",
+        "begin push.3 push.0 u32unchecked_div end",
+        "begin push.4294967296 push.1 u32checked_and end",
+        "begin push.2 push.1 and end",
+        "begin push.2 if.true push.1 end end",
+        "begin push.4294967296 mem_load end",
+        "begin push.5 push.0.0.0.0.0.0.0.0.0 fri_ext2fold4 end",
+    ];
+    for source in sources {
+        assert_same_error(source, &[], ExecutionOptions::default());
+    }
+
+    // the maximum number of cycles is exceeded
+    let options = ExecutionOptions::new(Some(1 << 10), 1 << 10).unwrap();
+    assert_same_error("begin repeat.1000 push.1 drop end end", &[], options);
+
+    // a called procedure leaves more than 16 items on the stack
+    let source = "proc.foo push.1 end begin call.foo end";
+    assert_same_error(source, &[], ExecutionOptions::default());
+}
+
+#[test]
+fn fast_run_output() {
+    let output = run_both(
+        Assembler::default(),
+        "begin push.5 mem_store.3 push.1.2.3.4 mem_storew.1 dropw end",
+        &[],
+        AdviceInputs::default(),
+    );
+    let word = |values: [u64; 4]| -> Word { values.map(Felt::new) };
+    assert_eq!(Some(word([5, 0, 0, 0])), output.get_mem_value(0, 3));
+    assert_eq!(Some(word([1, 2, 3, 4])), output.get_mem_value(0, 1));
+    assert_eq!(None, output.get_mem_value(0, 2));
+    assert_eq!(vec![(1, word([1, 2, 3, 4])), (3, word([5, 0, 0, 0]))], output.get_mem_state(0));
+    assert!(output.get_mem_state(1).is_empty());
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Executes the program compiled from the specified source with and without generating an
+/// execution trace, asserts that both executions produce the same outputs, and returns the outputs
+/// of the fast processor.
+fn run_both(
+    assembler: Assembler,
+    source: &str,
+    stack_inputs: &[u64],
+    advice_inputs: AdviceInputs,
+) -> RunOutput {
+    let program = assembler.compile(source).unwrap();
+    let stack_inputs = StackInputs::try_from_values(stack_inputs.iter().copied()).unwrap();
+
+    let host = DefaultHost::new(MemAdviceProvider::from(advice_inputs.clone()));
+    let mut process = Process::new(
+        program.kernel().clone(),
+        stack_inputs.clone(),
+        host,
+        ExecutionOptions::default(),
+    );
+    let stack_outputs = process.execute(&program).unwrap();
+
+    let host = DefaultHost::new(MemAdviceProvider::from(advice_inputs));
+    let output = run(&program, stack_inputs, host, ExecutionOptions::default()).unwrap();
+
+    let clk = process.system.clk();
+    assert_eq!(&stack_outputs, output.stack_outputs());
+    assert_eq!(clk, output.num_cycles());
+    for &ctx in output.memory.keys() {
+        assert_eq!(process.chiplets.get_mem_state_at(ctx, clk), output.get_mem_state(ctx));
+    }
+    output
+}
+
+/// Asserts that executing the program compiled from the specified source with and without
+/// generating an execution trace fails with the same error.
+fn assert_same_error(source: &str, stack_inputs: &[u64], options: ExecutionOptions) {
+    let program = Assembler::default().compile(source).unwrap();
+    let stack_inputs = StackInputs::try_from_values(stack_inputs.iter().copied()).unwrap();
+
+    let mut process = Process::new(
+        program.kernel().clone(),
+        stack_inputs.clone(),
+        DefaultHost::default(),
+        options,
+    );
+    let expected: ExecutionError = process.execute(&program).unwrap_err();
+    let error = run(&program, stack_inputs, DefaultHost::default(), options).unwrap_err();
+    assert_eq!(format!("{expected:?}"), format!("{error:?}"), "{source}");
+}
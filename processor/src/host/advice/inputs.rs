@@ -1,4 +1,7 @@
-use super::{BTreeMap, Felt, InnerNodeInfo, InputError, MerkleStore, Vec};
+use super::{
+    BTreeMap, ByteReader, ByteWriter, Deserializable, DeserializationError, Felt, InnerNodeInfo,
+    InputError, MerkleStore, Serializable, Vec,
+};
 
 // ADVICE INPUTS
 // ================================================================================================
@@ -14,6 +17,10 @@ use super::{BTreeMap, Felt, InnerNodeInfo, InputError, MerkleStore, Vec};
 /// 2. Key-mapped element lists which can be pushed onto the advice stack.
 /// 3. Merkle store, which is used to provide nondeterministic inputs for instructions that
 ///    operates with Merkle trees.
+///
+/// Advice inputs can be serialized into a versioned binary blob. Together with
+/// [AdviceProvider::snapshot()](super::AdviceProvider::snapshot), this allows the advice left
+/// over after executing one program to be stored and used as advice inputs of another program.
 #[cfg(not(feature = "internals"))]
 #[derive(Clone, Debug, Default)]
 pub struct AdviceInputs {
@@ -23,6 +30,10 @@ pub struct AdviceInputs {
 }
 
 impl AdviceInputs {
+    /// Version of the binary format produced by serializing advice inputs. Advice inputs
+    /// serialized with a different version are rejected during deserialization.
+    pub const SERIALIZATION_VERSION: u8 = 1;
+
     // CONSTRUCTORS
     // --------------------------------------------------------------------------------------------
 
@@ -124,6 +135,60 @@ impl AdviceInputs {
     }
 }
 
+// SERIALIZATION
+// ================================================================================================
+
+impl Serializable for AdviceInputs {
+    fn write_into<W: ByteWriter>(&self, target: &mut W) {
+        target.write_u8(Self::SERIALIZATION_VERSION);
+
+        // the stack is serialized with the top element first
+        target.write_u64(self.stack.len() as u64);
+        self.stack.iter().copied().for_each(|value| target.write(value));
+
+        target.write_u64(self.map.len() as u64);
+        for (key, values) in self.map.iter() {
+            target.write_bytes(key);
+            target.write_u64(values.len() as u64);
+            values.iter().copied().for_each(|value| target.write(value));
+        }
+
+        self.store.write_into(target);
+    }
+}
+
+impl Deserializable for AdviceInputs {
+    fn read_from<R: ByteReader>(source: &mut R) -> Result<Self, DeserializationError> {
+        let version = source.read_u8()?;
+        if version != Self::SERIALIZATION_VERSION {
+            return Err(DeserializationError::InvalidValue(format!(
+                "unsupported advice inputs version {version}, expected version {}",
+                Self::SERIALIZATION_VERSION
+            )));
+        }
+
+        let stack = read_elements(source)?;
+
+        let num_entries = source.read_u64()?;
+        let mut map = BTreeMap::new();
+        for _ in 0..num_entries {
+            let key = source.read_array()?;
+            let values = read_elements(source)?;
+            map.insert(key, values);
+        }
+
+        let store = MerkleStore::read_from(source)?;
+
+        Ok(Self { stack, map, store })
+    }
+}
+
+/// Reads a length-prefixed list of field elements from the specified source.
+fn read_elements<R: ByteReader>(source: &mut R) -> Result<Vec<Felt>, DeserializationError> {
+    let num_elements = source.read_u64()?;
+    (0..num_elements).map(|_| source.read()).collect()
+}
+
 // INTERNALS
 // ================================================================================================
 
@@ -8,7 +8,7 @@ use vm_core::{
     },
     utils::{
         collections::{BTreeMap, KvMap, RecordingMap, Vec},
-        IntoBytes,
+        ByteReader, ByteWriter, Deserializable, DeserializationError, IntoBytes, Serializable,
     },
     AdviceInjector, SignatureKind,
 };
@@ -698,6 +698,22 @@ pub trait AdviceProvider: Sized {
     where
         I: Iterator<Item = R>,
         R: Borrow<RpoDigest>;
+
+    // SNAPSHOT
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the current state of this advice provider (i.e., the advice stack, the advice map,
+    /// and the Merkle store) as [AdviceInputs], or None if this advice provider does not support
+    /// snapshots.
+    ///
+    /// The advice provider is not modified; in particular, taking a snapshot of a provider which
+    /// records data accesses does not record any accesses. An advice provider instantiated from
+    /// the returned inputs is in the same state as this advice provider.
+    ///
+    /// The default implementation returns None.
+    fn snapshot(&self) -> Option<AdviceInputs> {
+        None
+    }
 }
 
 impl<'a, T> AdviceProvider for &'a mut T
@@ -797,4 +813,8 @@ where
     {
         T::get_store_subset(self, roots)
     }
+
+    fn snapshot(&self) -> Option<AdviceInputs> {
+        T::snapshot(self)
+    }
 }
@@ -233,6 +233,19 @@ where
     {
         self.store.subset(roots).into_inner().into_iter().collect()
     }
+
+    // SNAPSHOT
+    // --------------------------------------------------------------------------------------------
+
+    fn snapshot(&self) -> Option<AdviceInputs> {
+        // the advice stack is stored with its top element last, while advice inputs list the top
+        // element first
+        let inputs = AdviceInputs::default()
+            .with_stack(self.stack.iter().rev().copied())
+            .with_map(self.map.iter().map(|(key, values)| (*key, values.clone())))
+            .with_merkle_store(self.store.inner_nodes().collect());
+        Some(inputs)
+    }
 }
 
 // MEMORY ADVICE PROVIDER
@@ -339,6 +352,9 @@ impl AdviceProvider for MemAdviceProvider {
         self.provider.get_store_subset(roots)
     }
 
+    fn snapshot(&self) -> Option<AdviceInputs> {
+        self.provider.snapshot()
+    }
 }
 
 impl MemAdviceProvider {
@@ -463,6 +479,10 @@ impl AdviceProvider for RecAdviceProvider {
             R: core::borrow::Borrow<RpoDigest> {
         self.provider.get_store_subset(roots)
     }
+
+    fn snapshot(&self) -> Option<AdviceInputs> {
+        self.provider.snapshot()
+    }
 }
 
 impl RecAdviceProvider {
@@ -65,6 +65,18 @@ pub trait Host {
         Ok(HostResponse::None)
     }
 
+    /// Handles the application-defined event with the specified ID emitted by the VM.
+    ///
+    /// Events are emitted by the `emit.<event_id>` instruction and do not affect the state of the
+    /// VM; by default, they are ignored.
+    fn on_event<S: ProcessState>(
+        &mut self,
+        _process: &S,
+        _event_id: u32,
+    ) -> Result<HostResponse, ExecutionError> {
+        Ok(HostResponse::None)
+    }
+
     /// Pops an element from the advice stack and returns it.
     ///
     /// # Errors
@@ -139,6 +151,22 @@ where
     ) -> Result<HostResponse, ExecutionError> {
         H::set_advice(self, process, injector)
     }
+
+    fn on_debug<S: ProcessState>(
+        &mut self,
+        process: &S,
+        options: &DebugOptions,
+    ) -> Result<HostResponse, ExecutionError> {
+        H::on_debug(self, process, options)
+    }
+
+    fn on_event<S: ProcessState>(
+        &mut self,
+        process: &S,
+        event_id: u32,
+    ) -> Result<HostResponse, ExecutionError> {
+        H::on_event(self, process, event_id)
+    }
 }
 
 // HOST RESPONSE
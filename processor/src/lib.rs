@@ -13,8 +13,8 @@ use miden_air::trace::{
 pub use miden_air::{ExecutionOptions, ExecutionOptionsError};
 pub use vm_core::{
     chiplets::hasher::Digest, errors::InputError, utils::DeserializationError, AdviceInjector,
-    AssemblyOp, Kernel, Operation, Program, ProgramInfo, QuadExtension, StackInputs, StackOutputs,
-    Word, EMPTY_WORD, ONE, ZERO,
+    AsmOpLocation, AssemblyOp, DebugOptions, Kernel, Operation, Program, ProgramInfo,
+    QuadExtension, SourceMap, StackInputs, StackOutputs, Word, EMPTY_WORD, ONE, ZERO,
 };
 use vm_core::{
     code_blocks::{
@@ -43,8 +43,11 @@ use range::RangeChecker;
 
 mod host;
 pub use host::{
-    advice::{AdviceInputs, AdviceProvider, AdviceSource, MemAdviceProvider, RecAdviceProvider},
-    DefaultHost, Host,
+    advice::{
+        AdviceExtractor, AdviceInputs, AdviceProvider, AdviceSource, MemAdviceProvider,
+        RecAdviceProvider,
+    },
+    DefaultHost, Host, HostResponse,
 };
 
 mod chiplets;
@@ -62,6 +65,12 @@ pub mod utils;
 mod debug;
 pub use debug::{AsmOpInfo, VmState, VmStateIterator};
 
+mod profiler;
+pub use profiler::{CallStack, ProcedureProfile, Profile, ProfileCost};
+
+mod fast;
+pub use fast::{FastProcessor, RunOutput};
+
 // RE-EXPORTS
 // ================================================================================================
 
@@ -147,6 +156,25 @@ where
     VmStateIterator::new(process, result)
 }
 
+/// Executes the provided program against the provided inputs without generating an execution
+/// trace, and returns the outputs of the program.
+///
+/// The program is executed with the same semantics as in [execute()], but this is considerably
+/// faster when the program does not need to be proven.
+pub fn run<H>(
+    program: &Program,
+    stack_inputs: StackInputs,
+    host: H,
+    options: ExecutionOptions,
+) -> Result<RunOutput, ExecutionError>
+where
+    H: Host,
+{
+    let mut processor = FastProcessor::new(program.kernel().clone(), stack_inputs, host, options);
+    let stack_outputs = processor.execute(program)?;
+    Ok(processor.into_output(stack_outputs))
+}
+
 // PROCESS
 // ================================================================================================
 
@@ -192,7 +220,7 @@ where
         in_debug_mode: bool,
         execution_options: ExecutionOptions,
     ) -> Self {
-        Self {
+        let mut process = Self {
             system: System::new(execution_options.expected_cycles() as usize),
             decoder: Decoder::new(in_debug_mode),
             stack: Stack::new(&stack, execution_options.expected_cycles() as usize, in_debug_mode),
@@ -200,7 +228,9 @@ where
             chiplets: Chiplets::new(kernel),
             host: RefCell::new(host),
             max_cycles: execution_options.max_cycles(),
-        }
+        };
+        process.decoder.append_chiplets_lengths(ChipletsLengths::new(&process.chiplets));
+        process
     }
 
     // PROGRAM EXECUTOR
@@ -226,15 +256,29 @@ where
         block: &CodeBlock,
         cb_table: &CodeBlockTable,
     ) -> Result<(), ExecutionError> {
+        // decorators attached to control blocks are executed outside of the blocks; decorators
+        // attached to SPAN blocks are executed together with the operations of the blocks
+        for decorator in block.before_enter() {
+            self.execute_decorator(decorator)?;
+        }
+
         match block {
-            CodeBlock::Join(block) => self.execute_join_block(block, cb_table),
-            CodeBlock::Split(block) => self.execute_split_block(block, cb_table),
-            CodeBlock::Loop(block) => self.execute_loop_block(block, cb_table),
-            CodeBlock::Call(block) => self.execute_call_block(block, cb_table),
-            CodeBlock::Dyn(block) => self.execute_dyn_block(block, cb_table),
-            CodeBlock::Span(block) => self.execute_span_block(block),
-            CodeBlock::Proxy(_) => Err(ExecutionError::UnexecutableCodeBlock(block.clone())),
+            CodeBlock::Join(block) => self.execute_join_block(block, cb_table)?,
+            CodeBlock::Split(block) => self.execute_split_block(block, cb_table)?,
+            CodeBlock::Loop(block) => self.execute_loop_block(block, cb_table)?,
+            CodeBlock::Call(block) => self.execute_call_block(block, cb_table)?,
+            CodeBlock::Dyn(block) => self.execute_dyn_block(block, cb_table)?,
+            CodeBlock::Span(block) => self.execute_span_block(block)?,
+            CodeBlock::Proxy(_) => {
+                return Err(ExecutionError::UnexecutableCodeBlock(block.clone()))
+            }
         }
+
+        for decorator in block.after_exit() {
+            self.execute_decorator(decorator)?;
+        }
+
+        Ok(())
     }
 
     /// Executes the specified [Join] block.
@@ -364,11 +408,12 @@ where
     fn execute_span_block(&mut self, block: &Span) -> Result<(), ExecutionError> {
         self.start_span_block(block)?;
 
+        let span_hash = block.hash();
         let mut op_offset = 0;
         let mut decorators = block.decorator_iter();
 
         // execute the first operation batch
-        self.execute_op_batch(&block.op_batches()[0], &mut decorators, op_offset)?;
+        self.execute_op_batch(span_hash, &block.op_batches()[0], &mut decorators, op_offset)?;
         op_offset += block.op_batches()[0].ops().len();
 
         // if the span contains more operation batches, execute them. each additional batch is
@@ -377,7 +422,7 @@ where
         for op_batch in block.op_batches().iter().skip(1) {
             self.respan(op_batch);
             self.execute_op(Operation::Noop)?;
-            self.execute_op_batch(op_batch, &mut decorators, op_offset)?;
+            self.execute_op_batch(span_hash, op_batch, &mut decorators, op_offset)?;
             op_offset += op_batch.ops().len();
         }
 
@@ -387,7 +432,7 @@ where
         // can happen for decorators appearing after all operations in a block. these decorators
         // are executed after SPAN block is closed to make sure the VM clock cycle advances beyond
         // the last clock cycle of the SPAN block ops.
-        if let Some(decorator) = decorators.next() {
+        for decorator in decorators {
             self.execute_decorator(decorator)?;
         }
 
@@ -403,6 +448,7 @@ where
     #[inline(always)]
     fn execute_op_batch(
         &mut self,
+        span_hash: Digest,
         batch: &OpBatch,
         decorators: &mut DecoratorIterator,
         op_offset: usize,
@@ -424,6 +470,7 @@ where
             }
 
             // decode and execute the operation
+            self.decoder.append_span_op(self.system.clk(), span_hash, i + op_offset);
             self.decoder.execute_user_op(op, op_idx);
             self.execute_op(op)?;
 
@@ -490,6 +537,9 @@ where
             Decorator::Debug(options) => {
                 self.host.borrow_mut().on_debug(self, options)?;
             }
+            Decorator::Event(event_id) => {
+                self.host.borrow_mut().on_event(self, *event_id)?;
+            }
             Decorator::AsmOp(assembly_op) => {
                 if self.decoder.in_debug_mode() {
                     self.decoder.append_asmop(self.system.clk(), assembly_op.clone());
@@ -1,10 +1,12 @@
-use super::{ExecutionError, Felt, FieldElement, Host, Operation, Process, StarkField};
+use super::{
+    ChipletsLengths, ExecutionError, Felt, FieldElement, Host, Operation, Process, StarkField,
+};
 use vm_core::stack::STACK_TOP_SIZE;
 
 mod crypto_ops;
 mod ext2_ops;
 mod field_ops;
-mod fri_ops;
+pub(super) mod fri_ops;
 mod io_ops;
 mod stack_ops;
 mod sys_ops;
@@ -160,6 +162,12 @@ where
         self.system.advance_clock(self.max_cycles)?;
         self.stack.advance_clock();
         self.chiplets.advance_clock();
+
+        // in debug mode, record the lengths of the chiplet traces so that the rows added to the
+        // chiplets can be attributed to the executed operations
+        if self.decoder.in_debug_mode() {
+            self.decoder.append_chiplets_lengths(ChipletsLengths::new(&self.chiplets));
+        }
         Ok(())
     }
 
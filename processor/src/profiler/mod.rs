@@ -0,0 +1,440 @@
+use crate::{ChipletsLengths, ExecutionError, Operation, VmState, VmStateIterator};
+use core::{fmt, ops::AddAssign};
+use vm_core::utils::{
+    collections::{BTreeMap, Vec},
+    string::{String, ToString},
+};
+
+#[cfg(test)]
+mod tests;
+
+// CONSTANTS
+// ================================================================================================
+
+/// Context name of the program body assigned by the assembler.
+const PROGRAM_BODY: &str = "#main";
+
+// PROFILE
+// ================================================================================================
+
+/// Cycles and chiplet trace rows attributed to the procedures of an executed program.
+///
+/// A profile is built from the states of a program executed in debug mode (see
+/// [execute_iter()](crate::execute_iter)). At every cycle, the stack of procedures being executed
+/// is reconstructed from the context names and exec paths of AsmOp decorators (for procedures
+/// invoked via `exec`) and from CALL, SYSCALL, and DYN blocks (for procedures invoked via `call`,
+/// `syscall`, `dynexec`, and `dyncall`). Procedures are identified by their context names.
+///
+/// Each cycle, together with the chiplet rows added by its operation, is attributed to the
+/// procedure on the top of the stack (exclusive cost), and to all procedures on the stack
+/// (inclusive cost). Operations which do not belong to any instruction (e.g., control flow
+/// operations and NOOPs padding operation batches) are attributed to the procedure executing the
+/// next instruction, except for END operations, which are attributed to the procedure whose block
+/// they end. Cycles which cannot be attributed to any procedure (e.g., for programs compiled
+/// without debug info) are included only in the total cost.
+///
+/// Since control blocks do not carry decorators, a procedure invoked via `exec` is identified only
+/// once it executes an instruction of its own. Thus, if such a procedure invokes another procedure
+/// via `call`, `syscall`, `dynexec`, or `dyncall` before executing any of its instructions (e.g.,
+/// `proc.y call.z end` invoked via `exec.y` from the program body), it is missing from the stack
+/// of the invoked procedure, which is profiled as `#main;z` rather than `#main;y;z`.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Profile {
+    total: ProfileCost,
+    procedures: BTreeMap<String, ProcedureProfile>,
+    stacks: BTreeMap<Vec<String>, ProfileCost>,
+}
+
+impl Profile {
+    // CONSTRUCTOR
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the profile of the execution described by the provided states.
+    ///
+    /// # Errors
+    /// Returns an error if the execution failed.
+    pub fn new(mut states: VmStateIterator) -> Result<Self, ExecutionError> {
+        let mut profile = Self::default();
+        let mut stack = CallStack::default();
+        let mut lengths = states.chiplets_lengths_at(0).unwrap_or_default();
+
+        // cost of the cycles executed since the last instruction which were not attributed yet
+        let mut pending: Option<ProfileCost> = None;
+
+        while let Some(state) = states.next() {
+            let state = state?;
+            let Some(op) = state.op else {
+                continue;
+            };
+
+            let next_lengths = states.chiplets_lengths_at(state.clk).unwrap_or(lengths);
+            let cost = ProfileCost::of_cycle(&lengths, &next_lengths);
+            lengths = next_lengths;
+            profile.total += cost;
+
+            match (&state.asmop, op) {
+                (Some(_), _) => {
+                    stack.update(&state);
+                    if let Some(pending) = pending.take() {
+                        profile.attribute(&stack.frames, pending);
+                    }
+                    profile.attribute(&stack.frames, cost);
+                }
+                (None, Operation::End) => {
+                    let block = stack.blocks.last().expect("no block to end");
+                    match pending.as_mut() {
+                        Some(pending) => *pending += cost,
+                        None => profile.attribute(stack.owner(block), cost),
+                    }
+
+                    // when a called procedure returns, whatever was not attributed yet belongs to
+                    // the procedure on the top of the stack (i.e., the returning procedure, or its
+                    // caller if the procedure did not execute any instructions)
+                    if block.call {
+                        if let Some(pending) = pending.take() {
+                            profile.attribute(&stack.frames, pending);
+                        }
+                    }
+                    stack.update(&state);
+                }
+                (None, _) => {
+                    *pending.get_or_insert_with(ProfileCost::default) += cost;
+                    stack.update(&state);
+                }
+            }
+        }
+
+        if let Some(pending) = pending.take() {
+            profile.attribute(&stack.frames, pending);
+        }
+        Ok(profile)
+    }
+
+    // PUBLIC ACCESSORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the total cost of the execution.
+    pub fn total(&self) -> &ProfileCost {
+        &self.total
+    }
+
+    /// Returns the profile of the procedure with the specified context name, or None if the
+    /// procedure was not executed.
+    pub fn procedure(&self, name: &str) -> Option<&ProcedureProfile> {
+        self.procedures.get(name)
+    }
+
+    /// Returns an iterator over the context names and profiles of all executed procedures, sorted
+    /// by name.
+    pub fn procedures(&self) -> impl Iterator<Item = (&str, &ProcedureProfile)> {
+        self.procedures.iter().map(|(name, profile)| (name.as_str(), profile))
+    }
+
+    /// Returns an iterator over all distinct procedure stacks observed during the execution
+    /// (starting from the outermost procedure) together with the cost attributed to the
+    /// procedure on the top of each stack.
+    pub fn stacks(&self) -> impl Iterator<Item = (&[String], &ProfileCost)> {
+        self.stacks.iter().map(|(stack, cost)| (stack.as_slice(), cost))
+    }
+
+    /// Returns the cycles attributed to each procedure stack in the collapsed-stack format (e.g.,
+    /// `#main;foo;bar 42`), which can be rendered by standard flamegraph tools.
+    pub fn collapsed_stacks(&self) -> String {
+        let mut collapsed = String::new();
+        for (stack, cost) in self.stacks.iter() {
+            collapsed.push_str(&format!("{} {}\n", stack.join(";"), cost.cycles()));
+        }
+        collapsed
+    }
+
+    // HELPER METHODS
+    // --------------------------------------------------------------------------------------------
+
+    /// Attributes the specified cost to the provided procedure stack.
+    fn attribute(&mut self, frames: &[String], cost: ProfileCost) {
+        let Some(top) = frames.last() else {
+            return;
+        };
+
+        match self.stacks.get_mut(frames) {
+            Some(stack_cost) => *stack_cost += cost,
+            None => {
+                self.stacks.insert(frames.to_vec(), cost);
+            }
+        }
+
+        // procedures appearing on the stack more than once are counted only once
+        for (i, name) in frames.iter().enumerate() {
+            if !frames[..i].contains(name) {
+                self.procedure_mut(name).inclusive += cost;
+            }
+        }
+        self.procedure_mut(top).exclusive += cost;
+    }
+
+    /// Returns a mutable reference to the profile of the specified procedure, inserting an empty
+    /// profile if the procedure was not profiled yet.
+    fn procedure_mut(&mut self, name: &str) -> &mut ProcedureProfile {
+        if !self.procedures.contains_key(name) {
+            self.procedures.insert(name.to_string(), ProcedureProfile::default());
+        }
+        self.procedures.get_mut(name).expect("no procedure profile")
+    }
+}
+
+impl fmt::Display for Profile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // list procedures starting from the most expensive ones
+        let mut procedures = self.procedures().collect::<Vec<_>>();
+        procedures.sort_by(|(a_name, a), (b_name, b)| {
+            b.inclusive().cycles().cmp(&a.inclusive().cycles()).then(a_name.cmp(b_name))
+        });
+        let width = procedures.iter().fold(20, |width, (name, _)| width.max(name.len()));
+
+        writeln!(
+            f,
+            "{:<width$} | {:>12} | {:>12} | {:>12} | {:>12} | {:>12}",
+            "Procedure", "Inclusive", "Exclusive", "Hasher rows", "Bitwise rows", "Memory rows",
+        )?;
+        writeln!(f, "{}", "-".repeat(width + 75))?;
+        for (name, profile) in procedures {
+            let inclusive = profile.inclusive();
+            writeln!(
+                f,
+                "{:<width$} | {:>12} | {:>12} | {:>12} | {:>12} | {:>12}",
+                name,
+                inclusive.cycles(),
+                profile.exclusive().cycles(),
+                inclusive.hasher_rows(),
+                inclusive.bitwise_rows(),
+                inclusive.memory_rows(),
+            )?;
+        }
+        writeln!(f, "{}", "-".repeat(width + 75))?;
+        writeln!(
+            f,
+            "{:<width$} | {:>12} | {:>12} | {:>12} | {:>12} | {:>12}",
+            "Total",
+            self.total.cycles(),
+            "",
+            self.total.hasher_rows(),
+            self.total.bitwise_rows(),
+            self.total.memory_rows(),
+        )
+    }
+}
+
+// PROCEDURE PROFILE
+// ================================================================================================
+
+/// Cost attributed to a single procedure.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ProcedureProfile {
+    inclusive: ProfileCost,
+    exclusive: ProfileCost,
+}
+
+impl ProcedureProfile {
+    /// Returns the cost of the procedure including the cost of the procedures it invoked.
+    pub fn inclusive(&self) -> &ProfileCost {
+        &self.inclusive
+    }
+
+    /// Returns the cost of the procedure excluding the cost of the procedures it invoked.
+    pub fn exclusive(&self) -> &ProfileCost {
+        &self.exclusive
+    }
+}
+
+// PROFILE COST
+// ================================================================================================
+
+/// Number of cycles and of chiplet trace rows attributed to a part of a program.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ProfileCost {
+    cycles: usize,
+    hasher_rows: usize,
+    bitwise_rows: usize,
+    memory_rows: usize,
+    kernel_rom_rows: usize,
+}
+
+impl ProfileCost {
+    /// Returns the cost of a single cycle during which the chiplet traces grew from the first to
+    /// the second specified lengths.
+    fn of_cycle(before: &ChipletsLengths, after: &ChipletsLengths) -> Self {
+        Self {
+            cycles: 1,
+            hasher_rows: after.hash_chiplet_len() - before.hash_chiplet_len(),
+            bitwise_rows: after.bitwise_chiplet_len() - before.bitwise_chiplet_len(),
+            memory_rows: after.memory_chiplet_len() - before.memory_chiplet_len(),
+            kernel_rom_rows: after.kernel_rom_len() - before.kernel_rom_len(),
+        }
+    }
+
+    /// Returns the number of VM cycles.
+    pub fn cycles(&self) -> usize {
+        self.cycles
+    }
+
+    /// Returns the number of rows added to the hash chiplet trace.
+    pub fn hasher_rows(&self) -> usize {
+        self.hasher_rows
+    }
+
+    /// Returns the number of rows added to the bitwise chiplet trace.
+    pub fn bitwise_rows(&self) -> usize {
+        self.bitwise_rows
+    }
+
+    /// Returns the number of rows added to the memory chiplet trace.
+    pub fn memory_rows(&self) -> usize {
+        self.memory_rows
+    }
+
+    /// Returns the number of rows added to the kernel ROM trace.
+    pub fn kernel_rom_rows(&self) -> usize {
+        self.kernel_rom_rows
+    }
+}
+
+impl AddAssign for ProfileCost {
+    fn add_assign(&mut self, rhs: Self) {
+        self.cycles += rhs.cycles;
+        self.hasher_rows += rhs.hasher_rows;
+        self.bitwise_rows += rhs.bitwise_rows;
+        self.memory_rows += rhs.memory_rows;
+        self.kernel_rom_rows += rhs.kernel_rom_rows;
+    }
+}
+
+// CALL STACK
+// ================================================================================================
+
+/// Stack of procedures being executed, reconstructed from the states of a program executed in
+/// debug mode (see [execute_iter()](crate::execute_iter)).
+///
+/// The stack is reconstructed in the same way as for a [Profile]: procedures invoked via `exec`
+/// are identified by the context names and exec paths of AsmOp decorators, and procedures invoked
+/// via `call`, `syscall`, `dynexec`, and `dyncall` by CALL, SYSCALL, and DYN blocks. Hence, the
+/// stack has the same limitations as the stacks of a profile.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct CallStack {
+    /// Context names of the procedures being executed, starting from the outermost one.
+    frames: Vec<String>,
+    /// Code blocks being executed, starting from the outermost one.
+    blocks: Vec<Block>,
+    /// Number of frames below each procedure invoked via a CALL, SYSCALL, or DYN block.
+    boundaries: Vec<usize>,
+}
+
+/// A code block being executed.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+struct Block {
+    /// True for CALL, SYSCALL, and DYN blocks.
+    call: bool,
+    /// Number of frames shared by all instructions executed in this block so far, i.e., the
+    /// depth of the procedure owning this block; None if no instruction was executed yet.
+    depth: Option<usize>,
+}
+
+impl CallStack {
+    // PUBLIC ACCESSORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the context names of the procedures being executed, starting from the outermost
+    /// one.
+    ///
+    /// The stack is empty until the first instruction of the program is executed.
+    pub fn frames(&self) -> &[String] {
+        &self.frames
+    }
+
+    // STATE MUTATORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Updates the stack with the operation executed at the specified state.
+    ///
+    /// States must be provided in the order of execution, starting from the first cycle.
+    pub fn update(&mut self, state: &VmState) {
+        let Some(op) = state.op else {
+            return;
+        };
+
+        match (&state.asmop, op) {
+            (Some(asmop), _) => self.enter(asmop.exec_path(), asmop.context_name()),
+            (None, Operation::End) => {
+                let block = self.blocks.pop().expect("no block to end");
+                if block.call {
+                    let boundary = self.boundaries.pop().expect("no call boundary");
+                    self.frames.truncate(boundary);
+                }
+            }
+            (None, _) => (),
+        }
+
+        match op {
+            Operation::Join | Operation::Split | Operation::Loop | Operation::Span => {
+                self.blocks.push(Block::default())
+            }
+            Operation::Call | Operation::SysCall | Operation::Dyn => self.call(),
+            _ => (),
+        }
+    }
+
+    // HELPER METHODS
+    // --------------------------------------------------------------------------------------------
+
+    /// Updates the frames above the innermost call boundary to the procedure with the specified
+    /// context name, inlined through the procedures in the specified exec path.
+    fn enter(&mut self, exec_path: &[String], context_name: &str) {
+        let boundary = self.boundaries.last().copied().unwrap_or(0);
+        let path = exec_path.iter().map(String::as_str).chain([context_name]);
+        let shared = boundary
+            + self.frames[boundary..]
+                .iter()
+                .zip(path.clone())
+                .take_while(|(frame, name)| frame.as_str() == *name)
+                .count();
+        self.frames.truncate(shared);
+        self.frames.extend(path.skip(shared - boundary).map(ToString::to_string));
+
+        // blocks enclosing other blocks share at most as many frames as the enclosed blocks, thus
+        // only the innermost blocks need to be updated
+        for block in self.blocks.iter_mut().rev() {
+            match block.depth {
+                None => block.depth = Some(self.frames.len()),
+                Some(depth) if depth > shared => block.depth = Some(shared),
+                Some(_) => break,
+            }
+        }
+    }
+
+    /// Enters a procedure invoked via a CALL, SYSCALL, or DYN block from the procedure owning the
+    /// innermost block executed so far.
+    ///
+    /// The procedure on the top of the stack may be an inlined procedure which executed the last
+    /// instruction before the invocation, rather than the invoking procedure, thus the frames are
+    /// truncated to the owner of the block.
+    fn call(&mut self) {
+        if let Some(depth) = self.blocks.iter().rev().find_map(|block| block.depth) {
+            self.frames.truncate(depth);
+        }
+        // procedures invoked before any instruction was executed are invoked from the program
+        // body
+        if self.frames.is_empty() {
+            self.frames.push(PROGRAM_BODY.to_string());
+        }
+        self.boundaries.push(self.frames.len());
+        self.blocks.push(Block {
+            call: true,
+            depth: None,
+        });
+    }
+
+    /// Returns the frames of the procedure owning the specified block.
+    fn owner(&self, block: &Block) -> &[String] {
+        let depth = block.depth.unwrap_or(self.frames.len()).min(self.frames.len());
+        &self.frames[..depth]
+    }
+}
@@ -0,0 +1,269 @@
+use super::{CallStack, Profile};
+use crate::{execute_iter, DefaultHost, StackInputs};
+use miden_assembly::Assembler;
+use vm_core::utils::{collections::Vec, string::String};
+
+// TESTS
+// ================================================================================================
+
+#[test]
+fn profile_exec() {
+    let source = "
+        proc.bar
+            push.1 add
+        end
+        proc.foo
+            exec.bar
+            push.2 mul
+        end
+        begin
+            push.3
+            exec.bar
+            exec.foo
+            push.4 drop
+        end";
+    let profile = profile(Assembler::default(), source);
+
+    // procedures inlined at the start of other procedures are attributed correctly
+    assert_eq!(
+        vec![
+            vec!["#main"],
+            vec!["#main", "bar"],
+            vec!["#main", "foo"],
+            vec!["#main", "foo", "bar"],
+        ],
+        stacks(&profile)
+    );
+
+    let total = profile.total().cycles();
+    let main = profile.procedure("#main").unwrap();
+    let foo = profile.procedure("foo").unwrap();
+    let bar = profile.procedure("bar").unwrap();
+    assert_eq!(total, main.inclusive().cycles());
+    assert_eq!(
+        total,
+        main.exclusive().cycles() + foo.exclusive().cycles() + bar.exclusive().cycles()
+    );
+    assert_eq!(
+        stack_cycles(&profile, &["#main", "foo", "bar"]),
+        foo.inclusive().cycles() - foo.exclusive().cycles()
+    );
+    assert_eq!(bar.inclusive(), bar.exclusive());
+
+    // both copies of bar take the same number of cycles
+    assert_eq!(
+        stack_cycles(&profile, &["#main", "bar"]),
+        stack_cycles(&profile, &["#main", "foo", "bar"])
+    );
+}
+
+#[test]
+fn profile_call() {
+    let kernel = "
+        export.baz
+            push.4 add
+        end";
+    let source = "
+        proc.bar
+            push.1 add
+        end
+        proc.foo
+            push.2 mul
+            exec.bar
+            syscall.baz
+        end
+        begin
+            push.3
+            call.foo
+            push.5 add
+            exec.bar
+        end";
+    let assembler = Assembler::default().with_debug_mode(true).with_kernel(kernel).unwrap();
+    let profile = profile(assembler, source);
+
+    // procedures invoked right after an inlined procedure are invoked by the inlining procedure,
+    // and the caller of a procedure is restored when the procedure returns
+    assert_eq!(
+        vec![
+            vec!["#main"],
+            vec!["#main", "bar"],
+            vec!["#main", "foo"],
+            vec!["#main", "foo", "bar"],
+            vec!["#main", "foo", "baz"],
+        ],
+        stacks(&profile)
+    );
+
+    let total = profile.total().cycles();
+    let exclusive = profile.procedures().map(|(_, profile)| profile.exclusive().cycles()).sum();
+    assert_eq!(total, profile.procedure("#main").unwrap().inclusive().cycles());
+    assert_eq!(total, exclusive);
+}
+
+#[test]
+fn profile_call_at_start() {
+    let source = "
+        proc.foo
+            push.1 add
+        end
+        begin
+            call.foo
+            push.2 add
+        end";
+    let profile = profile(Assembler::default(), source);
+
+    // procedures called before the program body executed any instruction are called from it
+    assert_eq!(vec![vec!["#main"], vec!["#main", "foo"]], stacks(&profile));
+    let foo = profile.procedure("foo").unwrap();
+    assert_eq!(foo.inclusive(), foo.exclusive());
+    assert_eq!(profile.total(), profile.procedure("#main").unwrap().inclusive());
+}
+
+#[test]
+fn profile_exec_without_instructions_before_call() {
+    let source = "
+        proc.z
+            push.1 drop
+        end
+        proc.y
+            call.z
+        end
+        begin
+            exec.y
+        end";
+    let profile = profile(Assembler::default(), source);
+
+    // inlined procedures which execute no instruction of their own before invoking a procedure
+    // are not identified, thus the invoked procedure is attributed to the enclosing procedure
+    assert_eq!(vec![vec!["#main", "z"]], stacks(&profile));
+    assert!(profile.procedure("y").is_none());
+    assert_eq!(profile.total(), profile.procedure("#main").unwrap().inclusive());
+}
+
+#[test]
+fn profile_chiplets() {
+    let source = "
+        proc.bitwise
+            push.3 push.5 u32checked_and drop
+        end
+        proc.memory
+            push.7 mem_store.0 mem_load.0 drop
+        end
+        proc.hasher
+            padw padw padw hperm dropw dropw dropw
+        end
+        begin
+            push.1 drop
+            exec.bitwise
+            exec.memory
+            exec.hasher
+        end";
+    let profile = profile(Assembler::default(), source);
+
+    let bitwise = profile.procedure("bitwise").unwrap().exclusive();
+    assert_eq!(
+        (8, 0, 0),
+        (bitwise.bitwise_rows(), bitwise.memory_rows(), bitwise.hasher_rows())
+    );
+    let memory = profile.procedure("memory").unwrap().exclusive();
+    assert_eq!((0, 2, 0), (memory.bitwise_rows(), memory.memory_rows(), memory.hasher_rows()));
+    let hasher = profile.procedure("hasher").unwrap().exclusive();
+    assert_eq!((0, 0, 8), (hasher.bitwise_rows(), hasher.memory_rows(), hasher.hasher_rows()));
+
+    // hashing of the program MAST is attributed to the program body
+    let main = profile.procedure("#main").unwrap();
+    assert_eq!(profile.total().hasher_rows(), main.inclusive().hasher_rows());
+    assert!(main.exclusive().hasher_rows() > 0);
+}
+
+#[test]
+fn collapsed_stacks() {
+    let source = "
+        proc.foo
+            push.1 add
+        end
+        begin
+            push.2
+            exec.foo
+        end";
+    let profile = profile(Assembler::default(), source);
+
+    let collapsed = profile.collapsed_stacks();
+    let lines = collapsed.lines().collect::<Vec<_>>();
+    assert_eq!(2, lines.len());
+    assert_eq!(format!("#main {}", stack_cycles(&profile, &["#main"])), lines[0]);
+    assert_eq!(format!("#main;foo {}", stack_cycles(&profile, &["#main", "foo"])), lines[1]);
+}
+
+#[test]
+fn profile_without_debug_info() {
+    let assembler = Assembler::default();
+    let program = assembler.compile("begin push.1 push.2 add drop end").unwrap();
+    let states = execute_iter(&program, StackInputs::default(), DefaultHost::default());
+    let profile = Profile::new(states).unwrap();
+
+    assert!(profile.total().cycles() > 0);
+    assert_eq!(0, profile.procedures().count());
+    assert!(profile.collapsed_stacks().is_empty());
+}
+
+#[test]
+fn call_stack() {
+    let source = "
+        proc.bar
+            push.1 add
+        end
+        proc.foo
+            exec.bar
+            push.2 mul
+        end
+        begin
+            push.3
+            call.foo
+            push.4 drop
+        end";
+    let program = Assembler::default().with_debug_mode(true).compile(source).unwrap();
+
+    // collect the distinct stacks in the order in which they were observed
+    let mut stack = CallStack::default();
+    let mut observed: Vec<Vec<String>> = Vec::new();
+    for state in execute_iter(&program, StackInputs::default(), DefaultHost::default()) {
+        stack.update(&state.unwrap());
+        if observed.last().map(Vec::as_slice) != Some(stack.frames()) {
+            observed.push(stack.frames().to_vec());
+        }
+    }
+
+    let expected: Vec<Vec<&str>> = vec![
+        vec![],
+        vec!["#main"],
+        vec!["#main", "foo", "bar"],
+        vec!["#main", "foo"],
+        vec!["#main"],
+    ];
+    assert_eq!(expected, observed);
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+fn profile(assembler: Assembler, source: &str) -> Profile {
+    let program = assembler.with_debug_mode(true).compile(source).unwrap();
+    let states = execute_iter(&program, StackInputs::default(), DefaultHost::default());
+    Profile::new(states).unwrap()
+}
+
+fn stacks(profile: &Profile) -> Vec<Vec<&str>> {
+    profile
+        .stacks()
+        .map(|(stack, _)| stack.iter().map(String::as_str).collect())
+        .collect()
+}
+
+fn stack_cycles(profile: &Profile, stack: &[&str]) -> usize {
+    profile
+        .stacks()
+        .find(|(frames, _)| frames.iter().map(String::as_str).eq(stack.iter().copied()))
+        .map(|(_, cost)| cost.cycles())
+        .unwrap_or_default()
+}
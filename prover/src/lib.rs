@@ -9,7 +9,7 @@ use processor::{
     math::Felt,
     ExecutionTrace,
 };
-use winter_prover::{ProofOptions as WinterProofOptions, Prover};
+use winter_prover::{ProofGenerator, ProofOptions as WinterProofOptions, Prover};
 
 #[cfg(feature = "std")]
 use log::debug;
@@ -29,7 +29,7 @@ pub use processor::{
     crypto, math, utils, AdviceInputs, Digest, ExecutionError, Host, InputError, MemAdviceProvider,
     Program, StackInputs, StackOutputs, Word,
 };
-pub use winter_prover::StarkProof;
+pub use winter_prover::{ProvingStage, StarkProof};
 
 // PROVER
 // ================================================================================================
@@ -105,6 +105,139 @@ where
     Ok((stack_outputs, proof))
 }
 
+// STEPWISE PROVER
+// ================================================================================================
+
+/// Generates a STARK proof of a program's execution one [ProvingStage] at a time.
+///
+/// Unlike [prove()], which blocks until the proof is generated, this struct executes a single
+/// stage of proof generation on every call to [step()](Self::step). This allows the caller to
+/// report progress and to yield to other tasks (e.g., to a browser's event loop) between stages.
+/// The resulting proof is the same as the one generated by [prove()] for the same trace.
+///
+/// Proofs are always generated on the CPU, even if the `metal` feature is enabled.
+pub struct ExecutionProofGenerator {
+    stack_outputs: StackOutputs,
+    hash_fn: HashFunction,
+    inner: GeneratorInner,
+}
+
+enum GeneratorInner {
+    Blake3_192(
+        ExecutionProver<Blake3_192, WinterRandomCoin<Blake3_192>>,
+        ProofGenerator<ExecutionProver<Blake3_192, WinterRandomCoin<Blake3_192>>>,
+    ),
+    Blake3_256(
+        ExecutionProver<Blake3_256, WinterRandomCoin<Blake3_256>>,
+        ProofGenerator<ExecutionProver<Blake3_256, WinterRandomCoin<Blake3_256>>>,
+    ),
+    Rpo256(
+        ExecutionProver<Rpo256, RpoRandomCoin>,
+        ProofGenerator<ExecutionProver<Rpo256, RpoRandomCoin>>,
+    ),
+}
+
+impl ExecutionProofGenerator {
+    // CONSTRUCTOR
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns a new proof generator for the provided execution trace.
+    ///
+    /// `stack_inputs` must be the inputs against which the trace was generated, and `options`
+    /// define parameters for STARK proof generation.
+    ///
+    /// # Errors
+    /// Returns an error if proof generation cannot be started with the specified options.
+    pub fn new(
+        trace: ExecutionTrace,
+        stack_inputs: StackInputs,
+        options: ProvingOptions,
+    ) -> Result<Self, ExecutionError> {
+        let stack_outputs = trace.stack_outputs().clone();
+        let hash_fn = options.hash_fn();
+
+        let inner = match hash_fn {
+            HashFunction::Blake3_192 => {
+                let prover = ExecutionProver::new(options, stack_inputs, stack_outputs.clone());
+                let generator = ProofGenerator::new(&prover, trace);
+                GeneratorInner::Blake3_192(prover, generator.map_err(ExecutionError::ProverError)?)
+            }
+            HashFunction::Blake3_256 => {
+                let prover = ExecutionProver::new(options, stack_inputs, stack_outputs.clone());
+                let generator = ProofGenerator::new(&prover, trace);
+                GeneratorInner::Blake3_256(prover, generator.map_err(ExecutionError::ProverError)?)
+            }
+            HashFunction::Rpo256 => {
+                let prover = ExecutionProver::new(options, stack_inputs, stack_outputs.clone());
+                let generator = ProofGenerator::new(&prover, trace);
+                GeneratorInner::Rpo256(prover, generator.map_err(ExecutionError::ProverError)?)
+            }
+        };
+
+        Ok(Self {
+            stack_outputs,
+            hash_fn,
+            inner,
+        })
+    }
+
+    // PUBLIC ACCESSORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the stack outputs of the execution being proven.
+    pub fn stack_outputs(&self) -> &StackOutputs {
+        &self.stack_outputs
+    }
+
+    /// Returns the hash function used to generate the proof.
+    pub fn hash_fn(&self) -> HashFunction {
+        self.hash_fn
+    }
+
+    /// Returns the stage which will be executed on the next call to [step()](Self::step).
+    pub fn stage(&self) -> ProvingStage {
+        match &self.inner {
+            GeneratorInner::Blake3_192(_, generator) => generator.stage(),
+            GeneratorInner::Blake3_256(_, generator) => generator.stage(),
+            GeneratorInner::Rpo256(_, generator) => generator.stage(),
+        }
+    }
+
+    /// Returns true if all stages of proof generation have been executed successfully.
+    pub fn is_complete(&self) -> bool {
+        self.stage() == ProvingStage::Complete
+    }
+
+    // PROOF GENERATION
+    // --------------------------------------------------------------------------------------------
+
+    /// Executes the next stage of proof generation and returns the stage which was executed.
+    ///
+    /// # Errors
+    /// Returns an error if the stage fails; proof generation cannot be resumed after a failure.
+    ///
+    /// # Panics
+    /// Panics if proof generation has already been completed or previously failed.
+    pub fn step(&mut self) -> Result<ProvingStage, ExecutionError> {
+        match &mut self.inner {
+            GeneratorInner::Blake3_192(prover, generator) => generator.step(prover),
+            GeneratorInner::Blake3_256(prover, generator) => generator.step(prover),
+            GeneratorInner::Rpo256(prover, generator) => generator.step(prover),
+        }
+        .map_err(ExecutionError::ProverError)
+    }
+
+    /// Returns the generated proof, or None if proof generation has not been completed.
+    pub fn into_proof(self) -> Option<ExecutionProof> {
+        let proof = match self.inner {
+            GeneratorInner::Blake3_192(_, generator) => generator.into_proof(),
+            GeneratorInner::Blake3_256(_, generator) => generator.into_proof(),
+            GeneratorInner::Rpo256(_, generator) => generator.into_proof(),
+        }?;
+        Some(ExecutionProof::new(proof, self.hash_fn))
+    }
+}
+
 // PROVER
 // ================================================================================================
 
@@ -0,0 +1,629 @@
+use super::{
+    DefaultHost, ExecutionError, MemAdviceProvider, StackInputs, Test, VmStateIterator, ONE, ZERO,
+};
+use assembly::{
+    ast::{CodeBody, Instruction, ModuleAst, Node, ProcedureAst, ProgramAst},
+    Library, ProcedureName,
+};
+use std::{
+    collections::BTreeMap,
+    fmt::Write,
+    path::Path,
+    sync::{Mutex, OnceLock},
+};
+use vm_core::{AsmOpLocation, Operation, SourceMap};
+
+#[cfg(test)]
+mod tests;
+
+// CONSTANTS
+// ================================================================================================
+
+/// Name of the environment variable specifying the path of the lcov file into which the coverage
+/// collected by all tests executed by the current process is written.
+pub const COVERAGE_ENV_VAR: &str = "MIDEN_COVERAGE";
+
+/// Path under which the source code of test programs is recorded.
+const TEST_SOURCE_PATH: &str = "#test.masm";
+
+// COVERAGE
+// ================================================================================================
+
+/// Execution counts of the procedures, branches, and instructions of Miden assembly source files.
+///
+/// Source files are added to the coverage from their ASTs, which must contain source locations;
+/// all procedures, conditional and loop statements, and instructions compiled into VM operations
+/// are initially not covered. Executions of programs compiled with a source map (see
+/// [assembly::Assembler::compile_with_source_map()]) are then recorded from their VM states:
+/// instructions are identified by the source locations of their AsmOp decorators, and branches
+/// by the SPLIT and LOOP blocks started by the decoder together with the instructions executed
+/// around them. Executions of code from files which were not added to the coverage are ignored.
+///
+/// Coverage collected from many executions can be written in the lcov format with
+/// [Coverage::to_lcov()].
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Coverage {
+    files: BTreeMap<String, FileCoverage>,
+}
+
+impl Coverage {
+    // CONSTRUCTOR
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns a new coverage without any source files.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // SOURCE FILES
+    // --------------------------------------------------------------------------------------------
+
+    /// Adds the source file of the specified program located at the specified path.
+    pub fn add_program(&mut self, path: &str, program: &ProgramAst) {
+        let file = self.files.entry(path.to_string()).or_default();
+        for procedure in program.procedures() {
+            file.add_procedure(procedure);
+        }
+        file.add_body(ProcedureName::MAIN_PROC_NAME, program.start().line(), program.body());
+    }
+
+    /// Adds the source file of the specified module located at the specified path.
+    pub fn add_module(&mut self, path: &str, module: &ModuleAst) {
+        let file = self.files.entry(path.to_string()).or_default();
+        for procedure in module.procs() {
+            file.add_procedure(procedure);
+        }
+    }
+
+    /// Adds the source files of all modules of the specified library which were not added yet.
+    ///
+    /// Modules are located at paths relative to the root directory of the library (e.g.,
+    /// `std/math/u64.masm`), as in the source maps of programs using the library.
+    pub fn add_library<L: Library>(&mut self, library: &L) {
+        for module in library.modules() {
+            let path = module.path.to_source_path();
+            if !self.files.contains_key(&path) {
+                self.add_module(&path, &module.ast);
+            }
+        }
+    }
+
+    // EXECUTIONS
+    // --------------------------------------------------------------------------------------------
+
+    /// Records the execution described by the provided states of a program with the specified
+    /// source map.
+    ///
+    /// # Errors
+    /// Returns an error if the execution failed; the cycles executed before the failure are
+    /// recorded nevertheless.
+    pub fn record(
+        &mut self,
+        source_map: &SourceMap,
+        mut states: VmStateIterator,
+    ) -> Result<(), ExecutionError> {
+        // control blocks being executed, and the location of the last executed instruction
+        let mut blocks: Vec<Block> = Vec::new();
+        let mut last_location: Option<AsmOpLocation> = None;
+        let mut stack_top = ZERO;
+        let mut result = Ok(());
+
+        while let Some(state) = states.next() {
+            let state = match state {
+                Ok(state) => state,
+                Err(err) => {
+                    result = Err(err);
+                    break;
+                }
+            };
+            if let Some(asmop) = &state.asmop {
+                if let (1, Some(location)) = (asmop.cycle_idx(), asmop.location()) {
+                    self.record_instruction(location);
+                    for block in blocks.iter_mut().filter(|block| block.location.is_none()) {
+                        if let Some(statement) = block.statement_before(location) {
+                            self.resolve_block(block, statement);
+                        }
+                    }
+                    last_location = Some(location.clone());
+                }
+            }
+
+            // the block started by the operation of a state is recorded for the previous cycle,
+            // and the condition of SPLIT and LOOP operations is on the top of the previous stack
+            let candidates = || match states.block_hash_at(state.clk - 1) {
+                Some(block_hash) => source_map.get_branches(block_hash),
+                None => &[],
+            };
+            match state.op {
+                Some(Operation::Split) => {
+                    let mut block = Block::new(candidates(), false, last_location.clone());
+                    self.record_block(&mut block, stack_top == ONE);
+                    blocks.push(block);
+                }
+                Some(Operation::Loop) => {
+                    let mut block = Block::new(candidates(), true, last_location.clone());
+                    if stack_top == ONE {
+                        self.record_block(&mut block, true);
+                    }
+                    blocks.push(block);
+                }
+                Some(Operation::Repeat) => {
+                    if let Some(block) = blocks.last_mut() {
+                        self.record_block(block, true);
+                    }
+                }
+                Some(Operation::End) => {
+                    if let Some(mut block) = blocks.pop() {
+                        if block.is_loop {
+                            self.record_block(&mut block, false);
+                        }
+                        self.finish_block(&mut block);
+                    }
+                }
+                Some(
+                    Operation::Join
+                    | Operation::Span
+                    | Operation::Call
+                    | Operation::SysCall
+                    | Operation::Dyn,
+                ) => blocks.push(Block::new(&[], false, None)),
+                _ => (),
+            }
+            stack_top = state.stack.first().copied().unwrap_or(ZERO);
+        }
+
+        // branches of blocks interrupted by a failure are recorded as well
+        while let Some(mut block) = blocks.pop() {
+            self.finish_block(&mut block);
+        }
+
+        result
+    }
+
+    /// Adds the execution counts of the provided coverage to this coverage.
+    pub fn merge(&mut self, other: &Self) {
+        for (path, other_file) in other.files.iter() {
+            match self.files.get_mut(path) {
+                Some(file) => file.merge(other_file),
+                None => {
+                    self.files.insert(path.clone(), other_file.clone());
+                }
+            }
+        }
+    }
+
+    // PUBLIC ACCESSORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the number of times the instruction starting at the specified line and column of
+    /// the specified file was executed, or None if the file does not contain such an instruction.
+    pub fn instruction_hits(&self, path: &str, line: u32, column: u32) -> Option<u64> {
+        self.files.get(path)?.instructions.get(&(line, column)).copied()
+    }
+
+    /// Returns the number of times each branch of the conditional or loop statement starting at
+    /// the specified line and column of the specified file was taken, or None if the file does not
+    /// contain such a statement.
+    ///
+    /// For `if.true` statements, the first count is the number of times the condition was true,
+    /// and the second the number of times it was false. For `while.true` statements, the first
+    /// count is the number of iterations, and the second the number of times the loop was exited.
+    pub fn branch_hits(&self, path: &str, line: u32, column: u32) -> Option<[u64; 2]> {
+        self.files.get(path)?.branches.get(&(line, column)).copied()
+    }
+
+    /// Returns the number of times the specified procedure of the specified file was executed, or
+    /// None if the file does not contain such a procedure.
+    ///
+    /// The procedure body is named `#main`.
+    pub fn procedure_hits(&self, path: &str, name: &str) -> Option<u64> {
+        let file = self.files.get(path)?;
+        let procedure = file.procedures.iter().find(|procedure| procedure.name == name)?;
+        Some(file.entries(&procedure.statements))
+    }
+
+    // LCOV
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns this coverage in the lcov tracefile format.
+    ///
+    /// Lines are reported with the highest execution count of the instructions starting on them;
+    /// each conditional and loop statement is reported as a block with two branches (see
+    /// [Coverage::branch_hits()]).
+    pub fn to_lcov(&self) -> String {
+        let mut lcov = String::new();
+        for (path, file) in self.files.iter() {
+            file.write_lcov(path, &mut lcov).expect("failed to write lcov record");
+        }
+        lcov
+    }
+
+    /// Writes this coverage in the lcov tracefile format into the file at the specified path.
+    pub fn write_lcov(&self, path: &Path) -> std::io::Result<()> {
+        std::fs::write(path, self.to_lcov())
+    }
+
+    // HELPER METHODS
+    // --------------------------------------------------------------------------------------------
+
+    /// Increments the execution count of the instruction at the specified location.
+    fn record_instruction(&mut self, location: &AsmOpLocation) {
+        let Some(file) = self.files.get_mut(location.path()) else {
+            return;
+        };
+        if let Some(hits) = file.instructions.get_mut(&(location.line(), location.column())) {
+            *hits += 1;
+        }
+    }
+
+    /// Records that the first or the second branch of the specified block was taken; if the
+    /// statement of the block is not resolved yet, the branch is recorded once it is resolved.
+    fn record_block(&mut self, block: &mut Block, first: bool) {
+        match block.location {
+            Some(location) => self.record_branch(location, first),
+            None => block.pending[usize::from(!first)] += 1,
+        }
+    }
+
+    /// Resolves the statement of the specified block to the statement at the specified location,
+    /// and records the branches of the block taken before the resolution.
+    fn resolve_block<'a>(&mut self, block: &mut Block<'a>, location: &'a AsmOpLocation) {
+        block.location = Some(location);
+        for (idx, count) in core::mem::take(&mut block.pending).into_iter().enumerate() {
+            for _ in 0..count {
+                self.record_branch(location, idx == 0);
+            }
+        }
+    }
+
+    /// Resolves the statement of the specified finished block if no instruction executed inside
+    /// the block identified it.
+    ///
+    /// The statement is then the first candidate following the last instruction executed before
+    /// the block in the same procedure or, failing that, the first candidate.
+    fn finish_block(&mut self, block: &mut Block) {
+        if block.location.is_some() {
+            return;
+        }
+        let statement = block.statement_after().or_else(|| block.candidates.first());
+        if let Some(statement) = statement {
+            self.resolve_block(block, statement);
+        }
+    }
+
+    /// Increments the count of the first or the second branch of the statement at the specified
+    /// location.
+    fn record_branch(&mut self, location: &AsmOpLocation, first: bool) {
+        let Some(file) = self.files.get_mut(location.path()) else {
+            return;
+        };
+        if let Some(hits) = file.branches.get_mut(&(location.line(), location.column())) {
+            hits[usize::from(!first)] += 1;
+        }
+    }
+}
+
+// BLOCK
+// ================================================================================================
+
+/// A control block being executed.
+///
+/// Identical conditional and loop statements are compiled into blocks with the same hash, so the
+/// source map may provide several candidate statements for a SPLIT or LOOP block. The statement
+/// is then resolved from the first instruction of the same procedure executed inside the block:
+/// since a block cannot contain a block identical to itself, the statement is the last candidate
+/// preceding this instruction.
+struct Block<'a> {
+    /// Statements which the block may have been compiled from; empty for blocks which were not
+    /// compiled from a conditional or loop statement.
+    candidates: &'a [AsmOpLocation],
+    /// Statement which the block was compiled from, once resolved among the candidates.
+    location: Option<&'a AsmOpLocation>,
+    is_loop: bool,
+    /// Counts of the branches taken before the statement was resolved.
+    pending: [u64; 2],
+    /// Location of the last instruction executed before the block started.
+    last_location: Option<AsmOpLocation>,
+}
+
+impl<'a> Block<'a> {
+    fn new(
+        candidates: &'a [AsmOpLocation],
+        is_loop: bool,
+        last_location: Option<AsmOpLocation>,
+    ) -> Self {
+        let location = match candidates {
+            [location] => Some(location),
+            _ => None,
+        };
+        Self {
+            candidates,
+            location,
+            is_loop,
+            pending: [0; 2],
+            last_location,
+        }
+    }
+
+    /// Returns the last candidate statement preceding the specified instruction executed inside
+    /// this block in the same procedure.
+    fn statement_before(&self, instruction: &AsmOpLocation) -> Option<&'a AsmOpLocation> {
+        self.candidates
+            .iter()
+            .filter(|statement| same_procedure(statement, instruction))
+            .filter(|statement| position(statement) <= position(instruction))
+            .max_by_key(|statement| position(statement))
+    }
+
+    /// Returns the first candidate statement following the last instruction executed before this
+    /// block in the same procedure.
+    fn statement_after(&self) -> Option<&'a AsmOpLocation> {
+        let instruction = self.last_location.as_ref()?;
+        self.candidates
+            .iter()
+            .filter(|statement| same_procedure(statement, instruction))
+            .filter(|statement| position(statement) > position(instruction))
+            .min_by_key(|statement| position(statement))
+    }
+}
+
+/// Returns true if the specified locations are in the same procedure of the same file.
+fn same_procedure(a: &AsmOpLocation, b: &AsmOpLocation) -> bool {
+    a.path() == b.path() && a.procedure() == b.procedure()
+}
+
+/// Returns the position of the specified location in its source file.
+fn position(location: &AsmOpLocation) -> Position {
+    (location.line(), location.column())
+}
+
+// TEST COVERAGE
+// ================================================================================================
+
+impl Test {
+    /// Compiles the test's source with a source map, executes it with the test's inputs, and
+    /// records the execution in the provided coverage.
+    ///
+    /// The modules of the test's libraries are added to the coverage, and so is the test's
+    /// program under the `#test.masm` path; failed executions are recorded up to the failure.
+    pub fn record_coverage(&self, coverage: &mut Coverage) {
+        self.record_coverage_with_inputs(coverage, self.stack_inputs.clone())
+    }
+
+    /// Records the execution of the test with the specified stack inputs in the provided coverage.
+    fn record_coverage_with_inputs(&self, coverage: &mut Coverage, stack_inputs: StackInputs) {
+        let program = ProgramAst::parse(&self.source).expect("Failed to parse test source.");
+        let (compiled, source_map) = self
+            .assembler()
+            .compile_with_source_map(&program, TEST_SOURCE_PATH)
+            .expect("Failed to compile test source.");
+
+        for library in self.libraries.iter() {
+            coverage.add_library(library);
+        }
+        coverage.add_program(TEST_SOURCE_PATH, &program);
+
+        let host = DefaultHost::new(MemAdviceProvider::from(self.advice_inputs.clone()));
+        let states = processor::execute_iter(&compiled, stack_inputs, host);
+        let _ = coverage.record(&source_map, states);
+    }
+}
+
+/// Records the execution of the specified test with the specified stack inputs in the coverage
+/// collected by the current process, and writes the collected coverage into the lcov file
+/// specified by the [COVERAGE_ENV_VAR] environment variable. This is a no-op if the variable is
+/// not set.
+///
+/// Coverage of test programs is not retained, since each test has its own program.
+pub(super) fn record_test_coverage(test: &Test, stack_inputs: &StackInputs) {
+    static COVERAGE: OnceLock<Mutex<Coverage>> = OnceLock::new();
+    let Some(lcov_path) = std::env::var_os(COVERAGE_ENV_VAR) else {
+        return;
+    };
+
+    let mut test_coverage = Coverage::new();
+    test.record_coverage_with_inputs(&mut test_coverage, stack_inputs.clone());
+    test_coverage.files.remove(TEST_SOURCE_PATH);
+
+    let mut coverage = COVERAGE.get_or_init(Default::default).lock().expect("poisoned lock");
+    coverage.merge(&test_coverage);
+    coverage.write_lcov(Path::new(&lcov_path)).expect("failed to write coverage");
+}
+
+// FILE COVERAGE
+// ================================================================================================
+
+/// Line and column of a statement in a source file.
+type Position = (u32, u32);
+
+/// Coverage of a single source file.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+struct FileCoverage {
+    procedures: Vec<ProcedureCoverage>,
+    instructions: BTreeMap<Position, u64>,
+    branches: BTreeMap<Position, [u64; 2]>,
+}
+
+/// A procedure defined in a source file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct ProcedureCoverage {
+    name: String,
+    line: u32,
+    /// Statements at the top level of the procedure body.
+    statements: Vec<Statement>,
+}
+
+/// A statement whose execution count can be determined from the coverage of its file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Statement {
+    Instruction(Position),
+    IfElse(Position),
+    While(Position),
+    Repeat(u32, Vec<Statement>),
+}
+
+impl FileCoverage {
+    /// Adds the specified procedure to this file.
+    fn add_procedure(&mut self, procedure: &ProcedureAst) {
+        self.add_body(procedure.name.as_ref(), procedure.start.line(), &procedure.body);
+    }
+
+    /// Adds a procedure with the specified name and body starting at the specified line to this
+    /// file.
+    fn add_body(&mut self, name: &str, line: u32, body: &CodeBody) {
+        if self.procedures.iter().any(|procedure| procedure.name == name) {
+            return;
+        }
+        let statements = self.add_statements(body);
+        self.procedures.push(ProcedureCoverage {
+            name: name.to_string(),
+            line,
+            statements,
+        });
+    }
+
+    /// Adds the statements of the specified body to this file, and returns the statements at the
+    /// top level of the body.
+    fn add_statements(&mut self, body: &CodeBody) -> Vec<Statement> {
+        let mut statements = Vec::new();
+        for (node, location) in body.nodes().iter().zip(body.source_locations()) {
+            let position = (location.line(), location.column());
+            match node {
+                Node::Instruction(instruction) => {
+                    if compiles_to_operations(instruction) {
+                        self.instructions.entry(position).or_default();
+                        statements.push(Statement::Instruction(position));
+                    }
+                }
+                Node::IfElse {
+                    true_case,
+                    false_case,
+                } => {
+                    self.add_statements(true_case);
+                    self.add_statements(false_case);
+                    self.branches.entry(position).or_default();
+                    statements.push(Statement::IfElse(position));
+                }
+                Node::While { body } => {
+                    self.add_statements(body);
+                    self.branches.entry(position).or_default();
+                    statements.push(Statement::While(position));
+                }
+                Node::Repeat { times, body } => {
+                    let body_statements = self.add_statements(body);
+                    statements.push(Statement::Repeat(*times, body_statements));
+                }
+            }
+        }
+        statements
+    }
+
+    /// Adds the execution counts of the provided file to this file.
+    fn merge(&mut self, other: &Self) {
+        for (position, hits) in other.instructions.iter() {
+            *self.instructions.entry(*position).or_default() += hits;
+        }
+        for (position, [first, second]) in other.branches.iter() {
+            let hits = self.branches.entry(*position).or_default();
+            hits[0] += first;
+            hits[1] += second;
+        }
+        for procedure in other.procedures.iter() {
+            if !self.procedures.iter().any(|p| p.name == procedure.name) {
+                self.procedures.push(procedure.clone());
+            }
+        }
+    }
+
+    /// Returns the number of times a sequence of statements was executed.
+    ///
+    /// Each statement at the top level of a sequence is executed once per execution of the
+    /// sequence; thus, the count is taken from any statement whose count is known.
+    fn entries(&self, statements: &[Statement]) -> u64 {
+        statements
+            .iter()
+            .map(|statement| match statement {
+                Statement::Instruction(position) => self.instructions[position],
+                Statement::IfElse(position) => self.branches[position].iter().sum(),
+                Statement::While(position) => self.branches[position][1],
+                Statement::Repeat(times, body) => self.entries(body) / u64::from(*times).max(1),
+            })
+            .max()
+            .unwrap_or_default()
+    }
+
+    /// Writes the lcov record of this file located at the specified path into the target.
+    fn write_lcov(&self, path: &str, target: &mut String) -> core::fmt::Result {
+        writeln!(target, "TN:")?;
+        writeln!(target, "SF:{path}")?;
+
+        let mut procedures = self.procedures.iter().collect::<Vec<_>>();
+        procedures.sort_by_key(|procedure| procedure.line);
+        let mut procedures_hit = 0;
+        for procedure in procedures.iter() {
+            writeln!(target, "FN:{},{}", procedure.line, procedure.name)?;
+        }
+        for procedure in procedures.iter() {
+            let hits = self.entries(&procedure.statements);
+            procedures_hit += usize::from(hits > 0);
+            writeln!(target, "FNDA:{hits},{}", procedure.name)?;
+        }
+        writeln!(target, "FNF:{}", procedures.len())?;
+        writeln!(target, "FNH:{procedures_hit}")?;
+
+        // branches of statements which were never reached are reported as not executed
+        let mut branches_hit = 0;
+        for (block, ((line, _), hits)) in self.branches.iter().enumerate() {
+            let reached = hits.iter().any(|hits| *hits > 0);
+            for (branch, hits) in hits.iter().enumerate() {
+                branches_hit += usize::from(*hits > 0);
+                if reached {
+                    writeln!(target, "BRDA:{line},{block},{branch},{hits}")?;
+                } else {
+                    writeln!(target, "BRDA:{line},{block},{branch},-")?;
+                }
+            }
+        }
+        writeln!(target, "BRF:{}", self.branches.len() * 2)?;
+        writeln!(target, "BRH:{branches_hit}")?;
+
+        let mut lines = BTreeMap::<u32, u64>::new();
+        for ((line, _), hits) in self.instructions.iter() {
+            let line_hits = lines.entry(*line).or_default();
+            *line_hits = (*line_hits).max(*hits);
+        }
+        for (line, hits) in lines.iter() {
+            writeln!(target, "DA:{line},{hits}")?;
+        }
+        writeln!(target, "LF:{}", lines.len())?;
+        writeln!(target, "LH:{}", lines.values().filter(|hits| **hits > 0).count())?;
+        writeln!(target, "end_of_record")
+    }
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Returns true if the specified instruction is compiled into VM operations of its own, and thus,
+/// its executions can be recorded.
+///
+/// Procedure invocations are compiled into the code of the invoked procedures or into separate
+/// code blocks, and events, breakpoints, debug and advice injection instructions are compiled
+/// into decorators only.
+fn compiles_to_operations(instruction: &Instruction) -> bool {
+    !matches!(
+        instruction,
+        Instruction::ExecLocal(_)
+            | Instruction::ExecImported(_)
+            | Instruction::CallLocal(_)
+            | Instruction::CallMastRoot(_)
+            | Instruction::CallImported(_)
+            | Instruction::SysCall(_)
+            | Instruction::DynExec
+            | Instruction::DynCall
+            | Instruction::Emit(_)
+            | Instruction::Breakpoint
+            | Instruction::Debug(_)
+            | Instruction::AdvInject(_)
+    )
+}
@@ -0,0 +1,206 @@
+use super::{Coverage, TEST_SOURCE_PATH};
+use crate::{StackInputs, Test};
+
+// TESTS
+// ================================================================================================
+
+const SOURCE: &str = "\
+proc.foo
+    push.4
+end
+
+begin
+    if.true
+        exec.foo
+    else
+        push.2
+    end
+    dup.0 neq.0
+    while.true
+        sub.1 dup.0 neq.0
+    end
+    drop
+end";
+
+#[test]
+fn coverage_hits() {
+    let coverage = run(&[1]);
+
+    // instructions of the taken branch are executed, and the others are not
+    assert_eq!(Some(1), coverage.instruction_hits(TEST_SOURCE_PATH, 2, 5));
+    assert_eq!(Some(0), coverage.instruction_hits(TEST_SOURCE_PATH, 9, 9));
+    assert_eq!(Some(4), coverage.instruction_hits(TEST_SOURCE_PATH, 13, 9));
+    assert_eq!(Some(4), coverage.instruction_hits(TEST_SOURCE_PATH, 13, 15));
+    assert_eq!(Some(1), coverage.instruction_hits(TEST_SOURCE_PATH, 15, 5));
+
+    // invocations are not instructions of their own
+    assert_eq!(None, coverage.instruction_hits(TEST_SOURCE_PATH, 7, 9));
+
+    assert_eq!(Some([1, 0]), coverage.branch_hits(TEST_SOURCE_PATH, 6, 5));
+    assert_eq!(Some([4, 1]), coverage.branch_hits(TEST_SOURCE_PATH, 12, 5));
+
+    assert_eq!(Some(1), coverage.procedure_hits(TEST_SOURCE_PATH, "foo"));
+    assert_eq!(Some(1), coverage.procedure_hits(TEST_SOURCE_PATH, "#main"));
+    assert_eq!(None, coverage.procedure_hits(TEST_SOURCE_PATH, "bar"));
+}
+
+#[test]
+fn coverage_merge() {
+    let mut coverage = run(&[1]);
+    coverage.merge(&run(&[0]));
+
+    assert_eq!(Some(1), coverage.instruction_hits(TEST_SOURCE_PATH, 2, 5));
+    assert_eq!(Some(1), coverage.instruction_hits(TEST_SOURCE_PATH, 9, 9));
+    assert_eq!(Some(6), coverage.instruction_hits(TEST_SOURCE_PATH, 13, 9));
+    assert_eq!(Some([1, 1]), coverage.branch_hits(TEST_SOURCE_PATH, 6, 5));
+    assert_eq!(Some([6, 2]), coverage.branch_hits(TEST_SOURCE_PATH, 12, 5));
+    assert_eq!(Some(2), coverage.procedure_hits(TEST_SOURCE_PATH, "#main"));
+}
+
+#[test]
+fn coverage_failed_execution() {
+    let source = "\
+begin
+    if.true
+        push.1
+    else
+        push.2
+    end
+    assertz
+    push.3
+end";
+
+    // the instructions executed before the failure are recorded
+    let mut coverage = Coverage::new();
+    Test::new(source, false).record_coverage(&mut coverage);
+
+    assert_eq!(Some(1), coverage.instruction_hits(TEST_SOURCE_PATH, 5, 9));
+    assert_eq!(Some(1), coverage.instruction_hits(TEST_SOURCE_PATH, 7, 5));
+    assert_eq!(Some(0), coverage.instruction_hits(TEST_SOURCE_PATH, 8, 5));
+    assert_eq!(Some([0, 1]), coverage.branch_hits(TEST_SOURCE_PATH, 2, 5));
+}
+
+#[test]
+fn coverage_identical_branches() {
+    // identical statements are compiled into the same block, but are attributed separately
+    let source = "\
+proc.a
+    if.true
+        push.1
+    else
+        push.2
+    end
+    drop
+end
+
+proc.b
+    if.true
+        push.1
+    else
+        push.2
+    end
+    drop
+end
+
+begin
+    push.0 exec.a
+    push.1 exec.a
+    push.0
+    if.true
+        push.1
+    else
+        push.2
+    end
+    drop
+end";
+    let mut coverage = Coverage::new();
+    Test::new(source, false).record_coverage(&mut coverage);
+
+    assert_eq!(Some([1, 1]), coverage.branch_hits(TEST_SOURCE_PATH, 2, 5));
+    assert_eq!(Some([0, 0]), coverage.branch_hits(TEST_SOURCE_PATH, 11, 5));
+    assert_eq!(Some([0, 1]), coverage.branch_hits(TEST_SOURCE_PATH, 23, 5));
+    assert_eq!(Some(0), coverage.procedure_hits(TEST_SOURCE_PATH, "b"));
+
+    // statements without instructions of their own are attributed by the preceding instruction
+    let source = "\
+proc.c
+    push.3
+end
+
+proc.a
+    push.1
+    if.true
+        exec.c
+    end
+    drop
+end
+
+proc.b
+    push.1
+    if.true
+        exec.c
+    end
+    drop
+end
+
+begin
+    exec.b
+end";
+    let mut coverage = Coverage::new();
+    Test::new(source, false).record_coverage(&mut coverage);
+
+    assert_eq!(Some([0, 0]), coverage.branch_hits(TEST_SOURCE_PATH, 7, 5));
+    assert_eq!(Some([1, 0]), coverage.branch_hits(TEST_SOURCE_PATH, 15, 5));
+}
+
+#[test]
+fn coverage_lcov() {
+    let mut coverage = run(&[1]);
+    coverage.merge(&run(&[0]));
+    let expected = "\
+TN:
+SF:#test.masm
+FN:1,foo
+FN:5,#main
+FNDA:1,foo
+FNDA:2,#main
+FNF:2
+FNH:2
+BRDA:6,0,0,1
+BRDA:6,0,1,1
+BRDA:12,1,0,6
+BRDA:12,1,1,2
+BRF:4
+BRH:4
+DA:2,1
+DA:9,1
+DA:11,2
+DA:13,6
+DA:15,2
+LF:5
+LH:5
+end_of_record
+";
+    assert_eq!(expected, coverage.to_lcov());
+
+    // branches of statements which were never reached are reported as not executed
+    let source = "begin push.0 if.true if.true push.1 end end end";
+    let mut coverage = Coverage::new();
+    Test::new(source, false).record_coverage(&mut coverage);
+    Test::new(source, false).record_coverage(&mut coverage);
+    let lcov = coverage.to_lcov();
+    assert!(lcov.contains("BRDA:1,0,0,0\nBRDA:1,0,1,2\nBRDA:1,1,0,-\nBRDA:1,1,1,-\n"));
+    assert!(lcov.contains("BRF:4\nBRH:1\n"));
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+fn run(stack_inputs: &[u64]) -> Coverage {
+    let mut test = Test::new(SOURCE, false);
+    test.stack_inputs = StackInputs::try_from_values(stack_inputs.iter().copied()).unwrap();
+
+    let mut coverage = Coverage::new();
+    test.record_coverage(&mut coverage);
+    coverage
+}
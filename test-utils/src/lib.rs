@@ -20,7 +20,7 @@ pub use vm_core::chiplets::hasher::{hash_elements, STATE_WIDTH};
 pub use assembly::{Library, MaslLibrary};
 pub use processor::{
     AdviceInputs, AdviceProvider, DefaultHost, ExecutionError, ExecutionOptions, ExecutionTrace,
-    Process, ProcessState, StackInputs, VmStateIterator,
+    Process, ProcessState, RunOutput, StackInputs, VmStateIterator,
 };
 pub use prover::{prove, MemAdviceProvider, ProvingOptions};
 pub use test_case::test_case;
@@ -51,6 +51,11 @@ pub mod rand;
 mod test_builders;
 pub use test_builders::*;
 
+#[cfg(feature = "std")]
+pub mod coverage;
+#[cfg(feature = "std")]
+pub use coverage::Coverage;
+
 #[cfg(not(target_family = "wasm"))]
 pub use proptest;
 
@@ -150,6 +155,16 @@ impl Test {
         assert_eq!(expected, result, "Expected stack to be {:?}, found {:?}", expected, result);
     }
 
+    /// Builds a final stack from the provided stack-ordered array and asserts that running the
+    /// test without generating an execution trace (see [Test::run()]) will result in the expected
+    /// final stack state.
+    pub fn expect_stack_fast(&self, final_stack: &[u64]) {
+        let output = self.run().unwrap();
+        let result = stack_to_ints(&output.stack_outputs().stack_top());
+        let expected = stack_top_to_ints(final_stack);
+        assert_eq!(expected, result, "Expected stack to be {:?}, found {:?}", expected, result);
+    }
+
     /// Executes the test and validates that the process memory has the elements of `expected_mem`
     /// at address `mem_start_addr` and that the end of the stack execution trace matches the
     /// `final_stack`.
@@ -209,6 +224,11 @@ impl Test {
 
     /// Compiles a test's source and returns the resulting Program.
     pub fn compile(&self) -> Program {
+        self.assembler().compile(&self.source).expect("Failed to compile test source.")
+    }
+
+    /// Returns an assembler with the test's libraries and kernel.
+    fn assembler(&self) -> assembly::Assembler {
         let assembler = assembly::Assembler::default()
             .with_debug_mode(self.in_debug_mode)
             .with_libraries(self.libraries.iter())
@@ -218,23 +238,38 @@ impl Test {
             Some(kernel) => assembler.with_kernel(kernel).expect("kernel compilation failed"),
             None => assembler,
         }
-        .compile(&self.source)
-        .expect("Failed to compile test source.")
     }
 
     /// Compiles the test's source to a Program and executes it with the tests inputs. Returns a
     /// resulting execution trace or error.
     pub fn execute(&self) -> Result<ExecutionTrace, ExecutionError> {
+        #[cfg(feature = "std")]
+        coverage::record_test_coverage(self, &self.stack_inputs);
+
         let program = self.compile();
         let host = DefaultHost::new(MemAdviceProvider::from(self.advice_inputs.clone()));
         processor::execute(&program, self.stack_inputs.clone(), host, ExecutionOptions::default())
     }
 
+    /// Compiles the test's source to a Program and executes it with the tests inputs without
+    /// generating an execution trace. Returns the outputs of the program or an error.
+    pub fn run(&self) -> Result<RunOutput, ExecutionError> {
+        #[cfg(feature = "std")]
+        coverage::record_test_coverage(self, &self.stack_inputs);
+
+        let program = self.compile();
+        let host = DefaultHost::new(MemAdviceProvider::from(self.advice_inputs.clone()));
+        processor::run(&program, self.stack_inputs.clone(), host, ExecutionOptions::default())
+    }
+
     /// Compiles the test's source to a Program and executes it with the tests inputs. Returns the
     /// process once execution is finished.
     pub fn execute_process(
         &self,
     ) -> Result<Process<DefaultHost<MemAdviceProvider>>, ExecutionError> {
+        #[cfg(feature = "std")]
+        coverage::record_test_coverage(self, &self.stack_inputs);
+
         let program = self.compile();
         let host = DefaultHost::new(MemAdviceProvider::from(self.advice_inputs.clone()));
         let mut process = Process::new(
@@ -252,6 +287,9 @@ impl Test {
     /// is true, this function will force a failure by modifying the first output.
     pub fn prove_and_verify(&self, pub_inputs: Vec<u64>, test_fail: bool) {
         let stack_inputs = StackInputs::try_from_values(pub_inputs).unwrap();
+        #[cfg(feature = "std")]
+        coverage::record_test_coverage(self, &stack_inputs);
+
         let program = self.compile();
         let host = DefaultHost::new(MemAdviceProvider::from(self.advice_inputs.clone()));
         let (mut stack_outputs, proof) =
@@ -271,6 +309,9 @@ impl Test {
     /// VmStateIterator that allows us to iterate through each clock cycle and inspect the process
     /// state.
     pub fn execute_iter(&self) -> VmStateIterator {
+        #[cfg(feature = "std")]
+        coverage::record_test_coverage(self, &self.stack_inputs);
+
         let program = self.compile();
         let host = DefaultHost::new(MemAdviceProvider::from(self.advice_inputs.clone()));
         processor::execute_iter(&program, self.stack_inputs.clone(), host)
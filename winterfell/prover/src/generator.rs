@@ -0,0 +1,976 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use super::{
+    infer_degree, Air, AuxTraceRandElements, ColMatrix, CompositionPoly, ConstraintCommitment,
+    ConstraintEvaluator, CubeExtension, DeepCompositionPoly, FieldElement, FieldExtension,
+    FriProver, Prover, ProverError, QuadExtension, StarkDomain, StarkProof, Trace, TraceCommitment,
+    TracePolyTable, Vec,
+};
+use air::{
+    proof::{Commitments, Context, OodFrame, Queries},
+    ConstraintCompositionCoefficients, DeepCompositionCoefficients,
+};
+use core::{fmt, marker::PhantomData, mem};
+use crypto::{ElementHasher, RandomCoin};
+use fri::FriProof;
+use math::ToElements;
+
+#[cfg(feature = "concurrent")]
+use utils::iterators::*;
+
+#[cfg(feature = "std")]
+use log::debug;
+#[cfg(feature = "std")]
+use std::time::Instant;
+
+// PROVING STAGE
+// ================================================================================================
+
+/// Stages of STARK proof generation, in the order in which they are executed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ProvingStage {
+    /// Extend the main segment of the execution trace and commit to its low-degree extension.
+    MainTraceCommitment,
+    /// Build auxiliary trace segments (if any) and commit to their low-degree extensions.
+    AuxTraceCommitment,
+    /// Evaluate constraints over the constraint evaluation domain and commit to the resulting
+    /// constraint composition polynomial.
+    ConstraintEvaluation,
+    /// Build the DEEP composition polynomial and evaluate it over the LDE domain.
+    DeepComposition,
+    /// Compute FRI layers for the DEEP composition polynomial.
+    FriLayers,
+    /// Determine query positions and build the proof object.
+    Queries,
+    /// Proof generation has been completed.
+    Complete,
+    /// A stage of proof generation has failed; proof generation cannot be resumed.
+    Failed,
+}
+
+impl ProvingStage {
+    /// Number of stages which need to be executed to generate a proof.
+    pub const NUM_STAGES: usize = 6;
+
+    /// Returns the number of stages which are executed before this stage; a failed proof
+    /// generation is past all stages.
+    pub const fn index(&self) -> usize {
+        match self {
+            Self::Failed => Self::NUM_STAGES,
+            _ => *self as usize,
+        }
+    }
+}
+
+impl fmt::Display for ProvingStage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MainTraceCommitment => write!(f, "main trace commitment"),
+            Self::AuxTraceCommitment => write!(f, "auxiliary trace commitment"),
+            Self::ConstraintEvaluation => write!(f, "constraint evaluation"),
+            Self::DeepComposition => write!(f, "DEEP composition"),
+            Self::FriLayers => write!(f, "FRI layers"),
+            Self::Queries => write!(f, "queries"),
+            Self::Complete => write!(f, "complete"),
+            Self::Failed => write!(f, "failed"),
+        }
+    }
+}
+
+// PROOF GENERATOR
+// ================================================================================================
+
+/// Generates a STARK proof one [ProvingStage] at a time.
+///
+/// [Prover::prove()] runs all stages of proof generation at once; a proof generator instead
+/// executes a single stage on every call to [step()](ProofGenerator::step), which allows the
+/// caller to report progress and to yield to other tasks between stages. A proof generated this
+/// way is identical to the one returned by [Prover::prove()] for the same trace.
+///
+/// Proof generators are opt-in: [Prover::prove()] does not use them.
+///
+/// The generator does not hold a reference to the prover; the same prover must be passed into
+/// every call to [step()](ProofGenerator::step).
+pub struct ProofGenerator<P: Prover + ?Sized> {
+    inner: GeneratorInner<P>,
+}
+
+enum GeneratorInner<P: Prover + ?Sized> {
+    Base(StageRunner<P, P::BaseField>),
+    Quadratic(StageRunner<P, QuadExtension<P::BaseField>>),
+    Cubic(StageRunner<P, CubeExtension<P::BaseField>>),
+}
+
+impl<P: Prover + ?Sized> ProofGenerator<P> {
+    // CONSTRUCTOR
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns a new proof generator for the provided execution `trace`.
+    ///
+    /// # Errors
+    /// Returns an error if the field extension specified by the prover's options is not supported.
+    pub fn new(prover: &P, trace: P::Trace) -> Result<Self, ProverError> {
+        // this mirrors the static dispatch performed by [Prover::prove()]
+        let inner = match prover.options().field_extension() {
+            FieldExtension::None => GeneratorInner::Base(StageRunner::new(prover, trace)),
+            FieldExtension::Quadratic => {
+                if !<QuadExtension<P::BaseField>>::is_supported() {
+                    return Err(ProverError::UnsupportedFieldExtension(2));
+                }
+                GeneratorInner::Quadratic(StageRunner::new(prover, trace))
+            }
+            FieldExtension::Cubic => {
+                if !<CubeExtension<P::BaseField>>::is_supported() {
+                    return Err(ProverError::UnsupportedFieldExtension(3));
+                }
+                GeneratorInner::Cubic(StageRunner::new(prover, trace))
+            }
+        };
+        Ok(Self { inner })
+    }
+
+    // PUBLIC ACCESSORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the stage which will be executed on the next call to [step()](Self::step).
+    pub fn stage(&self) -> ProvingStage {
+        match &self.inner {
+            GeneratorInner::Base(runner) => runner.stage(),
+            GeneratorInner::Quadratic(runner) => runner.stage(),
+            GeneratorInner::Cubic(runner) => runner.stage(),
+        }
+    }
+
+    /// Returns true if all stages of proof generation have been executed successfully.
+    pub fn is_complete(&self) -> bool {
+        self.stage() == ProvingStage::Complete
+    }
+
+    // PROOF GENERATION
+    // --------------------------------------------------------------------------------------------
+
+    /// Executes the next stage of proof generation and returns the stage which was executed.
+    ///
+    /// # Errors
+    /// Returns an error if the stage fails; proof generation cannot be resumed after a failure.
+    ///
+    /// # Panics
+    /// Panics if proof generation has already been completed or previously failed.
+    pub fn step(&mut self, prover: &P) -> Result<ProvingStage, ProverError> {
+        match &mut self.inner {
+            GeneratorInner::Base(runner) => runner.step(prover),
+            GeneratorInner::Quadratic(runner) => runner.step(prover),
+            GeneratorInner::Cubic(runner) => runner.step(prover),
+        }
+    }
+
+    /// Returns the generated proof, or None if proof generation has not been completed.
+    pub fn into_proof(self) -> Option<StarkProof> {
+        match self.inner {
+            GeneratorInner::Base(runner) => runner.into_proof(),
+            GeneratorInner::Quadratic(runner) => runner.into_proof(),
+            GeneratorInner::Cubic(runner) => runner.into_proof(),
+        }
+    }
+}
+
+// STAGE RUNNER
+// ================================================================================================
+
+type Channel<P, E> =
+    GeneratorChannel<<P as Prover>::Air, E, <P as Prover>::HashFn, <P as Prover>::RandomCoin>;
+
+/// Intermediate results of proof generation carried over from one stage to the next.
+enum StageState<P: Prover + ?Sized, E: FieldElement<BaseField = P::BaseField>> {
+    Started {
+        trace: P::Trace,
+    },
+    MainTraceCommitted {
+        trace: P::Trace,
+        domain: StarkDomain<P::BaseField>,
+        trace_commitment: TraceCommitment<E, P::HashFn>,
+        trace_polys: TracePolyTable<E>,
+    },
+    AuxTraceCommitted {
+        domain: StarkDomain<P::BaseField>,
+        trace_commitment: TraceCommitment<E, P::HashFn>,
+        trace_polys: TracePolyTable<E>,
+        aux_trace_rand_elements: AuxTraceRandElements<E>,
+    },
+    ConstraintsCommitted {
+        domain: StarkDomain<P::BaseField>,
+        trace_commitment: TraceCommitment<E, P::HashFn>,
+        trace_polys: TracePolyTable<E>,
+        composition_poly: CompositionPoly<E>,
+        constraint_commitment: ConstraintCommitment<E, P::HashFn>,
+    },
+    DeepCompositionEvaluated {
+        trace_commitment: TraceCommitment<E, P::HashFn>,
+        constraint_commitment: ConstraintCommitment<E, P::HashFn>,
+        deep_evaluations: Vec<E>,
+    },
+    FriLayersBuilt {
+        trace_commitment: TraceCommitment<E, P::HashFn>,
+        constraint_commitment: ConstraintCommitment<E, P::HashFn>,
+        fri_prover: FriProver<P::BaseField, E, Channel<P, E>, P::HashFn>,
+    },
+    Complete,
+    Failed,
+}
+
+/// Executes the stages of proof generation for a specific extension field.
+struct StageRunner<P: Prover + ?Sized, E: FieldElement<BaseField = P::BaseField>> {
+    channel: Option<Channel<P, E>>,
+    state: StageState<P, E>,
+    proof: Option<StarkProof>,
+}
+
+impl<P, E> StageRunner<P, E>
+where
+    P: Prover + ?Sized,
+    E: FieldElement<BaseField = P::BaseField>,
+{
+    /// Instantiates the AIR and the prover channel for the provided execution trace.
+    pub fn new(prover: &P, trace: P::Trace) -> Self {
+        // serialize public inputs; these will be included in the seed for the public coin
+        let pub_inputs = prover.get_pub_inputs(&trace);
+        let pub_inputs_elements = pub_inputs.to_elements();
+
+        // create an instance of AIR for the provided parameters. this takes a generic description
+        // of the computation (provided via AIR type), and creates a description of a specific
+        // execution of the computation for the provided public inputs.
+        let air = P::Air::new(trace.get_info(), pub_inputs, prover.options().clone());
+
+        // create a channel which is used to simulate interaction between the prover and the
+        // verifier; the channel will be used to commit to values and to draw randomness that
+        // should come from the verifier.
+        let channel = GeneratorChannel::new(air, pub_inputs_elements);
+
+        Self {
+            channel: Some(channel),
+            state: StageState::Started { trace },
+            proof: None,
+        }
+    }
+
+    /// Returns the stage which will be executed next.
+    pub fn stage(&self) -> ProvingStage {
+        match &self.state {
+            StageState::Started { .. } => ProvingStage::MainTraceCommitment,
+            StageState::MainTraceCommitted { .. } => ProvingStage::AuxTraceCommitment,
+            StageState::AuxTraceCommitted { .. } => ProvingStage::ConstraintEvaluation,
+            StageState::ConstraintsCommitted { .. } => ProvingStage::DeepComposition,
+            StageState::DeepCompositionEvaluated { .. } => ProvingStage::FriLayers,
+            StageState::FriLayersBuilt { .. } => ProvingStage::Queries,
+            StageState::Complete => ProvingStage::Complete,
+            StageState::Failed => ProvingStage::Failed,
+        }
+    }
+
+    /// Returns the generated proof, or None if proof generation has not been completed.
+    pub fn into_proof(self) -> Option<StarkProof> {
+        self.proof
+    }
+
+    /// Executes the next stage of proof generation and returns the stage which was executed.
+    pub fn step(&mut self, prover: &P) -> Result<ProvingStage, ProverError> {
+        let stage = self.stage();
+        self.state = match mem::replace(&mut self.state, StageState::Failed) {
+            StageState::Started { trace } => self.commit_main_trace(prover, trace),
+            StageState::MainTraceCommitted {
+                trace,
+                domain,
+                trace_commitment,
+                trace_polys,
+            } => self.commit_aux_trace(prover, trace, domain, trace_commitment, trace_polys),
+            StageState::AuxTraceCommitted {
+                domain,
+                trace_commitment,
+                trace_polys,
+                aux_trace_rand_elements,
+            } => self.commit_constraints(
+                prover,
+                domain,
+                trace_commitment,
+                trace_polys,
+                aux_trace_rand_elements,
+            )?,
+            StageState::ConstraintsCommitted {
+                domain,
+                trace_commitment,
+                trace_polys,
+                composition_poly,
+                constraint_commitment,
+            } => self.evaluate_deep_composition(
+                domain,
+                trace_commitment,
+                trace_polys,
+                composition_poly,
+                constraint_commitment,
+            ),
+            StageState::DeepCompositionEvaluated {
+                trace_commitment,
+                constraint_commitment,
+                deep_evaluations,
+            } => self.build_fri_layers(trace_commitment, constraint_commitment, deep_evaluations),
+            StageState::FriLayersBuilt {
+                trace_commitment,
+                constraint_commitment,
+                fri_prover,
+            } => self.build_proof(trace_commitment, constraint_commitment, fri_prover),
+            StageState::Complete | StageState::Failed => {
+                panic!("proof generation has already been completed or has failed")
+            }
+        };
+        Ok(stage)
+    }
+
+    // STAGES
+    // --------------------------------------------------------------------------------------------
+
+    /// Extends the main execution trace and commits to its low-degree extension.
+    fn commit_main_trace(&mut self, prover: &P, trace: P::Trace) -> StageState<P, E> {
+        let channel = self.channel_mut();
+
+        // build computation domain; this is used later for polynomial evaluations
+        #[cfg(feature = "std")]
+        let now = Instant::now();
+        let domain = StarkDomain::new(channel.air());
+        #[cfg(feature = "std")]
+        debug!(
+            "Built domain of 2^{} elements in {} ms",
+            domain.lde_domain_size().ilog2(),
+            now.elapsed().as_millis()
+        );
+
+        // extend the main execution trace and build a Merkle tree from the extended trace
+        let (main_trace_lde, main_trace_tree, main_trace_polys) =
+            prover.build_trace_commitment::<P::BaseField>(trace.main_segment(), &domain);
+
+        // commit to the LDE of the main trace by writing the root of its Merkle tree into
+        // the channel
+        channel.commit_trace(*main_trace_tree.root());
+
+        // initialize trace commitment and trace polynomial table structs with the main trace
+        // data; for multi-segment traces these structs will be used as accumulators of all
+        // trace segments
+        let trace_commitment =
+            TraceCommitment::new(main_trace_lde, main_trace_tree, domain.trace_to_lde_blowup());
+        let trace_polys = TracePolyTable::new(main_trace_polys);
+
+        StageState::MainTraceCommitted {
+            trace,
+            domain,
+            trace_commitment,
+            trace_polys,
+        }
+    }
+
+    /// Builds auxiliary trace segments (if any) and commits to their low-degree extensions.
+    fn commit_aux_trace(
+        &mut self,
+        prover: &P,
+        mut trace: P::Trace,
+        domain: StarkDomain<P::BaseField>,
+        mut trace_commitment: TraceCommitment<E, P::HashFn>,
+        mut trace_polys: TracePolyTable<E>,
+    ) -> StageState<P, E> {
+        let channel = self.channel_mut();
+
+        // build auxiliary trace segments (if any), and append the resulting segments to trace
+        // commitment and trace polynomial table structs
+        let mut aux_trace_segments: Vec<ColMatrix<E>> = Vec::new();
+        let mut aux_trace_rand_elements = AuxTraceRandElements::new();
+        for i in 0..trace.layout().num_aux_segments() {
+            #[cfg(feature = "std")]
+            let now = Instant::now();
+
+            // draw a set of random elements required to build an auxiliary trace segment
+            let rand_elements = channel.get_aux_trace_segment_rand_elements(i);
+
+            // build the trace segment
+            let aux_segment = trace
+                .build_aux_segment(&aux_trace_segments, &rand_elements)
+                .expect("failed build auxiliary trace segment");
+            #[cfg(feature = "std")]
+            debug!(
+                "Built auxiliary trace segment of {} columns and 2^{} steps in {} ms",
+                aux_segment.num_cols(),
+                aux_segment.num_rows().ilog2(),
+                now.elapsed().as_millis()
+            );
+
+            // extend the auxiliary trace segment and build a Merkle tree from the extended trace
+            let (aux_segment_lde, aux_segment_tree, aux_segment_polys) =
+                prover.build_trace_commitment::<E>(&aux_segment, &domain);
+
+            // commit to the LDE of the extended auxiliary trace segment  by writing the root of
+            // its Merkle tree into the channel
+            channel.commit_trace(*aux_segment_tree.root());
+
+            // append the segment to the trace commitment and trace polynomial table structs
+            trace_commitment.add_segment(aux_segment_lde, aux_segment_tree);
+            trace_polys.add_aux_segment(aux_segment_polys);
+            aux_trace_rand_elements.add_segment_elements(rand_elements);
+            aux_trace_segments.push(aux_segment);
+        }
+
+        // make sure the specified trace (including auxiliary segments) is valid against the AIR.
+        // This checks validity of both, assertions and state transitions. We do this in debug
+        // mode only because this is a very expensive operation.
+        #[cfg(debug_assertions)]
+        trace.validate(channel.air(), &aux_trace_segments, &aux_trace_rand_elements);
+
+        StageState::AuxTraceCommitted {
+            domain,
+            trace_commitment,
+            trace_polys,
+            aux_trace_rand_elements,
+        }
+    }
+
+    /// Evaluates constraints over the constraint evaluation domain and commits to the resulting
+    /// constraint composition polynomial.
+    fn commit_constraints(
+        &mut self,
+        prover: &P,
+        domain: StarkDomain<P::BaseField>,
+        trace_commitment: TraceCommitment<E, P::HashFn>,
+        trace_polys: TracePolyTable<E>,
+        aux_trace_rand_elements: AuxTraceRandElements<E>,
+    ) -> Result<StageState<P, E>, ProverError> {
+        let channel = self.channel_mut();
+
+        // evaluate constraints specified by the AIR over the constraint evaluation domain, and
+        // compute random linear combinations of these evaluations using coefficients drawn from
+        // the channel; this step evaluates only constraint numerators, thus, only constraints with
+        // identical denominators are merged together. the results are saved into a constraint
+        // evaluation table where each column contains merged evaluations of constraints with
+        // identical denominators.
+        #[cfg(feature = "std")]
+        let now = Instant::now();
+        let constraint_coeffs = channel.get_constraint_composition_coeffs();
+        let air = channel.air();
+        let evaluator = ConstraintEvaluator::new(air, aux_trace_rand_elements, constraint_coeffs);
+        let constraint_evaluations = evaluator.evaluate(trace_commitment.trace_table(), &domain);
+        #[cfg(feature = "std")]
+        debug!(
+            "Evaluated constraints over domain of 2^{} elements in {} ms",
+            constraint_evaluations.num_rows().ilog2(),
+            now.elapsed().as_millis()
+        );
+
+        // first, build constraint composition polynomial from the constraint evaluation table:
+        // - divide all constraint evaluation columns by their respective divisors
+        // - combine them into a single column of evaluations,
+        // - interpolate the column into a polynomial in coefficient form
+        // - "break" the polynomial into a set of column polynomials each of degree equal to
+        //   trace_length - 1
+        #[cfg(feature = "std")]
+        let now = Instant::now();
+        let composition_poly =
+            constraint_evaluations.into_poly(air.context().num_constraint_composition_columns())?;
+        #[cfg(feature = "std")]
+        debug!(
+            "Converted constraint evaluations into {} composition polynomial columns of degree {} in {} ms",
+            composition_poly.num_columns(),
+            composition_poly.column_degree(),
+            now.elapsed().as_millis()
+        );
+
+        // then, build a commitment to the evaluations of the composition polynomial columns
+        let constraint_commitment =
+            prover.build_constraint_commitment::<E>(&composition_poly, &domain);
+
+        // then, commit to the evaluations of constraints by writing the root of the constraint
+        // Merkle tree into the channel
+        channel.commit_constraints(constraint_commitment.root());
+
+        Ok(StageState::ConstraintsCommitted {
+            domain,
+            trace_commitment,
+            trace_polys,
+            composition_poly,
+            constraint_commitment,
+        })
+    }
+
+    /// Builds the DEEP composition polynomial and evaluates it over the LDE domain.
+    fn evaluate_deep_composition(
+        &mut self,
+        domain: StarkDomain<P::BaseField>,
+        trace_commitment: TraceCommitment<E, P::HashFn>,
+        trace_polys: TracePolyTable<E>,
+        composition_poly: CompositionPoly<E>,
+        constraint_commitment: ConstraintCommitment<E, P::HashFn>,
+    ) -> StageState<P, E> {
+        let channel = self.channel_mut();
+
+        #[cfg(feature = "std")]
+        let now = Instant::now();
+
+        // draw an out-of-domain point z. Depending on the type of E, the point is drawn either
+        // from the base field or from an extension field defined by E.
+        //
+        // The purpose of sampling from the extension field here (instead of the base field) is to
+        // increase security. Soundness is limited by the size of the field that the random point
+        // is drawn from, and we can potentially save on performance by only drawing this point
+        // from an extension field, rather than increasing the size of the field overall.
+        let z = channel.get_ood_point();
+
+        // evaluate trace and constraint polynomials at the OOD point z, and send the results to
+        // the verifier. the trace polynomials are actually evaluated over two points: z and z * g,
+        // where g is the generator of the trace domain.
+        let ood_trace_states = trace_polys.get_ood_frame(z);
+        channel.send_ood_trace_states(&ood_trace_states);
+
+        let ood_evaluations = composition_poly.evaluate_at(z);
+        channel.send_ood_constraint_evaluations(&ood_evaluations);
+
+        // draw random coefficients to use during DEEP polynomial composition, and use them to
+        // initialize the DEEP composition polynomial
+        let deep_coefficients = channel.get_deep_composition_coeffs();
+        let mut deep_composition_poly = DeepCompositionPoly::new(z, deep_coefficients);
+
+        // combine all trace polynomials together and merge them into the DEEP composition
+        // polynomial
+        deep_composition_poly.add_trace_polys(trace_polys, ood_trace_states);
+
+        // merge columns of constraint composition polynomial into the DEEP composition polynomial;
+        deep_composition_poly.add_composition_poly(composition_poly, ood_evaluations);
+
+        #[cfg(feature = "std")]
+        debug!(
+            "Built DEEP composition polynomial of degree {} in {} ms",
+            deep_composition_poly.degree(),
+            now.elapsed().as_millis()
+        );
+
+        // make sure the degree of the DEEP composition polynomial is equal to trace polynomial
+        // degree minus 1.
+        assert_eq!(domain.trace_length() - 2, deep_composition_poly.degree());
+
+        // evaluate DEEP composition polynomial over LDE domain
+        #[cfg(feature = "std")]
+        let now = Instant::now();
+        let deep_evaluations = deep_composition_poly.evaluate(&domain);
+        // we check the following condition in debug mode only because infer_degree is an expensive
+        // operation
+        debug_assert_eq!(
+            domain.trace_length() - 2,
+            infer_degree(&deep_evaluations, domain.offset())
+        );
+        #[cfg(feature = "std")]
+        debug!(
+            "Evaluated DEEP composition polynomial over LDE domain (2^{} elements) in {} ms",
+            domain.lde_domain_size().ilog2(),
+            now.elapsed().as_millis()
+        );
+
+        StageState::DeepCompositionEvaluated {
+            trace_commitment,
+            constraint_commitment,
+            deep_evaluations,
+        }
+    }
+
+    /// Computes FRI layers for the DEEP composition polynomial.
+    fn build_fri_layers(
+        &mut self,
+        trace_commitment: TraceCommitment<E, P::HashFn>,
+        constraint_commitment: ConstraintCommitment<E, P::HashFn>,
+        deep_evaluations: Vec<E>,
+    ) -> StageState<P, E> {
+        let channel = self.channel_mut();
+
+        #[cfg(feature = "std")]
+        let now = Instant::now();
+        let mut fri_prover = FriProver::new(channel.air().options().to_fri_options());
+        fri_prover.build_layers(channel, deep_evaluations);
+        #[cfg(feature = "std")]
+        debug!(
+            "Computed {} FRI layers from composition polynomial evaluations in {} ms",
+            fri_prover.num_layers(),
+            now.elapsed().as_millis()
+        );
+
+        StageState::FriLayersBuilt {
+            trace_commitment,
+            constraint_commitment,
+            fri_prover,
+        }
+    }
+
+    /// Determines query positions and builds the proof object.
+    fn build_proof(
+        &mut self,
+        trace_commitment: TraceCommitment<E, P::HashFn>,
+        constraint_commitment: ConstraintCommitment<E, P::HashFn>,
+        mut fri_prover: FriProver<P::BaseField, E, Channel<P, E>, P::HashFn>,
+    ) -> StageState<P, E> {
+        let mut channel = self.channel.take().expect("prover channel has already been consumed");
+
+        // determine query positions
+        #[cfg(feature = "std")]
+        let now = Instant::now();
+
+        // apply proof-of-work to the query seed
+        channel.grind_query_seed();
+
+        // generate pseudo-random query positions
+        let query_positions = channel.get_query_positions();
+        #[cfg(feature = "std")]
+        debug!(
+            "Determined {} query positions in {} ms",
+            query_positions.len(),
+            now.elapsed().as_millis()
+        );
+
+        // build proof object
+        #[cfg(feature = "std")]
+        let now = Instant::now();
+
+        // generate FRI proof
+        let fri_proof = fri_prover.build_proof(&query_positions);
+
+        // query the execution trace at the selected position; for each query, we need the
+        // state of the trace at that position + Merkle authentication path
+        let trace_queries = trace_commitment.query(&query_positions);
+
+        // query the constraint commitment at the selected positions; for each query, we need just
+        // a Merkle authentication path. this is because constraint evaluations for each step are
+        // merged into a single value and Merkle authentication paths contain these values already
+        let constraint_queries = constraint_commitment.query(&query_positions);
+
+        // build the proof object
+        self.proof = Some(channel.build_proof(trace_queries, constraint_queries, fri_proof));
+        #[cfg(feature = "std")]
+        debug!("Built proof object in {} ms", now.elapsed().as_millis());
+
+        StageState::Complete
+    }
+
+    // HELPER METHODS
+    // --------------------------------------------------------------------------------------------
+
+    fn channel_mut(&mut self) -> &mut Channel<P, E> {
+        self.channel.as_mut().expect("prover channel has already been consumed")
+    }
+}
+
+// GENERATOR CHANNEL
+// ================================================================================================
+
+/// A prover channel which owns the AIR of the computation.
+///
+/// This mirrors [ProverChannel](crate::channel::ProverChannel) used by [Prover::prove()], except
+/// that the channel owns the AIR instead of borrowing it. This allows the channel to be stored in
+/// a [ProofGenerator] between stages of proof generation.
+struct GeneratorChannel<A, E, H, R>
+where
+    A: Air,
+    E: FieldElement<BaseField = A::BaseField>,
+    H: ElementHasher<BaseField = A::BaseField>,
+    R: RandomCoin<BaseField = E::BaseField, Hasher = H>,
+{
+    air: A,
+    public_coin: R,
+    context: Context,
+    commitments: Commitments,
+    ood_frame: OodFrame,
+    pow_nonce: u64,
+    _field_element: PhantomData<E>,
+}
+
+impl<A, E, H, R> GeneratorChannel<A, E, H, R>
+where
+    A: Air,
+    E: FieldElement<BaseField = A::BaseField>,
+    H: ElementHasher<BaseField = A::BaseField>,
+    R: RandomCoin<BaseField = A::BaseField, Hasher = H>,
+{
+    /// Creates a new prover channel for the specified `air` and public inputs.
+    fn new(air: A, mut pub_inputs_elements: Vec<A::BaseField>) -> Self {
+        let context = Context::new::<A::BaseField>(air.trace_info(), air.options().clone());
+
+        // build a seed for the public coin; the initial seed is a hash of the proof context and
+        // the public inputs, but as the protocol progresses, the coin will be reseeded with the
+        // info sent to the verifier
+        let mut coin_seed_elements = context.to_elements();
+        coin_seed_elements.append(&mut pub_inputs_elements);
+
+        Self {
+            air,
+            public_coin: RandomCoin::new(&coin_seed_elements),
+            context,
+            commitments: Commitments::default(),
+            ood_frame: OodFrame::default(),
+            pow_nonce: 0,
+            _field_element: PhantomData,
+        }
+    }
+
+    /// Returns the AIR of the computation for which the proof is being generated.
+    fn air(&self) -> &A {
+        &self.air
+    }
+
+    /// Commits the prover the extended execution trace.
+    fn commit_trace(&mut self, trace_root: H::Digest) {
+        self.commitments.add::<H>(&trace_root);
+        self.public_coin.reseed(trace_root);
+    }
+
+    /// Commits the prover to the evaluations of the constraint composition polynomial.
+    fn commit_constraints(&mut self, constraint_root: H::Digest) {
+        self.commitments.add::<H>(&constraint_root);
+        self.public_coin.reseed(constraint_root);
+    }
+
+    /// Saves the evaluations of trace polynomials over the out-of-domain evaluation frame. This
+    /// also reseeds the public coin with the hashes of the evaluation frame states.
+    fn send_ood_trace_states(&mut self, trace_states: &[Vec<E>]) {
+        let result = self.ood_frame.set_trace_states(trace_states);
+        self.public_coin.reseed(H::hash_elements(&result));
+    }
+
+    /// Saves the evaluations of constraint composition polynomial columns at the out-of-domain
+    /// point. This also reseeds the public coin wit the hash of the evaluations.
+    fn send_ood_constraint_evaluations(&mut self, evaluations: &[E]) {
+        self.ood_frame.set_constraint_evaluations(evaluations);
+        self.public_coin.reseed(H::hash_elements(evaluations));
+    }
+
+    /// Returns a set of random elements required for constructing an auxiliary trace segment with
+    /// the specified index.
+    fn get_aux_trace_segment_rand_elements(&mut self, aux_segment_idx: usize) -> Vec<E> {
+        self.air
+            .get_aux_trace_segment_random_elements(aux_segment_idx, &mut self.public_coin)
+            .expect("failed to draw random elements for an auxiliary trace segment")
+    }
+
+    /// Returns a set of coefficients for constructing a constraint composition polynomial.
+    fn get_constraint_composition_coeffs(&mut self) -> ConstraintCompositionCoefficients<E> {
+        self.air
+            .get_constraint_composition_coefficients(&mut self.public_coin)
+            .expect("failed to draw composition coefficients")
+    }
+
+    /// Returns an out-of-domain point drawn uniformly at random from the public coin.
+    fn get_ood_point(&mut self) -> E {
+        self.public_coin.draw().expect("failed to draw OOD point")
+    }
+
+    /// Returns a set of coefficients for constructing a DEEP composition polynomial.
+    fn get_deep_composition_coeffs(&mut self) -> DeepCompositionCoefficients<E> {
+        self.air
+            .get_deep_composition_coefficients(&mut self.public_coin)
+            .expect("failed to draw DEEP composition coefficients")
+    }
+
+    /// Returns a set of positions in the LDE domain against which the evaluations of trace and
+    /// constraint composition polynomials should be queried.
+    fn get_query_positions(&mut self) -> Vec<usize> {
+        let num_queries = self.context.options().num_queries();
+        let lde_domain_size = self.context.lde_domain_size();
+        self.public_coin
+            .draw_integers(num_queries, lde_domain_size)
+            .expect("failed to draw query position")
+    }
+
+    /// Determines a nonce, which when hashed with the current seed of the public coin results
+    /// in a new seed with the number of leading zeros equal to the grinding_factor specified
+    /// in the proof options.
+    fn grind_query_seed(&mut self) {
+        let grinding_factor = self.context.options().grinding_factor();
+
+        #[cfg(not(feature = "concurrent"))]
+        let nonce = (1..u64::MAX)
+            .find(|&nonce| self.public_coin.check_leading_zeros(nonce) >= grinding_factor)
+            .expect("nonce not found");
+
+        #[cfg(feature = "concurrent")]
+        let nonce = (1..u64::MAX)
+            .into_par_iter()
+            .find_any(|&nonce| self.public_coin.check_leading_zeros(nonce) >= grinding_factor)
+            .expect("nonce not found");
+
+        self.pow_nonce = nonce;
+        self.public_coin.reseed_with_int(nonce);
+    }
+
+    /// Builds a proof from the previously committed values as well as values passed into
+    /// this method.
+    fn build_proof(
+        self,
+        trace_queries: Vec<Queries>,
+        constraint_queries: Queries,
+        fri_proof: FriProof,
+    ) -> StarkProof {
+        StarkProof {
+            context: self.context,
+            commitments: self.commitments,
+            ood_frame: self.ood_frame,
+            trace_queries,
+            constraint_queries,
+            fri_proof,
+            pow_nonce: self.pow_nonce,
+        }
+    }
+}
+
+impl<A, E, H, R> fri::ProverChannel<E> for GeneratorChannel<A, E, H, R>
+where
+    A: Air,
+    E: FieldElement<BaseField = A::BaseField>,
+    H: ElementHasher<BaseField = A::BaseField>,
+    R: RandomCoin<BaseField = A::BaseField, Hasher = H>,
+{
+    type Hasher = H;
+
+    /// Commits the prover to a FRI layer.
+    fn commit_fri_layer(&mut self, layer_root: H::Digest) {
+        self.commitments.add::<H>(&layer_root);
+        self.public_coin.reseed(layer_root);
+    }
+
+    /// Returns a new alpha drawn from the public coin.
+    fn draw_fri_alpha(&mut self) -> E {
+        self.public_coin.draw().expect("failed to draw FRI alpha")
+    }
+}
+
+// TESTS
+// ================================================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::{ProofGenerator, ProvingStage};
+    use crate::{
+        Air, AirContext, Assertion, EvaluationFrame, FieldExtension, ProofOptions, Prover, Trace,
+        TraceInfo, TraceTable, TransitionConstraintDegree,
+    };
+    use crypto::{hashers::Blake3_256, DefaultRandomCoin};
+    use math::{fields::f128::BaseElement, FieldElement};
+    use utils::collections::Vec;
+
+    #[test]
+    fn proof_generator_matches_prover() {
+        for field_extension in [FieldExtension::None, FieldExtension::Quadratic] {
+            let prover = FibProver(ProofOptions::new(28, 8, 4, field_extension, 4, 31));
+            let expected = prover.prove(build_fib_trace(64)).unwrap();
+
+            // stages are executed in order, and the resulting proof is identical to the proof
+            // generated by the prover
+            let mut generator = ProofGenerator::new(&prover, build_fib_trace(64)).unwrap();
+            let mut stages = Vec::new();
+            while !generator.is_complete() {
+                stages.push(generator.step(&prover).unwrap());
+            }
+            let expected_stages = vec![
+                ProvingStage::MainTraceCommitment,
+                ProvingStage::AuxTraceCommitment,
+                ProvingStage::ConstraintEvaluation,
+                ProvingStage::DeepComposition,
+                ProvingStage::FriLayers,
+                ProvingStage::Queries,
+            ];
+            assert_eq!(expected_stages, stages);
+            assert_eq!(ProvingStage::NUM_STAGES, stages.len());
+
+            let proof = generator.into_proof().unwrap();
+            assert_eq!(expected.to_bytes(), proof.to_bytes());
+        }
+    }
+
+    #[test]
+    fn proof_generator_unsupported_extension() {
+        let prover = FibProver(ProofOptions::new(28, 8, 0, FieldExtension::Cubic, 4, 31));
+        assert!(prover.prove(build_fib_trace(64)).is_err());
+        assert!(ProofGenerator::new(&prover, build_fib_trace(64)).is_err());
+    }
+
+    // FIBONACCI PROVER
+    // --------------------------------------------------------------------------------------------
+
+    /// Builds a trace of a Fibonacci sequence of the specified length which advances the sequence
+    /// by 2 terms per row.
+    fn build_fib_trace(sequence_length: usize) -> TraceTable<BaseElement> {
+        let mut trace = TraceTable::new(2, sequence_length / 2);
+        trace.fill(
+            |state| {
+                state[0] = BaseElement::ONE;
+                state[1] = BaseElement::ONE;
+            },
+            |_, state| {
+                state[0] += state[1];
+                state[1] += state[0];
+            },
+        );
+        trace
+    }
+
+    struct FibProver(ProofOptions);
+
+    impl Prover for FibProver {
+        type BaseField = BaseElement;
+        type Air = FibAir;
+        type Trace = TraceTable<BaseElement>;
+        type HashFn = Blake3_256<BaseElement>;
+        type RandomCoin = DefaultRandomCoin<Self::HashFn>;
+
+        fn get_pub_inputs(&self, trace: &Self::Trace) -> BaseElement {
+            trace.get(1, trace.length() - 1)
+        }
+
+        fn options(&self) -> &ProofOptions {
+            &self.0
+        }
+    }
+
+    struct FibAir {
+        context: AirContext<BaseElement>,
+        result: BaseElement,
+    }
+
+    impl Air for FibAir {
+        type BaseField = BaseElement;
+        type PublicInputs = BaseElement;
+
+        fn new(trace_info: TraceInfo, pub_inputs: BaseElement, options: ProofOptions) -> Self {
+            let degrees = vec![TransitionConstraintDegree::new(1); 2];
+            FibAir {
+                context: AirContext::new(trace_info, degrees, 3, options),
+                result: pub_inputs,
+            }
+        }
+
+        fn context(&self) -> &AirContext<Self::BaseField> {
+            &self.context
+        }
+
+        fn evaluate_transition<E: FieldElement + From<Self::BaseField>>(
+            &self,
+            frame: &EvaluationFrame<E>,
+            _periodic_values: &[E],
+            result: &mut [E],
+        ) {
+            let current = frame.current();
+            let next = frame.next();
+            result[0] = next[0] - (current[0] + current[1]);
+            result[1] = next[1] - (current[1] + next[0]);
+        }
+
+        fn get_assertions(&self) -> Vec<Assertion<Self::BaseField>> {
+            let last_step = self.trace_length() - 1;
+            vec![
+                Assertion::single(0, 0, BaseElement::ONE),
+                Assertion::single(1, 0, BaseElement::ONE),
+                Assertion::single(1, last_step, self.result),
+            ]
+        }
+    }
+}
@@ -92,6 +92,9 @@ use trace::{TraceCommitment, TraceLde, TracePolyTable};
 mod channel;
 use channel::ProverChannel;
 
+mod generator;
+pub use generator::{ProofGenerator, ProvingStage};
+
 mod errors;
 pub use errors::ProverError;
 